@@ -0,0 +1,89 @@
+//! Admin tasks that are easier from a shell than from the site.
+//!
+//! Uses the same environment as the site server to reach the database and
+//! the object store.
+
+use std::{fs::File, io::BufReader, path::PathBuf};
+
+use archive::import::{
+  import_recordings, DirectorySource, ImportOptions, ZipSource,
+};
+use clap::{Parser, Subcommand};
+use core_types::TranslationRecordId;
+use eyre::{OptionExt, Result};
+
+/// Admin tasks for the site.
+#[derive(Parser)]
+struct Cli {
+  #[command(subcommand)]
+  command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+  /// Imports a ZIP or directory of recordings into a translation.
+  ///
+  /// Files are matched to passages by a `manifest.json`, or by names like
+  /// `JHN_003.mp3` or `JHN_003_016-018.mp3`. Without `--apply`, this only
+  /// reports what would be imported.
+  Import {
+    /// The ID of the translation to import into.
+    #[arg(long)]
+    translation: core_types::Ulid,
+    /// The email of the user the recordings are uploaded as.
+    #[arg(long)]
+    user:        String,
+    /// Create the recordings, instead of a dry run.
+    #[arg(long)]
+    apply:       bool,
+    /// Import passages that are already recorded, as new current takes.
+    #[arg(long)]
+    replace:     bool,
+    /// A `.zip` file or a directory.
+    path:        PathBuf,
+  },
+}
+
+#[tokio::main]
+async fn main() -> Result<()> {
+  tracing_subscriber::fmt()
+    .with_env_filter(
+      tracing_subscriber::EnvFilter::try_from_default_env()
+        .unwrap_or(tracing_subscriber::EnvFilter::new("warn,archive=info")),
+    )
+    .init();
+
+  match Cli::parse().command {
+    Command::Import {
+      translation,
+      user,
+      apply,
+      replace,
+      path,
+    } => {
+      let db = db::DbConnection::new().await?;
+      let user = db
+        .select_all_users_matching_email(&user)
+        .await?
+        .into_iter()
+        .next()
+        .ok_or_eyre("no user has that email")?;
+      let options = ImportOptions {
+        apply,
+        replace_existing: replace,
+      };
+      let translation = TranslationRecordId(translation);
+
+      let report = if path.is_dir() {
+        let source = DirectorySource::new(path)?;
+        import_recordings(&db, translation, user.id, source, options).await?
+      } else {
+        let source = ZipSource::new(BufReader::new(File::open(path)?))?;
+        import_recordings(&db, translation, user.id, source, options).await?
+      };
+      print!("{report}");
+    }
+  }
+
+  Ok(())
+}
@@ -0,0 +1,439 @@
+//! Offline bundles of a translation's approved recordings.
+//!
+//! Bundles are laid out by book and chapter, so they can be browsed on a
+//! device with nothing but a file manager:
+//!
+//! ```text
+//! LICENSE.txt
+//! manifest.json
+//! playlist.m3u
+//! 01_GEN/001/GEN_001.mp3
+//! 43_JHN/003/JHN_003_001-015.webm
+//! 43_JHN/003/JHN_003_016-036.webm
+//! ```
+
+use std::{
+  fs::File,
+  io::{BufWriter, Seek, Write},
+  path::Path,
+};
+
+use core_types::{
+  audio_extension, scripture::Passage, ApprovedRecording, Bundle, BundleFile,
+  BundleManifest, Playlist, BUNDLE_MANIFEST_NAME, BUNDLE_MANIFEST_VERSION,
+};
+use eyre::{Context, OptionExt, Result};
+use sha2::{Digest, Sha256};
+use tokio::sync::mpsc;
+use zip::{write::SimpleFileOptions, CompressionMethod, ZipWriter};
+
+pub(crate) const PLAYLIST_NAME: &str = "playlist.m3u";
+pub(crate) const LICENSE_NAME: &str = "LICENSE.txt";
+/// The extension for recordings in a format we don't know.
+const UNKNOWN_EXTENSION: &str = "bin";
+
+/// Names a recording of `passage` like `JHN_003`, or `JHN_003_016-018` for
+/// some of its verses.
+pub fn file_stem(passage: &Passage) -> String {
+  match passage.verses {
+    Some(verses) => format!(
+      "{}_{:03}_{:03}-{:03}",
+      passage.book, passage.chapter, verses.start, verses.end
+    ),
+    None => format!("{}_{:03}", passage.book, passage.chapter),
+  }
+}
+
+/// Where a recording of `passage` goes in a bundle: a folder for its book,
+/// numbered so books sort in canonical order, then one for its chapter.
+pub fn bundle_path(passage: &Passage, extension: &str) -> String {
+  let (book_index, ..) = passage.canonical_key();
+  format!(
+    "{:02}_{}/{:03}/{}.{extension}",
+    book_index + 1,
+    passage.book,
+    passage.chapter,
+    file_stem(passage)
+  )
+}
+
+/// Names a passage like "John 3" or "John 3:16-18".
+fn passage_title(passage: &Passage) -> String {
+  let book = core_types::scripture::book(&passage.book)
+    .map_or(passage.book.as_str(), |b| b.name);
+  match passage.verses {
+    Some(verses) if verses.start == verses.end => {
+      format!("{book} {}:{}", passage.chapter, verses.start)
+    }
+    Some(verses) => {
+      format!("{book} {}:{}-{}", passage.chapter, verses.start, verses.end)
+    }
+    None => format!("{book} {}", passage.chapter),
+  }
+}
+
+/// Lists the verses a recording covers.
+fn verses(passage: &Passage) -> Vec<u16> {
+  passage
+    .verse_range()
+    .map_or_else(Vec::new, |range| (range.start..=range.end).collect())
+}
+
+/// Writes an extended M3U playlist of the bundle's recordings, in order.
+fn m3u(manifest: &BundleManifest) -> String {
+  let mut playlist = String::from("#EXTM3U\n");
+  for file in &manifest.files {
+    let seconds = file
+      .duration_ms
+      .map_or(-1, |ms| i64::from(ms.div_ceil(1000)));
+    let title = file
+      .passage
+      .parse::<Passage>()
+      .map_or_else(|_| file.passage.clone(), |p| passage_title(&p));
+    playlist.push_str(&format!(
+      "#EXTINF:{seconds},{} - {title}\n{}\n",
+      manifest.name, file.path
+    ));
+  }
+  playlist
+}
+
+/// Writes the license file, which says whose recordings these are and what
+/// can be done with them.
+fn license_text(manifest: &BundleManifest, public_url: &str) -> String {
+  let mut text = format!(
+    "{}\nRecordings of the Bible in {}, from \
+     {public_url}/translation/{}\nBundled on {}.\n\nLicense: {}\n",
+    manifest.name,
+    manifest.mother_tongue,
+    manifest.translation.0,
+    manifest.generated_at.date(),
+    manifest.license.label(),
+  );
+  if let Some(url) = manifest.license.url() {
+    text.push_str(url);
+    text.push('\n');
+  }
+  text.push('\n');
+  text.push_str(manifest.license.summary());
+  text.push('\n');
+  text
+}
+
+/// Builds a bundle, uploads it to the object store and records how it went
+/// on the bundle's record. `public_url` is the site's origin, without a
+/// trailing slash.
+pub async fn build_bundle(
+  db: &db::DbConnection,
+  public_url: &str,
+  bundle: Bundle,
+) {
+  match write_bundle(db, public_url, &bundle).await {
+    Ok((size, recording_count)) => {
+      tracing::info!(
+        "built bundle {} of translation {} with {recording_count} recordings",
+        bundle.id.0,
+        bundle.translation.0
+      );
+      if let Err(e) = db.finish_bundle(bundle.id, size, recording_count).await {
+        tracing::error!("failed to mark bundle {} ready: {e:?}", bundle.id.0);
+      }
+    }
+    Err(e) => {
+      tracing::error!("failed to build bundle {}: {e:?}", bundle.id.0);
+      if let Err(e) = db.fail_bundle(bundle.id).await {
+        tracing::error!("failed to mark bundle {} failed: {e:?}", bundle.id.0);
+      }
+    }
+  }
+}
+
+/// Writes the bundle's ZIP and uploads it, returning its size and how many
+/// recordings are in it.
+///
+/// Whole translations make large bundles, so the ZIP is written to a
+/// temporary file rather than held in memory.
+async fn write_bundle(
+  db: &db::DbConnection,
+  public_url: &str,
+  bundle: &Bundle,
+) -> Result<(u64, u32)> {
+  let path =
+    std::env::temp_dir().join(format!("omthub-bundle-{}.zip", bundle.id.0));
+  let result = write_bundle_to(db, public_url, bundle, &path).await;
+  match std::fs::remove_file(&path) {
+    Err(e) if e.kind() != std::io::ErrorKind::NotFound => {
+      tracing::warn!("failed to remove {}: {e}", path.display());
+    }
+    _ => (),
+  }
+  result
+}
+
+/// An entry in a bundle's ZIP.
+struct ZipEntry {
+  path:     String,
+  contents: bytes::Bytes,
+  /// Whether to deflate it. Recordings are compressed already, so they're
+  /// stored as they are.
+  deflate:  bool,
+}
+
+/// Writes entries to a new ZIP at `path` as they arrive, until the sender is
+/// dropped, returning the finished file rewound to its start.
+///
+/// This does blocking I/O, so it runs on a blocking thread while recordings
+/// are fetched.
+fn write_zip(
+  path: &Path,
+  mut entries: mpsc::Receiver<ZipEntry>,
+) -> Result<File> {
+  let file = File::options()
+    .read(true)
+    .write(true)
+    .create(true)
+    .truncate(true)
+    .open(path)
+    .wrap_err("failed to create bundle file")?;
+  let mut zip = ZipWriter::new(BufWriter::new(file));
+  let stored =
+    SimpleFileOptions::default().compression_method(CompressionMethod::Stored);
+  let deflated = SimpleFileOptions::default()
+    .compression_method(CompressionMethod::Deflated);
+
+  while let Some(entry) = entries.blocking_recv() {
+    let options = match entry.deflate {
+      true => deflated,
+      false => stored,
+    };
+    zip
+      .start_file(entry.path.as_str(), options)
+      .wrap_err("failed to start zip entry")?;
+    zip
+      .write_all(&entry.contents)
+      .wrap_err_with(|| format!("failed to write {}", entry.path))?;
+  }
+
+  let mut file = zip
+    .finish()
+    .wrap_err("failed to finish zip")?
+    .into_inner()
+    .map_err(|e| e.into_error())
+    .wrap_err("failed to write bundle file")?;
+  file.rewind().wrap_err("failed to rewind bundle file")?;
+  Ok(file)
+}
+
+/// Hands an entry to the ZIP writer.
+async fn send_entry(
+  entries: &mpsc::Sender<ZipEntry>,
+  entry: ZipEntry,
+) -> Result<()> {
+  entries
+    .send(entry)
+    .await
+    .map_err(|_| eyre::eyre!("bundle writer stopped"))
+}
+
+async fn write_bundle_to(
+  db: &db::DbConnection,
+  public_url: &str,
+  bundle: &Bundle,
+  path: &Path,
+) -> Result<(u64, u32)> {
+  let (entries, received) = mpsc::channel(1);
+  let zip_path = path.to_path_buf();
+  let writer =
+    tokio::task::spawn_blocking(move || write_zip(&zip_path, received));
+
+  let sent = send_bundle_entries(db, public_url, bundle, &entries).await;
+  drop(entries);
+  // the writer stopping explains why sending failed, so its error comes
+  // first
+  let file = writer.await.wrap_err("bundle writer panicked")??;
+  let recording_count = sent?;
+
+  let size = file
+    .metadata()
+    .wrap_err("failed to read bundle file size")?
+    .len();
+  artifact::upload_file(&bundle.object_key, file)
+    .await
+    .wrap_err("failed to upload bundle")?;
+
+  Ok((size, recording_count))
+}
+
+/// Fetches the bundle's recordings and sends them to the ZIP writer, then
+/// the manifest, playlist and license, returning how many recordings there
+/// are.
+async fn send_bundle_entries(
+  db: &db::DbConnection,
+  public_url: &str,
+  bundle: &Bundle,
+  entries: &mpsc::Sender<ZipEntry>,
+) -> Result<u32> {
+  let translation = db
+    .select_translation(bundle.translation)
+    .await
+    .wrap_err("failed to select translation")?
+    .ok_or_eyre("translation doesn't exist")?;
+  let mother_tongue = db
+    .select_mother_tongue(translation.mother_tongue)
+    .await
+    .wrap_err("failed to select mother tongue")?
+    .ok_or_eyre("mother tongue doesn't exist")?;
+  let recordings = db
+    .select_approved_recordings(bundle.translation)
+    .await
+    .wrap_err("failed to select approved recordings")?;
+
+  // the same recordings listeners hear, in the same order
+  let playlist = Playlist::new(
+    String::new(),
+    recordings.iter().map(ApprovedRecording::track),
+  );
+  let recordings = playlist
+    .chapters
+    .iter()
+    .flat_map(|c| &c.tracks)
+    .filter_map(|track| recordings.iter().find(|r| r.id == track.id))
+    .collect::<Vec<_>>();
+
+  let mut files = Vec::with_capacity(recordings.len());
+  for recording in recordings {
+    let bytes = artifact::fetch_object_bytes(&recording.object_key, None)
+      .await
+      .wrap_err_with(|| {
+        format!("failed to fetch artifact {}", recording.id.0)
+      })?;
+    let extension = recording
+      .content_type
+      .as_deref()
+      .and_then(audio_extension)
+      .unwrap_or(UNKNOWN_EXTENSION);
+    let path = bundle_path(&recording.passage, extension);
+    let hashed = bytes.clone();
+    let sha256 = tokio::task::spawn_blocking(move || {
+      format!("{:x}", Sha256::digest(&hashed))
+    })
+    .await
+    .wrap_err("hashing panicked")?;
+
+    files.push(BundleFile {
+      path: path.clone(),
+      artifact: recording.id,
+      passage: recording.passage.to_string(),
+      content_type: recording.content_type.clone(),
+      size: bytes.len() as u64,
+      sha256,
+      duration_ms: recording.duration_ms,
+      verses: verses(&recording.passage),
+    });
+    send_entry(entries, ZipEntry {
+      path,
+      contents: bytes,
+      deflate: false,
+    })
+    .await?;
+  }
+
+  let manifest = BundleManifest {
+    version: BUNDLE_MANIFEST_VERSION,
+    translation: translation.id,
+    name: translation.name,
+    language: mother_tongue.iso_639_3.or(mother_tongue.parent_language),
+    mother_tongue: mother_tongue.name,
+    license: translation.license,
+    generated_at: time::OffsetDateTime::now_utc(),
+    files,
+  };
+
+  let manifest_json = serde_json::to_vec_pretty(&manifest)
+    .wrap_err("failed to write manifest")?;
+  send_entry(entries, ZipEntry {
+    path:     BUNDLE_MANIFEST_NAME.to_string(),
+    contents: manifest_json.into(),
+    deflate:  true,
+  })
+  .await?;
+  send_entry(entries, ZipEntry {
+    path:     PLAYLIST_NAME.to_string(),
+    contents: m3u(&manifest).into(),
+    deflate:  true,
+  })
+  .await?;
+  send_entry(entries, ZipEntry {
+    path:     LICENSE_NAME.to_string(),
+    contents: license_text(&manifest, public_url).into(),
+    deflate:  true,
+  })
+  .await?;
+
+  Ok(manifest.files.len() as u32)
+}
+
+#[cfg(test)]
+mod tests {
+  use core_types::{ArtifactRecordId, License, TranslationRecordId};
+
+  use super::*;
+
+  fn passage(s: &str) -> Passage { s.parse().unwrap() }
+
+  #[test]
+  fn recordings_are_filed_by_book_and_chapter() {
+    assert_eq!(
+      bundle_path(&passage("GEN 1"), "mp3"),
+      "01_GEN/001/GEN_001.mp3"
+    );
+    assert_eq!(
+      bundle_path(&passage("JHN 3:16-18"), "webm"),
+      "43_JHN/003/JHN_003_016-018.webm"
+    );
+    assert_eq!(
+      bundle_path(&passage("REV 22:21"), "ogg"),
+      "66_REV/022/REV_022_021-021.ogg"
+    );
+  }
+
+  #[test]
+  fn files_list_the_verses_they_cover() {
+    assert_eq!(verses(&passage("JHN 3:16-18")), vec![16, 17, 18]);
+    assert_eq!(verses(&passage("JUD 1")), (1..=25).collect::<Vec<_>>());
+  }
+
+  #[test]
+  fn playlists_list_files_in_order() {
+    let file = |path: &str, passage: &str, duration_ms| BundleFile {
+      path: path.to_string(),
+      artifact: ArtifactRecordId(core_types::Ulid::new()),
+      passage: passage.to_string(),
+      content_type: None,
+      size: 0,
+      sha256: String::new(),
+      duration_ms,
+      verses: Vec::new(),
+    };
+    let manifest = BundleManifest {
+      version:       BUNDLE_MANIFEST_VERSION,
+      translation:   TranslationRecordId(core_types::Ulid::new()),
+      name:          "Test".to_string(),
+      mother_tongue: "Testish".to_string(),
+      language:      None,
+      license:       License::CcBy4,
+      generated_at:  time::OffsetDateTime::UNIX_EPOCH,
+      files:         vec![
+        file("01_GEN/001/GEN_001.mp3", "GEN 1", Some(61_500)),
+        file("43_JHN/003/JHN_003_016-016.mp3", "JHN 3:16", None),
+      ],
+    };
+
+    assert_eq!(
+      m3u(&manifest),
+      "#EXTM3U\n#EXTINF:62,Test - Genesis \
+       1\n01_GEN/001/GEN_001.mp3\n#EXTINF:-1,Test - John \
+       3:16\n43_JHN/003/JHN_003_016-016.mp3\n"
+    );
+  }
+}
@@ -0,0 +1,840 @@
+//! Imports existing collections of recordings into a translation.
+//!
+//! An import is a ZIP or a directory of audio files. Each file is matched to
+//! the passage it records, either from a `manifest.json` that lists them,
+//! like the one in an offline bundle, or from its name: `JHN_003.mp3` for
+//! John 3, or `JHN_003_016-018.mp3` for John 3:16-18.
+//!
+//! Imports are dry runs unless they're applied, so the report of what
+//! matched, what conflicts and what wasn't recognized can be checked first.
+
+use std::{
+  collections::HashMap,
+  fmt,
+  io::{Read, Seek},
+  path::{Path, PathBuf},
+};
+
+use core_types::{
+  audio_content_type, audio_mime_type,
+  scripture::{Passage, PassageError},
+  ssr::CoreId,
+  Artifact, ArtifactRecordId, RecordingStatus, TranslationRecordId,
+  UserRecordId, BUNDLE_MANIFEST_NAME,
+};
+use eyre::{Context, OptionExt, Result};
+use serde::{Deserialize, Serialize, Serializer};
+use zip::ZipArchive;
+
+use crate::export::{LICENSE_NAME, PLAYLIST_NAME};
+
+/// The largest file an import reads, the same as the largest upload.
+pub const MAX_IMPORT_FILE_BYTES: u64 = 512 * 1024 * 1024;
+
+/// A file in an import is larger than [`MAX_IMPORT_FILE_BYTES`].
+#[derive(Debug)]
+pub struct FileTooLarge;
+
+impl fmt::Display for FileTooLarge {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "larger than {} MiB", MAX_IMPORT_FILE_BYTES / 1024 / 1024)
+  }
+}
+
+impl std::error::Error for FileTooLarge {}
+
+/// Reads all of `reader`, failing with [`FileTooLarge`] once it's read more
+/// than `limit` bytes.
+fn read_limited(reader: impl Read, limit: u64) -> Result<Vec<u8>> {
+  let mut bytes = Vec::new();
+  reader.take(limit + 1).read_to_end(&mut bytes)?;
+  if bytes.len() as u64 > limit {
+    return Err(FileTooLarge.into());
+  }
+  Ok(bytes)
+}
+
+/// Somewhere to import files from.
+pub trait ImportSource {
+  /// Lists every file by its path from the root, separated with `/`.
+  fn paths(&self) -> Vec<String>;
+  fn read(&mut self, path: &str) -> Result<Vec<u8>>;
+}
+
+/// Imports from a ZIP archive.
+pub struct ZipSource<R> {
+  archive: ZipArchive<R>,
+}
+
+impl<R: Read + Seek> ZipSource<R> {
+  pub fn new(reader: R) -> Result<Self> {
+    Ok(ZipSource {
+      archive: ZipArchive::new(reader).wrap_err("failed to read zip")?,
+    })
+  }
+}
+
+impl<R: Read + Seek> ImportSource for ZipSource<R> {
+  fn paths(&self) -> Vec<String> {
+    self
+      .archive
+      .file_names()
+      .filter(|name| !name.ends_with('/'))
+      .map(str::to_string)
+      .collect()
+  }
+
+  fn read(&mut self, path: &str) -> Result<Vec<u8>> {
+    let mut file = self
+      .archive
+      .by_name(path)
+      .wrap_err_with(|| format!("failed to find `{path}` in zip"))?;
+    // the size in the zip is only what it claims, so it isn't trusted
+    read_limited(&mut file, MAX_IMPORT_FILE_BYTES)
+      .wrap_err_with(|| format!("failed to read `{path}` from zip"))
+  }
+}
+
+/// Imports from a directory and everything under it.
+pub struct DirectorySource {
+  root:  PathBuf,
+  paths: Vec<String>,
+}
+
+impl DirectorySource {
+  pub fn new(root: impl Into<PathBuf>) -> Result<Self> {
+    let root = root.into();
+    let mut paths = Vec::new();
+    let mut pending = vec![root.clone()];
+    while let Some(dir) = pending.pop() {
+      let entries = std::fs::read_dir(&dir)
+        .wrap_err_with(|| format!("failed to read `{}`", dir.display()))?;
+      for entry in entries {
+        let path = entry.wrap_err("failed to read directory entry")?.path();
+        if path.is_dir() {
+          pending.push(path);
+        } else if let Ok(relative) = path.strip_prefix(&root) {
+          let components = relative
+            .components()
+            .map(|c| c.as_os_str().to_string_lossy())
+            .collect::<Vec<_>>();
+          paths.push(components.join("/"));
+        }
+      }
+    }
+    paths.sort();
+
+    Ok(DirectorySource { root, paths })
+  }
+}
+
+impl ImportSource for DirectorySource {
+  fn paths(&self) -> Vec<String> { self.paths.clone() }
+
+  fn read(&mut self, path: &str) -> Result<Vec<u8>> {
+    let full_path = self.root.join(path);
+    std::fs::File::open(&full_path)
+      .map_err(eyre::Report::from)
+      .and_then(|file| read_limited(file, MAX_IMPORT_FILE_BYTES))
+      .wrap_err_with(|| format!("failed to read `{}`", full_path.display()))
+  }
+}
+
+/// How an import is run.
+#[derive(Clone, Copy, Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct ImportOptions {
+  /// Create the recordings. Otherwise only report what would happen.
+  pub apply:            bool,
+  /// Import passages the translation already has, as new current takes.
+  /// Otherwise they're reported as conflicts and left alone.
+  pub replace_existing: bool,
+}
+
+/// A manifest listing the files in an import. Offline bundle manifests are
+/// read as these too.
+#[derive(Debug, Deserialize)]
+struct ImportManifest {
+  files: Vec<ManifestEntry>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ManifestEntry {
+  /// Where the file is, relative to the manifest.
+  path:         String,
+  /// The passage it records, like `JHN 3` or `JHN 3:16-18`.
+  passage:      String,
+  #[serde(default)]
+  content_type: Option<String>,
+  #[serde(default)]
+  duration_ms:  Option<u32>,
+}
+
+/// A manifest found in an import.
+struct FoundManifest {
+  /// The folder the manifest is in, with a trailing `/`, or empty at the
+  /// root.
+  root:     String,
+  manifest: ImportManifest,
+}
+
+fn serialize_passage<S: Serializer>(
+  passage: &Passage,
+  serializer: S,
+) -> Result<S::Ok, S::Error> {
+  serializer.collect_str(passage)
+}
+
+/// A file that will be, or was, imported as a recording.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct ImportMatch {
+  pub path:           String,
+  #[serde(serialize_with = "serialize_passage")]
+  pub passage:        Passage,
+  pub content_type:   String,
+  pub duration_ms:    Option<u32>,
+  /// How many takes of the passage the translation has already.
+  pub existing_takes: u32,
+}
+
+/// Why a recognized file won't be imported.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ConflictReason {
+  /// Other files in the import record the same passage.
+  Duplicate { others: Vec<String> },
+  /// The translation has recorded the passage already.
+  AlreadyRecorded { takes: u32 },
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct ImportConflict {
+  pub path:    String,
+  #[serde(serialize_with = "serialize_passage")]
+  pub passage: Passage,
+  pub reason:  ConflictReason,
+}
+
+/// A file that was left out, and why.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct SkippedFile {
+  pub path:   String,
+  pub reason: String,
+}
+
+/// What an import matched, and what it did if it was applied.
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+pub struct ImportReport {
+  pub matched:      Vec<ImportMatch>,
+  pub conflicts:    Vec<ImportConflict>,
+  pub unrecognized: Vec<SkippedFile>,
+  /// Whether the matched files were imported.
+  pub applied:      bool,
+  /// The recordings created, in the order they were matched.
+  pub imported:     Vec<ArtifactRecordId>,
+  /// Matched files that couldn't be imported. The reasons are logged.
+  pub failed:       Vec<SkippedFile>,
+}
+
+impl fmt::Display for ImportReport {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    writeln!(f, "Matched ({}):", self.matched.len())?;
+    for m in &self.matched {
+      write!(f, "  {} -> {}", m.path, m.passage)?;
+      match m.existing_takes {
+        0 => writeln!(f)?,
+        takes => writeln!(f, " (replacing the current of {takes} takes)")?,
+      }
+    }
+    writeln!(f, "Conflicts ({}):", self.conflicts.len())?;
+    for c in &self.conflicts {
+      match &c.reason {
+        ConflictReason::Duplicate { others } => writeln!(
+          f,
+          "  {} -> {}: also matched by {}",
+          c.path,
+          c.passage,
+          others.join(", ")
+        )?,
+        ConflictReason::AlreadyRecorded { takes } => writeln!(
+          f,
+          "  {} -> {}: already recorded ({takes} takes)",
+          c.path, c.passage
+        )?,
+      }
+    }
+    writeln!(f, "Unrecognized ({}):", self.unrecognized.len())?;
+    for s in &self.unrecognized {
+      writeln!(f, "  {}: {}", s.path, s.reason)?;
+    }
+    if !self.applied {
+      return writeln!(f, "This was a dry run, so nothing was imported.");
+    }
+    writeln!(
+      f,
+      "Imported {} recordings as drafts for review.",
+      self.imported.len()
+    )?;
+    if !self.failed.is_empty() {
+      writeln!(f, "Failed ({}):", self.failed.len())?;
+      for s in &self.failed {
+        writeln!(f, "  {}: {}", s.path, s.reason)?;
+      }
+    }
+    Ok(())
+  }
+}
+
+/// Reads the passage from a file name without its extension, like
+/// `JHN_003`, `JHN_003_016` or `JHN_003_016-018`. Book codes can be in any
+/// case.
+///
+/// `None` if the name doesn't follow the convention at all.
+pub fn parse_file_stem(stem: &str) -> Option<Result<Passage, PassageError>> {
+  let mut parts = stem.split('_');
+  let book = parts.next()?;
+  let chapter = parts.next()?.parse::<u16>().ok()?;
+  let verses = match parts.next() {
+    Some(verses) => {
+      let (start, end) = verses.split_once('-').unwrap_or((verses, verses));
+      Some((start.parse::<u16>().ok()?, end.parse::<u16>().ok()?))
+    }
+    None => None,
+  };
+  if parts.next().is_some() || !book.chars().all(|c| c.is_ascii_alphanumeric())
+  {
+    return None;
+  }
+
+  let reference = match verses {
+    Some((start, end)) => format!("{book} {chapter}:{start}-{end}"),
+    None => format!("{book} {chapter}"),
+  };
+  Some(reference.parse())
+}
+
+/// Whether a file is clutter from the OS that made the import, like
+/// `.DS_Store` or `__MACOSX/`.
+fn is_clutter(path: &str) -> bool {
+  path
+    .split('/')
+    .any(|part| part.starts_with('.') || part == "__MACOSX")
+}
+
+fn extension(path: &str) -> Option<&str> {
+  Path::new(path).extension().and_then(|e| e.to_str())
+}
+
+/// Finds the manifest nearest the root, if there is one. A manifest that
+/// can't be parsed is skipped, with the reason, and files are matched by
+/// their names instead.
+fn find_manifest(
+  source: &mut impl ImportSource,
+) -> Result<Option<Result<FoundManifest, SkippedFile>>> {
+  let Some(path) = source
+    .paths()
+    .into_iter()
+    .filter(|p| !is_clutter(p))
+    .filter(|p| p.rsplit('/').next() == Some(BUNDLE_MANIFEST_NAME))
+    .min_by_key(|p| p.matches('/').count())
+  else {
+    return Ok(None);
+  };
+
+  let manifest = match serde_json::from_slice(&source.read(&path)?) {
+    Ok(manifest) => manifest,
+    Err(e) => {
+      return Ok(Some(Err(SkippedFile {
+        path,
+        reason: format!("not a valid manifest: {e}"),
+      })))
+    }
+  };
+  let root = path
+    .strip_suffix(BUNDLE_MANIFEST_NAME)
+    .unwrap_or_default()
+    .to_string();
+  Ok(Some(Ok(FoundManifest { root, manifest })))
+}
+
+/// A file matched to a passage, before conflicts are checked.
+struct Candidate {
+  path:         String,
+  passage:      Passage,
+  content_type: String,
+  duration_ms:  Option<u32>,
+}
+
+/// Works out what an import would do with the files at `paths`, given how
+/// many takes of each passage the translation already has.
+fn plan(
+  paths: &[String],
+  manifest: Option<&FoundManifest>,
+  existing: &HashMap<Passage, u32>,
+  options: ImportOptions,
+) -> ImportReport {
+  let mut report = ImportReport::default();
+  let mut candidates = Vec::new();
+  let mut unrecognized = |path: &str, reason: String| {
+    report.unrecognized.push(SkippedFile {
+      path: path.to_string(),
+      reason,
+    })
+  };
+
+  match manifest {
+    Some(found) => {
+      let mut listed = Vec::new();
+      for entry in &found.manifest.files {
+        let path = format!("{}{}", found.root, entry.path);
+        listed.push(path.clone());
+        if !paths.contains(&path) {
+          unrecognized(&path, "listed in the manifest but missing".to_string());
+          continue;
+        }
+        let passage = match entry.passage.parse::<Passage>() {
+          Ok(passage) => passage,
+          Err(e) => {
+            unrecognized(&path, e.to_string());
+            continue;
+          }
+        };
+        // manifests can claim any type, so only audio ones are believed
+        let content_type = match entry.content_type.as_deref() {
+          Some(content_type) => audio_mime_type(content_type),
+          None => extension(&path).and_then(audio_content_type),
+        };
+        let Some(content_type) = content_type else {
+          unrecognized(&path, "not an audio file we know".to_string());
+          continue;
+        };
+        candidates.push(Candidate {
+          path,
+          passage,
+          content_type: content_type.to_string(),
+          duration_ms: entry.duration_ms,
+        });
+      }
+
+      let known = [BUNDLE_MANIFEST_NAME, PLAYLIST_NAME, LICENSE_NAME]
+        .map(|name| format!("{}{name}", found.root));
+      for path in paths {
+        if !is_clutter(path) && !listed.contains(path) && !known.contains(path)
+        {
+          unrecognized(path, "not listed in the manifest".to_string());
+        }
+      }
+    }
+    None => {
+      for path in paths.iter().filter(|p| !is_clutter(p)) {
+        let Some(content_type) = extension(path).and_then(audio_content_type)
+        else {
+          unrecognized(path, "not an audio file we know".to_string());
+          continue;
+        };
+        let stem = Path::new(path)
+          .file_stem()
+          .and_then(|s| s.to_str())
+          .unwrap_or_default();
+        match parse_file_stem(stem) {
+          Some(Ok(passage)) => candidates.push(Candidate {
+            path: path.clone(),
+            passage,
+            content_type: content_type.to_string(),
+            duration_ms: None,
+          }),
+          Some(Err(e)) => unrecognized(path, e.to_string()),
+          None => unrecognized(
+            path,
+            "the name isn't like `JHN_003` or `JHN_003_016-018`".to_string(),
+          ),
+        }
+      }
+    }
+  }
+
+  let mut by_passage = HashMap::<&Passage, Vec<&str>>::new();
+  for candidate in &candidates {
+    by_passage
+      .entry(&candidate.passage)
+      .or_default()
+      .push(&candidate.path);
+  }
+
+  for candidate in &candidates {
+    let same_passage = &by_passage[&candidate.passage];
+    let existing_takes = existing.get(&candidate.passage).copied().unwrap_or(0);
+    let reason = if same_passage.len() > 1 {
+      Some(ConflictReason::Duplicate {
+        others: same_passage
+          .iter()
+          .filter(|p| **p != candidate.path)
+          .map(|p| p.to_string())
+          .collect(),
+      })
+    } else if existing_takes > 0 && !options.replace_existing {
+      Some(ConflictReason::AlreadyRecorded {
+        takes: existing_takes,
+      })
+    } else {
+      None
+    };
+
+    match reason {
+      Some(reason) => report.conflicts.push(ImportConflict {
+        path: candidate.path.clone(),
+        passage: candidate.passage.clone(),
+        reason,
+      }),
+      None => report.matched.push(ImportMatch {
+        path: candidate.path.clone(),
+        passage: candidate.passage.clone(),
+        content_type: candidate.content_type.clone(),
+        duration_ms: candidate.duration_ms,
+        existing_takes,
+      }),
+    }
+  }
+
+  report.matched.sort_by(|a, b| {
+    (a.passage.canonical_key(), &a.path)
+      .cmp(&(b.passage.canonical_key(), &b.path))
+  });
+  report.conflicts.sort_by(|a, b| {
+    (a.passage.canonical_key(), &a.path)
+      .cmp(&(b.passage.canonical_key(), &b.path))
+  });
+  report.unrecognized.sort_by(|a, b| a.path.cmp(&b.path));
+  report
+}
+
+/// Runs `f` on a blocking thread, since sources read files and archives
+/// with blocking I/O, handing the source back with its result.
+async fn on_source<S, T>(
+  mut source: S,
+  f: impl FnOnce(&mut S) -> T + Send + 'static,
+) -> Result<(S, T)>
+where
+  S: ImportSource + Send + 'static,
+  T: Send + 'static,
+{
+  tokio::task::spawn_blocking(move || {
+    let result = f(&mut source);
+    (source, result)
+  })
+  .await
+  .wrap_err("import source panicked")
+}
+
+/// Works out what importing the files in `source` would do, given how many
+/// takes of each passage the translation already has.
+fn plan_import(
+  source: &mut impl ImportSource,
+  existing: &HashMap<Passage, u32>,
+  options: ImportOptions,
+) -> Result<ImportReport> {
+  let (manifest, invalid_manifest) = match find_manifest(source)? {
+    Some(Ok(manifest)) => (Some(manifest), None),
+    Some(Err(skipped)) => (None, Some(skipped)),
+    None => (None, None),
+  };
+  let mut report = plan(&source.paths(), manifest.as_ref(), existing, options);
+  if let Some(invalid) = invalid_manifest {
+    report.unrecognized.retain(|s| s.path != invalid.path);
+    report.unrecognized.push(invalid);
+    report.unrecognized.sort_by(|a, b| a.path.cmp(&b.path));
+  }
+  Ok(report)
+}
+
+/// Matches the files in `source` to passages of a translation and, if
+/// `options.apply` is set, creates a recording for each match as a draft
+/// take uploaded by `creator`.
+///
+/// A file that fails to import doesn't stop the rest; it's listed in the
+/// report's `failed` files.
+pub async fn import_recordings<S>(
+  db: &db::DbConnection,
+  translation: TranslationRecordId,
+  creator: UserRecordId,
+  source: S,
+  options: ImportOptions,
+) -> Result<ImportReport>
+where
+  S: ImportSource + Send + 'static,
+{
+  db.select_translation(translation)
+    .await
+    .wrap_err("failed to select translation")?
+    .ok_or_eyre("translation doesn't exist")?;
+  let existing = db
+    .select_current_takes(translation)
+    .await
+    .wrap_err("failed to select current takes")?
+    .into_iter()
+    .map(|t| (t.passage, t.take_count))
+    .collect::<HashMap<_, _>>();
+
+  let (mut source, report) = on_source(source, move |source| {
+    plan_import(source, &existing, options)
+  })
+  .await?;
+  let mut report = report?;
+  if !options.apply {
+    return Ok(report);
+  }
+
+  report.applied = true;
+  for matched in report.matched.clone() {
+    let path = matched.path.clone();
+    let (returned, bytes) =
+      on_source(source, move |source| source.read(&path)).await?;
+    source = returned;
+    let result = match bytes {
+      Ok(bytes) => import_file(db, translation, creator, bytes, &matched).await,
+      Err(e) => Err(e),
+    };
+    match result {
+      Ok(id) => report.imported.push(id),
+      Err(e) => {
+        tracing::error!("failed to import `{}`: {e:?}", matched.path);
+        let reason = match e.downcast_ref::<FileTooLarge>() {
+          Some(too_large) => format!("it's {too_large}"),
+          None => "couldn't be imported".to_string(),
+        };
+        report.failed.push(SkippedFile {
+          path: matched.path,
+          reason,
+        });
+      }
+    }
+  }
+  tracing::info!(
+    "user {} imported {} recordings into translation {}",
+    creator.0,
+    report.imported.len(),
+    translation.0
+  );
+
+  Ok(report)
+}
+
+async fn import_file(
+  db: &db::DbConnection,
+  translation: TranslationRecordId,
+  creator: UserRecordId,
+  bytes: Vec<u8>,
+  matched: &ImportMatch,
+) -> Result<ArtifactRecordId> {
+  let id = ArtifactRecordId::new();
+  let artifact = Artifact {
+    id,
+    object_key: format!("artifacts/{}", id.0),
+    content_type: Some(matched.content_type.clone()),
+    size: Some(bytes.len() as u64),
+    duration_ms: matched.duration_ms,
+    translation: Some(translation),
+    passage: Some(matched.passage.clone()),
+    status: RecordingStatus::Draft,
+    // numbered when it's inserted, after any earlier takes of the passage
+    take: 1,
+    is_current: true,
+  };
+  let object_key = artifact.object_key.clone();
+
+  artifact::upload_object(&object_key, bytes.into())
+    .await
+    .wrap_err("failed to upload artifact")?;
+  let created = db
+    .insert_take(artifact, creator)
+    .await
+    .wrap_err("failed to create artifact")
+    .and_then(|a| a.ok_or_eyre("artifact was not created"));
+  if let Err(e) = created {
+    // the blob is useless without its record
+    if let Err(e) = artifact::delete_object(&object_key).await {
+      tracing::error!("failed to delete orphaned upload {object_key}: {e:?}");
+    }
+    return Err(e);
+  }
+
+  Ok(id)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn passage(s: &str) -> Passage { s.parse().unwrap() }
+
+  fn paths(paths: &[&str]) -> Vec<String> {
+    paths.iter().map(|p| p.to_string()).collect()
+  }
+
+  #[test]
+  fn file_names_give_passages() {
+    assert_eq!(parse_file_stem("JHN_003"), Some(Ok(passage("JHN 3"))));
+    assert_eq!(parse_file_stem("jhn_3_16"), Some(Ok(passage("JHN 3:16"))));
+    assert_eq!(
+      parse_file_stem("JHN_003_016-018"),
+      Some(Ok(passage("JHN 3:16-18")))
+    );
+    assert!(matches!(
+      parse_file_stem("JHN_099"),
+      Some(Err(PassageError::NoSuchChapter { .. }))
+    ));
+    assert_eq!(parse_file_stem("chapter one"), None);
+    assert_eq!(parse_file_stem("JHN_003_take_2"), None);
+  }
+
+  #[test]
+  fn files_are_matched_by_name() {
+    let existing = HashMap::from([(passage("GEN 1"), 2)]);
+    let report = plan(
+      &paths(&[
+        "john/JHN_003.mp3",
+        "john/JHN_003 (copy).mp3",
+        "john/jhn_003.wav",
+        "GEN_001.mp3",
+        "MRK_001_001-008.ogg",
+        "notes.txt",
+        ".DS_Store",
+        "__MACOSX/._JHN_003.mp3",
+      ]),
+      None,
+      &existing,
+      ImportOptions::default(),
+    );
+
+    assert_eq!(report.matched, vec![ImportMatch {
+      path:           "MRK_001_001-008.ogg".to_string(),
+      passage:        passage("MRK 1:1-8"),
+      content_type:   "audio/ogg".to_string(),
+      duration_ms:    None,
+      existing_takes: 0,
+    }]);
+    assert_eq!(
+      report
+        .conflicts
+        .iter()
+        .map(|c| (c.path.as_str(), &c.reason))
+        .collect::<Vec<_>>(),
+      vec![
+        ("GEN_001.mp3", &ConflictReason::AlreadyRecorded { takes: 2 }),
+        ("john/JHN_003.mp3", &ConflictReason::Duplicate {
+          others: vec!["john/jhn_003.wav".to_string()],
+        }),
+        ("john/jhn_003.wav", &ConflictReason::Duplicate {
+          others: vec!["john/JHN_003.mp3".to_string()],
+        }),
+      ]
+    );
+    assert_eq!(
+      report
+        .unrecognized
+        .iter()
+        .map(|s| s.path.as_str())
+        .collect::<Vec<_>>(),
+      vec!["john/JHN_003 (copy).mp3", "notes.txt"]
+    );
+
+    let report =
+      plan(&paths(&["GEN_001.mp3"]), None, &existing, ImportOptions {
+        apply:            false,
+        replace_existing: true,
+      });
+    assert_eq!(report.matched.len(), 1);
+    assert_eq!(report.matched[0].existing_takes, 2);
+  }
+
+  #[test]
+  fn manifests_list_files() {
+    let manifest = FoundManifest {
+      root:     "bundle/".to_string(),
+      manifest: serde_json::from_str(
+        r#"{ "version": 1, "files": [
+          { "path": "43_JHN/003/JHN_003.webm", "passage": "JHN 3",
+            "content_type": "audio/webm", "duration_ms": 1000 },
+          { "path": "43_JHN/004/JHN_004.webm", "passage": "JHN 4" },
+          { "path": "notes.txt", "passage": "JHN 99" }
+        ] }"#,
+      )
+      .unwrap(),
+    };
+    let report = plan(
+      &paths(&[
+        "bundle/manifest.json",
+        "bundle/playlist.m3u",
+        "bundle/LICENSE.txt",
+        "bundle/43_JHN/003/JHN_003.webm",
+        "bundle/notes.txt",
+        "bundle/extra.mp3",
+      ]),
+      Some(&manifest),
+      &HashMap::new(),
+      ImportOptions::default(),
+    );
+
+    assert_eq!(report.matched, vec![ImportMatch {
+      path:           "bundle/43_JHN/003/JHN_003.webm".to_string(),
+      passage:        passage("JHN 3"),
+      content_type:   "audio/webm".to_string(),
+      duration_ms:    Some(1000),
+      existing_takes: 0,
+    }]);
+    assert_eq!(
+      report
+        .unrecognized
+        .iter()
+        .map(|s| s.path.as_str())
+        .collect::<Vec<_>>(),
+      vec![
+        "bundle/43_JHN/004/JHN_004.webm",
+        "bundle/extra.mp3",
+        "bundle/notes.txt"
+      ]
+    );
+  }
+
+  #[test]
+  fn manifests_only_list_audio() {
+    let manifest = FoundManifest {
+      root:     String::new(),
+      manifest: serde_json::from_str(
+        r#"{ "files": [
+          { "path": "JHN_003.html", "passage": "JHN 3",
+            "content_type": "text/html" },
+          { "path": "JHN_004.mp3", "passage": "JHN 4",
+            "content_type": "audio/mpeg; codecs=mp3" }
+        ] }"#,
+      )
+      .unwrap(),
+    };
+    let report = plan(
+      &paths(&["manifest.json", "JHN_003.html", "JHN_004.mp3"]),
+      Some(&manifest),
+      &HashMap::new(),
+      ImportOptions::default(),
+    );
+
+    assert_eq!(
+      report
+        .matched
+        .iter()
+        .map(|m| (m.path.as_str(), m.content_type.as_str()))
+        .collect::<Vec<_>>(),
+      vec![("JHN_004.mp3", "audio/mpeg")]
+    );
+    assert_eq!(report.unrecognized, vec![SkippedFile {
+      path:   "JHN_003.html".to_string(),
+      reason: "not an audio file we know".to_string(),
+    }]);
+  }
+
+  #[test]
+  fn reads_stop_at_the_limit() {
+    assert_eq!(read_limited(&[1, 2, 3][..], 3).unwrap(), vec![1, 2, 3]);
+    let too_large = read_limited(&[1, 2, 3, 4][..], 3).unwrap_err();
+    assert!(too_large.downcast_ref::<FileTooLarge>().is_some());
+  }
+}
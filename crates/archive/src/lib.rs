@@ -0,0 +1,5 @@
+//! Moving a translation's recordings in and out of the site in bulk, as ZIP
+//! archives.
+
+pub mod export;
+pub mod import;
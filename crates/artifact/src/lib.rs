@@ -1,7 +1,15 @@
-use std::sync::Arc;
+use std::{io::Read, ops::Range, sync::Arc};
 
 use eyre::{Context, Result};
-use object_store::{aws::AmazonS3Builder, ObjectStore};
+use futures::{stream::BoxStream, Stream, StreamExt};
+use object_store::{
+  aws::AmazonS3Builder, GetOptions, ObjectStore, WriteMultipart,
+};
+
+/// How much of a file to read at a time when uploading it.
+const UPLOAD_READ_SIZE: usize = 1024 * 1024;
+/// How many parts of an upload to send at once.
+const UPLOAD_CONCURRENCY: usize = 4;
 
 #[allow(dead_code)]
 pub struct FetchedArtifact {
@@ -50,3 +58,182 @@ pub async fn fetch_object(
     },
   }
 }
+
+/// Uploads a blob to the object store under `object_key`.
+pub async fn upload_object(object_key: &str, blob: bytes::Bytes) -> Result<()> {
+  let object_store = get_object_store()?;
+
+  let path = object_store::path::Path::from(object_key);
+  object_store
+    .put(&path, blob.into())
+    .await
+    .wrap_err("failed to upload blob")?;
+
+  Ok(())
+}
+
+/// Uploads a file to the object store under `object_key` in parts, reading
+/// it from its current position, so it needn't fit in memory.
+pub async fn upload_file(
+  object_key: &str,
+  mut file: std::fs::File,
+) -> Result<()> {
+  let object_store = get_object_store()?;
+
+  let path = object_store::path::Path::from(object_key);
+  let upload = object_store
+    .put_multipart(&path)
+    .await
+    .wrap_err("failed to start upload")?;
+  let mut writer = WriteMultipart::new(upload);
+
+  let mut buf = vec![0; UPLOAD_READ_SIZE];
+  loop {
+    let read = match file.read(&mut buf) {
+      Ok(0) => break,
+      Ok(read) => read,
+      Err(e) => {
+        writer.abort().await.wrap_err("failed to abort upload")?;
+        return Err(e).wrap_err("failed to read file to upload");
+      }
+    };
+    writer
+      .wait_for_capacity(UPLOAD_CONCURRENCY)
+      .await
+      .wrap_err("failed to upload part")?;
+    writer.write(&buf[..read]);
+  }
+  writer.finish().await.wrap_err("failed to finish upload")?;
+
+  Ok(())
+}
+
+/// How a streamed upload ended.
+pub enum StreamedUpload {
+  /// The whole stream was uploaded, with this many bytes.
+  Uploaded(u64),
+  /// The stream had no bytes, so nothing was uploaded.
+  Empty,
+  /// The stream ran past the size limit, so the upload was abandoned.
+  TooLarge,
+}
+
+/// Uploads a stream to the object store under `object_key` in parts as it
+/// arrives, so it needn't fit in memory. The upload is abandoned if the
+/// stream fails or runs past `max_size` bytes.
+pub async fn upload_stream<E>(
+  object_key: &str,
+  mut stream: impl Stream<Item = Result<bytes::Bytes, E>> + Unpin,
+  max_size: u64,
+) -> Result<StreamedUpload>
+where
+  E: std::error::Error + Send + Sync + 'static,
+{
+  let object_store = get_object_store()?;
+
+  let path = object_store::path::Path::from(object_key);
+  let upload = object_store
+    .put_multipart(&path)
+    .await
+    .wrap_err("failed to start upload")?;
+  let mut writer = WriteMultipart::new(upload);
+
+  let mut size = 0_u64;
+  while let Some(chunk) = stream.next().await {
+    let chunk = match chunk {
+      Ok(chunk) => chunk,
+      Err(e) => {
+        writer.abort().await.wrap_err("failed to abort upload")?;
+        return Err(e).wrap_err("failed to read stream to upload");
+      }
+    };
+    size += chunk.len() as u64;
+    if size > max_size {
+      writer.abort().await.wrap_err("failed to abort upload")?;
+      return Ok(StreamedUpload::TooLarge);
+    }
+    writer
+      .wait_for_capacity(UPLOAD_CONCURRENCY)
+      .await
+      .wrap_err("failed to upload part")?;
+    writer.put(chunk);
+  }
+  if size == 0 {
+    writer.abort().await.wrap_err("failed to abort upload")?;
+    return Ok(StreamedUpload::Empty);
+  }
+  writer.finish().await.wrap_err("failed to finish upload")?;
+
+  Ok(StreamedUpload::Uploaded(size))
+}
+
+/// Deletes an object from the object store. Deleting one that doesn't exist
+/// isn't an error.
+pub async fn delete_object(object_key: &str) -> Result<()> {
+  let object_store = get_object_store()?;
+
+  let path = object_store::path::Path::from(object_key);
+  match object_store.delete(&path).await {
+    Ok(()) | Err(object_store::Error::NotFound { .. }) => Ok(()),
+    Err(e) => Err(e).wrap_err("failed to delete blob"),
+  }
+}
+
+/// Gets the size of an object in bytes, or `None` if it doesn't exist.
+pub async fn object_size(object_key: &str) -> Result<Option<usize>> {
+  let object_store = get_object_store()?;
+
+  let path = object_store::path::Path::from(object_key);
+  match object_store.head(&path).await {
+    Ok(meta) => Ok(Some(meta.size)),
+    Err(object_store::Error::NotFound { .. }) => Ok(None),
+    Err(e) => Err(e.into()),
+  }
+}
+
+/// Fetches part of an object, or all of it if `range` is `None`.
+pub async fn fetch_object_bytes(
+  object_key: &str,
+  range: Option<Range<usize>>,
+) -> Result<bytes::Bytes> {
+  let object_store = get_object_store()?;
+
+  let path = object_store::path::Path::from(object_key);
+  match range {
+    Some(range) => object_store
+      .get_range(&path, range)
+      .await
+      .wrap_err("failed to fetch blob range"),
+    None => object_store
+      .get(&path)
+      .await
+      .wrap_err("failed to fetch blob")?
+      .bytes()
+      .await
+      .wrap_err("failed to fetch all bytes of blob"),
+  }
+}
+
+/// A stream of an object's bytes.
+pub type ObjectStream = BoxStream<'static, object_store::Result<bytes::Bytes>>;
+
+/// Streams an object, or part of it if `range` is given, along with the size
+/// of the whole object, so large objects needn't be held in memory. `None` if
+/// it doesn't exist.
+pub async fn stream_object(
+  object_key: &str,
+  range: Option<Range<usize>>,
+) -> Result<Option<(usize, ObjectStream)>> {
+  let object_store = get_object_store()?;
+
+  let path = object_store::path::Path::from(object_key);
+  let options = GetOptions {
+    range: range.map(Into::into),
+    ..Default::default()
+  };
+  match object_store.get_opts(&path, options).await {
+    Ok(result) => Ok(Some((result.meta.size, result.into_stream()))),
+    Err(object_store::Error::NotFound { .. }) => Ok(None),
+    Err(e) => Err(e).wrap_err("failed to fetch blob"),
+  }
+}
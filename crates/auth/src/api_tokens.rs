@@ -0,0 +1,166 @@
+//! Personal API tokens for scripted access.
+
+use argon2::password_hash::rand_core::{OsRng, RngCore};
+use core_types::{
+  ssr::CoreId, validation::validate_api_token, ApiToken, ApiTokenRecordId,
+  ApiTokenScope, ApiTokenSummary, NewApiToken, Secret, User, UserRecordId,
+  API_TOKEN_PREFIX,
+};
+use eyre::OptionExt;
+use sha2::{Digest, Sha256};
+use tracing::instrument;
+
+use crate::{AuthError, Backend};
+
+/// The secret half of a token is this many alphanumeric characters.
+const SECRET_LENGTH: usize = 40;
+const SECRET_ALPHABET: &[u8] =
+  b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789";
+
+pub(crate) fn generate_secret() -> String {
+  (0..SECRET_LENGTH)
+    .map(|_| {
+      SECRET_ALPHABET[OsRng.next_u32() as usize % SECRET_ALPHABET.len()] as char
+    })
+    .collect()
+}
+
+/// Tokens are random and long, so a fast hash is enough here.
+pub(crate) fn hash_secret(secret: &str) -> String {
+  format!("{:x}", Sha256::digest(secret.as_bytes()))
+}
+
+/// Splits `omt_<id>_<secret>` into its id and secret.
+fn parse_token(token: &str) -> Option<(ApiTokenRecordId, &str)> {
+  let (id, secret) = token.strip_prefix(API_TOKEN_PREFIX)?.split_once('_')?;
+  Some((ApiTokenRecordId(id.parse().ok()?), secret))
+}
+
+impl Backend {
+  /// Creates an API token for a user.
+  ///
+  /// The returned [`NewApiToken`] is the only place the plaintext token is
+  /// available.
+  #[instrument(skip(self))]
+  pub async fn create_api_token(
+    &self,
+    user_id: UserRecordId,
+    name: String,
+    scopes: Vec<ApiTokenScope>,
+    lifetime_days: u32,
+  ) -> Result<NewApiToken, AuthError> {
+    validate_api_token(&name, &scopes, lifetime_days)
+      .map_err(AuthError::Validation)?;
+
+    let now = time::OffsetDateTime::now_utc();
+    let secret = generate_secret();
+    let api_token = ApiToken {
+      id: ApiTokenRecordId::new(),
+      user: user_id,
+      name: name.trim().to_string(),
+      secret_hash: hash_secret(&secret),
+      scopes,
+      created_at: now,
+      expires_at: now + time::Duration::days(lifetime_days.into()),
+      last_used_at: None,
+    };
+
+    let api_token = self
+      .db
+      .insert_api_token(api_token)
+      .await
+      .map_err(AuthError::from)?
+      .ok_or_eyre("failed to create api token")?;
+
+    Ok(NewApiToken {
+      token:   format!("{API_TOKEN_PREFIX}{}_{secret}", api_token.id.0).into(),
+      summary: api_token.into(),
+    })
+  }
+
+  /// Lists a user's API tokens, including expired ones.
+  #[instrument(skip(self))]
+  pub async fn list_api_tokens(
+    &self,
+    user_id: UserRecordId,
+  ) -> Result<Vec<ApiTokenSummary>, AuthError> {
+    Ok(
+      self
+        .db
+        .select_api_tokens_for_user(user_id)
+        .await
+        .map_err(AuthError::from)?
+        .into_iter()
+        .map(ApiTokenSummary::from)
+        .collect(),
+    )
+  }
+
+  /// Deletes one of a user's API tokens.
+  #[instrument(skip(self))]
+  pub async fn revoke_api_token(
+    &self,
+    user_id: UserRecordId,
+    id: ApiTokenRecordId,
+  ) -> Result<(), AuthError> {
+    self
+      .db
+      .select_api_token(id)
+      .await
+      .map_err(AuthError::from)?
+      .filter(|t| t.user == user_id)
+      .ok_or(AuthError::ApiTokenNotFound)?;
+
+    self
+      .db
+      .delete_api_token(id)
+      .await
+      .map_err(AuthError::from)?;
+    Ok(())
+  }
+
+  /// Looks up the user for a bearer token.
+  ///
+  /// Returns `None` if the token is malformed, unknown, expired, or belongs
+  /// to an inactive user.
+  #[instrument(skip(self))]
+  pub async fn authenticate_api_token(
+    &self,
+    token: &Secret,
+  ) -> Result<Option<(User, ApiToken)>, AuthError> {
+    let Some((id, secret)) = parse_token(token.expose()) else {
+      return Ok(None);
+    };
+    let Some(mut api_token) = self
+      .db
+      .select_api_token(id)
+      .await
+      .map_err(AuthError::from)?
+    else {
+      return Ok(None);
+    };
+    if api_token.secret_hash != hash_secret(secret) || api_token.is_expired() {
+      return Ok(None);
+    }
+
+    let user = self
+      .db
+      .select_user(api_token.user)
+      .await
+      .map_err(AuthError::from)?
+      .filter(|u| u.is_active);
+    let Some(user) = user else {
+      return Ok(None);
+    };
+
+    api_token.last_used_at = Some(time::OffsetDateTime::now_utc());
+    let api_token = self
+      .db
+      .update_api_token(api_token)
+      .await
+      .map_err(AuthError::from)?
+      .ok_or_eyre("failed to update api token")?;
+
+    Ok(Some((user, api_token)))
+  }
+}
@@ -0,0 +1,18 @@
+//! Secrets for the links that invite people onto a translation's team.
+
+use core_types::Secret;
+
+use crate::api_tokens::{generate_secret, hash_secret};
+
+/// Makes a secret for an invitation link, returning it along with the hash
+/// to store. The secret itself isn't stored.
+pub fn new_invitation_secret() -> (Secret, String) {
+  let secret = generate_secret();
+  let hash = hash_secret(&secret);
+  (secret.into(), hash)
+}
+
+/// Whether a secret from an invitation link matches the stored hash.
+pub fn invitation_secret_matches(secret: &str, hash: &str) -> bool {
+  hash_secret(secret) == hash
+}
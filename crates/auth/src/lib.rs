@@ -1,63 +1,77 @@
 //! This crate implements [`axum_login`] for picturepro types, using a SurrealDB
 //! backend.
 
+mod api_tokens;
+mod invitations;
+mod oidc;
+mod passkey;
+mod password;
+mod sessions;
+mod totp;
+
 use axum_login::{
   AuthManagerLayer, AuthManagerLayerBuilder, AuthnBackend, UserId,
 };
-use core_types::ssr::CoreId;
-use eyre::{eyre, Context, OptionExt, Result};
+use core_types::{ssr::CoreId, Pii, Secret};
+use eyre::{Context, OptionExt, Result};
 use serde::{Deserialize, Serialize};
 use tower_sessions::ExpiredDeletion;
 use tracing::instrument;
+pub use webauthn_rs::prelude::{
+  CreationChallengeResponse, PasskeyAuthentication, PasskeyRegistration,
+  PublicKeyCredential, RegisterPublicKeyCredential, RequestChallengeResponse,
+};
+
+pub use self::{
+  invitations::{invitation_secret_matches, new_invitation_secret},
+  oidc::{OidcClaims, OidcResolution, PendingOidcLogin, OIDC_CALLBACK_PATH},
+  sessions::{current_device_session, AuthSessionStore, SessionActivity},
+};
 
 /// The credentials type for the authentication layer.
-///
-/// This type will be transformed into an enum when we implement additional
-/// authentication methods.
 #[derive(Clone, Debug, Serialize, Deserialize)]
-pub struct Credentials {
-  /// The email address of the user.
-  pub email:    String,
-  /// The password of the user.
-  pub password: String,
-  /// The remember-me flag.
-  pub remember: bool,
+pub enum Credentials {
+  /// An email and password.
+  Password {
+    /// The email address of the user.
+    email:    Pii,
+    /// The password of the user.
+    password: Secret,
+    /// The remember-me flag.
+    remember: bool,
+  },
+  /// A passkey assertion, answering a challenge from
+  /// [`Backend::start_passkey_authentication`].
+  Passkey {
+    /// The user the challenge was issued for.
+    user_id:  core_types::UserRecordId,
+    /// The browser's response to the challenge.
+    response: Box<PublicKeyCredential>,
+    /// The server-side state from starting the challenge.
+    state:    Box<PasskeyAuthentication>,
+    /// The remember-me flag.
+    remember: bool,
+  },
 }
 
-/// Takes in a password and produces a PHC string ($argon2id$v=19$...)
-fn hash_password(password: &str) -> Result<String> {
-  use argon2::{
-    password_hash::{rand_core::OsRng, PasswordHasher, SaltString},
-    Argon2,
-  };
-
-  let salt = SaltString::generate(&mut OsRng);
-  let argon2 = Argon2::default();
-
-  let password_hash = argon2
-    .hash_password(password.as_bytes(), &salt)
-    .map_err(|e| eyre::eyre!(e))
-    .wrap_err("failed to hash password")?
-    .to_string();
-
-  Ok(password_hash)
+impl Credentials {
+  /// Whether the user asked to stay logged in.
+  pub fn remember(&self) -> bool {
+    match self {
+      Credentials::Password { remember, .. } => *remember,
+      Credentials::Passkey { remember, .. } => *remember,
+    }
+  }
 }
 
-fn verify_password(pw_hash: &str, password: &str) -> Result<bool> {
-  use argon2::{
-    password_hash::{PasswordHash, PasswordVerifier},
-    Argon2,
-  };
-
-  let parsed_hash = PasswordHash::new(pw_hash)
-    .map_err(|e| eyre::eyre!(e))
-    .wrap_err("failed to parse password hash")?;
-
-  Ok(
-    Argon2::default()
-      .verify_password(password.as_bytes(), &parsed_hash)
-      .is_ok(),
-  )
+/// The origin used when `PUBLIC_URL` isn't set, matching `cargo leptos`.
+const DEFAULT_PUBLIC_URL: &str = "http://localhost:3000";
+
+/// The origin that users load the site from, from the `PUBLIC_URL` env var.
+pub fn public_url() -> Result<url::Url> {
+  let public_url = std::env::var("PUBLIC_URL")
+    .unwrap_or_else(|_| DEFAULT_PUBLIC_URL.to_string());
+  url::Url::parse(&public_url).wrap_err("failed to parse `PUBLIC_URL`")
 }
 
 /// The backend type for the authentication layer.
@@ -66,63 +80,246 @@ fn verify_password(pw_hash: &str, password: &str) -> Result<bool> {
 /// and has a [`signup`](Backend::signup) method for creating new users.
 #[derive(Clone, Debug)]
 pub struct Backend {
-  db: db::DbConnection,
+  db:        db::DbConnection,
+  passkeys:  passkey::PasskeyVerifier,
+  passwords: password::PasswordHashing,
+  oidc:      Option<oidc::OidcProvider>,
+  sessions:  AuthSessionStore,
 }
 
 impl Backend {
   /// Create a new backend instance.
-  pub async fn new() -> eyre::Result<Self> {
+  ///
+  /// The session store must be the one used by the session layer, so that
+  /// sessions can be revoked.
+  pub async fn new(sessions: AuthSessionStore) -> eyre::Result<Self> {
     Ok(Self {
       db: db::DbConnection::new().await?,
+      passkeys: passkey::PasskeyVerifier::from_env()?,
+      passwords: password::PasswordHashing::from_env()?,
+      oidc: oidc::OidcProvider::from_env().await?,
+      sessions,
     })
   }
 
   /// Create a new user.
   ///
-  /// This method has checks to ensure that a user with the given email does
-  /// not already exist.
-  #[instrument(skip(password))]
+  /// The inputs are validated with [`core_types::validation`], and this
+  /// method has checks to ensure that a user with the given email does not
+  /// already exist, returning [`AuthError::EmailTaken`] if one does.
+  #[instrument(skip(self))]
   pub async fn signup(
     &self,
     name: String,
-    email: String,
-    password: String,
-  ) -> Result<core_types::User> {
+    email: Pii,
+    password: Secret,
+  ) -> Result<core_types::User, AuthError> {
+    core_types::validation::validate_signup(
+      &name,
+      email.expose(),
+      password.expose(),
+    )
+    .map_err(AuthError::Validation)?;
+
     let existing_users = self
       .db
-      .select_all_users_matching_email(&email)
+      .select_all_users_matching_email(email.expose())
       .await
-      .map_err(|e| eyre!("surrealdb error: {e}"))?;
+      .map_err(AuthError::from)?;
 
     if !existing_users.is_empty() {
-      return Err(eyre!("User with email {} already exists", email));
+      return Err(AuthError::EmailTaken);
     }
 
     let user_to_create: core_types::User = core_types::User {
       id: core_types::UserRecordId::new(),
       name,
       email,
-      pw_hash: hash_password(&password)?,
+      pw_hash: self.passwords.hash(password.expose())?.into(),
       is_active: true,
       registered_at: time::OffsetDateTime::now_utc(),
+      avatar_url: None,
+      roles: Vec::new(),
+      totp: None,
     };
 
     let user: Option<core_types::User> = self
       .db
       .insert_user(user_to_create)
       .await
-      .map_err(|e| eyre!("surrealdb error: {e}"))?;
+      .map_err(AuthError::from)?;
 
-    user.ok_or_eyre("Failed to create user")
+    Ok(user.ok_or_eyre("Failed to create user")?)
+  }
+}
+
+impl Backend {
+  async fn authenticate_password(
+    &self,
+    email: &Pii,
+    password: &Secret,
+  ) -> Result<Option<core_types::User>, AuthError> {
+    let users = self
+      .db
+      .select_all_users_matching_email(email.expose())
+      .await
+      .map_err(AuthError::from)?;
+    if users.len() > 1 {
+      tracing::warn!("found {} users for email {email:?}", users.len());
+    }
+
+    let users = users
+      .into_iter()
+      .filter(|u| {
+        self
+          .passwords
+          .verify(u.pw_hash.expose(), password.expose())
+          .is_ok_and(|v| v)
+      })
+      .collect::<Vec<_>>();
+
+    let mut user = match users.len() {
+      0 => return Ok(None),
+      1 => users.into_iter().next().unwrap(),
+      _ => {
+        return Err(AuthError::Duplicates(
+          users.into_iter().map(|u| u.id).collect(),
+        ))
+      }
+    };
+
+    // upgrade hashes made with old costs while we have the plaintext. this
+    // changes the session auth hash, so the user's other sessions are logged
+    // out.
+    if self.passwords.needs_rehash(user.pw_hash.expose()) {
+      user.pw_hash = self.passwords.hash(password.expose())?.into();
+      user = self
+        .db
+        .update_user(user)
+        .await
+        .map_err(AuthError::from)?
+        .ok_or_eyre("failed to update user")?;
+      tracing::info!("rehashed password for user {}", user.id.0);
+    }
+
+    Ok(Some(user))
   }
 }
 
 #[derive(thiserror::Error, Debug)]
 pub enum AuthError {
   #[error("failed: {0}")]
-  Surreal(db::SurrealError),
+  Surreal(Box<db::SurrealError>),
   #[error("duplicate users exist: {0:?}")]
   Duplicates(Vec<core_types::UserRecordId>),
+  #[error("a user with that email already exists")]
+  EmailTaken,
+  #[error("invalid input: {0:?}")]
+  Validation(core_types::validation::ValidationErrors),
+  #[error("the two-factor code is invalid")]
+  InvalidCode,
+  #[error("two-factor authentication is already enabled")]
+  TotpAlreadyEnabled,
+  #[error("two-factor authentication is not set up")]
+  TotpNotEnrolled,
+  #[error("too many wrong two-factor codes have been entered")]
+  TooManyAttempts,
+  #[error("no passkeys are registered for that user")]
+  NoPasskeys,
+  #[error("the passkey does not exist")]
+  PasskeyNotFound,
+  #[error("the api token does not exist")]
+  ApiTokenNotFound,
+  #[error("OIDC is not configured")]
+  OidcDisabled,
+  #[error("the OIDC login was rejected: {0}")]
+  OidcRejected(String),
+  #[error("the OIDC identity is linked to another user")]
+  OidcIdentityTaken,
+  #[error("the OIDC identity does not exist")]
+  OidcIdentityNotFound,
+  #[error("the OIDC identity is the user's only way to log in")]
+  OidcIdentityRequired,
+  #[error("the session does not exist")]
+  SessionNotFound,
+  #[error("webauthn failed: {0}")]
+  Webauthn(webauthn_rs::prelude::WebauthnError),
+  #[error("the passkey assertion was rejected")]
+  PasskeyRejected,
+  #[error(transparent)]
+  Other(#[from] eyre::Report),
+}
+
+impl From<db::SurrealError> for AuthError {
+  fn from(error: db::SurrealError) -> Self {
+    AuthError::Surreal(Box::new(error))
+  }
+}
+
+impl From<AuthError> for core_types::AppError {
+  fn from(error: AuthError) -> Self {
+    match error {
+      AuthError::EmailTaken => core_types::AppError::Conflict(
+        "An account with that email already exists.".to_string(),
+      ),
+      AuthError::Validation(errors) => core_types::AppError::Validation(errors),
+      AuthError::InvalidCode => {
+        let mut errors = core_types::validation::ValidationErrors::new();
+        errors.check(
+          core_types::validation::CODE_FIELD,
+          Some("That code isn't valid. Please try again.".to_string()),
+        );
+        core_types::AppError::Validation(errors)
+      }
+      AuthError::TotpAlreadyEnabled => core_types::AppError::Conflict(
+        "Two-factor authentication is already enabled.".to_string(),
+      ),
+      AuthError::TotpNotEnrolled => core_types::AppError::Conflict(
+        "Two-factor authentication isn't set up.".to_string(),
+      ),
+      AuthError::TooManyAttempts => core_types::AppError::RateLimited,
+      AuthError::OidcIdentityTaken => core_types::AppError::Conflict(
+        "That account is already linked to another user.".to_string(),
+      ),
+      AuthError::OidcIdentityRequired => core_types::AppError::Conflict(
+        "You signed up with this account, so it can't be unlinked.".to_string(),
+      ),
+      AuthError::OidcRejected(_) => core_types::AppError::Unauthorized,
+      // the same as a wrong password, so it doesn't reveal which emails
+      // have accounts or passkeys
+      AuthError::NoPasskeys => core_types::AppError::Unauthorized,
+      AuthError::PasskeyNotFound
+      | AuthError::SessionNotFound
+      | AuthError::ApiTokenNotFound
+      | AuthError::OidcDisabled
+      | AuthError::OidcIdentityNotFound => core_types::AppError::NotFound,
+      AuthError::Webauthn(_) | AuthError::PasskeyRejected => {
+        let mut errors = core_types::validation::ValidationErrors::new();
+        errors.check(
+          core_types::validation::PASSKEY_FIELD,
+          Some(
+            "The passkey couldn't be verified. Please try again.".to_string(),
+          ),
+        );
+        core_types::AppError::Validation(errors)
+      }
+      AuthError::Surreal(_)
+      | AuthError::Duplicates(_)
+      | AuthError::Other(_) => core_types::AppError::Internal,
+    }
+  }
+}
+
+/// Drops users whose accounts have been deactivated. They keep their
+/// credentials, but can't log in with any of them.
+fn active_user(user: Option<core_types::User>) -> Option<core_types::User> {
+  match user {
+    Some(user) if !user.is_active => {
+      tracing::info!("refused login for inactive user {}", user.id.0);
+      None
+    }
+    user => user,
+  }
 }
 
 #[async_trait::async_trait]
@@ -136,33 +333,22 @@ impl AuthnBackend for Backend {
     &self,
     credentials: Self::Credentials,
   ) -> Result<Option<Self::User>, Self::Error> {
-    let users = self
-      .db
-      .select_all_users_matching_email(&credentials.email)
-      .await
-      .map_err(AuthError::Surreal)?;
-    if users.len() > 1 {
-      tracing::warn!(
-        "found {} users for email {:?}",
-        users.len(),
-        &credentials.email
-      );
-    }
-
-    let users = users
-      .into_iter()
-      .filter(|u| {
-        verify_password(&u.pw_hash, &credentials.password).is_ok_and(|v| v)
-      })
-      .collect::<Vec<_>>();
-
-    match users.len() {
-      0 => Ok(None),
-      1 => Ok(Some(users.first().unwrap().clone())),
-      _ => Err(AuthError::Duplicates(
-        users.into_iter().map(|u| u.id).collect(),
-      )),
-    }
+    let user = match credentials {
+      Credentials::Password {
+        email, password, ..
+      } => self.authenticate_password(&email, &password).await?,
+      Credentials::Passkey {
+        user_id,
+        response,
+        state,
+        ..
+      } => {
+        self
+          .authenticate_passkey(user_id, &response, &state)
+          .await?
+      }
+    };
+    Ok(active_user(user))
   }
 
   #[instrument(skip(self))]
@@ -175,7 +361,7 @@ impl AuthnBackend for Backend {
         .db
         .select_user(core_types::UserRecordId(*user_id))
         .await
-        .map_err(AuthError::Surreal)?,
+        .map_err(AuthError::from)?,
     )
   }
 }
@@ -189,15 +375,8 @@ impl AuthnBackend for Backend {
 pub type AuthSession = axum_login::AuthSession<Backend>;
 
 /// Builds an authentication layer for use with an Axum router.
-pub async fn build_auth_layer() -> Result<
-  AuthManagerLayer<
-    Backend,
-    tower_sessions::CachingSessionStore<
-      tower_sessions::MemoryStore,
-      tower_sessions_surrealdb_store::SurrealSessionStore<db::WsClient>,
-    >,
-  >,
-> {
+pub async fn build_auth_layer(
+) -> Result<AuthManagerLayer<Backend, AuthSessionStore>> {
   let surreal_client = db::DbConnection::new().await?.into_inner().await?;
 
   tracing::debug!("connected to db with sqlx");
@@ -221,13 +400,39 @@ pub async fn build_auth_layer() -> Result<
   //   .await
   //   .wrap_err("failed to perform db migration for auth backend")?;
 
+  let backend = Backend::new(session_store.clone()).await?;
   let session_manager_layer =
     tower_sessions::SessionManagerLayer::new(session_store).with_expiry(
       tower_sessions::Expiry::OnInactivity(time::Duration::days(30)),
     );
 
-  Ok(
-    AuthManagerLayerBuilder::new(Backend::new().await?, session_manager_layer)
-      .build(),
-  )
+  Ok(AuthManagerLayerBuilder::new(backend, session_manager_layer).build())
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn user(is_active: bool) -> core_types::User {
+    core_types::User {
+      id: core_types::UserRecordId::new(),
+      name: "Test".to_string(),
+      email: "test@example.com".to_string().into(),
+      pw_hash: String::new().into(),
+      is_active,
+      registered_at: time::OffsetDateTime::UNIX_EPOCH,
+      avatar_url: None,
+      roles: Vec::new(),
+      totp: None,
+    }
+  }
+
+  #[test]
+  fn inactive_users_cant_log_in() {
+    // passkey and password logins both end here, after the credential
+    // itself has been verified
+    assert!(active_user(Some(user(true))).is_some());
+    assert!(active_user(Some(user(false))).is_none());
+    assert!(active_user(None).is_none());
+  }
 }
@@ -0,0 +1,269 @@
+//! WebAuthn passkey registration and authentication.
+
+use std::{fmt, sync::Arc};
+
+use core_types::{
+  ssr::CoreId, Passkey, PasskeyRecordId, PasskeySummary, Pii, User,
+  UserRecordId,
+};
+use eyre::{Context, OptionExt, Result};
+use tracing::instrument;
+use webauthn_rs::prelude::{
+  CreationChallengeResponse, Passkey as WebauthnPasskey, PasskeyAuthentication,
+  PasskeyRegistration, PublicKeyCredential, RegisterPublicKeyCredential,
+  RequestChallengeResponse, Uuid, Webauthn, WebauthnBuilder,
+};
+
+use crate::{public_url, AuthError, Backend};
+
+/// Holds the relying party configuration for WebAuthn.
+#[derive(Clone)]
+pub(crate) struct PasskeyVerifier(Arc<Webauthn>);
+
+impl fmt::Debug for PasskeyVerifier {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.debug_struct("PasskeyVerifier").finish_non_exhaustive()
+  }
+}
+
+impl PasskeyVerifier {
+  /// Builds the verifier for the origin in [`public_url`].
+  pub(crate) fn from_env() -> Result<Self> {
+    let origin = public_url()?;
+    let rp_id = origin
+      .host_str()
+      .ok_or_eyre("`PUBLIC_URL` must have a host")?
+      .to_string();
+
+    let webauthn = WebauthnBuilder::new(&rp_id, &origin)
+      .wrap_err("failed to configure webauthn")?
+      .rp_name("OMTHub")
+      .build()
+      .wrap_err("failed to build webauthn")?;
+
+    Ok(Self(Arc::new(webauthn)))
+  }
+}
+
+fn decode_credential(passkey: &Passkey) -> Result<WebauthnPasskey, AuthError> {
+  Ok(
+    serde_json::from_value(passkey.credential.clone())
+      .wrap_err("failed to decode stored passkey")?,
+  )
+}
+
+/// WebAuthn wants a UUID for the user handle; ULIDs are the same size.
+fn user_handle(user_id: UserRecordId) -> Uuid { Uuid::from_u128(user_id.0 .0) }
+
+impl Backend {
+  /// Lists the passkeys registered to a user.
+  #[instrument(skip(self))]
+  pub async fn list_passkeys(
+    &self,
+    user_id: UserRecordId,
+  ) -> Result<Vec<PasskeySummary>, AuthError> {
+    Ok(
+      self
+        .db
+        .select_passkeys_for_user(user_id)
+        .await
+        .map_err(AuthError::from)?
+        .into_iter()
+        .map(PasskeySummary::from)
+        .collect(),
+    )
+  }
+
+  /// Removes one of a user's passkeys.
+  #[instrument(skip(self))]
+  pub async fn delete_passkey(
+    &self,
+    user_id: UserRecordId,
+    passkey_id: PasskeyRecordId,
+  ) -> Result<(), AuthError> {
+    let passkeys = self
+      .db
+      .select_passkeys_for_user(user_id)
+      .await
+      .map_err(AuthError::from)?;
+    if !passkeys.iter().any(|p| p.id == passkey_id) {
+      return Err(AuthError::PasskeyNotFound);
+    }
+
+    self
+      .db
+      .delete_passkey(passkey_id)
+      .await
+      .map_err(AuthError::from)?;
+    Ok(())
+  }
+
+  /// Starts registering a new passkey for a user.
+  ///
+  /// The returned challenge goes to the browser, and the registration state
+  /// must be kept server-side until
+  /// [`finish_passkey_registration`](Backend::finish_passkey_registration).
+  #[instrument(skip(self))]
+  pub async fn start_passkey_registration(
+    &self,
+    user: &User,
+  ) -> Result<(CreationChallengeResponse, PasskeyRegistration), AuthError> {
+    let existing = self
+      .db
+      .select_passkeys_for_user(user.id)
+      .await
+      .map_err(AuthError::from)?
+      .iter()
+      .map(|p| decode_credential(p).map(|c| c.cred_id().clone()))
+      .collect::<Result<Vec<_>, _>>()?;
+
+    self
+      .passkeys
+      .0
+      .start_passkey_registration(
+        user_handle(user.id),
+        user.email.expose(),
+        &user.name,
+        Some(existing),
+      )
+      .map_err(AuthError::Webauthn)
+  }
+
+  /// Verifies the browser's response to a registration challenge and stores
+  /// the new passkey.
+  #[instrument(skip(self, response, state))]
+  pub async fn finish_passkey_registration(
+    &self,
+    user_id: UserRecordId,
+    name: String,
+    response: &RegisterPublicKeyCredential,
+    state: &PasskeyRegistration,
+  ) -> Result<PasskeySummary, AuthError> {
+    let credential = self
+      .passkeys
+      .0
+      .finish_passkey_registration(response, state)
+      .map_err(AuthError::Webauthn)?;
+
+    let passkey = Passkey {
+      id: PasskeyRecordId::new(),
+      user: user_id,
+      name,
+      credential: serde_json::to_value(&credential)
+        .wrap_err("failed to encode passkey")?,
+      created_at: time::OffsetDateTime::now_utc(),
+      last_used_at: None,
+    };
+
+    let passkey = self
+      .db
+      .insert_passkey(passkey)
+      .await
+      .map_err(AuthError::from)?
+      .ok_or_eyre("failed to create passkey")?;
+
+    Ok(passkey.into())
+  }
+
+  /// Starts a passkey login for the user with the given email.
+  ///
+  /// Returns the user's id alongside the challenge, which must be kept
+  /// server-side with the authentication state and passed back in
+  /// [`Credentials::Passkey`](crate::Credentials::Passkey).
+  #[instrument(skip(self))]
+  pub async fn start_passkey_authentication(
+    &self,
+    email: &Pii,
+  ) -> Result<
+    (
+      RequestChallengeResponse,
+      PasskeyAuthentication,
+      UserRecordId,
+    ),
+    AuthError,
+  > {
+    let users = self
+      .db
+      .select_all_users_matching_email(email.expose())
+      .await
+      .map_err(AuthError::from)?;
+    let user = match users.as_slice() {
+      [] => return Err(AuthError::NoPasskeys),
+      [user] => user,
+      _ => {
+        return Err(AuthError::Duplicates(users.iter().map(|u| u.id).collect()))
+      }
+    };
+
+    let credentials = self
+      .db
+      .select_passkeys_for_user(user.id)
+      .await
+      .map_err(AuthError::from)?
+      .iter()
+      .map(decode_credential)
+      .collect::<Result<Vec<_>, _>>()?;
+    if credentials.is_empty() {
+      return Err(AuthError::NoPasskeys);
+    }
+
+    let (challenge, state) = self
+      .passkeys
+      .0
+      .start_passkey_authentication(&credentials)
+      .map_err(AuthError::Webauthn)?;
+
+    Ok((challenge, state, user.id))
+  }
+
+  /// Verifies a passkey assertion, returning the user if it's valid.
+  pub(crate) async fn authenticate_passkey(
+    &self,
+    user_id: UserRecordId,
+    response: &PublicKeyCredential,
+    state: &PasskeyAuthentication,
+  ) -> Result<Option<User>, AuthError> {
+    let result = match self
+      .passkeys
+      .0
+      .finish_passkey_authentication(response, state)
+    {
+      Ok(result) => result,
+      Err(e) => {
+        tracing::info!("rejected passkey assertion: {e}");
+        return Ok(None);
+      }
+    };
+
+    let passkeys = self
+      .db
+      .select_passkeys_for_user(user_id)
+      .await
+      .map_err(AuthError::from)?;
+    for mut passkey in passkeys {
+      let mut credential = decode_credential(&passkey)?;
+      if credential.cred_id() != result.cred_id() {
+        continue;
+      }
+
+      // keep the signature counter current to detect cloned authenticators
+      credential.update_credential(&result);
+      passkey.credential = serde_json::to_value(&credential)
+        .wrap_err("failed to encode passkey")?;
+      passkey.last_used_at = Some(time::OffsetDateTime::now_utc());
+      self
+        .db
+        .update_passkey(passkey)
+        .await
+        .map_err(AuthError::from)?;
+
+      return self.db.select_user(user_id).await.map_err(AuthError::from);
+    }
+
+    tracing::warn!(
+      "passkey assertion for user {} matched no stored passkey",
+      user_id.to_thing()
+    );
+    Ok(None)
+  }
+}
@@ -0,0 +1,155 @@
+//! Password hashing with configurable argon2 costs.
+
+use argon2::{
+  password_hash::{
+    rand_core::OsRng, PasswordHash, PasswordHasher, PasswordVerifier,
+    SaltString,
+  },
+  Algorithm, Argon2, Params, Version,
+};
+use eyre::{Context, Result};
+
+/// Hashes passwords with the argon2id costs from the environment.
+///
+/// The costs are read from `ARGON2_MEMORY_KIB`, `ARGON2_ITERATIONS` and
+/// `ARGON2_PARALLELISM`, each falling back to the OWASP-recommended
+/// defaults (19 MiB, 2 iterations, 1 lane).
+#[derive(Clone, Debug)]
+pub(crate) struct PasswordHashing {
+  params: Params,
+}
+
+fn env_cost(name: &str, default: u32) -> Result<u32> {
+  match std::env::var(name) {
+    Ok(value) => value
+      .parse()
+      .wrap_err_with(|| format!("failed to parse `{name}` as a number")),
+    Err(_) => Ok(default),
+  }
+}
+
+impl PasswordHashing {
+  pub(crate) fn from_env() -> Result<Self> {
+    let params = Params::new(
+      env_cost("ARGON2_MEMORY_KIB", Params::DEFAULT_M_COST)?,
+      env_cost("ARGON2_ITERATIONS", Params::DEFAULT_T_COST)?,
+      env_cost("ARGON2_PARALLELISM", Params::DEFAULT_P_COST)?,
+      None,
+    )
+    .map_err(|e| eyre::eyre!(e))
+    .wrap_err("invalid argon2 parameters")?;
+
+    Ok(Self { params })
+  }
+
+  fn argon2(&self) -> Argon2<'static> {
+    Argon2::new(Algorithm::Argon2id, Version::V0x13, self.params.clone())
+  }
+
+  /// Takes in a password and produces a PHC string ($argon2id$v=19$...)
+  pub(crate) fn hash(&self, password: &str) -> Result<String> {
+    let salt = SaltString::generate(&mut OsRng);
+
+    let password_hash = self
+      .argon2()
+      .hash_password(password.as_bytes(), &salt)
+      .map_err(|e| eyre::eyre!(e))
+      .wrap_err("failed to hash password")?
+      .to_string();
+
+    Ok(password_hash)
+  }
+
+  /// Whether a hash was made with a different algorithm or weaker costs
+  /// than are currently configured, and should be replaced. Hashes with
+  /// stronger costs are kept, so lowering a cost doesn't weaken them.
+  pub(crate) fn needs_rehash(&self, pw_hash: &str) -> bool {
+    let Ok(parsed_hash) = PasswordHash::new(pw_hash) else {
+      return false;
+    };
+    if parsed_hash.algorithm != Algorithm::Argon2id.ident()
+      || parsed_hash.version != Some(Version::V0x13.into())
+    {
+      return true;
+    }
+
+    match Params::try_from(&parsed_hash) {
+      Ok(params) => {
+        params.m_cost() < self.params.m_cost()
+          || params.t_cost() < self.params.t_cost()
+          || params.p_cost() < self.params.p_cost()
+      }
+      Err(_) => true,
+    }
+  }
+
+  /// Checks a password against a PHC string, using the costs in the string.
+  pub(crate) fn verify(&self, pw_hash: &str, password: &str) -> Result<bool> {
+    let parsed_hash = PasswordHash::new(pw_hash)
+      .map_err(|e| eyre::eyre!(e))
+      .wrap_err("failed to parse password hash")?;
+
+    Ok(
+      self
+        .argon2()
+        .verify_password(password.as_bytes(), &parsed_hash)
+        .is_ok(),
+    )
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn hashing(m_cost: u32, t_cost: u32, p_cost: u32) -> PasswordHashing {
+    PasswordHashing {
+      params: Params::new(m_cost, t_cost, p_cost, None).unwrap(),
+    }
+  }
+
+  #[test]
+  fn weaker_hashes_need_rehash() {
+    let current = hashing(2048, 2, 2);
+    for weaker in [
+      hashing(1024, 2, 2),
+      hashing(2048, 1, 2),
+      hashing(2048, 2, 1),
+    ] {
+      let pw_hash = weaker.hash("password").unwrap();
+      assert!(current.needs_rehash(&pw_hash));
+    }
+  }
+
+  #[test]
+  fn equal_hashes_dont_need_rehash() {
+    let current = hashing(2048, 2, 2);
+    let pw_hash = current.hash("password").unwrap();
+    assert!(!current.needs_rehash(&pw_hash));
+  }
+
+  #[test]
+  fn stronger_hashes_dont_need_rehash() {
+    let current = hashing(2048, 2, 2);
+    for stronger in [
+      hashing(4096, 2, 2),
+      hashing(2048, 3, 2),
+      hashing(2048, 2, 3),
+    ] {
+      let pw_hash = stronger.hash("password").unwrap();
+      assert!(!current.needs_rehash(&pw_hash));
+      assert!(current.verify(&pw_hash, "password").unwrap());
+    }
+  }
+
+  #[test]
+  fn other_algorithms_need_rehash() {
+    let current = hashing(2048, 2, 2);
+    let pw_hash =
+      Argon2::new(Algorithm::Argon2i, Version::V0x13, current.params.clone())
+        .hash_password(b"password", &SaltString::generate(&mut OsRng))
+        .unwrap()
+        .to_string();
+    assert!(current.needs_rehash(&pw_hash));
+  }
+}
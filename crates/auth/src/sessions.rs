@@ -0,0 +1,258 @@
+//! Per-device session metadata, for listing and revoking sessions.
+
+use std::str::FromStr;
+
+use core_types::{
+  ssr::CoreId, ActiveSession, DeviceSession, DeviceSessionRecordId,
+  UserRecordId,
+};
+use eyre::OptionExt;
+use serde::{Deserialize, Serialize};
+use tower_sessions::{session::Id, Session, SessionStore};
+use tracing::instrument;
+
+use crate::{AuthError, Backend};
+
+/// The session store used by the auth layer.
+pub type AuthSessionStore = tower_sessions::CachingSessionStore<
+  tower_sessions::MemoryStore,
+  tower_sessions_surrealdb_store::SurrealSessionStore<db::WsClient>,
+>;
+
+/// The session key holding a [`TrackedSession`].
+const TRACKED_SESSION_KEY: &str = "tracked_session";
+/// How often the last-seen time is refreshed for an active session.
+const ACTIVITY_INTERVAL: time::Duration = time::Duration::minutes(5);
+
+/// Remembers when a session's metadata was last written, so that it isn't
+/// written on every request.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct TrackedSession {
+  device_session_id: DeviceSessionRecordId,
+  session_id:        String,
+  recorded_at:       i64,
+}
+
+/// Gets the metadata id for the session making the request, if it's been
+/// recorded.
+pub async fn current_device_session(
+  session: &Session,
+) -> Option<DeviceSessionRecordId> {
+  session
+    .get::<TrackedSession>(TRACKED_SESSION_KEY)
+    .await
+    .ok()
+    .flatten()
+    .map(|t| t.device_session_id)
+}
+
+/// Where a request came from, as recorded against its session.
+#[derive(Clone, Debug, Default)]
+pub struct SessionActivity {
+  pub user_agent: Option<String>,
+  pub ip:         Option<String>,
+}
+
+impl Backend {
+  /// Records a request from a logged in session.
+  ///
+  /// This is throttled to [`ACTIVITY_INTERVAL`], except when the session id
+  /// has changed, which happens on login.
+  pub async fn track_session(
+    &self,
+    user_id: UserRecordId,
+    session: &Session,
+    activity: SessionActivity,
+  ) -> Result<(), AuthError> {
+    // new sessions don't have an id until they're saved
+    let Some(session_id) = session.id() else {
+      return Ok(());
+    };
+    let tracked = session
+      .get::<TrackedSession>(TRACKED_SESSION_KEY)
+      .await
+      .map_err(|e| AuthError::Other(e.into()))?;
+
+    let now = time::OffsetDateTime::now_utc().unix_timestamp();
+    if let Some(tracked) = &tracked {
+      if tracked.session_id == session_id.to_string()
+        && now - tracked.recorded_at < ACTIVITY_INTERVAL.whole_seconds()
+      {
+        return Ok(());
+      }
+    }
+
+    let device_session_id = self
+      .record_session_activity(
+        user_id,
+        tracked.map(|t| t.device_session_id),
+        session_id,
+        activity,
+      )
+      .await?;
+    session
+      .insert(TRACKED_SESSION_KEY, TrackedSession {
+        device_session_id,
+        session_id: session_id.to_string(),
+        recorded_at: now,
+      })
+      .await
+      .map_err(|e| AuthError::Other(e.into()))?;
+    Ok(())
+  }
+
+  /// Records that a session was used, creating its metadata if needed.
+  #[instrument(skip(self, activity))]
+  async fn record_session_activity(
+    &self,
+    user_id: UserRecordId,
+    device_session_id: Option<DeviceSessionRecordId>,
+    session_id: Id,
+    activity: SessionActivity,
+  ) -> Result<DeviceSessionRecordId, AuthError> {
+    let now = time::OffsetDateTime::now_utc();
+
+    let existing = match device_session_id {
+      Some(id) => self
+        .db
+        .select_device_session(id)
+        .await
+        .map_err(AuthError::from)?
+        // don't let a stale id attach one user's metadata to another
+        .filter(|s| s.user == user_id),
+      None => None,
+    };
+
+    let device_session = match existing {
+      Some(existing) => DeviceSession {
+        session_id: session_id.to_string(),
+        user_agent: activity.user_agent.or(existing.user_agent),
+        ip: activity.ip.or(existing.ip),
+        last_seen_at: now,
+        ..existing
+      },
+      None => DeviceSession {
+        id:           DeviceSessionRecordId::new(),
+        user:         user_id,
+        session_id:   session_id.to_string(),
+        user_agent:   activity.user_agent,
+        ip:           activity.ip,
+        created_at:   now,
+        last_seen_at: now,
+      },
+    };
+
+    let device_session = self
+      .db
+      .upsert_device_session(device_session)
+      .await
+      .map_err(AuthError::from)?
+      .ok_or_eyre("failed to store device session")?;
+    Ok(device_session.id)
+  }
+
+  /// Lists a user's live sessions, most recently used first.
+  ///
+  /// Metadata for sessions that have expired or logged out is cleaned up
+  /// along the way.
+  #[instrument(skip(self))]
+  pub async fn list_sessions(
+    &self,
+    user_id: UserRecordId,
+    current: Option<DeviceSessionRecordId>,
+  ) -> Result<Vec<ActiveSession>, AuthError> {
+    let device_sessions = self
+      .db
+      .select_device_sessions_for_user(user_id)
+      .await
+      .map_err(AuthError::from)?;
+
+    let mut active = Vec::with_capacity(device_sessions.len());
+    for device_session in device_sessions {
+      if !self.session_is_live(&device_session).await? {
+        self
+          .db
+          .delete_device_session(device_session.id)
+          .await
+          .map_err(AuthError::from)?;
+        continue;
+      }
+      active.push(
+        device_session.to_active_session(Some(device_session.id) == current),
+      );
+    }
+
+    Ok(active)
+  }
+
+  /// Ends one of a user's sessions, logging that device out.
+  #[instrument(skip(self))]
+  pub async fn revoke_session(
+    &self,
+    user_id: UserRecordId,
+    id: DeviceSessionRecordId,
+  ) -> Result<(), AuthError> {
+    let device_session = self
+      .db
+      .select_device_session(id)
+      .await
+      .map_err(AuthError::from)?
+      .filter(|s| s.user == user_id)
+      .ok_or(AuthError::SessionNotFound)?;
+
+    self.end_session(&device_session).await
+  }
+
+  /// Ends all of a user's sessions except `current`.
+  #[instrument(skip(self))]
+  pub async fn revoke_other_sessions(
+    &self,
+    user_id: UserRecordId,
+    current: DeviceSessionRecordId,
+  ) -> Result<(), AuthError> {
+    let device_sessions = self
+      .db
+      .select_device_sessions_for_user(user_id)
+      .await
+      .map_err(AuthError::from)?;
+
+    for device_session in device_sessions.iter().filter(|s| s.id != current) {
+      self.end_session(device_session).await?;
+    }
+    Ok(())
+  }
+
+  async fn session_is_live(
+    &self,
+    device_session: &DeviceSession,
+  ) -> Result<bool, AuthError> {
+    let Ok(session_id) = Id::from_str(&device_session.session_id) else {
+      return Ok(false);
+    };
+    let record = self
+      .sessions
+      .load(&session_id)
+      .await
+      .map_err(|e| AuthError::Other(eyre::eyre!("{e}")))?;
+    Ok(record.is_some())
+  }
+
+  async fn end_session(
+    &self,
+    device_session: &DeviceSession,
+  ) -> Result<(), AuthError> {
+    if let Ok(session_id) = Id::from_str(&device_session.session_id) {
+      self
+        .sessions
+        .delete(&session_id)
+        .await
+        .map_err(|e| AuthError::Other(eyre::eyre!("{e}")))?;
+    }
+    self
+      .db
+      .delete_device_session(device_session.id)
+      .await
+      .map_err(AuthError::from)?;
+    Ok(())
+  }
+}
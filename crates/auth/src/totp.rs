@@ -0,0 +1,397 @@
+//! TOTP two-factor authentication.
+
+use argon2::password_hash::rand_core::{OsRng, RngCore};
+use core_types::{TotpConfig, TotpEnrollment, User, UserRecordId};
+use eyre::{eyre, OptionExt};
+use time::OffsetDateTime;
+use totp_rs::{Algorithm, Secret, TOTP};
+use tracing::instrument;
+
+use crate::{AuthError, Backend};
+
+/// The issuer shown in authenticator apps.
+const TOTP_ISSUER: &str = "OMTHub";
+/// How many recovery codes are generated on enrollment.
+const RECOVERY_CODE_COUNT: usize = 10;
+/// Recovery codes avoid characters that are easily confused with each other.
+const RECOVERY_CODE_ALPHABET: &[u8] = b"abcdefghjkmnpqrstuvwxyz23456789";
+/// How many wrong codes in a row lock a user out of the second factor.
+const MAX_SECOND_FACTOR_FAILURES: u32 = 5;
+/// How long a lockout lasts, from the last wrong code. Wrong codes further
+/// apart than this aren't counted together.
+const SECOND_FACTOR_LOCKOUT: time::Duration = time::Duration::minutes(15);
+
+fn build_totp(secret: Vec<u8>, account_name: &str) -> Result<TOTP, AuthError> {
+  TOTP::new(
+    Algorithm::SHA1,
+    6,
+    // clock drift is allowed for in `accepted_step`, which also refuses
+    // steps that have been used
+    0,
+    30,
+    secret,
+    Some(TOTP_ISSUER.to_string()),
+    // the account name can't contain colons, and only labels the entry
+    account_name.replace(':', ""),
+  )
+  .map_err(|e| AuthError::Other(eyre!("failed to build TOTP: {e}")))
+}
+
+fn totp_from_config(
+  config: &TotpConfig,
+  account_name: &str,
+) -> Result<TOTP, AuthError> {
+  let secret = Secret::Encoded(config.secret.expose().to_string())
+    .to_bytes()
+    .map_err(|e| {
+      AuthError::Other(eyre!("failed to decode TOTP secret: {e}"))
+    })?;
+  build_totp(secret, account_name)
+}
+
+/// Generates a recovery code like `k7mqp-x3d9a`.
+fn generate_recovery_code() -> String {
+  (0..10)
+    .map(|i| {
+      let c = RECOVERY_CODE_ALPHABET
+        [OsRng.next_u32() as usize % RECOVERY_CODE_ALPHABET.len()]
+        as char;
+      match i {
+        5 => format!("-{c}"),
+        _ => c.to_string(),
+      }
+    })
+    .collect()
+}
+
+/// Normalizes user-entered codes, which may contain spaces or capitals.
+fn normalize_code(code: &str) -> String {
+  code
+    .chars()
+    .filter(|c| !c.is_whitespace())
+    .collect::<String>()
+    .to_lowercase()
+}
+
+/// Finds the time step `code` is for, allowing a step of clock drift either
+/// way. Steps at or before `last_used_step` are refused, so each code only
+/// works once.
+fn accepted_step(
+  totp: &TOTP,
+  code: &str,
+  now: u64,
+  last_used_step: Option<u64>,
+) -> Option<u64> {
+  let current = now / totp.step;
+  (current.saturating_sub(1)..=current + 1)
+    .filter(|step| last_used_step.is_none_or(|last| *step > last))
+    .find(|step| totp.check(code, step * totp.step))
+}
+
+/// Whether too many wrong codes have been entered recently to try again.
+fn locked_out(config: &TotpConfig, now: OffsetDateTime) -> bool {
+  config.failed_attempts >= MAX_SECOND_FACTOR_FAILURES
+    && config
+      .last_failed_at
+      .is_some_and(|at| now - at < SECOND_FACTOR_LOCKOUT)
+}
+
+/// The last wrong code, if it's too old to count with one entered `now`.
+fn expired_failure(
+  config: &TotpConfig,
+  now: OffsetDateTime,
+) -> Option<OffsetDateTime> {
+  config
+    .last_failed_at
+    .filter(|at| now - *at >= SECOND_FACTOR_LOCKOUT)
+}
+
+fn unix_now() -> u64 {
+  time::OffsetDateTime::now_utc().unix_timestamp().max(0) as u64
+}
+
+impl Backend {
+  async fn fetch_user(&self, user_id: UserRecordId) -> Result<User, AuthError> {
+    let user = self
+      .db
+      .select_user(user_id)
+      .await
+      .map_err(AuthError::from)?;
+    Ok(user.ok_or_eyre("user does not exist")?)
+  }
+
+  async fn store_user(&self, user: User) -> Result<User, AuthError> {
+    let user = self.db.update_user(user).await.map_err(AuthError::from)?;
+    Ok(user.ok_or_eyre("failed to update user")?)
+  }
+
+  /// Starts TOTP enrollment by generating and storing a new secret.
+  ///
+  /// The secret isn't used at login until it's confirmed with
+  /// [`confirm_totp_enrollment`](Backend::confirm_totp_enrollment).
+  #[instrument(skip(self))]
+  pub async fn begin_totp_enrollment(
+    &self,
+    user_id: UserRecordId,
+  ) -> Result<TotpEnrollment, AuthError> {
+    let mut user = self.fetch_user(user_id).await?;
+    if user.totp_enabled() {
+      return Err(AuthError::TotpAlreadyEnabled);
+    }
+
+    let secret = Secret::generate_secret()
+      .to_bytes()
+      .map_err(|e| AuthError::Other(eyre!("failed to generate secret: {e}")))?;
+    let totp = build_totp(secret, user.email.expose())?;
+
+    let enrollment = TotpEnrollment {
+      otpauth_uri:   totp.get_url().into(),
+      qr_code_png:   totp.get_qr_base64().map_err(|e| {
+        AuthError::Other(eyre!("failed to render TOTP QR code: {e}"))
+      })?,
+      secret_base32: totp.get_secret_base32().into(),
+    };
+
+    user.totp = Some(TotpConfig {
+      secret:               totp.get_secret_base32().into(),
+      enabled:              false,
+      recovery_code_hashes: Vec::new(),
+      last_used_step:       None,
+      failed_attempts:      0,
+      last_failed_at:       None,
+    });
+    self.store_user(user).await?;
+
+    Ok(enrollment)
+  }
+
+  /// Confirms TOTP enrollment with a code from the user's authenticator.
+  ///
+  /// Returns the plaintext recovery codes, which are only stored hashed and
+  /// so can only be shown to the user now.
+  #[instrument(skip(self, code))]
+  pub async fn confirm_totp_enrollment(
+    &self,
+    user_id: UserRecordId,
+    code: &str,
+  ) -> Result<Vec<String>, AuthError> {
+    let mut user = self.fetch_user(user_id).await?;
+    let Some(config) = user.totp.as_mut() else {
+      return Err(AuthError::TotpNotEnrolled);
+    };
+    if config.enabled {
+      return Err(AuthError::TotpAlreadyEnabled);
+    }
+
+    let totp = totp_from_config(config, user.email.expose())?;
+    let Some(step) =
+      accepted_step(&totp, &normalize_code(code), unix_now(), None)
+    else {
+      return Err(AuthError::InvalidCode);
+    };
+    config.last_used_step = Some(step);
+
+    let recovery_codes = (0..RECOVERY_CODE_COUNT)
+      .map(|_| generate_recovery_code())
+      .collect::<Vec<_>>();
+    config.recovery_code_hashes = recovery_codes
+      .iter()
+      .map(|c| self.passwords.hash(c))
+      .collect::<eyre::Result<_>>()?;
+    config.enabled = true;
+    self.store_user(user).await?;
+
+    Ok(recovery_codes)
+  }
+
+  /// Turns off TOTP for a user, after checking a current code or a recovery
+  /// code.
+  #[instrument(skip(self, code))]
+  pub async fn disable_totp(
+    &self,
+    user_id: UserRecordId,
+    code: &str,
+  ) -> Result<(), AuthError> {
+    let user = self.fetch_user(user_id).await?;
+    let Some(mut user) = self.check_second_factor(&user, code).await? else {
+      return Err(AuthError::InvalidCode);
+    };
+
+    user.totp = None;
+    self.store_user(user).await?;
+    Ok(())
+  }
+
+  /// Verifies the second login step for a user who has already passed the
+  /// password check.
+  ///
+  /// Accepts either a current TOTP code or an unused recovery code, which is
+  /// consumed. Returns `None` if the code is wrong, and
+  /// [`AuthError::TooManyAttempts`] once too many have been.
+  #[instrument(skip(self, code))]
+  pub async fn verify_second_factor(
+    &self,
+    user_id: UserRecordId,
+    code: &str,
+  ) -> Result<Option<User>, AuthError> {
+    let user = self.fetch_user(user_id).await?;
+    let recovery_codes_before = user
+      .totp
+      .as_ref()
+      .map(|t| t.recovery_code_hashes.len())
+      .unwrap_or_default();
+
+    let Some(user) = self.check_second_factor(&user, code).await? else {
+      return Ok(None);
+    };
+
+    let remaining_recovery_codes = user
+      .totp
+      .as_ref()
+      .map(|t| t.recovery_code_hashes.len())
+      .unwrap_or_default();
+    if remaining_recovery_codes != recovery_codes_before {
+      tracing::info!(
+        "user {} logged in with a recovery code, {} remaining",
+        user.id.0,
+        remaining_recovery_codes
+      );
+    }
+
+    Ok(Some(user))
+  }
+
+  /// Checks a TOTP or recovery code and records the attempt: a matched
+  /// recovery code is used up, a matched TOTP step can't be used again, and
+  /// wrong codes count towards a lockout. Each is a guarded update, so a
+  /// code used by a concurrent attempt counts as wrong. Returns the updated
+  /// user if the code was accepted.
+  async fn check_second_factor(
+    &self,
+    user: &User,
+    code: &str,
+  ) -> Result<Option<User>, AuthError> {
+    let Some(config) = user.totp.as_ref().filter(|t| t.enabled) else {
+      return Err(AuthError::TotpNotEnrolled);
+    };
+    let now = OffsetDateTime::now_utc();
+    if locked_out(config, now) {
+      return Err(AuthError::TooManyAttempts);
+    }
+    let code = normalize_code(code);
+
+    let totp = totp_from_config(config, user.email.expose())?;
+    let step = accepted_step(
+      &totp,
+      &code,
+      now.unix_timestamp().max(0) as u64,
+      config.last_used_step,
+    );
+    let accepted = match step {
+      Some(step) => self.db.accept_totp_step(user.id, step).await,
+      None => match config
+        .recovery_code_hashes
+        .iter()
+        .find(|h| self.passwords.verify(h, &code).is_ok_and(|v| v))
+      {
+        Some(hash) => self.db.use_recovery_code(user.id, hash).await,
+        None => Ok(None),
+      },
+    }
+    .map_err(AuthError::from)?;
+    if accepted.is_some() {
+      return Ok(accepted);
+    }
+
+    self
+      .db
+      .record_second_factor_failure(user.id, now, expired_failure(config, now))
+      .await
+      .map_err(AuthError::from)?;
+    Ok(None)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  const NOW: u64 = 1_700_000_000;
+
+  fn totp() -> TOTP {
+    build_totp(b"an example secret, 20b+".to_vec(), "test@example.com").unwrap()
+  }
+
+  #[test]
+  fn codes_are_normalized() {
+    assert_eq!(normalize_code(" 123 456 "), "123456");
+    assert_eq!(normalize_code("K7MQP-X3D9A"), "k7mqp-x3d9a");
+  }
+
+  #[test]
+  fn codes_are_accepted_within_a_step_of_now() {
+    let totp = totp();
+    let step = NOW / totp.step;
+
+    let current = totp.generate(NOW);
+    assert_eq!(accepted_step(&totp, &current, NOW, None), Some(step));
+    let previous = totp.generate(NOW - totp.step);
+    assert_eq!(accepted_step(&totp, &previous, NOW, None), Some(step - 1));
+    let next = totp.generate(NOW + totp.step);
+    assert_eq!(accepted_step(&totp, &next, NOW, None), Some(step + 1));
+
+    let stale = totp.generate(NOW - 3 * totp.step);
+    assert_eq!(accepted_step(&totp, &stale, NOW, None), None);
+    assert_eq!(accepted_step(&totp, "000000x", NOW, None), None);
+  }
+
+  fn config(
+    failed_attempts: u32,
+    last_failed_at: OffsetDateTime,
+  ) -> TotpConfig {
+    TotpConfig {
+      secret: totp().get_secret_base32().into(),
+      enabled: true,
+      recovery_code_hashes: Vec::new(),
+      last_used_step: None,
+      failed_attempts,
+      last_failed_at: Some(last_failed_at),
+    }
+  }
+
+  #[test]
+  fn lockouts_expire() {
+    let now = OffsetDateTime::from_unix_timestamp(NOW as i64).unwrap();
+    let recent = now - time::Duration::minutes(1);
+    let old = now - SECOND_FACTOR_LOCKOUT;
+
+    assert!(locked_out(&config(MAX_SECOND_FACTOR_FAILURES, recent), now));
+    assert!(!locked_out(
+      &config(MAX_SECOND_FACTOR_FAILURES - 1, recent),
+      now
+    ));
+    assert!(!locked_out(&config(MAX_SECOND_FACTOR_FAILURES, old), now));
+
+    // the count starts again after an expired failure
+    assert_eq!(expired_failure(&config(1, recent), now), None);
+    assert_eq!(
+      expired_failure(&config(MAX_SECOND_FACTOR_FAILURES, old), now),
+      Some(old)
+    );
+  }
+
+  #[test]
+  fn codes_cant_be_replayed() {
+    let totp = totp();
+    let step = NOW / totp.step;
+    let current = totp.generate(NOW);
+
+    assert_eq!(accepted_step(&totp, &current, NOW, Some(step)), None);
+    assert_eq!(accepted_step(&totp, &current, NOW + 10, Some(step)), None);
+    // an older code is refused once a newer one has been used
+    let previous = totp.generate(NOW - totp.step);
+    assert_eq!(accepted_step(&totp, &previous, NOW, Some(step)), None);
+    let next = totp.generate(NOW + totp.step);
+    assert_eq!(accepted_step(&totp, &next, NOW, Some(step)), Some(step + 1));
+  }
+}
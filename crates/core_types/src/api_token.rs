@@ -0,0 +1,116 @@
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "ssr")]
+use crate::UserRecordId;
+use crate::{iso8601, Secret};
+
+pub const API_TOKEN_TABLE: &str = "api_tokens";
+
+/// Every plaintext token starts with this, to make leaked tokens easy to spot.
+pub const API_TOKEN_PREFIX: &str = "omt_";
+/// The header the site's own pages send with API requests that change
+/// anything. Other sites can't send it without a CORS preflight, which the
+/// API doesn't allow, so it shows a cookie-authenticated request isn't a
+/// cross-site forgery.
+pub const API_REQUESTED_WITH_HEADER: &str = "X-Requested-With";
+/// The value pages send in [`API_REQUESTED_WITH_HEADER`].
+pub const API_REQUESTED_WITH: &str = "XMLHttpRequest";
+/// The lifetimes a user can choose from when creating a token.
+pub const API_TOKEN_LIFETIME_DAYS: [u32; 4] = [7, 30, 90, 365];
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "ssr", serde(from = "crate::ssr::UlidOrThing"))]
+pub struct ApiTokenRecordId(pub ulid::Ulid);
+
+/// What an API token is allowed to do.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum ApiTokenScope {
+  /// Read recordings and their metadata.
+  Read,
+  /// Upload new recordings.
+  Upload,
+}
+
+impl ApiTokenScope {
+  pub const ALL: [ApiTokenScope; 2] =
+    [ApiTokenScope::Read, ApiTokenScope::Upload];
+
+  /// A short description for the token creation form.
+  pub fn label(&self) -> &'static str {
+    match self {
+      ApiTokenScope::Read => "Read recordings",
+      ApiTokenScope::Upload => "Upload recordings",
+    }
+  }
+}
+
+/// A personal access token for scripted use of the API.
+///
+/// Only a hash of the secret is stored. The plaintext token is
+/// `omt_<id>_<secret>`, so the record can be found without scanning.
+#[cfg(feature = "ssr")]
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ApiToken {
+  pub id:           ApiTokenRecordId,
+  #[serde(serialize_with = "crate::ssr::serialize_record_link")]
+  pub user:         UserRecordId,
+  pub name:         String,
+  /// The hex-encoded SHA-256 hash of the token's secret.
+  pub secret_hash:  String,
+  pub scopes:       Vec<ApiTokenScope>,
+  #[serde(with = "iso8601")]
+  pub created_at:   time::OffsetDateTime,
+  #[serde(with = "iso8601")]
+  pub expires_at:   time::OffsetDateTime,
+  #[serde(default, with = "iso8601::option")]
+  pub last_used_at: Option<time::OffsetDateTime>,
+}
+
+#[cfg(feature = "ssr")]
+impl ApiToken {
+  pub fn is_expired(&self) -> bool {
+    self.expires_at <= time::OffsetDateTime::now_utc()
+  }
+
+  pub fn has_scope(&self, scope: ApiTokenScope) -> bool {
+    self.scopes.contains(&scope)
+  }
+}
+
+/// The parts of an [`ApiToken`] that are safe to show the user.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ApiTokenSummary {
+  pub id:           ApiTokenRecordId,
+  pub name:         String,
+  pub scopes:       Vec<ApiTokenScope>,
+  #[serde(with = "iso8601")]
+  pub created_at:   time::OffsetDateTime,
+  #[serde(with = "iso8601")]
+  pub expires_at:   time::OffsetDateTime,
+  #[serde(with = "iso8601::option")]
+  pub last_used_at: Option<time::OffsetDateTime>,
+  pub expired:      bool,
+}
+
+#[cfg(feature = "ssr")]
+impl From<ApiToken> for ApiTokenSummary {
+  fn from(value: ApiToken) -> Self {
+    ApiTokenSummary {
+      expired:      value.is_expired(),
+      id:           value.id,
+      name:         value.name,
+      scopes:       value.scopes,
+      created_at:   value.created_at,
+      expires_at:   value.expires_at,
+      last_used_at: value.last_used_at,
+    }
+  }
+}
+
+/// A newly created token. The plaintext token is only available here.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct NewApiToken {
+  pub summary: ApiTokenSummary,
+  pub token:   Secret,
+}
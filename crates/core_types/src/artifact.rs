@@ -1,13 +1,132 @@
 use serde::{Deserialize, Serialize};
 
+#[cfg(feature = "ssr")]
+use crate::{scripture::Passage, RecordingStatus, TranslationRecordId};
+
 pub const ARTIFACT_TABLE: &str = "artifacts";
 
 #[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "ssr", serde(from = "crate::ssr::UlidOrThing"))]
 pub struct ArtifactRecordId(pub ulid::Ulid);
 
+/// A file in the object store, usually a recording.
+#[cfg(feature = "ssr")]
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct Artifact {
-  pub id:         ArtifactRecordId,
-  pub object_key: String,
+  pub id:           ArtifactRecordId,
+  pub object_key:   String,
+  /// The MIME type given when the artifact was uploaded.
+  #[serde(default)]
+  pub content_type: Option<String>,
+  /// The size of the object in bytes.
+  #[serde(default)]
+  pub size:         Option<u64>,
+  /// How long the recording plays for, as given by the uploader.
+  #[serde(default)]
+  pub duration_ms:  Option<u32>,
+  /// The translation this is a recording for. Only recordings are served
+  /// publicly.
+  #[serde(
+    default,
+    serialize_with = "crate::ssr::serialize_optional_record_link"
+  )]
+  pub translation:  Option<TranslationRecordId>,
+  /// The passage the recording covers, which counts towards the
+  /// translation's progress.
+  #[serde(default)]
+  pub passage:      Option<Passage>,
+  /// Where the recording is in review.
+  #[serde(default)]
+  pub status:       RecordingStatus,
+  /// Which take of its passage the recording is, counting from 1.
+  #[serde(default = "first_take")]
+  pub take:         u32,
+  /// Whether this is the take of its passage that's used. Re-recording a
+  /// passage adds a take and makes it current; older takes are kept.
+  #[serde(default = "current_by_default")]
+  pub is_current:   bool,
+}
+
+#[cfg(feature = "ssr")]
+fn first_take() -> u32 { 1 }
+
+#[cfg(feature = "ssr")]
+fn current_by_default() -> bool { true }
+
+/// The public URL that streams an artifact's contents.
+pub fn artifact_media_url(id: ArtifactRecordId) -> String {
+  format!("/media/artifacts/{}", id.0)
+}
+
+/// The audio formats recordings come in, as MIME types and the file
+/// extensions they're saved with. The first entry for an extension is the
+/// one it's read as.
+const AUDIO_FORMATS: &[(&str, &str)] = &[
+  ("audio/mpeg", "mp3"),
+  ("audio/mp4", "m4a"),
+  ("audio/x-m4a", "m4a"),
+  ("audio/aac", "aac"),
+  ("audio/ogg", "ogg"),
+  ("audio/opus", "opus"),
+  ("audio/webm", "webm"),
+  ("audio/wav", "wav"),
+  ("audio/x-wav", "wav"),
+  ("audio/flac", "flac"),
+];
+
+fn audio_format(
+  content_type: &str,
+) -> Option<&'static (&'static str, &'static str)> {
+  let essence = content_type.split(';').next()?.trim();
+  AUDIO_FORMATS
+    .iter()
+    .find(|(mime, _)| mime.eq_ignore_ascii_case(essence))
+}
+
+/// The audio MIME type `content_type` is, ignoring parameters like `codecs`.
+/// Uploads can claim any type, so only these are stored and served as
+/// given.
+pub fn audio_mime_type(content_type: &str) -> Option<&'static str> {
+  audio_format(content_type).map(|(mime, _)| *mime)
+}
+
+/// The file extension for an audio MIME type, ignoring parameters like
+/// `codecs`.
+pub fn audio_extension(content_type: &str) -> Option<&'static str> {
+  audio_format(content_type).map(|(_, extension)| *extension)
+}
+
+/// The MIME type for an audio file extension.
+pub fn audio_content_type(extension: &str) -> Option<&'static str> {
+  AUDIO_FORMATS
+    .iter()
+    .find(|(_, ext)| ext.eq_ignore_ascii_case(extension))
+    .map(|(mime, _)| *mime)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn audio_formats_map_both_ways() {
+    assert_eq!(audio_extension("audio/webm;codecs=opus"), Some("webm"));
+    assert_eq!(audio_extension("Audio/MPEG"), Some("mp3"));
+    assert_eq!(audio_extension("video/mp4"), None);
+    assert_eq!(audio_content_type("MP3"), Some("audio/mpeg"));
+    assert_eq!(audio_content_type("m4a"), Some("audio/mp4"));
+    assert_eq!(audio_content_type("txt"), None);
+  }
+
+  #[test]
+  fn only_audio_types_are_allowed() {
+    assert_eq!(
+      audio_mime_type("audio/webm; codecs=opus"),
+      Some("audio/webm")
+    );
+    assert_eq!(audio_mime_type("AUDIO/MPEG"), Some("audio/mpeg"));
+    assert_eq!(audio_mime_type("text/html"), None);
+    assert_eq!(audio_mime_type("image/svg+xml"), None);
+    assert_eq!(audio_mime_type("text/html; x=audio/mpeg"), None);
+  }
 }
@@ -0,0 +1,134 @@
+//! Offline bundles: a translation's approved recordings packed into a ZIP,
+//! for communities that side-load them onto phones and SD cards.
+
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "ssr")]
+use crate::UserRecordId;
+use crate::{iso8601, ArtifactRecordId, License, TranslationRecordId};
+
+pub const BUNDLE_TABLE: &str = "bundles";
+/// The version of [`BundleManifest`] written into bundles.
+pub const BUNDLE_MANIFEST_VERSION: u32 = 1;
+/// What the manifest is called inside a bundle.
+pub const BUNDLE_MANIFEST_NAME: &str = "manifest.json";
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "ssr", serde(from = "crate::ssr::UlidOrThing"))]
+pub struct BundleRecordId(pub ulid::Ulid);
+
+/// Where a bundle is up to.
+#[derive(
+  Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq, Hash,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum BundleStatus {
+  #[default]
+  Building,
+  Ready,
+  /// Building stopped partway. The reason is only logged.
+  Failed,
+}
+
+/// A bundle of a translation, as its recordings stood when it was built.
+#[cfg(feature = "ssr")]
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Bundle {
+  pub id:              BundleRecordId,
+  #[serde(serialize_with = "crate::ssr::serialize_record_link")]
+  pub translation:     TranslationRecordId,
+  pub status:          BundleStatus,
+  /// Where the ZIP is kept in the object store, once it's built.
+  pub object_key:      String,
+  /// The size of the ZIP in bytes.
+  #[serde(default)]
+  pub size:            Option<u64>,
+  #[serde(default)]
+  pub recording_count: u32,
+  #[serde(serialize_with = "crate::ssr::serialize_record_link")]
+  pub requested_by:    UserRecordId,
+  #[serde(with = "iso8601")]
+  pub created_at:      time::OffsetDateTime,
+  #[serde(default, with = "iso8601::option")]
+  pub finished_at:     Option<time::OffsetDateTime>,
+}
+
+/// A [`Bundle`] as shown on its translation's page.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct BundleSummary {
+  pub id:              BundleRecordId,
+  pub status:          BundleStatus,
+  pub size:            Option<u64>,
+  pub recording_count: u32,
+  #[serde(with = "iso8601")]
+  pub created_at:      time::OffsetDateTime,
+}
+
+#[cfg(feature = "ssr")]
+impl From<Bundle> for BundleSummary {
+  fn from(value: Bundle) -> Self {
+    BundleSummary {
+      id:              value.id,
+      status:          value.status,
+      size:            value.size,
+      recording_count: value.recording_count,
+      created_at:      value.created_at,
+    }
+  }
+}
+
+/// A translation's bundles: the newest one that can be downloaded, and
+/// whether a newer one is being built.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct TranslationBundles {
+  pub latest_ready: Option<BundleSummary>,
+  pub building:     Option<BundleSummary>,
+  /// Whether the current user can build a new bundle.
+  pub can_build:    bool,
+  pub license:      License,
+  /// Whether the current user can change the license.
+  pub can_license:  bool,
+}
+
+/// The link a bundle is downloaded from. Bundles only hold approved
+/// recordings, which are public anyway, so anyone with it can download.
+pub fn bundle_download_url(id: BundleRecordId) -> String {
+  format!("/bundles/{}/download", id.0)
+}
+
+/// Describes a bundle's contents, for apps that load bundles and for
+/// importing them elsewhere.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct BundleManifest {
+  pub version:       u32,
+  pub translation:   TranslationRecordId,
+  pub name:          String,
+  pub mother_tongue: String,
+  /// The ISO 639-3 code of the tongue, or of its parent language.
+  pub language:      Option<String>,
+  pub license:       License,
+  #[serde(with = "iso8601")]
+  pub generated_at:  time::OffsetDateTime,
+  /// Every recording, in canonical order.
+  pub files:         Vec<BundleFile>,
+}
+
+/// A recording in a bundle.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct BundleFile {
+  /// Where the file is in the bundle, like `43_JHN/003/JHN_003.webm`.
+  pub path:         String,
+  pub artifact:     ArtifactRecordId,
+  /// The passage the recording covers, like `JHN 3` or `JHN 3:1-15`.
+  pub passage:      String,
+  #[serde(default)]
+  pub content_type: Option<String>,
+  pub size:         u64,
+  /// The hex-encoded SHA-256 hash of the file.
+  pub sha256:       String,
+  #[serde(default)]
+  pub duration_ms:  Option<u32>,
+  /// The verses the recording covers, in order. Recordings aren't aligned
+  /// with their text, so where each verse starts isn't known.
+  pub verses:       Vec<u16>,
+}
@@ -0,0 +1,171 @@
+//! Comment threads pinned to a moment or stretch of a recording, so
+//! checkers can point at exactly what needs fixing.
+
+use std::{fmt, str::FromStr};
+
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "ssr")]
+use crate::ArtifactRecordId;
+use crate::{iso8601, PublicProfile};
+
+pub const COMMENT_THREAD_TABLE: &str = "comment_threads";
+pub const COMMENTED_IN_THREAD_TABLE: &str = "commented_in_thread";
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "ssr", serde(from = "crate::ssr::UlidOrThing"))]
+pub struct CommentThreadRecordId(pub ulid::Ulid);
+
+/// A moment in a recording, or a stretch of it, in milliseconds from the
+/// start.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct TimeAnchor {
+  pub start_ms: u32,
+  /// Where the stretch ends, or `None` for a single moment.
+  #[serde(default)]
+  pub end_ms:   Option<u32>,
+}
+
+impl TimeAnchor {
+  pub fn at(start_ms: u32) -> Self {
+    TimeAnchor {
+      start_ms,
+      end_ms: None,
+    }
+  }
+
+  /// Whether the anchor makes sense: ranges have to end after they start.
+  pub fn is_valid(&self) -> bool {
+    self.end_ms.is_none_or(|end| end > self.start_ms)
+  }
+}
+
+/// Formats a time like `1:32`, or `1:02:05` past an hour. Milliseconds are
+/// dropped.
+pub fn format_timestamp(ms: u32) -> String {
+  let seconds = ms / 1000;
+  let (hours, minutes, seconds) =
+    (seconds / 3600, seconds / 60 % 60, seconds % 60);
+  match hours {
+    0 => format!("{minutes}:{seconds:02}"),
+    hours => format!("{hours}:{minutes:02}:{seconds:02}"),
+  }
+}
+
+/// Parses a time like `1:32` or `1:02:05` into milliseconds.
+fn parse_timestamp(s: &str) -> Option<u32> {
+  let mut ms = 0_u32;
+  let parts = s.trim().split(':').collect::<Vec<_>>();
+  if parts.is_empty() || parts.len() > 3 {
+    return None;
+  }
+  for (i, part) in parts.iter().enumerate() {
+    let value = part.parse::<u32>().ok()?;
+    // only the leading part may exceed a minute's worth
+    if i > 0 && (value >= 60 || part.len() != 2) {
+      return None;
+    }
+    ms = ms.checked_mul(60)?.checked_add(value.checked_mul(1000)?)?;
+  }
+  Some(ms)
+}
+
+impl fmt::Display for TimeAnchor {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.write_str(&format_timestamp(self.start_ms))?;
+    match self.end_ms {
+      Some(end) => write!(f, "-{}", format_timestamp(end)),
+      None => Ok(()),
+    }
+  }
+}
+
+impl FromStr for TimeAnchor {
+  type Err = String;
+
+  /// Parses a moment like `1:32` or a stretch like `1:32-1:40`.
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    let malformed = || {
+      "Write a time like \"1:32\", or a range like \"1:32-1:40\".".to_string()
+    };
+
+    let (start, end) = match s.split_once('-') {
+      Some((start, end)) => (start, Some(end)),
+      None => (s, None),
+    };
+    let anchor = TimeAnchor {
+      start_ms: parse_timestamp(start).ok_or_else(malformed)?,
+      end_ms:   end
+        .map(|end| parse_timestamp(end).ok_or_else(malformed))
+        .transpose()?,
+    };
+    match anchor.is_valid() {
+      true => Ok(anchor),
+      false => Err("A range has to end after it starts.".to_string()),
+    }
+  }
+}
+
+/// A thread of comments on part of a recording.
+#[cfg(feature = "ssr")]
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct CommentThread {
+  pub id:         CommentThreadRecordId,
+  #[serde(serialize_with = "crate::ssr::serialize_record_link")]
+  pub artifact:   ArtifactRecordId,
+  pub anchor:     TimeAnchor,
+  /// Whether whatever the thread raised has been dealt with.
+  pub resolved:   bool,
+  #[serde(with = "iso8601")]
+  pub created_at: time::OffsetDateTime,
+}
+
+/// A comment in a thread, from a `commented_in_thread` edge.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ThreadComment {
+  pub author: Option<PublicProfile>,
+  pub body:   String,
+  #[serde(with = "iso8601")]
+  pub at:     time::OffsetDateTime,
+}
+
+/// A thread with its comments, oldest first. The first comment is the one
+/// that started the thread.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct CommentThreadDetails {
+  pub id:       CommentThreadRecordId,
+  pub anchor:   TimeAnchor,
+  pub resolved: bool,
+  pub comments: Vec<ThreadComment>,
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn anchors_round_trip_through_timestamps() {
+    for text in ["0:00", "1:32", "1:32-1:40", "1:02:05", "59:59-1:00:00"] {
+      let anchor: TimeAnchor = text.parse().unwrap();
+      assert_eq!(anchor.to_string(), text);
+    }
+
+    assert_eq!("1:32".parse::<TimeAnchor>(), Ok(TimeAnchor::at(92_000)));
+    assert_eq!(format_timestamp(92_999), "1:32");
+  }
+
+  #[test]
+  fn malformed_anchors_are_rejected() {
+    for text in [
+      "",
+      "1:5",
+      "1:60",
+      "a:00",
+      "1:00:00:00",
+      "1:40-1:32",
+      "1:32-1:32",
+    ] {
+      assert!(text.parse::<TimeAnchor>().is_err(), "{text} parsed");
+    }
+  }
+}
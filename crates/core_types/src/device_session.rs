@@ -0,0 +1,132 @@
+use serde::{Deserialize, Serialize};
+
+use crate::iso8601;
+#[cfg(feature = "ssr")]
+use crate::UserRecordId;
+
+pub const DEVICE_SESSION_TABLE: &str = "device_sessions";
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "ssr", serde(from = "crate::ssr::UlidOrThing"))]
+pub struct DeviceSessionRecordId(pub ulid::Ulid);
+
+/// Metadata about the device behind a logged in session.
+///
+/// The session itself lives in the session store; this records who is using
+/// it and from where, so that users can review and revoke their sessions.
+#[cfg(feature = "ssr")]
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct DeviceSession {
+  pub id:           DeviceSessionRecordId,
+  #[serde(serialize_with = "crate::ssr::serialize_record_link")]
+  pub user:         UserRecordId,
+  /// The current `tower_sessions` id. This changes when the session id is
+  /// cycled, e.g. on login.
+  pub session_id:   String,
+  pub user_agent:   Option<String>,
+  pub ip:           Option<String>,
+  #[serde(with = "iso8601")]
+  pub created_at:   time::OffsetDateTime,
+  #[serde(with = "iso8601")]
+  pub last_seen_at: time::OffsetDateTime,
+}
+
+/// A session as shown to its user.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ActiveSession {
+  pub id:           DeviceSessionRecordId,
+  /// A short description of the browser and OS, like "Firefox on Linux".
+  pub device:       String,
+  pub ip:           Option<String>,
+  #[serde(with = "iso8601")]
+  pub created_at:   time::OffsetDateTime,
+  #[serde(with = "iso8601")]
+  pub last_seen_at: time::OffsetDateTime,
+  /// Whether this is the session making the request.
+  pub current:      bool,
+}
+
+#[cfg(feature = "ssr")]
+impl DeviceSession {
+  /// Builds the view of this session for its user.
+  pub fn to_active_session(&self, current: bool) -> ActiveSession {
+    ActiveSession {
+      id: self.id,
+      device: describe_user_agent(self.user_agent.as_deref().unwrap_or("")),
+      ip: self.ip.clone(),
+      created_at: self.created_at,
+      last_seen_at: self.last_seen_at,
+      current,
+    }
+  }
+}
+
+/// Summarizes a user agent string as "<browser> on <OS>".
+///
+/// This is only a label for humans, so it checks for the common browsers and
+/// falls back to "Unknown".
+pub fn describe_user_agent(user_agent: &str) -> String {
+  // order matters: Edge and Opera also claim to be Chrome, and Chrome also
+  // claims to be Safari
+  let browser = [
+    ("Edg/", "Edge"),
+    ("OPR/", "Opera"),
+    ("Firefox/", "Firefox"),
+    ("Chrome/", "Chrome"),
+    ("Safari/", "Safari"),
+  ]
+  .into_iter()
+  .find(|(token, _)| user_agent.contains(token))
+  .map(|(_, name)| name);
+
+  let os = [
+    ("Android", "Android"),
+    ("iPhone", "iOS"),
+    ("iPad", "iPadOS"),
+    ("Windows", "Windows"),
+    ("Mac OS X", "macOS"),
+    ("CrOS", "ChromeOS"),
+    ("Linux", "Linux"),
+  ]
+  .into_iter()
+  .find(|(token, _)| user_agent.contains(token))
+  .map(|(_, name)| name);
+
+  match (browser, os) {
+    (Some(browser), Some(os)) => format!("{browser} on {os}"),
+    (Some(name), None) | (None, Some(name)) => name.to_string(),
+    (None, None) => "Unknown device".to_string(),
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn describe_user_agent_works() {
+    assert_eq!(
+      describe_user_agent(
+        "Mozilla/5.0 (X11; Linux x86_64; rv:126.0) Gecko/20100101 \
+         Firefox/126.0"
+      ),
+      "Firefox on Linux"
+    );
+    assert_eq!(
+      describe_user_agent(
+        "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, \
+         like Gecko) Chrome/125.0.0.0 Safari/537.36 Edg/125.0.0.0"
+      ),
+      "Edge on Windows"
+    );
+    assert_eq!(
+      describe_user_agent(
+        "Mozilla/5.0 (iPhone; CPU iPhone OS 17_5 like Mac OS X) \
+         AppleWebKit/605.1.15 (KHTML, like Gecko) Version/17.5 Mobile/15E148 \
+         Safari/604.1"
+      ),
+      "Safari on iOS"
+    );
+    assert_eq!(describe_user_agent("curl/8.6.0"), "Unknown device");
+  }
+}
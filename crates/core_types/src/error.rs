@@ -0,0 +1,70 @@
+use std::{fmt, str::FromStr};
+
+use serde::{Deserialize, Serialize};
+
+use crate::validation::ValidationErrors;
+
+/// The error type returned by server functions.
+///
+/// Server functions send their errors to the client as strings, so the
+/// [`Display`](fmt::Display) and [`FromStr`] impls round-trip the error as
+/// JSON. Use [`AppError::message`] to get something to show a user.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", content = "detail", rename_all = "snake_case")]
+pub enum AppError {
+  /// Some fields of the input were rejected.
+  Validation(ValidationErrors),
+  /// The input conflicts with existing data, e.g. a duplicate email.
+  Conflict(String),
+  /// The user isn't logged in, or their credentials are wrong.
+  Unauthorized,
+  /// The user is logged in but isn't allowed to do that.
+  Forbidden,
+  /// The requested resource doesn't exist.
+  NotFound,
+  /// The user has made too many requests.
+  RateLimited,
+  /// Something went wrong on the server. Details are logged, not sent.
+  Internal,
+}
+
+impl AppError {
+  /// A human-readable message describing the error.
+  pub fn message(&self) -> String {
+    match self {
+      AppError::Validation(errors) => match errors.0.len() {
+        1 => errors.0.values().next().cloned().unwrap_or_default(),
+        _ => "Please fix the highlighted fields.".to_string(),
+      },
+      AppError::Conflict(message) => message.clone(),
+      AppError::Unauthorized => "You need to log in to do that.".to_string(),
+      AppError::Forbidden => {
+        "You don't have permission to do that.".to_string()
+      }
+      AppError::NotFound => {
+        "It looks like that resource doesn't exist.".to_string()
+      }
+      AppError::RateLimited => "You're doing that too often. Please wait a \
+                                moment and try again."
+        .to_string(),
+      AppError::Internal => {
+        "Something went wrong. Please try again.".to_string()
+      }
+    }
+  }
+}
+
+impl fmt::Display for AppError {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    let json = serde_json::to_string(self).map_err(|_| fmt::Error)?;
+    f.write_str(&json)
+  }
+}
+
+impl FromStr for AppError {
+  type Err = serde_json::Error;
+
+  fn from_str(s: &str) -> Result<Self, Self::Err> { serde_json::from_str(s) }
+}
+
+impl std::error::Error for AppError {}
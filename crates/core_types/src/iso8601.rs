@@ -0,0 +1,62 @@
+//! Serde helpers for storing [`OffsetDateTime`]s as ISO 8601 strings.
+//!
+//! Use with `#[serde(with = "crate::iso8601")]`, or
+//! `#[serde(with = "crate::iso8601::option")]` for optional fields.
+
+use serde::{self, Deserialize, Deserializer, Serializer};
+use time::{format_description::well_known::Iso8601, OffsetDateTime};
+
+pub fn serialize<S>(
+  date: &OffsetDateTime,
+  serializer: S,
+) -> Result<S::Ok, S::Error>
+where
+  S: Serializer,
+{
+  let s = date
+    .format(&Iso8601::DEFAULT)
+    .map_err(serde::ser::Error::custom)?;
+  serializer.serialize_str(&s)
+}
+
+pub fn deserialize<'de, D>(deserializer: D) -> Result<OffsetDateTime, D::Error>
+where
+  D: Deserializer<'de>,
+{
+  let s = String::deserialize(deserializer)?;
+  let dt = OffsetDateTime::parse(&s, &Iso8601::DEFAULT)
+    .map_err(serde::de::Error::custom)?;
+  Ok(dt)
+}
+
+pub mod option {
+  use serde::{self, Deserialize, Deserializer, Serializer};
+  use time::{format_description::well_known::Iso8601, OffsetDateTime};
+
+  pub fn serialize<S>(
+    date: &Option<OffsetDateTime>,
+    serializer: S,
+  ) -> Result<S::Ok, S::Error>
+  where
+    S: Serializer,
+  {
+    match date {
+      Some(date) => super::serialize(date, serializer),
+      None => serializer.serialize_none(),
+    }
+  }
+
+  pub fn deserialize<'de, D>(
+    deserializer: D,
+  ) -> Result<Option<OffsetDateTime>, D::Error>
+  where
+    D: Deserializer<'de>,
+  {
+    let s = Option::<String>::deserialize(deserializer)?;
+    s.map(|s| {
+      OffsetDateTime::parse(&s, &Iso8601::DEFAULT)
+        .map_err(serde::de::Error::custom)
+    })
+    .transpose()
+  }
+}
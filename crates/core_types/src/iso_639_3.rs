@@ -0,0 +1,134 @@
+//! An offline copy of the ISO 639-3 code table, so language codes can be
+//! checked and named without a network lookup.
+//!
+//! The table is generated from `iso_639-3.json` in the Debian `iso-codes`
+//! project (version 4.15.0), which tracks the registry maintained by SIL.
+//! Each line of `data/iso_639_3.tsv` is `code`, scope, type, reference name
+//! and, for languages that have one, the ISO 639-1 code, separated by tabs
+//! and sorted by code.
+
+use std::sync::OnceLock;
+
+use serde::{Deserialize, Serialize};
+
+const TABLE: &str = include_str!("../data/iso_639_3.tsv");
+
+/// What a code covers.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LanguageScope {
+  Individual,
+  Macrolanguage,
+  Special,
+}
+
+/// What kind of language a code is for.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LanguageType {
+  Living,
+  Extinct,
+  Ancient,
+  Historical,
+  Constructed,
+  Special,
+}
+
+/// An entry in the ISO 639-3 table.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Language {
+  pub code:  String,
+  pub scope: LanguageScope,
+  pub kind:  LanguageType,
+  /// The reference name, like "English".
+  pub name:  String,
+  /// The two-letter ISO 639-1 code, like "en", for languages that have one.
+  pub part1: Option<String>,
+}
+
+impl Language {
+  /// The language's BCP 47 (RFC 5646) tag, which uses the shortest code
+  /// there is for it: "en" for English, but "mwp" for Kala Lagaw Ya.
+  pub fn language_tag(&self) -> &str {
+    self.part1.as_deref().unwrap_or(&self.code)
+  }
+}
+
+fn parse_line(line: &str) -> Option<Language> {
+  let mut fields = line.split('\t');
+  let code = fields.next()?;
+  let scope = match fields.next()? {
+    "I" => LanguageScope::Individual,
+    "M" => LanguageScope::Macrolanguage,
+    "S" => LanguageScope::Special,
+    _ => return None,
+  };
+  let kind = match fields.next()? {
+    "L" => LanguageType::Living,
+    "E" => LanguageType::Extinct,
+    "A" => LanguageType::Ancient,
+    "H" => LanguageType::Historical,
+    "C" => LanguageType::Constructed,
+    "S" => LanguageType::Special,
+    _ => return None,
+  };
+  let name = fields.next()?;
+  let part1 = fields.next();
+
+  Some(Language {
+    code: code.to_string(),
+    scope,
+    kind,
+    name: name.to_string(),
+    part1: part1.map(str::to_string),
+  })
+}
+
+/// Every language in the table, sorted by code.
+pub fn languages() -> &'static [Language] {
+  static LANGUAGES: OnceLock<Vec<Language>> = OnceLock::new();
+  LANGUAGES.get_or_init(|| TABLE.lines().filter_map(parse_line).collect())
+}
+
+/// Looks up a code, like `"eng"`.
+pub fn lookup(code: &str) -> Option<&'static Language> {
+  let languages = languages();
+  languages
+    .binary_search_by(|l| l.code.as_str().cmp(code))
+    .ok()
+    .map(|i| &languages[i])
+}
+
+/// Finds languages whose reference name starts with `prefix`, ignoring case.
+pub fn search_by_name(
+  prefix: &str,
+  limit: usize,
+) -> impl Iterator<Item = &'static Language> {
+  let prefix = prefix.trim().to_lowercase();
+  languages()
+    .iter()
+    .filter(move |l| l.name.to_lowercase().starts_with(&prefix))
+    .take(limit)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn lookup_finds_bundled_codes() {
+    assert_eq!(languages().len(), TABLE.lines().count());
+
+    let english = lookup("eng").unwrap();
+    assert_eq!(english.name, "English");
+    assert_eq!(english.scope, LanguageScope::Individual);
+    assert_eq!(english.kind, LanguageType::Living);
+    assert_eq!(lookup("ara").unwrap().scope, LanguageScope::Macrolanguage);
+    assert_eq!(english.language_tag(), "en");
+    assert_eq!(lookup("mwp").unwrap().language_tag(), "mwp");
+
+    // reserved for local use, so never in the table
+    assert_eq!(lookup("qqq"), None);
+    assert_eq!(lookup("ENG"), None);
+  }
+}
@@ -1,10 +1,34 @@
+pub mod api_token;
 pub mod artifact;
+pub mod bundle;
+pub mod comment_thread;
+pub mod device_session;
+pub mod error;
+pub mod iso8601;
+pub mod iso_639_3;
+pub mod listening;
+pub mod membership;
 pub mod mother_tongue;
+pub mod oidc;
+pub mod passkey;
+pub mod progress;
+pub mod redacted;
+pub mod review;
+pub mod scripture;
+pub mod take;
+pub mod tongue_search;
+pub mod translation;
 pub mod user;
+pub mod validation;
 
 #[cfg(feature = "ssr")]
 pub mod ssr;
 
 pub use ulid::Ulid;
 
-pub use self::{artifact::*, mother_tongue::*, user::*};
+pub use self::{
+  api_token::*, artifact::*, bundle::*, comment_thread::*, device_session::*,
+  error::*, listening::*, membership::*, mother_tongue::*, oidc::*, passkey::*,
+  progress::*, redacted::*, review::*, take::*, tongue_search::*,
+  translation::*, user::*,
+};
@@ -0,0 +1,199 @@
+//! What listeners hear: a translation's approved recordings, arranged into
+//! chapters to play one after another.
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+  scripture::{self, Passage},
+  ArtifactRecordId, TranslationRecordId,
+};
+
+/// The URL of a translation's podcast feed.
+pub fn translation_feed_url(id: TranslationRecordId) -> String {
+  format!("/feeds/translations/{}", id.0)
+}
+
+/// An approved recording, with what feeds and bundles say about it.
+#[cfg(feature = "ssr")]
+#[derive(Clone, Debug, Deserialize)]
+pub struct ApprovedRecording {
+  pub id:           ArtifactRecordId,
+  pub passage:      Passage,
+  pub object_key:   String,
+  #[serde(default)]
+  pub content_type: Option<String>,
+  #[serde(default)]
+  pub size:         Option<u64>,
+  #[serde(default)]
+  pub duration_ms:  Option<u32>,
+  /// When it was first approved, or uploaded if there's no record of that.
+  #[serde(with = "crate::iso8601")]
+  pub approved_at:  time::OffsetDateTime,
+}
+
+#[cfg(feature = "ssr")]
+impl ApprovedRecording {
+  pub fn track(&self) -> ListeningTrack {
+    ListeningTrack {
+      id:      self.id,
+      passage: self.passage.clone(),
+    }
+  }
+}
+
+/// A recording to play, and the passage it covers.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ListeningTrack {
+  pub id:      ArtifactRecordId,
+  pub passage: Passage,
+}
+
+/// The recordings that make up one chapter, in the order they're played.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ListeningChapter {
+  /// The book's USFM code.
+  pub book:      String,
+  pub book_name: String,
+  pub chapter:   u16,
+  pub tracks:    Vec<ListeningTrack>,
+}
+
+impl ListeningChapter {
+  /// Names the chapter like "John 3".
+  pub fn title(&self) -> String {
+    format!("{} {}", self.book_name, self.chapter)
+  }
+
+  /// Identifies the chapter like "JHN 3", for remembering where a listener
+  /// was.
+  pub fn key(&self) -> String { format!("{} {}", self.book, self.chapter) }
+}
+
+/// Everything there is to listen to in a translation, chapter by chapter in
+/// canonical order.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Playlist {
+  pub translation_name: String,
+  pub chapters:         Vec<ListeningChapter>,
+}
+
+impl Playlist {
+  /// Arranges recordings into chapters.
+  ///
+  /// A chapter recorded whole is played as it is. Otherwise its verse
+  /// ranges are played in order, skipping any that overlap one already
+  /// played.
+  pub fn new(
+    translation_name: String,
+    tracks: impl IntoIterator<Item = ListeningTrack>,
+  ) -> Self {
+    let mut tracks = tracks
+      .into_iter()
+      .filter(|t| t.passage.validate().is_ok())
+      .collect::<Vec<_>>();
+    tracks.sort_by_key(|t| t.passage.canonical_key());
+
+    let mut chapters: Vec<ListeningChapter> = Vec::new();
+    let mut played_to = 0;
+    for track in tracks {
+      let (start, end) = match track.passage.verses {
+        Some(verses) => (verses.start, verses.end),
+        None => (1, u16::MAX),
+      };
+
+      match chapters.last_mut() {
+        Some(chapter)
+          if chapter.book == track.passage.book
+            && chapter.chapter == track.passage.chapter =>
+        {
+          if start <= played_to {
+            continue;
+          }
+          chapter.tracks.push(track);
+        }
+        _ => {
+          let book_name = scripture::book(&track.passage.book)
+            .map(|b| b.name.to_string())
+            .unwrap_or_else(|| track.passage.book.clone());
+          chapters.push(ListeningChapter {
+            book: track.passage.book.clone(),
+            book_name,
+            chapter: track.passage.chapter,
+            tracks: vec![track],
+          });
+        }
+      }
+      played_to = end;
+    }
+
+    Playlist {
+      translation_name,
+      chapters,
+    }
+  }
+
+  /// The chapter after `index`, if it's in the same book. Listening carries
+  /// on through a book but stops at its end.
+  pub fn next_in_book(&self, index: usize) -> Option<usize> {
+    let current = self.chapters.get(index)?;
+    let next = self.chapters.get(index + 1)?;
+    (next.book == current.book).then_some(index + 1)
+  }
+
+  /// Finds a chapter by its [`key`](ListeningChapter::key).
+  pub fn find(&self, key: &str) -> Option<usize> {
+    self.chapters.iter().position(|c| c.key() == key)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn track(passage: &str) -> ListeningTrack {
+    ListeningTrack {
+      id:      ArtifactRecordId(ulid::Ulid::new()),
+      passage: passage.parse().unwrap(),
+    }
+  }
+
+  #[test]
+  fn playlists_play_each_verse_once_in_order() {
+    let playlist = Playlist::new("Test".to_string(), [
+      track("JHN 3:17-21"),
+      track("GEN 1"),
+      track("JHN 3:1-16"),
+      track("GEN 1:1-5"),
+      track("JHN 3:10-18"),
+      track("JHN 4"),
+      track("ACT 1"),
+    ]);
+
+    let passages = playlist
+      .chapters
+      .iter()
+      .map(|c| {
+        let tracks = c
+          .tracks
+          .iter()
+          .map(|t| t.passage.to_string())
+          .collect::<Vec<_>>();
+        (c.title(), tracks)
+      })
+      .collect::<Vec<_>>();
+    assert_eq!(passages, vec![
+      ("Genesis 1".to_string(), vec!["GEN 1".to_string()]),
+      ("John 3".to_string(), vec![
+        "JHN 3:1-16".to_string(),
+        "JHN 3:17-21".to_string()
+      ]),
+      ("John 4".to_string(), vec!["JHN 4".to_string()]),
+      ("Acts 1".to_string(), vec!["ACT 1".to_string()]),
+    ]);
+
+    assert_eq!(playlist.next_in_book(0), None);
+    assert_eq!(playlist.next_in_book(1), Some(2));
+    assert_eq!(playlist.next_in_book(2), None);
+    assert_eq!(playlist.find("JHN 4"), Some(2));
+  }
+}
@@ -0,0 +1,259 @@
+//! Who works on a translation, and what they do on it.
+//!
+//! A translation is made by a team. Members hold roles on the translation,
+//! which decide whether they can upload recordings for it and which checks
+//! they can take its recordings through. People join by accepting an
+//! invitation, which is a link a manager passes on to them.
+
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "ssr")]
+use crate::Secret;
+use crate::{
+  iso8601, PublicProfile, SiteRole, TranslationRecordId, UserRecordId,
+};
+
+pub const MEMBER_OF_TABLE: &str = "member_of";
+pub const INVITATION_TABLE: &str = "translation_invitations";
+/// How long an invitation can be accepted for.
+pub const INVITATION_LIFETIME_DAYS: i64 = 14;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "ssr", serde(from = "crate::ssr::UlidOrThing"))]
+pub struct InvitationRecordId(pub ulid::Ulid);
+
+/// What a member does on a translation.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum TranslationRole {
+  /// Runs the team: invites people and decides which takes are used.
+  Manager,
+  /// Records passages.
+  Speaker,
+  /// Checks recordings with speakers of the tongue.
+  CommunityChecker,
+  /// Checks recordings against the source text and approves them.
+  Consultant,
+}
+
+impl TranslationRole {
+  pub const ALL: [Self; 4] = [
+    Self::Manager,
+    Self::Speaker,
+    Self::CommunityChecker,
+    Self::Consultant,
+  ];
+
+  pub fn as_str(&self) -> &'static str {
+    match self {
+      Self::Manager => "manager",
+      Self::Speaker => "speaker",
+      Self::CommunityChecker => "community_checker",
+      Self::Consultant => "consultant",
+    }
+  }
+
+  pub fn parse(s: &str) -> Option<Self> {
+    Self::ALL.into_iter().find(|r| r.as_str() == s)
+  }
+
+  pub fn label(&self) -> &'static str {
+    match self {
+      Self::Manager => "Manager",
+      Self::Speaker => "Speaker",
+      Self::CommunityChecker => "Community checker",
+      Self::Consultant => "Consultant",
+    }
+  }
+
+  /// Whether holding this role lets a member upload recordings.
+  pub fn can_upload(&self) -> bool {
+    matches!(self, Self::Manager | Self::Speaker)
+  }
+
+  /// The site role this stands in for in review, within the translation.
+  fn review_role(&self) -> Option<SiteRole> {
+    match self {
+      Self::CommunityChecker => Some(SiteRole::CommunityChecker),
+      Self::Consultant => Some(SiteRole::Consultant),
+      Self::Manager | Self::Speaker => None,
+    }
+  }
+}
+
+/// The roles a user reviews a translation's recordings with.
+///
+/// Members review with their site roles and with what their roles on the
+/// translation stand in for. Non-members (`member_roles` is `None`) can't
+/// review at all, unless they're admins.
+pub fn review_roles(
+  site_roles: &[SiteRole],
+  member_roles: Option<&[TranslationRole]>,
+) -> Vec<SiteRole> {
+  let Some(member_roles) = member_roles else {
+    return site_roles
+      .iter()
+      .copied()
+      .filter(|r| *r == SiteRole::Admin)
+      .collect();
+  };
+
+  let mut roles = site_roles.to_vec();
+  for role in member_roles.iter().filter_map(TranslationRole::review_role) {
+    if !roles.contains(&role) {
+      roles.push(role);
+    }
+  }
+  roles
+}
+
+/// A user's membership of a translation, from a `member_of` edge.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Membership {
+  pub translation: TranslationRecordId,
+  pub roles:       Vec<TranslationRole>,
+}
+
+impl Membership {
+  pub fn has_role(&self, role: TranslationRole) -> bool {
+    self.roles.contains(&role)
+  }
+
+  pub fn can_upload(&self) -> bool {
+    self.roles.iter().any(TranslationRole::can_upload)
+  }
+}
+
+/// A member as shown in a translation's team.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct TranslationMember {
+  pub profile:   PublicProfile,
+  pub roles:     Vec<TranslationRole>,
+  #[serde(with = "iso8601")]
+  pub joined_at: time::OffsetDateTime,
+}
+
+/// What became of an invitation.
+#[derive(
+  Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq, Hash,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum InvitationStatus {
+  /// Waiting on an answer. Link invitations stay pending until they expire
+  /// or are revoked, so anyone with the link can use them.
+  #[default]
+  Pending,
+  Accepted,
+  Declined,
+  Revoked,
+}
+
+/// An invitation to join a translation's team.
+///
+/// Every invitation is a link carrying a secret, which is needed to answer
+/// it. Invitations addressed to an email are used up once answered, since
+/// emails aren't verified and can't prove who's answering. Invitations
+/// without one can be used by anyone with the link until they expire.
+#[cfg(feature = "ssr")]
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Invitation {
+  pub id:          InvitationRecordId,
+  #[serde(serialize_with = "crate::ssr::serialize_record_link")]
+  pub translation: TranslationRecordId,
+  pub roles:       Vec<TranslationRole>,
+  #[serde(default)]
+  pub email:       Option<crate::Pii>,
+  /// The hex-encoded SHA-256 hash of the link's secret.
+  pub secret_hash: String,
+  #[serde(serialize_with = "crate::ssr::serialize_record_link")]
+  pub invited_by:  UserRecordId,
+  pub status:      InvitationStatus,
+  #[serde(with = "iso8601")]
+  pub created_at:  time::OffsetDateTime,
+  #[serde(with = "iso8601")]
+  pub expires_at:  time::OffsetDateTime,
+}
+
+#[cfg(feature = "ssr")]
+impl Invitation {
+  /// Whether the invitation can still be accepted.
+  pub fn is_open(&self) -> bool {
+    self.status == InvitationStatus::Pending
+      && self.expires_at > time::OffsetDateTime::now_utc()
+  }
+}
+
+/// The link that opens an invitation. Like API tokens, it carries the
+/// record id alongside the secret so the record can be found directly.
+#[cfg(feature = "ssr")]
+pub fn invitation_path(id: InvitationRecordId, secret: &Secret) -> String {
+  format!("/invitation/{}_{}", id.0, secret.expose())
+}
+
+/// Splits the token at the end of an invitation link into its id and secret.
+pub fn parse_invitation_token(
+  token: &str,
+) -> Option<(InvitationRecordId, &str)> {
+  let (id, secret) = token.split_once('_')?;
+  Some((InvitationRecordId(id.parse().ok()?), secret))
+}
+
+/// An invitation as shown to the people it's for, and to the team's
+/// managers.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct InvitationSummary {
+  pub id:               InvitationRecordId,
+  pub translation:      TranslationRecordId,
+  pub translation_name: String,
+  pub roles:            Vec<TranslationRole>,
+  /// Who it's addressed to, or `None` for a link anyone can use.
+  pub email:            Option<String>,
+  pub invited_by:       Option<PublicProfile>,
+  #[serde(with = "iso8601")]
+  pub expires_at:       time::OffsetDateTime,
+}
+
+/// A translation's team, with what the current user can do to it.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct TranslationTeam {
+  pub members:     Vec<TranslationMember>,
+  /// Open invitations. Only managers see these.
+  pub invitations: Vec<InvitationSummary>,
+  pub can_manage:  bool,
+  /// The current user, if they're logged in.
+  pub viewer:      Option<UserRecordId>,
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn only_members_review_with_their_roles() {
+    use TranslationRole::*;
+
+    assert_eq!(review_roles(&[SiteRole::Consultant], None), vec![]);
+    assert_eq!(review_roles(&[SiteRole::Admin], None), vec![
+      SiteRole::Admin
+    ]);
+    assert_eq!(
+      review_roles(&[SiteRole::Consultant], Some(&[Speaker])),
+      vec![SiteRole::Consultant]
+    );
+    assert_eq!(review_roles(&[], Some(&[Manager, CommunityChecker])), vec![
+      SiteRole::CommunityChecker
+    ]);
+  }
+
+  #[test]
+  fn invitation_tokens_split_into_id_and_secret() {
+    let id = ulid::Ulid::new();
+    let token = format!("{id}_abc123");
+    assert_eq!(
+      parse_invitation_token(&token),
+      Some((InvitationRecordId(id), "abc123"))
+    );
+    assert_eq!(parse_invitation_token("not-a-token"), None);
+    assert_eq!(parse_invitation_token("nope_abc123"), None);
+  }
+}
@@ -1,5 +1,10 @@
 use serde::{Deserialize, Serialize};
 
+use crate::{
+  iso8601, iso_639_3::Language, AppError, ArtifactRecordId, PublicProfile,
+  TranslationListing,
+};
+
 pub const MOTHER_TONGUE_TABLE: &str = "mother_tongues";
 
 #[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
@@ -8,8 +13,208 @@ pub struct MotherTongueRecordId(pub ulid::Ulid);
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct MotherTongue {
-  pub id:          MotherTongueRecordId,
-  pub name:        String,
-  pub description: String,
-  pub is_vetted:   bool,
+  pub id:               MotherTongueRecordId,
+  pub name:             String,
+  pub description:      String,
+  /// Other names for the tongue, including what its speakers call it.
+  #[serde(default)]
+  pub alternate_names:  Vec<String>,
+  /// Where the tongue is spoken, in the proposer's words.
+  #[serde(default)]
+  pub region:           Option<String>,
+  /// ISO 3166-1 alpha-2 codes of the countries the tongue is spoken in.
+  #[serde(default)]
+  pub countries:        Vec<String>,
+  /// The ISO 639-3 code of the language, if it has one.
+  #[serde(default)]
+  pub iso_639_3:        Option<String>,
+  /// The ISO 639-3 code of the language this is a variety of, for tongues
+  /// without a code of their own.
+  #[serde(default)]
+  pub parent_language:  Option<String>,
+  /// The ISO 15924 code of the script the tongue is usually written in, like
+  /// `Latn`.
+  #[serde(default)]
+  pub script:           Option<String>,
+  /// Whether a reviewer has approved the tongue. Only vetted tongues are
+  /// listed by default.
+  pub is_vetted:        bool,
+  /// Why a reviewer turned the tongue down, if they did.
+  #[serde(default)]
+  pub rejection_reason: Option<String>,
+}
+
+/// Where a mother tongue is in vetting.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum VettingStatus {
+  Pending,
+  Vetted,
+  Rejected,
+}
+
+impl MotherTongue {
+  pub fn vetting_status(&self) -> VettingStatus {
+    match (self.is_vetted, &self.rejection_reason) {
+      (true, _) => VettingStatus::Vetted,
+      (false, Some(_)) => VettingStatus::Rejected,
+      (false, None) => VettingStatus::Pending,
+    }
+  }
+
+  /// Fails with [`AppError::Conflict`] unless the tongue is waiting for
+  /// review. Reviewers only decide on proposals, so a vetted tongue can't be
+  /// unlisted or merged away.
+  fn check_pending(&self) -> Result<(), AppError> {
+    match self.vetting_status() {
+      VettingStatus::Pending => Ok(()),
+      VettingStatus::Vetted => Err(AppError::Conflict(
+        "That mother tongue has already been approved.".to_string(),
+      )),
+      VettingStatus::Rejected => Err(AppError::Conflict(
+        "That mother tongue has already been rejected.".to_string(),
+      )),
+    }
+  }
+
+  /// Vets a proposed tongue, listing it publicly.
+  pub fn approve(mut self) -> Result<Self, AppError> {
+    self.check_pending()?;
+    self.is_vetted = true;
+    Ok(self)
+  }
+
+  /// Turns down a proposed tongue, with the reason shown on its page.
+  pub fn reject(mut self, reason: String) -> Result<Self, AppError> {
+    self.check_pending()?;
+    self.rejection_reason = Some(reason);
+    Ok(self)
+  }
+
+  /// Checks that this proposed tongue can be merged into `target`, which
+  /// must be a different, vetted tongue.
+  pub fn check_merge_into(
+    &self,
+    target: &MotherTongue,
+  ) -> Result<(), AppError> {
+    if self.id == target.id {
+      return Err(AppError::Conflict(
+        "A mother tongue can't be merged into itself.".to_string(),
+      ));
+    }
+    self.check_pending()?;
+    if !target.is_vetted {
+      return Err(AppError::Conflict(
+        "Tongues can only be merged into a vetted tongue.".to_string(),
+      ));
+    }
+    Ok(())
+  }
+}
+
+/// A mother tongue waiting in the vetting queue.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct TongueProposal {
+  pub tongue:      MotherTongue,
+  pub proposer:    Option<PublicProfile>,
+  #[serde(with = "iso8601::option")]
+  pub proposed_at: Option<time::OffsetDateTime>,
+}
+
+/// Everything shown on a mother tongue's page.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct MotherTongueDetails {
+  pub tongue:           MotherTongue,
+  /// The ISO 639-3 entry for the tongue's code.
+  pub language:         Option<Language>,
+  /// The ISO 639-3 entry for the tongue's parent language.
+  pub parent_language:  Option<Language>,
+  /// Whoever added the tongue. Tongues from migrations have no creator.
+  pub creator:          Option<PublicProfile>,
+  pub translations:     Vec<TranslationListing>,
+  /// A recording from one of the translations, to hear what the tongue
+  /// sounds like.
+  pub sample_recording: Option<ArtifactRecordId>,
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn tongue(status: VettingStatus) -> MotherTongue {
+    MotherTongue {
+      id:               MotherTongueRecordId(ulid::Ulid::new()),
+      name:             "Example".to_string(),
+      description:      String::new(),
+      alternate_names:  Vec::new(),
+      region:           None,
+      countries:        Vec::new(),
+      iso_639_3:        None,
+      parent_language:  None,
+      script:           None,
+      is_vetted:        status == VettingStatus::Vetted,
+      rejection_reason: (status == VettingStatus::Rejected)
+        .then(|| "Not a language".to_string()),
+    }
+  }
+
+  #[test]
+  fn only_pending_tongues_are_approved() {
+    let approved = tongue(VettingStatus::Pending).approve().unwrap();
+    assert_eq!(approved.vetting_status(), VettingStatus::Vetted);
+
+    assert!(matches!(
+      tongue(VettingStatus::Vetted).approve(),
+      Err(AppError::Conflict(_))
+    ));
+    assert!(matches!(
+      tongue(VettingStatus::Rejected).approve(),
+      Err(AppError::Conflict(_))
+    ));
+  }
+
+  #[test]
+  fn only_pending_tongues_are_rejected() {
+    let rejected = tongue(VettingStatus::Pending)
+      .reject("Duplicate".to_string())
+      .unwrap();
+    assert_eq!(rejected.vetting_status(), VettingStatus::Rejected);
+    assert_eq!(rejected.rejection_reason.as_deref(), Some("Duplicate"));
+
+    // rejecting a vetted tongue would unlist it and its translations
+    assert!(matches!(
+      tongue(VettingStatus::Vetted).reject("Duplicate".to_string()),
+      Err(AppError::Conflict(_))
+    ));
+    assert!(matches!(
+      tongue(VettingStatus::Rejected).reject("Duplicate".to_string()),
+      Err(AppError::Conflict(_))
+    ));
+  }
+
+  #[test]
+  fn only_pending_tongues_are_merged_into_vetted_ones() {
+    let target = tongue(VettingStatus::Vetted);
+
+    assert!(tongue(VettingStatus::Pending)
+      .check_merge_into(&target)
+      .is_ok());
+    // merging deletes the tongue, so vetted ones can't be merged away
+    assert!(matches!(
+      tongue(VettingStatus::Vetted).check_merge_into(&target),
+      Err(AppError::Conflict(_))
+    ));
+    assert!(matches!(
+      tongue(VettingStatus::Rejected).check_merge_into(&target),
+      Err(AppError::Conflict(_))
+    ));
+    assert!(matches!(
+      tongue(VettingStatus::Pending)
+        .check_merge_into(&tongue(VettingStatus::Pending)),
+      Err(AppError::Conflict(_))
+    ));
+    assert!(matches!(
+      target.check_merge_into(&target),
+      Err(AppError::Conflict(_))
+    ));
+  }
 }
@@ -0,0 +1,102 @@
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "ssr")]
+use crate::UserRecordId;
+use crate::{iso8601, Pii};
+
+pub const OIDC_IDENTITY_TABLE: &str = "oidc_identities";
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "ssr", serde(from = "crate::ssr::UlidOrThing"))]
+pub struct OidcIdentityRecordId(pub ulid::Ulid);
+
+/// An account at an external OpenID Connect provider, linked to a user.
+///
+/// Identities are matched by `issuer` and `subject`, which the provider
+/// guarantees are stable. The email is only kept for display.
+#[cfg(feature = "ssr")]
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct OidcIdentity {
+  pub id:              OidcIdentityRecordId,
+  #[serde(serialize_with = "crate::ssr::serialize_record_link")]
+  pub user:            UserRecordId,
+  pub issuer:          String,
+  pub subject:         String,
+  pub email:           Option<Pii>,
+  /// Whether the user signed up through this identity, in which case they
+  /// have no usable password and it can't be unlinked.
+  pub created_account: bool,
+  #[serde(with = "iso8601")]
+  pub linked_at:       time::OffsetDateTime,
+}
+
+/// The parts of an [`OidcIdentity`] that are safe to show the user.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct OidcIdentitySummary {
+  pub id:              OidcIdentityRecordId,
+  pub email:           Option<Pii>,
+  pub created_account: bool,
+  #[serde(with = "iso8601")]
+  pub linked_at:       time::OffsetDateTime,
+}
+
+#[cfg(feature = "ssr")]
+impl From<OidcIdentity> for OidcIdentitySummary {
+  fn from(value: OidcIdentity) -> Self {
+    OidcIdentitySummary {
+      id:              value.id,
+      email:           value.email,
+      created_account: value.created_account,
+      linked_at:       value.linked_at,
+    }
+  }
+}
+
+/// Why an OIDC login was sent back to the login page.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OidcLoginError {
+  /// An account with the provider's email already exists, and must be linked
+  /// from the account page after logging in another way.
+  LinkRequired,
+  /// The provider didn't give us a verified email to sign up with.
+  EmailUnverified,
+  /// The flow failed or expired.
+  Failed,
+}
+
+impl OidcLoginError {
+  /// The value used in the `oidc_error` query parameter.
+  pub fn as_query(&self) -> &'static str {
+    match self {
+      OidcLoginError::LinkRequired => "link_required",
+      OidcLoginError::EmailUnverified => "email_unverified",
+      OidcLoginError::Failed => "failed",
+    }
+  }
+
+  pub fn from_query(value: &str) -> Option<Self> {
+    match value {
+      "link_required" => Some(OidcLoginError::LinkRequired),
+      "email_unverified" => Some(OidcLoginError::EmailUnverified),
+      "failed" => Some(OidcLoginError::Failed),
+      _ => None,
+    }
+  }
+
+  pub fn message(&self, provider: &str) -> String {
+    match self {
+      OidcLoginError::LinkRequired => format!(
+        "An account with your {provider} email already exists. Log in with \
+         your password, then link {provider} from your account page."
+      ),
+      OidcLoginError::EmailUnverified => format!(
+        "{provider} didn't confirm your email address, so we can't create an \
+         account with it."
+      ),
+      OidcLoginError::Failed => {
+        format!("Logging in with {provider} failed. Please try again.")
+      }
+    }
+  }
+}
@@ -0,0 +1,51 @@
+use serde::{Deserialize, Serialize};
+
+use crate::iso8601;
+#[cfg(feature = "ssr")]
+use crate::UserRecordId;
+
+pub const PASSKEY_TABLE: &str = "passkeys";
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "ssr", serde(from = "crate::ssr::UlidOrThing"))]
+pub struct PasskeyRecordId(pub ulid::Ulid);
+
+/// A WebAuthn passkey registered to a user.
+#[cfg(feature = "ssr")]
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Passkey {
+  pub id:           PasskeyRecordId,
+  #[serde(serialize_with = "crate::ssr::serialize_record_link")]
+  pub user:         UserRecordId,
+  /// A user-chosen label, like "Work phone".
+  pub name:         String,
+  /// The serialized `webauthn_rs` passkey, holding the public key.
+  pub credential:   serde_json::Value,
+  #[serde(with = "iso8601")]
+  pub created_at:   time::OffsetDateTime,
+  #[serde(default, with = "iso8601::option")]
+  pub last_used_at: Option<time::OffsetDateTime>,
+}
+
+/// The parts of a [`Passkey`] that are safe to show the user.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct PasskeySummary {
+  pub id:           PasskeyRecordId,
+  pub name:         String,
+  #[serde(with = "iso8601")]
+  pub created_at:   time::OffsetDateTime,
+  #[serde(with = "iso8601::option")]
+  pub last_used_at: Option<time::OffsetDateTime>,
+}
+
+#[cfg(feature = "ssr")]
+impl From<Passkey> for PasskeySummary {
+  fn from(value: Passkey) -> Self {
+    PasskeySummary {
+      id:           value.id,
+      name:         value.name,
+      created_at:   value.created_at,
+      last_used_at: value.last_used_at,
+    }
+  }
+}
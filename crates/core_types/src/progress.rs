@@ -0,0 +1,164 @@
+//! How much of the bible a translation has recorded.
+
+use serde::{Deserialize, Serialize};
+
+use crate::scripture::{Passage, BOOKS};
+#[cfg(feature = "ssr")]
+use crate::{MotherTongueRecordId, TranslationRecordId};
+
+/// A passage that has a recording in a translation.
+#[cfg(feature = "ssr")]
+#[derive(Clone, Debug, Deserialize)]
+pub struct RecordedPassage {
+  pub translation:   TranslationRecordId,
+  pub mother_tongue: MotherTongueRecordId,
+  pub passage:       Passage,
+}
+
+/// The share of a chapter's verses that have been recorded.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChapterProgress {
+  pub recorded: u16,
+  pub total:    u16,
+}
+
+impl ChapterProgress {
+  pub fn percent(&self) -> f32 {
+    percent(self.recorded.into(), self.total.into())
+  }
+}
+
+/// Progress through one book, chapter by chapter.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct BookProgress {
+  /// The book's USFM code.
+  pub code:     String,
+  pub name:     String,
+  /// Progress in each chapter, starting from chapter 1.
+  pub chapters: Vec<ChapterProgress>,
+}
+
+impl BookProgress {
+  pub fn recorded_verses(&self) -> u32 {
+    self.chapters.iter().map(|c| u32::from(c.recorded)).sum()
+  }
+
+  pub fn total_verses(&self) -> u32 {
+    self.chapters.iter().map(|c| u32::from(c.total)).sum()
+  }
+
+  pub fn percent(&self) -> f32 {
+    percent(self.recorded_verses(), self.total_verses())
+  }
+}
+
+/// Progress through the whole bible, book by book.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct TranslationProgress {
+  pub books: Vec<BookProgress>,
+}
+
+impl TranslationProgress {
+  /// Works out which verses the given passages cover. Overlapping passages
+  /// only count each verse once, and invalid passages are ignored.
+  pub fn from_passages<'a>(
+    passages: impl IntoIterator<Item = &'a Passage>,
+  ) -> Self {
+    // one flag per verse, per chapter, per book
+    let mut covered = BOOKS
+      .iter()
+      .map(|book| {
+        book
+          .verses
+          .iter()
+          .map(|&count| vec![false; count.into()])
+          .collect::<Vec<_>>()
+      })
+      .collect::<Vec<_>>();
+
+    for passage in passages {
+      let (Ok(book), Some(range)) = (passage.validate(), passage.verse_range())
+      else {
+        continue;
+      };
+      let Some(book_index) = BOOKS.iter().position(|b| b == book) else {
+        continue;
+      };
+      let chapter = &mut covered[book_index][usize::from(passage.chapter) - 1];
+      for verse in range.start..=range.end {
+        chapter[usize::from(verse) - 1] = true;
+      }
+    }
+
+    let books = BOOKS
+      .iter()
+      .zip(covered)
+      .map(|(book, chapters)| BookProgress {
+        code:     book.code.to_string(),
+        name:     book.name.to_string(),
+        chapters: chapters
+          .into_iter()
+          .map(|verses| ChapterProgress {
+            recorded: verses.iter().filter(|&&v| v).count() as u16,
+            total:    verses.len() as u16,
+          })
+          .collect(),
+      })
+      .collect();
+
+    TranslationProgress { books }
+  }
+
+  pub fn recorded_verses(&self) -> u32 {
+    self.books.iter().map(BookProgress::recorded_verses).sum()
+  }
+
+  pub fn total_verses(&self) -> u32 {
+    self.books.iter().map(BookProgress::total_verses).sum()
+  }
+
+  pub fn percent(&self) -> f32 {
+    percent(self.recorded_verses(), self.total_verses())
+  }
+}
+
+fn percent(recorded: u32, total: u32) -> f32 {
+  match total {
+    0 => 0.0,
+    total => recorded as f32 * 100.0 / total as f32,
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn progress_counts_each_verse_once() {
+    let passages = ["JHN 3:1-10", "JHN 3:5-20", "JHN 3:16", "JUD 1", "XYZ 1"]
+      .into_iter()
+      .filter_map(|r| r.parse::<Passage>().ok())
+      .chain([Passage {
+        book:    "JHN".to_string(),
+        chapter: 40,
+        verses:  None,
+      }])
+      .collect::<Vec<_>>();
+    let progress = TranslationProgress::from_passages(&passages);
+
+    assert_eq!(progress.books.len(), 66);
+    assert_eq!(progress.total_verses(), 31102);
+    assert_eq!(progress.recorded_verses(), 20 + 25);
+
+    let john = progress.books.iter().find(|b| b.code == "JHN").unwrap();
+    assert_eq!(john.chapters.len(), 21);
+    assert_eq!(john.chapters[2], ChapterProgress {
+      recorded: 20,
+      total:    36,
+    });
+    assert_eq!(john.chapters[3].recorded, 0);
+
+    let jude = progress.books.iter().find(|b| b.code == "JUD").unwrap();
+    assert_eq!(jude.percent(), 100.0);
+  }
+}
@@ -0,0 +1,80 @@
+//! Wrappers for sensitive strings that keep them out of logs.
+//!
+//! [`Secret`] is for credentials like passwords and tokens, and [`Pii`] is for
+//! personal information like emails. Both print as `[redacted]` in `Debug`
+//! and `Display` (so also in `tracing` fields), serialize transparently, and
+//! zero their memory when dropped. Use `expose()` to get the value where it's
+//! actually needed.
+
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+use zeroize::Zeroize;
+
+macro_rules! redacted_string {
+  ($(#[$meta:meta])* $name:ident) => {
+    $(#[$meta])*
+    #[derive(Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+    #[serde(transparent)]
+    pub struct $name(String);
+
+    impl $name {
+      pub fn new(value: impl Into<String>) -> Self { $name(value.into()) }
+
+      /// Gets the underlying value. Don't log it.
+      pub fn expose(&self) -> &str { &self.0 }
+    }
+
+    impl From<String> for $name {
+      fn from(value: String) -> Self { $name(value) }
+    }
+
+    impl From<&str> for $name {
+      fn from(value: &str) -> Self { $name(value.to_string()) }
+    }
+
+    impl fmt::Debug for $name {
+      fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("[redacted]")
+      }
+    }
+
+    impl fmt::Display for $name {
+      fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("[redacted]")
+      }
+    }
+
+    impl Drop for $name {
+      fn drop(&mut self) { self.0.zeroize(); }
+    }
+  };
+}
+
+redacted_string!(
+  /// A credential, like a password, token or one-time code.
+  Secret
+);
+
+redacted_string!(
+  /// Personal information, like an email address. It's fine to show this to
+  /// its owner, but it shouldn't end up in logs.
+  Pii
+);
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn redacted_strings_hide_their_values() {
+    let password = Secret::new("hunter22");
+    assert_eq!(format!("{password:?}"), "[redacted]");
+    assert_eq!(password.to_string(), "[redacted]");
+    assert_eq!(password.expose(), "hunter22");
+
+    let email = Pii::new("main@jlewis.sh");
+    assert_eq!(format!("{:?}", Some(&email)), "Some([redacted])");
+    assert_eq!(serde_json::to_string(&email).unwrap(), "\"main@jlewis.sh\"");
+  }
+}
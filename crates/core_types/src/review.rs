@@ -0,0 +1,255 @@
+//! The checks a recording goes through before it's published.
+//!
+//! A recording starts as a draft. Its uploader submits it, community
+//! checkers listen to it with speakers of the tongue, and a consultant
+//! checks it against the source text before approving it. Either check can
+//! reject it, after which the uploader can rework it as a draft.
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+  iso8601, roles_grant, scripture::Passage, ArtifactRecordId, Membership,
+  PublicProfile, SiteRole, TranslationRecordId, User, UserRecordId,
+};
+
+pub const REVIEWED_RECORDING_TABLE: &str = "reviewed_recording";
+
+/// Where a recording is in review.
+#[derive(
+  Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum RecordingStatus {
+  #[default]
+  Draft,
+  Submitted,
+  CommunityCheck,
+  ConsultantCheck,
+  Approved,
+  Rejected,
+}
+
+/// Who may make a move between statuses.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ReviewActor {
+  /// The user who uploaded the recording.
+  Uploader,
+  /// Anyone holding the role.
+  Role(SiteRole),
+}
+
+impl RecordingStatus {
+  pub const ALL: [Self; 6] = [
+    Self::Draft,
+    Self::Submitted,
+    Self::CommunityCheck,
+    Self::ConsultantCheck,
+    Self::Approved,
+    Self::Rejected,
+  ];
+
+  pub fn as_str(&self) -> &'static str {
+    match self {
+      Self::Draft => "draft",
+      Self::Submitted => "submitted",
+      Self::CommunityCheck => "community_check",
+      Self::ConsultantCheck => "consultant_check",
+      Self::Approved => "approved",
+      Self::Rejected => "rejected",
+    }
+  }
+
+  pub fn parse(s: &str) -> Option<Self> {
+    Self::ALL.into_iter().find(|f| f.as_str() == s)
+  }
+
+  pub fn label(&self) -> &'static str {
+    match self {
+      Self::Draft => "Draft",
+      Self::Submitted => "Submitted",
+      Self::CommunityCheck => "In community check",
+      Self::ConsultantCheck => "In consultant check",
+      Self::Approved => "Approved",
+      Self::Rejected => "Rejected",
+    }
+  }
+
+  /// What moving a recording to this status is called, for buttons.
+  pub fn action_label(&self) -> &'static str {
+    match self {
+      Self::Draft => "Return to draft",
+      Self::Submitted => "Submit",
+      Self::CommunityCheck => "Start community check",
+      Self::ConsultantCheck => "Pass to consultant",
+      Self::Approved => "Approve",
+      Self::Rejected => "Reject",
+    }
+  }
+
+  /// Who may move a recording from this status to `to`, or `None` if it
+  /// can't be moved there at all.
+  pub fn actor_for(&self, to: RecordingStatus) -> Option<ReviewActor> {
+    use RecordingStatus::*;
+
+    match (self, to) {
+      (Draft, Submitted) | (Submitted, Draft) | (Rejected, Draft) => {
+        Some(ReviewActor::Uploader)
+      }
+      (Submitted, CommunityCheck)
+      | (CommunityCheck, ConsultantCheck)
+      | (CommunityCheck, Rejected) => {
+        Some(ReviewActor::Role(SiteRole::CommunityChecker))
+      }
+      (ConsultantCheck, Approved) | (ConsultantCheck, Rejected) => {
+        Some(ReviewActor::Role(SiteRole::Consultant))
+      }
+      _ => None,
+    }
+  }
+
+  /// The statuses a user can move a recording to from this one.
+  pub fn next_statuses(
+    &self,
+    is_uploader: bool,
+    roles: &[SiteRole],
+  ) -> Vec<RecordingStatus> {
+    Self::ALL
+      .into_iter()
+      .filter(|&to| match self.actor_for(to) {
+        Some(ReviewActor::Uploader) => is_uploader,
+        Some(ReviewActor::Role(role)) => roles_grant(roles, role),
+        None => false,
+      })
+      .collect()
+  }
+
+  /// Whether a recording in this status is waiting on its uploader, rather
+  /// than on a checker.
+  pub fn waits_on_uploader(&self) -> bool {
+    matches!(self, Self::Draft | Self::Rejected)
+  }
+
+  /// The statuses holders of `roles` have work to do in.
+  pub fn waiting_on_roles(roles: &[SiteRole]) -> Vec<RecordingStatus> {
+    Self::ALL
+      .into_iter()
+      .filter(|from| {
+        Self::ALL.into_iter().any(|to| {
+          matches!(
+            from.actor_for(to),
+            Some(ReviewActor::Role(role)) if roles_grant(roles, role)
+          )
+        })
+      })
+      .collect()
+  }
+
+  /// Whether moving a recording to this status needs a comment saying why.
+  pub fn needs_comment(&self) -> bool { matches!(self, Self::Rejected) }
+}
+
+/// A recording as shown in the review queue.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct RecordingSummary {
+  pub id:               ArtifactRecordId,
+  pub status:           RecordingStatus,
+  pub translation:      TranslationRecordId,
+  pub translation_name: String,
+  #[serde(default)]
+  pub passage:          Option<Passage>,
+  pub uploader:         Option<PublicProfile>,
+  #[serde(with = "iso8601::option")]
+  pub uploaded_at:      Option<time::OffsetDateTime>,
+}
+
+impl RecordingSummary {
+  pub fn is_uploaded_by(&self, user: UserRecordId) -> bool {
+    self.uploader.as_ref().is_some_and(|u| u.id == user)
+  }
+
+  /// Whether `user` can see this recording's review, and hear it before
+  /// it's approved: its uploader, members of its translation and admins can.
+  /// `membership` is the user's membership of the recording's translation.
+  pub fn is_visible_to(
+    &self,
+    user: &User,
+    membership: Option<&Membership>,
+  ) -> bool {
+    self.is_uploaded_by(user.id)
+      || user.has_role(SiteRole::Admin)
+      || membership.is_some()
+  }
+}
+
+/// A recording in the review queue, with what the current user can do to
+/// it.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct QueuedRecording {
+  pub recording:     RecordingSummary,
+  /// The statuses the current user can move the recording to.
+  pub next_statuses: Vec<RecordingStatus>,
+  /// Whether the current user can comment on the recording.
+  pub can_comment:   bool,
+}
+
+/// Something that happened to a recording in review: a move between
+/// statuses, a comment, or both.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ReviewEvent {
+  pub by:       Option<PublicProfile>,
+  #[serde(with = "iso8601")]
+  pub at:       time::OffsetDateTime,
+  /// The status the recording moved from, if it moved.
+  #[serde(default)]
+  pub previous: Option<RecordingStatus>,
+  /// The status the recording moved to, if it moved.
+  #[serde(default)]
+  pub status:   Option<RecordingStatus>,
+  #[serde(default)]
+  pub comment:  Option<String>,
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn transitions_are_restricted_by_role() {
+    use RecordingStatus::*;
+
+    assert_eq!(Draft.next_statuses(true, &[]), vec![Submitted]);
+    assert_eq!(Draft.next_statuses(false, &[SiteRole::Admin]), vec![]);
+    assert_eq!(
+      Submitted.next_statuses(false, &[SiteRole::CommunityChecker]),
+      vec![CommunityCheck]
+    );
+    assert_eq!(
+      CommunityCheck.next_statuses(false, &[SiteRole::Consultant]),
+      vec![]
+    );
+    assert_eq!(
+      ConsultantCheck.next_statuses(false, &[SiteRole::Admin]),
+      vec![Approved, Rejected]
+    );
+    assert_eq!(Approved.next_statuses(true, &[SiteRole::Admin]), vec![]);
+    assert_eq!(Rejected.next_statuses(true, &[]), vec![Draft]);
+  }
+
+  #[test]
+  fn roles_wait_on_the_statuses_they_can_move() {
+    use RecordingStatus::*;
+
+    assert_eq!(
+      RecordingStatus::waiting_on_roles(&[SiteRole::CommunityChecker]),
+      vec![Submitted, CommunityCheck]
+    );
+    assert_eq!(
+      RecordingStatus::waiting_on_roles(&[SiteRole::Consultant]),
+      vec![ConsultantCheck]
+    );
+    assert_eq!(
+      RecordingStatus::waiting_on_roles(&[SiteRole::Reviewer]),
+      vec![]
+    );
+  }
+}
@@ -0,0 +1,715 @@
+//! The books of the bible, and references to passages in them.
+//!
+//! Chapter and verse counts follow the versification of the King James
+//! Version, which most English translations share. Books are identified by
+//! their USFM codes, like `JHN` for John.
+
+use std::{fmt, str::FromStr};
+
+use serde::{Deserialize, Serialize};
+
+/// A book of the bible.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Book {
+  /// The USFM code, like `"JHN"`.
+  pub code:   &'static str,
+  pub name:   &'static str,
+  /// How many verses are in each chapter, starting from chapter 1.
+  pub verses: &'static [u16],
+}
+
+impl Book {
+  pub fn chapter_count(&self) -> u16 { self.verses.len() as u16 }
+
+  /// How many verses are in a chapter, or `None` if the book doesn't have
+  /// that chapter.
+  pub fn verse_count(&self, chapter: u16) -> Option<u16> {
+    let index = usize::from(chapter).checked_sub(1)?;
+    self.verses.get(index).copied()
+  }
+
+  pub fn total_verses(&self) -> u32 {
+    self.verses.iter().map(|&v| u32::from(v)).sum()
+  }
+}
+
+/// Every book, in canonical order.
+pub const BOOKS: &[Book] = &[
+  Book {
+    code:   "GEN",
+    name:   "Genesis",
+    verses: &[
+      31, 25, 24, 26, 32, 22, 24, 22, 29, 32, 32, 20, 18, 24, 21, 16, 27, 33,
+      38, 18, 34, 24, 20, 67, 34, 35, 46, 22, 35, 43, 55, 32, 20, 31, 29, 43,
+      36, 30, 23, 23, 57, 38, 34, 34, 28, 34, 31, 22, 33, 26,
+    ],
+  },
+  Book {
+    code:   "EXO",
+    name:   "Exodus",
+    verses: &[
+      22, 25, 22, 31, 23, 30, 25, 32, 35, 29, 10, 51, 22, 31, 27, 36, 16, 27,
+      25, 26, 36, 31, 33, 18, 40, 37, 21, 43, 46, 38, 18, 35, 23, 35, 35, 38,
+      29, 31, 43, 38,
+    ],
+  },
+  Book {
+    code:   "LEV",
+    name:   "Leviticus",
+    verses: &[
+      17, 16, 17, 35, 19, 30, 38, 36, 24, 20, 47, 8, 59, 57, 33, 34, 16, 30,
+      37, 27, 24, 33, 44, 23, 55, 46, 34,
+    ],
+  },
+  Book {
+    code:   "NUM",
+    name:   "Numbers",
+    verses: &[
+      54, 34, 51, 49, 31, 27, 89, 26, 23, 36, 35, 16, 33, 45, 41, 50, 13, 32,
+      22, 29, 35, 41, 30, 25, 18, 65, 23, 31, 40, 16, 54, 42, 56, 29, 34, 13,
+    ],
+  },
+  Book {
+    code:   "DEU",
+    name:   "Deuteronomy",
+    verses: &[
+      46, 37, 29, 49, 33, 25, 26, 20, 29, 22, 32, 32, 18, 29, 23, 22, 20, 22,
+      21, 20, 23, 30, 25, 22, 19, 19, 26, 68, 29, 20, 30, 52, 29, 12,
+    ],
+  },
+  Book {
+    code:   "JOS",
+    name:   "Joshua",
+    verses: &[
+      18, 24, 17, 24, 15, 27, 26, 35, 27, 43, 23, 24, 33, 15, 63, 10, 18, 28,
+      51, 9, 45, 34, 16, 33,
+    ],
+  },
+  Book {
+    code:   "JDG",
+    name:   "Judges",
+    verses: &[
+      36, 23, 31, 24, 31, 40, 25, 35, 57, 18, 40, 15, 25, 20, 20, 31, 13, 31,
+      30, 48, 25,
+    ],
+  },
+  Book {
+    code:   "RUT",
+    name:   "Ruth",
+    verses: &[22, 23, 18, 22],
+  },
+  Book {
+    code:   "1SA",
+    name:   "1 Samuel",
+    verses: &[
+      28, 36, 21, 22, 12, 21, 17, 22, 27, 27, 15, 25, 23, 52, 35, 23, 58, 30,
+      24, 42, 15, 23, 29, 22, 44, 25, 12, 25, 11, 31, 13,
+    ],
+  },
+  Book {
+    code:   "2SA",
+    name:   "2 Samuel",
+    verses: &[
+      27, 32, 39, 12, 25, 23, 29, 18, 13, 19, 27, 31, 39, 33, 37, 23, 29, 33,
+      43, 26, 22, 51, 39, 25,
+    ],
+  },
+  Book {
+    code:   "1KI",
+    name:   "1 Kings",
+    verses: &[
+      53, 46, 28, 34, 18, 38, 51, 66, 28, 29, 43, 33, 34, 31, 34, 34, 24, 46,
+      21, 43, 29, 53,
+    ],
+  },
+  Book {
+    code:   "2KI",
+    name:   "2 Kings",
+    verses: &[
+      18, 25, 27, 44, 27, 33, 20, 29, 37, 36, 21, 21, 25, 29, 38, 20, 41, 37,
+      37, 21, 26, 20, 37, 20, 30,
+    ],
+  },
+  Book {
+    code:   "1CH",
+    name:   "1 Chronicles",
+    verses: &[
+      54, 55, 24, 43, 26, 81, 40, 40, 44, 14, 47, 40, 14, 17, 29, 43, 27, 17,
+      19, 8, 30, 19, 32, 31, 31, 32, 34, 21, 30,
+    ],
+  },
+  Book {
+    code:   "2CH",
+    name:   "2 Chronicles",
+    verses: &[
+      17, 18, 17, 22, 14, 42, 22, 18, 31, 19, 23, 16, 22, 15, 19, 14, 19, 34,
+      11, 37, 20, 12, 21, 27, 28, 23, 9, 27, 36, 27, 21, 33, 25, 33, 27, 23,
+    ],
+  },
+  Book {
+    code:   "EZR",
+    name:   "Ezra",
+    verses: &[11, 70, 13, 24, 17, 22, 28, 36, 15, 44],
+  },
+  Book {
+    code:   "NEH",
+    name:   "Nehemiah",
+    verses: &[11, 20, 32, 23, 19, 19, 73, 18, 38, 39, 36, 47, 31],
+  },
+  Book {
+    code:   "EST",
+    name:   "Esther",
+    verses: &[22, 23, 15, 17, 14, 14, 10, 17, 32, 3],
+  },
+  Book {
+    code:   "JOB",
+    name:   "Job",
+    verses: &[
+      22, 13, 26, 21, 27, 30, 21, 22, 35, 22, 20, 25, 28, 22, 35, 22, 16, 21,
+      29, 29, 34, 30, 17, 25, 6, 14, 23, 28, 25, 31, 40, 22, 33, 37, 16, 33,
+      24, 41, 30, 24, 34, 17,
+    ],
+  },
+  Book {
+    code:   "PSA",
+    name:   "Psalms",
+    verses: &[
+      6, 12, 8, 8, 12, 10, 17, 9, 20, 18, 7, 8, 6, 7, 5, 11, 15, 50, 14, 9, 13,
+      31, 6, 10, 22, 12, 14, 9, 11, 12, 24, 11, 22, 22, 28, 12, 40, 22, 13, 17,
+      13, 11, 5, 26, 17, 11, 9, 14, 20, 23, 19, 9, 6, 7, 23, 13, 11, 11, 17,
+      12, 8, 12, 11, 10, 13, 20, 7, 35, 36, 5, 24, 20, 28, 23, 10, 12, 20, 72,
+      13, 19, 16, 8, 18, 12, 13, 17, 7, 18, 52, 17, 16, 15, 5, 23, 11, 13, 12,
+      9, 9, 5, 8, 28, 22, 35, 45, 48, 43, 13, 31, 7, 10, 10, 9, 8, 18, 19, 2,
+      29, 176, 7, 8, 9, 4, 8, 5, 6, 5, 6, 8, 8, 3, 18, 3, 3, 21, 26, 9, 8, 24,
+      13, 10, 7, 12, 15, 21, 10, 20, 14, 9, 6,
+    ],
+  },
+  Book {
+    code:   "PRO",
+    name:   "Proverbs",
+    verses: &[
+      33, 22, 35, 27, 23, 35, 27, 36, 18, 32, 31, 28, 25, 35, 33, 33, 28, 24,
+      29, 30, 31, 29, 35, 34, 28, 28, 27, 28, 27, 33, 31,
+    ],
+  },
+  Book {
+    code:   "ECC",
+    name:   "Ecclesiastes",
+    verses: &[18, 26, 22, 16, 20, 12, 29, 17, 18, 20, 10, 14],
+  },
+  Book {
+    code:   "SNG",
+    name:   "Song of Songs",
+    verses: &[17, 17, 11, 16, 16, 13, 13, 14],
+  },
+  Book {
+    code:   "ISA",
+    name:   "Isaiah",
+    verses: &[
+      31, 22, 26, 6, 30, 13, 25, 22, 21, 34, 16, 6, 22, 32, 9, 14, 14, 7, 25,
+      6, 17, 25, 18, 23, 12, 21, 13, 29, 24, 33, 9, 20, 24, 17, 10, 22, 38, 22,
+      8, 31, 29, 25, 28, 28, 25, 13, 15, 22, 26, 11, 23, 15, 12, 17, 13, 12,
+      21, 14, 21, 22, 11, 12, 19, 12, 25, 24,
+    ],
+  },
+  Book {
+    code:   "JER",
+    name:   "Jeremiah",
+    verses: &[
+      19, 37, 25, 31, 31, 30, 34, 22, 26, 25, 23, 17, 27, 22, 21, 21, 27, 23,
+      15, 18, 14, 30, 40, 10, 38, 24, 22, 17, 32, 24, 40, 44, 26, 22, 19, 32,
+      21, 28, 18, 16, 18, 22, 13, 30, 5, 28, 7, 47, 39, 46, 64, 34,
+    ],
+  },
+  Book {
+    code:   "LAM",
+    name:   "Lamentations",
+    verses: &[22, 22, 66, 22, 22],
+  },
+  Book {
+    code:   "EZK",
+    name:   "Ezekiel",
+    verses: &[
+      28, 10, 27, 17, 17, 14, 27, 18, 11, 22, 25, 28, 23, 23, 8, 63, 24, 32,
+      14, 49, 32, 31, 49, 27, 17, 21, 36, 26, 21, 26, 18, 32, 33, 31, 15, 38,
+      28, 23, 29, 49, 26, 20, 27, 31, 25, 24, 23, 35,
+    ],
+  },
+  Book {
+    code:   "DAN",
+    name:   "Daniel",
+    verses: &[21, 49, 30, 37, 31, 28, 28, 27, 27, 21, 45, 13],
+  },
+  Book {
+    code:   "HOS",
+    name:   "Hosea",
+    verses: &[11, 23, 5, 19, 15, 11, 16, 14, 17, 15, 12, 14, 16, 9],
+  },
+  Book {
+    code:   "JOL",
+    name:   "Joel",
+    verses: &[20, 32, 21],
+  },
+  Book {
+    code:   "AMO",
+    name:   "Amos",
+    verses: &[15, 16, 15, 13, 27, 14, 17, 14, 15],
+  },
+  Book {
+    code:   "OBA",
+    name:   "Obadiah",
+    verses: &[21],
+  },
+  Book {
+    code:   "JON",
+    name:   "Jonah",
+    verses: &[17, 10, 10, 11],
+  },
+  Book {
+    code:   "MIC",
+    name:   "Micah",
+    verses: &[16, 13, 12, 13, 15, 16, 20],
+  },
+  Book {
+    code:   "NAM",
+    name:   "Nahum",
+    verses: &[15, 13, 19],
+  },
+  Book {
+    code:   "HAB",
+    name:   "Habakkuk",
+    verses: &[17, 20, 19],
+  },
+  Book {
+    code:   "ZEP",
+    name:   "Zephaniah",
+    verses: &[18, 15, 20],
+  },
+  Book {
+    code:   "HAG",
+    name:   "Haggai",
+    verses: &[15, 23],
+  },
+  Book {
+    code:   "ZEC",
+    name:   "Zechariah",
+    verses: &[21, 13, 10, 14, 11, 15, 14, 23, 17, 12, 17, 14, 9, 21],
+  },
+  Book {
+    code:   "MAL",
+    name:   "Malachi",
+    verses: &[14, 17, 18, 6],
+  },
+  Book {
+    code:   "MAT",
+    name:   "Matthew",
+    verses: &[
+      25, 23, 17, 25, 48, 34, 29, 34, 38, 42, 30, 50, 58, 36, 39, 28, 27, 35,
+      30, 34, 46, 46, 39, 51, 46, 75, 66, 20,
+    ],
+  },
+  Book {
+    code:   "MRK",
+    name:   "Mark",
+    verses: &[
+      45, 28, 35, 41, 43, 56, 37, 38, 50, 52, 33, 44, 37, 72, 47, 20,
+    ],
+  },
+  Book {
+    code:   "LUK",
+    name:   "Luke",
+    verses: &[
+      80, 52, 38, 44, 39, 49, 50, 56, 62, 42, 54, 59, 35, 35, 32, 31, 37, 43,
+      48, 47, 38, 71, 56, 53,
+    ],
+  },
+  Book {
+    code:   "JHN",
+    name:   "John",
+    verses: &[
+      51, 25, 36, 54, 47, 71, 53, 59, 41, 42, 57, 50, 38, 31, 27, 33, 26, 40,
+      42, 31, 25,
+    ],
+  },
+  Book {
+    code:   "ACT",
+    name:   "Acts",
+    verses: &[
+      26, 47, 26, 37, 42, 15, 60, 40, 43, 48, 30, 25, 52, 28, 41, 40, 34, 28,
+      41, 38, 40, 30, 35, 27, 27, 32, 44, 31,
+    ],
+  },
+  Book {
+    code:   "ROM",
+    name:   "Romans",
+    verses: &[
+      32, 29, 31, 25, 21, 23, 25, 39, 33, 21, 36, 21, 14, 23, 33, 27,
+    ],
+  },
+  Book {
+    code:   "1CO",
+    name:   "1 Corinthians",
+    verses: &[
+      31, 16, 23, 21, 13, 20, 40, 13, 27, 33, 34, 31, 13, 40, 58, 24,
+    ],
+  },
+  Book {
+    code:   "2CO",
+    name:   "2 Corinthians",
+    verses: &[24, 17, 18, 18, 21, 18, 16, 24, 15, 18, 33, 21, 14],
+  },
+  Book {
+    code:   "GAL",
+    name:   "Galatians",
+    verses: &[24, 21, 29, 31, 26, 18],
+  },
+  Book {
+    code:   "EPH",
+    name:   "Ephesians",
+    verses: &[23, 22, 21, 32, 33, 24],
+  },
+  Book {
+    code:   "PHP",
+    name:   "Philippians",
+    verses: &[30, 30, 21, 23],
+  },
+  Book {
+    code:   "COL",
+    name:   "Colossians",
+    verses: &[29, 23, 25, 18],
+  },
+  Book {
+    code:   "1TH",
+    name:   "1 Thessalonians",
+    verses: &[10, 20, 13, 18, 28],
+  },
+  Book {
+    code:   "2TH",
+    name:   "2 Thessalonians",
+    verses: &[12, 17, 18],
+  },
+  Book {
+    code:   "1TI",
+    name:   "1 Timothy",
+    verses: &[20, 15, 16, 16, 25, 21],
+  },
+  Book {
+    code:   "2TI",
+    name:   "2 Timothy",
+    verses: &[18, 26, 17, 22],
+  },
+  Book {
+    code:   "TIT",
+    name:   "Titus",
+    verses: &[16, 15, 15],
+  },
+  Book {
+    code:   "PHM",
+    name:   "Philemon",
+    verses: &[25],
+  },
+  Book {
+    code:   "HEB",
+    name:   "Hebrews",
+    verses: &[14, 18, 19, 16, 14, 20, 28, 13, 28, 39, 40, 29, 25],
+  },
+  Book {
+    code:   "JAS",
+    name:   "James",
+    verses: &[27, 26, 18, 17, 20],
+  },
+  Book {
+    code:   "1PE",
+    name:   "1 Peter",
+    verses: &[25, 25, 22, 19, 14],
+  },
+  Book {
+    code:   "2PE",
+    name:   "2 Peter",
+    verses: &[21, 22, 18],
+  },
+  Book {
+    code:   "1JN",
+    name:   "1 John",
+    verses: &[10, 29, 24, 21, 21],
+  },
+  Book {
+    code:   "2JN",
+    name:   "2 John",
+    verses: &[13],
+  },
+  Book {
+    code:   "3JN",
+    name:   "3 John",
+    verses: &[14],
+  },
+  Book {
+    code:   "JUD",
+    name:   "Jude",
+    verses: &[25],
+  },
+  Book {
+    code:   "REV",
+    name:   "Revelation",
+    verses: &[
+      20, 29, 22, 11, 14, 17, 17, 13, 21, 11, 19, 17, 18, 20, 8, 21, 18, 24,
+      21, 15, 27, 21,
+    ],
+  },
+];
+
+/// Finds a book by its USFM code, ignoring case.
+pub fn book(code: &str) -> Option<&'static Book> {
+  BOOKS.iter().find(|b| b.code.eq_ignore_ascii_case(code))
+}
+
+/// An inclusive range of verses within a chapter.
+#[derive(
+  Clone,
+  Copy,
+  Debug,
+  PartialEq,
+  Eq,
+  Hash,
+  PartialOrd,
+  Ord,
+  Serialize,
+  Deserialize,
+)]
+pub struct VerseRange {
+  pub start: u16,
+  pub end:   u16,
+}
+
+/// A whole chapter, or a range of verses in one.
+#[derive(
+  Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize,
+)]
+pub struct Passage {
+  /// The book's USFM code.
+  pub book:    String,
+  pub chapter: u16,
+  /// The verses covered, or `None` for the whole chapter.
+  #[serde(default)]
+  pub verses:  Option<VerseRange>,
+}
+
+/// Why a passage reference was rejected.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum PassageError {
+  /// The reference isn't in the `BOOK chapter[:verse[-verse]]` form.
+  Malformed,
+  UnknownBook(String),
+  NoSuchChapter {
+    book:    &'static str,
+    chapter: u16,
+  },
+  NoSuchVerses {
+    book:    &'static str,
+    chapter: u16,
+    verses:  VerseRange,
+  },
+}
+
+impl fmt::Display for PassageError {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      PassageError::Malformed => {
+        write!(f, "Passages are written like \"JHN 3\" or \"JHN 3:16-18\".")
+      }
+      PassageError::UnknownBook(code) => {
+        write!(f, "\"{code}\" isn't a book code we know.")
+      }
+      PassageError::NoSuchChapter { book, chapter } => {
+        write!(f, "{book} has no chapter {chapter}.")
+      }
+      PassageError::NoSuchVerses {
+        book,
+        chapter,
+        verses,
+      } => write!(
+        f,
+        "{book} {chapter} has no verses {}-{}.",
+        verses.start, verses.end
+      ),
+    }
+  }
+}
+
+impl std::error::Error for PassageError {}
+
+impl Passage {
+  /// A whole chapter.
+  pub fn chapter(book: &Book, chapter: u16) -> Self {
+    Passage {
+      book: book.code.to_string(),
+      chapter,
+      verses: None,
+    }
+  }
+
+  /// Checks the passage against the versification, returning its book.
+  pub fn validate(&self) -> Result<&'static Book, PassageError> {
+    let book = book(&self.book)
+      .ok_or_else(|| PassageError::UnknownBook(self.book.clone()))?;
+    let verse_count =
+      book
+        .verse_count(self.chapter)
+        .ok_or(PassageError::NoSuchChapter {
+          book:    book.code,
+          chapter: self.chapter,
+        })?;
+    if let Some(verses) = self.verses {
+      if verses.start == 0
+        || verses.start > verses.end
+        || verses.end > verse_count
+      {
+        return Err(PassageError::NoSuchVerses {
+          book: book.code,
+          chapter: self.chapter,
+          verses,
+        });
+      }
+    }
+    Ok(book)
+  }
+
+  /// Orders passages the way they appear in the Bible, with whole chapters
+  /// before the verse ranges in them. Unknown books sort last.
+  pub fn canonical_key(&self) -> (usize, u16, Option<VerseRange>) {
+    let book = BOOKS
+      .iter()
+      .position(|b| b.code.eq_ignore_ascii_case(&self.book))
+      .unwrap_or(BOOKS.len());
+    (book, self.chapter, self.verses)
+  }
+
+  /// The verses covered, with whole chapters resolved to their full range.
+  /// `None` if the passage isn't valid.
+  pub fn verse_range(&self) -> Option<VerseRange> {
+    let book = self.validate().ok()?;
+    Some(self.verses.unwrap_or(VerseRange {
+      start: 1,
+      end:   book.verse_count(self.chapter)?,
+    }))
+  }
+}
+
+impl fmt::Display for Passage {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "{} {}", self.book, self.chapter)?;
+    match self.verses {
+      Some(VerseRange { start, end }) if start == end => write!(f, ":{start}"),
+      Some(VerseRange { start, end }) => write!(f, ":{start}-{end}"),
+      None => Ok(()),
+    }
+  }
+}
+
+impl FromStr for Passage {
+  type Err = PassageError;
+
+  /// Parses a reference like `JHN 3`, `JHN 3:16` or `JHN 3:16-18`, checking
+  /// it against the versification.
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    let number = |s: &str| s.trim().parse::<u16>().ok();
+
+    let (code, reference) =
+      s.trim().split_once(' ').ok_or(PassageError::Malformed)?;
+    let (chapter, verses) = match reference.split_once(':') {
+      Some((chapter, verses)) => (chapter, Some(verses)),
+      None => (reference, None),
+    };
+    let chapter = number(chapter).ok_or(PassageError::Malformed)?;
+    let verses = match verses {
+      Some(verses) => {
+        let (start, end) = verses.split_once('-').unwrap_or((verses, verses));
+        Some(VerseRange {
+          start: number(start).ok_or(PassageError::Malformed)?,
+          end:   number(end).ok_or(PassageError::Malformed)?,
+        })
+      }
+      None => None,
+    };
+
+    let mut passage = Passage {
+      book: code.trim().to_string(),
+      chapter,
+      verses,
+    };
+    passage.book = passage.validate()?.code.to_string();
+    Ok(passage)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn versification_has_every_verse() {
+    assert_eq!(BOOKS.len(), 66);
+    let chapters: u32 =
+      BOOKS.iter().map(|b| u32::from(b.chapter_count())).sum();
+    assert_eq!(chapters, 1189);
+    assert_eq!(BOOKS.iter().map(Book::total_verses).sum::<u32>(), 31102);
+
+    let psalms = book("psa").unwrap();
+    assert_eq!(psalms.chapter_count(), 150);
+    assert_eq!(psalms.verse_count(119), Some(176));
+    assert_eq!(psalms.verse_count(0), None);
+    assert_eq!(psalms.verse_count(151), None);
+  }
+
+  #[test]
+  fn passages_sort_in_canonical_order() {
+    let mut passages = ["JHN 3:16", "GEN 2", "JHN 3", "MAT 1", "JHN 1:1-5"]
+      .map(|r| r.parse::<Passage>().unwrap());
+    passages.sort_by_key(Passage::canonical_key);
+    assert_eq!(passages.map(|p| p.to_string()), [
+      "GEN 2",
+      "MAT 1",
+      "JHN 1:1-5",
+      "JHN 3",
+      "JHN 3:16"
+    ]);
+  }
+
+  #[test]
+  fn passages_round_trip_through_references() {
+    for reference in ["JHN 3", "JHN 3:16", "GEN 1:1-31", "3JN 1:2-14"] {
+      let passage: Passage = reference.parse().unwrap();
+      assert_eq!(passage.to_string(), reference);
+    }
+
+    let passage: Passage = "jhn 3:16-18".parse().unwrap();
+    assert_eq!(passage.book, "JHN");
+    assert_eq!(
+      passage.verses,
+      Some(VerseRange {
+        start: 16,
+        end:   18,
+      })
+    );
+  }
+
+  #[test]
+  fn passages_outside_the_versification_are_rejected() {
+    assert_eq!("JHN".parse::<Passage>(), Err(PassageError::Malformed));
+    assert_eq!("JHN 3:x".parse::<Passage>(), Err(PassageError::Malformed));
+    assert_eq!(
+      "XYZ 1".parse::<Passage>(),
+      Err(PassageError::UnknownBook("XYZ".to_string()))
+    );
+    assert!(matches!(
+      "JHN 22".parse::<Passage>(),
+      Err(PassageError::NoSuchChapter { chapter: 22, .. })
+    ));
+    for reference in ["JHN 3:0", "JHN 3:37", "JHN 3:18-16"] {
+      assert!(matches!(
+        reference.parse::<Passage>(),
+        Err(PassageError::NoSuchVerses { .. })
+      ));
+    }
+  }
+}
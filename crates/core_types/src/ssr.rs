@@ -7,8 +7,14 @@ use surrealdb::{
 };
 
 use crate::{
-  Artifact, ArtifactRecordId, MotherTongue, MotherTongueRecordId, User,
-  UserRecordId, ARTIFACT_TABLE, MOTHER_TONGUE_TABLE, USER_TABLE,
+  ApiToken, ApiTokenRecordId, Artifact, ArtifactRecordId, Bundle,
+  BundleRecordId, CommentThread, CommentThreadRecordId, DeviceSession,
+  DeviceSessionRecordId, Invitation, InvitationRecordId, MotherTongue,
+  MotherTongueRecordId, OidcIdentity, OidcIdentityRecordId, Passkey,
+  PasskeyRecordId, Translation, TranslationRecordId, User, UserRecordId,
+  API_TOKEN_TABLE, ARTIFACT_TABLE, BUNDLE_TABLE, COMMENT_THREAD_TABLE,
+  DEVICE_SESSION_TABLE, INVITATION_TABLE, MOTHER_TONGUE_TABLE,
+  OIDC_IDENTITY_TABLE, PASSKEY_TABLE, TRANSLATION_TABLE, USER_TABLE,
 };
 
 #[derive(Deserialize, Debug, Clone)]
@@ -81,6 +87,35 @@ pub trait CoreId:
   fn to_thing(&self) -> Thing;
 }
 
+/// Serializes an id as a surrealdb [`Thing`], so that it's stored as a record
+/// link instead of a string.
+///
+/// Use with `#[serde(serialize_with = "crate::ssr::serialize_record_link")]`,
+/// and only on server-side models, since clients can't deserialize a
+/// [`Thing`].
+pub fn serialize_record_link<S, T>(
+  id: &T,
+  serializer: S,
+) -> Result<S::Ok, S::Error>
+where
+  S: serde::Serializer,
+  T: CoreId,
+{
+  id.to_thing().serialize(serializer)
+}
+
+/// Like [`serialize_record_link`], for optional links.
+pub fn serialize_optional_record_link<S, T>(
+  id: &Option<T>,
+  serializer: S,
+) -> Result<S::Ok, S::Error>
+where
+  S: serde::Serializer,
+  T: CoreId,
+{
+  id.map(|id| id.to_thing()).serialize(serializer)
+}
+
 macro_rules! impl_table {
   ($id_type:ident, $model_type:ident, $table:ident) => {
     impl From<UlidOrThing> for $id_type {
@@ -114,3 +149,11 @@ macro_rules! impl_table {
 impl_table!(UserRecordId, User, USER_TABLE);
 impl_table!(ArtifactRecordId, Artifact, ARTIFACT_TABLE);
 impl_table!(MotherTongueRecordId, MotherTongue, MOTHER_TONGUE_TABLE);
+impl_table!(PasskeyRecordId, Passkey, PASSKEY_TABLE);
+impl_table!(ApiTokenRecordId, ApiToken, API_TOKEN_TABLE);
+impl_table!(OidcIdentityRecordId, OidcIdentity, OIDC_IDENTITY_TABLE);
+impl_table!(DeviceSessionRecordId, DeviceSession, DEVICE_SESSION_TABLE);
+impl_table!(TranslationRecordId, Translation, TRANSLATION_TABLE);
+impl_table!(CommentThreadRecordId, CommentThread, COMMENT_THREAD_TABLE);
+impl_table!(InvitationRecordId, Invitation, INVITATION_TABLE);
+impl_table!(BundleRecordId, Bundle, BUNDLE_TABLE);
@@ -0,0 +1,33 @@
+//! Takes of a passage. Re-recording a passage adds a take instead of
+//! replacing the old recording, so takes can be compared and an earlier one
+//! made current again.
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+  iso8601, scripture::Passage, ArtifactRecordId, PublicProfile, RecordingStatus,
+};
+
+/// One recording of a passage.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Take {
+  pub id:          ArtifactRecordId,
+  /// The take's number, counting from 1 in the order they were uploaded.
+  pub take:        u32,
+  pub is_current:  bool,
+  pub status:      RecordingStatus,
+  pub uploader:    Option<PublicProfile>,
+  #[serde(with = "iso8601::option")]
+  pub uploaded_at: Option<time::OffsetDateTime>,
+  #[serde(default)]
+  pub size:        Option<u64>,
+}
+
+/// A recorded passage of a translation, with its current take.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct PassageTakes {
+  pub passage:    Passage,
+  pub current:    Take,
+  /// How many takes of the passage there are, including the current one.
+  pub take_count: u32,
+}
@@ -0,0 +1,251 @@
+//! Options and results for searching mother tongues.
+
+use serde::{Deserialize, Serialize};
+
+use crate::MotherTongue;
+
+/// Marks the start of a matched span in a highlighted field. Private-use
+/// characters are used so they can't collide with user text.
+pub const HIGHLIGHT_START: char = '\u{E000}';
+/// Marks the end of a matched span in a highlighted field.
+pub const HIGHLIGHT_END: char = '\u{E001}';
+
+/// Which tongues to include, by where they are in vetting. Rejected tongues
+/// are never included.
+#[derive(
+  Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum VettingFilter {
+  #[default]
+  Vetted,
+  Pending,
+  Any,
+}
+
+impl VettingFilter {
+  pub const ALL: [Self; 3] = [Self::Vetted, Self::Pending, Self::Any];
+
+  pub fn as_str(&self) -> &'static str {
+    match self {
+      Self::Vetted => "vetted",
+      Self::Pending => "pending",
+      Self::Any => "any",
+    }
+  }
+
+  pub fn parse(s: &str) -> Option<Self> {
+    Self::ALL.into_iter().find(|f| f.as_str() == s)
+  }
+
+  pub fn label(&self) -> &'static str {
+    match self {
+      Self::Vetted => "Vetted",
+      Self::Pending => "Awaiting vetting",
+      Self::Any => "Vetted or awaiting",
+    }
+  }
+}
+
+/// How to order search results.
+#[derive(
+  Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum TongueSort {
+  /// Best matches first. Without a search term this is the same as
+  /// [`TongueSort::Name`].
+  #[default]
+  Relevance,
+  Name,
+  Newest,
+  MostTranslations,
+}
+
+impl TongueSort {
+  pub const ALL: [Self; 4] = [
+    Self::Relevance,
+    Self::Name,
+    Self::Newest,
+    Self::MostTranslations,
+  ];
+
+  pub fn as_str(&self) -> &'static str {
+    match self {
+      Self::Relevance => "relevance",
+      Self::Name => "name",
+      Self::Newest => "newest",
+      Self::MostTranslations => "most_translations",
+    }
+  }
+
+  pub fn parse(s: &str) -> Option<Self> {
+    Self::ALL.into_iter().find(|f| f.as_str() == s)
+  }
+
+  pub fn label(&self) -> &'static str {
+    match self {
+      Self::Relevance => "Best match",
+      Self::Name => "Name",
+      Self::Newest => "Newest",
+      Self::MostTranslations => "Most translations",
+    }
+  }
+}
+
+/// A mother tongue search, as kept in the URL of the tongue listing.
+#[derive(
+  Clone, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize,
+)]
+pub struct TongueSearch {
+  pub term:     Option<String>,
+  pub vetting:  VettingFilter,
+  /// Matches the region text or a country code.
+  pub region:   Option<String>,
+  /// An ISO 639-3 code, matching the tongue's own code or its parent's.
+  pub language: Option<String>,
+  pub sort:     TongueSort,
+}
+
+impl TongueSearch {
+  pub const TERM_KEY: &'static str = "q";
+  pub const VETTING_KEY: &'static str = "vetting";
+  pub const REGION_KEY: &'static str = "region";
+  pub const LANGUAGE_KEY: &'static str = "language";
+  pub const SORT_KEY: &'static str = "sort";
+
+  /// Reads a search from query string parameters, ignoring anything that
+  /// doesn't parse.
+  pub fn from_query(get: impl Fn(&str) -> Option<String>) -> Self {
+    let text = |key| get(key).filter(|v| !v.trim().is_empty());
+    TongueSearch {
+      term:     text(Self::TERM_KEY),
+      vetting:  get(Self::VETTING_KEY)
+        .and_then(|v| VettingFilter::parse(&v))
+        .unwrap_or_default(),
+      region:   text(Self::REGION_KEY),
+      language: text(Self::LANGUAGE_KEY),
+      sort:     get(Self::SORT_KEY)
+        .and_then(|v| TongueSort::parse(&v))
+        .unwrap_or_default(),
+    }
+  }
+
+  /// The query string parameters for this search. Defaults are left out to
+  /// keep URLs short.
+  pub fn query_pairs(&self) -> Vec<(&'static str, String)> {
+    let mut pairs = Vec::new();
+    let mut text = |key, value: &Option<String>| {
+      if let Some(value) = value.as_ref().filter(|v| !v.trim().is_empty()) {
+        pairs.push((key, value.clone()));
+      }
+    };
+    text(Self::TERM_KEY, &self.term);
+    text(Self::REGION_KEY, &self.region);
+    text(Self::LANGUAGE_KEY, &self.language);
+    if self.vetting != VettingFilter::default() {
+      pairs.push((Self::VETTING_KEY, self.vetting.as_str().to_string()));
+    }
+    if self.sort != TongueSort::default() {
+      pairs.push((Self::SORT_KEY, self.sort.as_str().to_string()));
+    }
+    pairs
+  }
+}
+
+/// A mother tongue matched by a search.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct TongueSearchHit {
+  pub tongue:                MotherTongue,
+  pub translation_count:     usize,
+  /// How far the tongue's furthest translation has got, as a percentage.
+  #[serde(default)]
+  pub progress:              f32,
+  /// The name, with matched spans between [`HIGHLIGHT_START`] and
+  /// [`HIGHLIGHT_END`].
+  #[serde(default)]
+  pub name_highlight:        Option<String>,
+  /// The description, highlighted like the name.
+  #[serde(default)]
+  pub description_highlight: Option<String>,
+}
+
+/// A page of search results.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct TongueSearchResults {
+  pub hits:  Vec<TongueSearchHit>,
+  pub total: usize,
+  /// Whether nothing matched exactly, so these are close matches for a
+  /// possibly misspelled term.
+  pub fuzzy: bool,
+}
+
+/// Splits highlighted text into spans, each marked with whether it matched.
+pub fn highlight_segments(text: &str) -> Vec<(String, bool)> {
+  let mut segments = Vec::new();
+  let mut current = String::new();
+  let mut matched = false;
+  for c in text.chars() {
+    let boundary = match c {
+      HIGHLIGHT_START => Some(true),
+      HIGHLIGHT_END => Some(false),
+      _ => None,
+    };
+    match boundary {
+      Some(next) => {
+        if !current.is_empty() {
+          segments.push((std::mem::take(&mut current), matched));
+        }
+        matched = next;
+      }
+      None => current.push(c),
+    }
+  }
+  if !current.is_empty() {
+    segments.push((current, matched));
+  }
+  segments
+}
+
+#[cfg(test)]
+mod tests {
+  use std::collections::HashMap;
+
+  use super::*;
+
+  #[test]
+  fn highlights_split_into_segments() {
+    let text = format!("New {HIGHLIGHT_START}York{HIGHLIGHT_END} Latino");
+    assert_eq!(highlight_segments(&text), vec![
+      ("New ".to_string(), false),
+      ("York".to_string(), true),
+      (" Latino".to_string(), false),
+    ]);
+    assert_eq!(highlight_segments("plain"), vec![(
+      "plain".to_string(),
+      false
+    )]);
+  }
+
+  #[test]
+  fn searches_round_trip_through_the_query_string() {
+    let search = TongueSearch {
+      term:     Some("english".to_string()),
+      vetting:  VettingFilter::Any,
+      region:   None,
+      language: Some("eng".to_string()),
+      sort:     TongueSort::MostTranslations,
+    };
+    let pairs: HashMap<_, _> = search.query_pairs().into_iter().collect();
+    assert_eq!(pairs.len(), 4);
+    assert_eq!(
+      TongueSearch::from_query(|key| pairs.get(key).cloned()),
+      search
+    );
+
+    assert!(TongueSearch::default().query_pairs().is_empty());
+    let garbled = TongueSearch::from_query(|_| Some("nonsense".to_string()));
+    assert_eq!(garbled.vetting, VettingFilter::Vetted);
+    assert_eq!(garbled.sort, TongueSort::Relevance);
+  }
+}
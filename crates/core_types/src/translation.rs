@@ -0,0 +1,176 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{
+  iso8601, scripture::Passage, ArtifactRecordId, MotherTongue,
+  MotherTongueRecordId, PublicProfile,
+};
+
+pub const TRANSLATION_TABLE: &str = "translations";
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "ssr", serde(from = "crate::ssr::UlidOrThing"))]
+pub struct TranslationRecordId(pub ulid::Ulid);
+
+/// A translation of the bible into a mother tongue.
+#[cfg(feature = "ssr")]
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Translation {
+  pub id:            TranslationRecordId,
+  pub name:          String,
+  #[serde(serialize_with = "crate::ssr::serialize_record_link")]
+  pub mother_tongue: MotherTongueRecordId,
+  #[serde(with = "iso8601")]
+  pub created_at:    time::OffsetDateTime,
+  /// The terms the translation's recordings are shared under.
+  #[serde(default)]
+  pub license:       License,
+}
+
+/// The terms a translation's recordings are shared under, chosen by its
+/// managers.
+#[derive(
+  Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq, Hash,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum License {
+  /// Nothing has been granted beyond listening on the site.
+  #[default]
+  AllRightsReserved,
+  Cc0,
+  CcBy4,
+  CcBySa4,
+  CcByNd4,
+}
+
+impl License {
+  pub const ALL: [Self; 5] = [
+    Self::AllRightsReserved,
+    Self::Cc0,
+    Self::CcBy4,
+    Self::CcBySa4,
+    Self::CcByNd4,
+  ];
+
+  pub fn as_str(&self) -> &'static str {
+    match self {
+      Self::AllRightsReserved => "all_rights_reserved",
+      Self::Cc0 => "cc0",
+      Self::CcBy4 => "cc_by4",
+      Self::CcBySa4 => "cc_by_sa4",
+      Self::CcByNd4 => "cc_by_nd4",
+    }
+  }
+
+  pub fn parse(s: &str) -> Option<Self> {
+    Self::ALL.into_iter().find(|l| l.as_str() == s)
+  }
+
+  pub fn label(&self) -> &'static str {
+    match self {
+      Self::AllRightsReserved => "All rights reserved",
+      Self::Cc0 => "CC0 1.0 (public domain)",
+      Self::CcBy4 => "CC BY 4.0",
+      Self::CcBySa4 => "CC BY-SA 4.0",
+      Self::CcByNd4 => "CC BY-ND 4.0",
+    }
+  }
+
+  /// Where the license's full text is.
+  pub fn url(&self) -> Option<&'static str> {
+    match self {
+      Self::AllRightsReserved => None,
+      Self::Cc0 => Some("https://creativecommons.org/publicdomain/zero/1.0/"),
+      Self::CcBy4 => Some("https://creativecommons.org/licenses/by/4.0/"),
+      Self::CcBySa4 => Some("https://creativecommons.org/licenses/by-sa/4.0/"),
+      Self::CcByNd4 => Some("https://creativecommons.org/licenses/by-nd/4.0/"),
+    }
+  }
+
+  /// What the license lets people do, in a sentence or two.
+  pub fn summary(&self) -> &'static str {
+    match self {
+      Self::AllRightsReserved => {
+        "The translation team hasn't licensed these recordings. Ask them \
+         before copying, sharing or adapting them."
+      }
+      Self::Cc0 => {
+        "The translation team has given these recordings to the public domain. \
+         They can be used for any purpose without asking."
+      }
+      Self::CcBy4 => {
+        "These recordings can be shared and adapted for any purpose, as long \
+         as the translation team is credited."
+      }
+      Self::CcBySa4 => {
+        "These recordings can be shared and adapted for any purpose, as long \
+         as the translation team is credited and adaptations are shared under \
+         the same license."
+      }
+      Self::CcByNd4 => {
+        "These recordings can be shared unchanged for any purpose, as long as \
+         the translation team is credited."
+      }
+    }
+  }
+}
+
+/// A [`Translation`] as shown in listings.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct TranslationSummary {
+  pub id:            TranslationRecordId,
+  pub name:          String,
+  pub mother_tongue: MotherTongueRecordId,
+  #[serde(with = "iso8601")]
+  pub created_at:    time::OffsetDateTime,
+}
+
+#[cfg(feature = "ssr")]
+impl From<Translation> for TranslationSummary {
+  fn from(value: Translation) -> Self {
+    TranslationSummary {
+      id:            value.id,
+      name:          value.name,
+      mother_tongue: value.mother_tongue,
+      created_at:    value.created_at,
+    }
+  }
+}
+
+/// A translation in a mother tongue's listing.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct TranslationListing {
+  #[serde(flatten)]
+  pub translation:     TranslationSummary,
+  /// How many recordings have been uploaded for the translation.
+  pub recording_count: usize,
+  /// How much of the bible has been recorded, as a percentage.
+  #[serde(default)]
+  pub progress:        f32,
+}
+
+/// A translation along with the mother tongue it's in and who started it.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct TranslationDetails {
+  pub translation:   TranslationSummary,
+  pub mother_tongue: MotherTongue,
+  pub creator:       Option<PublicProfile>,
+}
+
+/// Something a user created, from one of the `created_*` edges.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Contribution<T> {
+  pub item: T,
+  /// When the user created it.
+  #[serde(with = "iso8601")]
+  pub at:   time::OffsetDateTime,
+}
+
+/// A recording as listed on its uploader's profile.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ContributedRecording {
+  pub id:               ArtifactRecordId,
+  pub translation:      TranslationRecordId,
+  pub translation_name: String,
+  #[serde(default)]
+  pub passage:          Option<Passage>,
+}
@@ -1,91 +1,217 @@
-use std::fmt::Debug;
-
 use serde::{Deserialize, Serialize};
 
+use crate::{iso8601, Pii, Secret};
+
 pub const USER_TABLE: &str = "users";
 
 #[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "ssr", serde(from = "crate::ssr::UlidOrThing"))]
 pub struct UserRecordId(pub ulid::Ulid);
 
+/// A site-wide permission beyond what every user has.
+///
+/// There's no UI for granting roles; set them on the user record directly.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum SiteRole {
+  /// Can vet proposed mother tongues.
+  Reviewer,
+  /// Can take recordings through community checking with speakers of the
+  /// tongue.
+  CommunityChecker,
+  /// Can check recordings against the source text and approve them.
+  Consultant,
+  /// Can do anything a reviewer can, and administer the site.
+  Admin,
+}
+
+impl SiteRole {
+  /// Whether holding this role grants `role`.
+  pub fn grants(&self, role: SiteRole) -> bool {
+    *self == role || *self == SiteRole::Admin
+  }
+}
+
+/// Whether any of `roles` grants `role`.
+pub(crate) fn roles_grant(roles: &[SiteRole], role: SiteRole) -> bool {
+  roles.iter().any(|r| r.grants(role))
+}
+
 #[cfg(feature = "ssr")]
-#[derive(Clone, Serialize, Deserialize)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct User {
   pub id:            UserRecordId,
   pub name:          String,
-  pub email:         String,
-  pub pw_hash:       String,
+  pub email:         Pii,
+  pub pw_hash:       Secret,
   pub is_active:     bool,
   #[serde(with = "iso8601")]
   pub registered_at: time::OffsetDateTime,
+  /// An image URL for the user's avatar, from their OIDC provider if they
+  /// signed up with one.
+  #[serde(default)]
+  pub avatar_url:    Option<String>,
+  #[serde(default)]
+  pub roles:         Vec<SiteRole>,
+  #[serde(default)]
+  pub totp:          Option<TotpConfig>,
 }
 
-mod iso8601 {
-  use serde::{self, Deserialize, Deserializer, Serializer};
-  use time::{format_description::well_known::Iso8601, OffsetDateTime};
-
-  pub fn serialize<S>(
-    date: &OffsetDateTime,
-    serializer: S,
-  ) -> Result<S::Ok, S::Error>
-  where
-    S: Serializer,
-  {
-    let s = date.format(&Iso8601::DEFAULT).unwrap();
-    serializer.serialize_str(&s)
+/// A user's TOTP two-factor configuration.
+#[cfg(feature = "ssr")]
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct TotpConfig {
+  /// The base32-encoded shared secret.
+  pub secret:               Secret,
+  /// Whether enrollment has been confirmed with a valid code. Unconfirmed
+  /// configs are ignored at login.
+  pub enabled:              bool,
+  /// PHC hashes of the recovery codes that haven't been used yet.
+  pub recovery_code_hashes: Vec<String>,
+  /// The time step of the last code accepted. Codes for it or any earlier
+  /// step are refused, so a code can't be used twice.
+  #[serde(default)]
+  pub last_used_step:       Option<u64>,
+  /// Wrong codes entered since the last right one. Kept here rather than in
+  /// the login session so logging in again doesn't reset it.
+  #[serde(default)]
+  pub failed_attempts:      u32,
+  #[serde(default, with = "iso8601::option")]
+  pub last_failed_at:       Option<time::OffsetDateTime>,
+}
+
+#[cfg(feature = "ssr")]
+impl User {
+  /// Whether the user must provide a second factor to log in.
+  pub fn totp_enabled(&self) -> bool {
+    self.totp.as_ref().is_some_and(|t| t.enabled)
   }
 
-  pub fn deserialize<'de, D>(
-    deserializer: D,
-  ) -> Result<OffsetDateTime, D::Error>
-  where
-    D: Deserializer<'de>,
-  {
-    let s = String::deserialize(deserializer)?;
-    let dt = OffsetDateTime::parse(&s, &Iso8601::DEFAULT)
-      .map_err(serde::de::Error::custom)?;
-    Ok(dt)
+  /// Whether the user holds `role`, directly or through another role.
+  pub fn has_role(&self, role: SiteRole) -> bool {
+    roles_grant(&self.roles, role)
   }
 }
 
-#[cfg(feature = "ssr")]
-impl Debug for User {
-  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-    f.debug_struct("User")
-      .field("id", &self.id)
-      .field("name", &self.name)
-      .field("email", &"[redacted]")
-      .field("pw_hash", &"[redacted]")
-      .field("is_active", &self.is_active)
-      .finish()
-  }
+/// The information needed to add a TOTP secret to an authenticator app.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct TotpEnrollment {
+  /// The `otpauth://` URI encoded in the QR code.
+  pub otpauth_uri:   Secret,
+  /// A base64-encoded PNG of the QR code.
+  pub qr_code_png:   String,
+  /// The base32-encoded secret, for manual entry.
+  pub secret_base32: Secret,
 }
 
+/// The logged in user's view of their own account.
+///
+/// This holds private details like the email, so only pass it to islands
+/// that need them. Use [`PublicProfile`] to show the user to others.
 #[derive(Clone, Debug, Serialize, Deserialize)]
-pub struct PublicUser {
+pub struct CurrentUser {
   pub id:            UserRecordId,
   pub name:          String,
-  pub email:         String,
+  pub email:         Pii,
+  pub avatar_url:    Option<String>,
+  pub roles:         Vec<SiteRole>,
   pub is_active:     bool,
   #[serde(with = "iso8601")]
   pub registered_at: time::OffsetDateTime,
+  pub totp_enabled:  bool,
 }
 
 #[cfg(feature = "ssr")]
-impl From<User> for PublicUser {
+impl From<User> for CurrentUser {
   fn from(value: User) -> Self {
-    PublicUser {
+    CurrentUser {
+      totp_enabled:  value.totp_enabled(),
       id:            value.id,
       name:          value.name,
       email:         value.email,
+      avatar_url:    value.avatar_url,
+      roles:         value.roles,
       is_active:     value.is_active,
       registered_at: value.registered_at,
     }
   }
 }
 
+impl CurrentUser {
+  /// Whether the user holds `role`, directly or through another role.
+  pub fn has_role(&self, role: SiteRole) -> bool {
+    roles_grant(&self.roles, role)
+  }
+
+  /// The parts of the account that anyone can see.
+  pub fn profile(&self) -> PublicProfile {
+    PublicProfile {
+      id:         self.id,
+      name:       self.name.clone(),
+      avatar_url: self.avatar_url.clone(),
+    }
+  }
+}
+
+/// What anyone can see about a user, like the creator of a mother tongue.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PublicProfile {
+  pub id:         UserRecordId,
+  pub name:       String,
+  pub avatar_url: Option<String>,
+}
+
+#[cfg(feature = "ssr")]
+impl From<User> for PublicProfile {
+  fn from(value: User) -> Self {
+    PublicProfile {
+      id:         value.id,
+      name:       value.name,
+      avatar_url: value.avatar_url,
+    }
+  }
+}
+
+impl PublicProfile {
+  /// Up to two initials from the name, for when there's no avatar image.
+  pub fn initials(&self) -> String {
+    self
+      .name
+      .split_whitespace()
+      .filter_map(|w| w.chars().next())
+      .take(2)
+      .flat_map(char::to_uppercase)
+      .collect()
+  }
+}
+
 #[derive(Clone, Debug)]
-pub struct LoggedInUser(pub Option<PublicUser>);
+pub struct LoggedInUser(pub Option<CurrentUser>);
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn initials_come_from_the_first_two_words() {
+    let profile = |name: &str| PublicProfile {
+      id:         UserRecordId(ulid::Ulid::nil()),
+      name:       name.to_string(),
+      avatar_url: None,
+    };
+    assert_eq!(profile("john lewis").initials(), "JL");
+    assert_eq!(profile("Ana María de la Cruz").initials(), "AM");
+    assert_eq!(profile("  ").initials(), "");
+  }
+
+  #[test]
+  fn admins_hold_every_role() {
+    assert!(roles_grant(&[SiteRole::Admin], SiteRole::Reviewer));
+    assert!(roles_grant(&[SiteRole::Reviewer], SiteRole::Reviewer));
+    assert!(!roles_grant(&[SiteRole::Reviewer], SiteRole::Admin));
+    assert!(!roles_grant(&[], SiteRole::Reviewer));
+  }
+}
 
 #[cfg(feature = "auth")]
 mod auth {
@@ -97,6 +223,6 @@ mod auth {
     type Id = ulid::Ulid;
 
     fn id(&self) -> <Self as AuthUser>::Id { self.id.0 }
-    fn session_auth_hash(&self) -> &[u8] { self.pw_hash.as_bytes() }
+    fn session_auth_hash(&self) -> &[u8] { self.pw_hash.expose().as_bytes() }
   }
 }
@@ -0,0 +1,412 @@
+//! Input validation shared between the frontend and the server.
+
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{ApiTokenScope, TranslationRole, API_TOKEN_LIFETIME_DAYS};
+
+pub const NAME_FIELD: &str = "name";
+pub const EMAIL_FIELD: &str = "email";
+pub const PASSWORD_FIELD: &str = "password";
+pub const CODE_FIELD: &str = "code";
+pub const PASSKEY_FIELD: &str = "passkey";
+pub const SCOPES_FIELD: &str = "scopes";
+pub const LIFETIME_FIELD: &str = "lifetime";
+pub const DESCRIPTION_FIELD: &str = "description";
+pub const ALTERNATE_NAMES_FIELD: &str = "alternate_names";
+pub const REGION_FIELD: &str = "region";
+pub const COUNTRIES_FIELD: &str = "countries";
+pub const ISO_639_3_FIELD: &str = "iso_639_3";
+pub const PARENT_LANGUAGE_FIELD: &str = "parent_language";
+pub const SCRIPT_FIELD: &str = "script";
+pub const REASON_FIELD: &str = "reason";
+pub const PASSAGE_FIELD: &str = "passage";
+pub const COMMENT_FIELD: &str = "comment";
+pub const ANCHOR_FIELD: &str = "anchor";
+pub const ROLES_FIELD: &str = "roles";
+pub const UPLOAD_FIELD: &str = "upload";
+
+pub const MIN_NAME_LENGTH: usize = 3;
+pub const MIN_PASSWORD_LENGTH: usize = 8;
+pub const MIN_DESCRIPTION_LENGTH: usize = 10;
+
+/// Validation failures, keyed by the name of the field they belong to.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ValidationErrors(pub BTreeMap<String, String>);
+
+impl ValidationErrors {
+  pub fn new() -> Self { Self::default() }
+
+  /// Records a failure for `field`, if there is one.
+  pub fn check(&mut self, field: &str, failure: Option<String>) {
+    if let Some(message) = failure {
+      self.0.insert(field.to_string(), message);
+    }
+  }
+
+  /// The failure message for `field`, if it failed.
+  pub fn get(&self, field: &str) -> Option<&String> { self.0.get(field) }
+
+  pub fn is_empty(&self) -> bool { self.0.is_empty() }
+
+  /// Returns `Err(self)` if any field failed.
+  pub fn into_result(self) -> Result<(), Self> {
+    match self.is_empty() {
+      true => Ok(()),
+      false => Err(self),
+    }
+  }
+}
+
+pub fn validate_name(name: &str) -> Option<String> {
+  if name.trim().len() < MIN_NAME_LENGTH {
+    return Some(format!(
+      "Name must be at least {MIN_NAME_LENGTH} characters long."
+    ));
+  }
+
+  None
+}
+
+pub fn validate_email(email: &str) -> Option<String> {
+  if email.is_empty() {
+    return Some("An email is required.".to_string());
+  }
+
+  // Split the email into local and domain parts
+  let parts: Vec<&str> = email.split('@').collect();
+  if parts.len() != 2 {
+    return Some(
+      "Your email must contain exactly one \"@\" symbol.".to_string(),
+    );
+  }
+
+  let local = parts[0];
+  let domain = parts[1];
+
+  // Check local part
+  if local.is_empty() {
+    return Some("The first part of the email is empty.".to_string());
+  }
+
+  if local.len() > 64 {
+    return Some(
+      "The first part of the email exceeds 64 characters.".to_string(),
+    );
+  }
+
+  // Check for valid characters in local part
+  for c in local.chars() {
+    if !(c.is_alphanumeric() || c == '.' || c == '_' || c == '-' || c == '+') {
+      return Some(format!("Invalid character '{}'.", c));
+    }
+  }
+
+  // Check domain part
+  if domain.is_empty() {
+    return Some("The second part of the email is empty.".to_string());
+  }
+
+  if domain.len() > 255 {
+    return Some(
+      "The second part of the email exceeds 255 characters.".to_string(),
+    );
+  }
+
+  // Split domain into labels
+  let domain_labels: Vec<&str> = domain.split('.').collect();
+  if domain_labels.len() < 2 {
+    return Some(
+      "The second part of the email must contain at least one '.' character."
+        .to_string(),
+    );
+  }
+
+  // Check each label in the domain
+  for label in domain_labels {
+    if label.is_empty() {
+      return Some("One of the domain labels is empty.".to_string());
+    }
+
+    if label.len() > 63 {
+      return Some(
+        "One of the domain labels exceeds 63 characters.".to_string(),
+      );
+    }
+
+    for c in label.chars() {
+      if !(c.is_alphanumeric() || c == '-') {
+        return Some(format!("Invalid character '{}'.", c));
+      }
+    }
+
+    // Labels must not start or end with a hyphen
+    if label.starts_with('-') {
+      return Some(
+        "One of the domain labels starts with a hyphen.".to_string(),
+      );
+    }
+
+    if label.ends_with('-') {
+      return Some("One of the domain labels ends with a hyphen.".to_string());
+    }
+  }
+
+  None
+}
+
+pub fn validate_password(password: &str) -> Option<String> {
+  if password.len() < MIN_PASSWORD_LENGTH {
+    return Some(format!(
+      "Password must be at least {MIN_PASSWORD_LENGTH} characters long."
+    ));
+  }
+
+  None
+}
+
+/// Validates everything needed to create an account.
+pub fn validate_signup(
+  name: &str,
+  email: &str,
+  password: &str,
+) -> Result<(), ValidationErrors> {
+  let mut errors = ValidationErrors::new();
+  errors.check(NAME_FIELD, validate_name(name));
+  errors.check(EMAIL_FIELD, validate_email(email));
+  errors.check(PASSWORD_FIELD, validate_password(password));
+  errors.into_result()
+}
+
+/// Validates login input.
+///
+/// Password rules aren't applied here so that accounts created under older
+/// rules can still log in.
+pub fn validate_login(
+  email: &str,
+  password: &str,
+) -> Result<(), ValidationErrors> {
+  let mut errors = ValidationErrors::new();
+  errors.check(EMAIL_FIELD, validate_email(email));
+  if password.is_empty() {
+    errors.check(PASSWORD_FIELD, Some("A password is required.".to_string()));
+  }
+  errors.into_result()
+}
+
+/// Validates the options for a new API token.
+pub fn validate_api_token(
+  name: &str,
+  scopes: &[ApiTokenScope],
+  lifetime_days: u32,
+) -> Result<(), ValidationErrors> {
+  let mut errors = ValidationErrors::new();
+  if name.trim().is_empty() {
+    errors.check(NAME_FIELD, Some("Give your token a name.".to_string()));
+  }
+  if scopes.is_empty() {
+    errors.check(
+      SCOPES_FIELD,
+      Some("Choose at least one permission.".to_string()),
+    );
+  }
+  if !API_TOKEN_LIFETIME_DAYS.contains(&lifetime_days) {
+    errors.check(
+      LIFETIME_FIELD,
+      Some("Choose one of the listed expiry options.".to_string()),
+    );
+  }
+  errors.into_result()
+}
+
+/// Validates a new invitation to a translation's team. A blank email makes
+/// the invitation a shareable link.
+pub fn validate_invitation(
+  email: &str,
+  roles: &[TranslationRole],
+) -> Result<(), ValidationErrors> {
+  let mut errors = ValidationErrors::new();
+  let email = email.trim();
+  if !email.is_empty() {
+    errors.check(EMAIL_FIELD, validate_email(email));
+  }
+  if roles.is_empty() {
+    errors.check(ROLES_FIELD, Some("Choose at least one role.".to_string()));
+  }
+  errors.into_result()
+}
+
+/// Checks an ISO 639-3 code.
+///
+/// The format is checked everywhere, but whether the code is in the
+/// [bundled table](crate::iso_639_3) is only checked on the server, to keep
+/// the table out of the frontend bundle.
+pub fn validate_iso_639_3(code: &str) -> Option<String> {
+  if code.len() != 3 || !code.chars().all(|c| c.is_ascii_lowercase()) {
+    return Some(
+      "ISO 639-3 codes are three lowercase letters, like \"eng\".".to_string(),
+    );
+  }
+
+  #[cfg(feature = "ssr")]
+  if crate::iso_639_3::lookup(code).is_none() {
+    return Some(format!("\"{code}\" isn't an ISO 639-3 code."));
+  }
+
+  None
+}
+
+pub fn validate_country_codes(codes: &[String]) -> Option<String> {
+  codes
+    .iter()
+    .find(|c| c.len() != 2 || !c.chars().all(|c| c.is_ascii_uppercase()))
+    .map(|c| {
+      format!(
+        "\"{c}\" isn't a country code. Use two capital letters, like \"US\"."
+      )
+    })
+}
+
+pub fn validate_script(script: &str) -> Option<String> {
+  let mut chars = script.chars();
+  let well_formed = script.len() == 4
+    && chars.next().is_some_and(|c| c.is_ascii_uppercase())
+    && chars.all(|c| c.is_ascii_lowercase());
+  if !well_formed {
+    return Some(
+      "Script codes are four letters with the first capitalized, like \
+       \"Latn\"."
+        .to_string(),
+    );
+  }
+
+  None
+}
+
+/// The fields of a proposed mother tongue that need checking. Empty optional
+/// fields should be passed as `None`.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct TongueProposalInput<'a> {
+  pub name:            &'a str,
+  pub description:     &'a str,
+  pub countries:       &'a [String],
+  pub iso_639_3:       Option<&'a str>,
+  pub parent_language: Option<&'a str>,
+  pub script:          Option<&'a str>,
+}
+
+/// Validates a proposed mother tongue.
+pub fn validate_tongue_proposal(
+  input: TongueProposalInput,
+) -> Result<(), ValidationErrors> {
+  let mut errors = ValidationErrors::new();
+  errors.check(NAME_FIELD, validate_name(input.name));
+  if input.description.trim().len() < MIN_DESCRIPTION_LENGTH {
+    errors.check(
+      DESCRIPTION_FIELD,
+      Some(format!(
+        "Describe the tongue in at least {MIN_DESCRIPTION_LENGTH} characters."
+      )),
+    );
+  }
+  errors.check(COUNTRIES_FIELD, validate_country_codes(input.countries));
+  errors.check(
+    ISO_639_3_FIELD,
+    input.iso_639_3.and_then(validate_iso_639_3),
+  );
+  errors.check(
+    PARENT_LANGUAGE_FIELD,
+    input.parent_language.and_then(validate_iso_639_3),
+  );
+  errors.check(SCRIPT_FIELD, input.script.and_then(validate_script));
+  errors.into_result()
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn validate_email_works() {
+    assert_eq!(validate_email("main@jlewis.sh"), None);
+    assert_eq!(validate_email("first.last+tag@mail.example.org"), None);
+    assert!(validate_email("").is_some());
+    assert!(validate_email("no-at-symbol.com").is_some());
+    assert!(validate_email("two@at@example.com").is_some());
+    assert!(validate_email("user@localhost").is_some());
+    assert!(validate_email("user@-example.com").is_some());
+    assert!(validate_email("us er@example.com").is_some());
+  }
+
+  #[test]
+  fn validate_signup_reports_each_field() {
+    assert_eq!(
+      validate_signup("John", "main@jlewis.sh", "hunter22"),
+      Ok(())
+    );
+
+    let errors = validate_signup("Jo", "main@jlewis.sh", "short").unwrap_err();
+    assert!(errors.get(NAME_FIELD).is_some());
+    assert!(errors.get(EMAIL_FIELD).is_none());
+    assert!(errors.get(PASSWORD_FIELD).is_some());
+  }
+
+  #[test]
+  fn validate_tongue_proposal_checks_code_formats() {
+    let countries = ["US".to_string()];
+    let input = TongueProposalInput {
+      name:            "Gullah",
+      description:     "Spoken along the coast.",
+      countries:       &countries,
+      iso_639_3:       Some("gul"),
+      parent_language: Some("eng"),
+      script:          Some("Latn"),
+    };
+    assert_eq!(validate_tongue_proposal(input), Ok(()));
+    assert_eq!(
+      validate_tongue_proposal(TongueProposalInput {
+        countries: &[],
+        iso_639_3: None,
+        parent_language: None,
+        script: None,
+        ..input
+      }),
+      Ok(())
+    );
+
+    let countries = ["usa".to_string()];
+    let errors = validate_tongue_proposal(TongueProposalInput {
+      description: "short",
+      countries: &countries,
+      iso_639_3: Some("GUL"),
+      parent_language: Some("english"),
+      script: Some("latn"),
+      ..input
+    })
+    .unwrap_err();
+    assert!(errors.get(DESCRIPTION_FIELD).is_some());
+    assert!(errors.get(COUNTRIES_FIELD).is_some());
+    assert!(errors.get(ISO_639_3_FIELD).is_some());
+    assert!(errors.get(PARENT_LANGUAGE_FIELD).is_some());
+    assert!(errors.get(SCRIPT_FIELD).is_some());
+  }
+
+  #[test]
+  fn validate_invitation_allows_links() {
+    assert!(validate_invitation("", &[TranslationRole::Speaker]).is_ok());
+    assert!(
+      validate_invitation("a@example.com", &[TranslationRole::Speaker]).is_ok()
+    );
+
+    let errors = validate_invitation("nope", &[]).unwrap_err();
+    assert!(errors.get(EMAIL_FIELD).is_some());
+    assert!(errors.get(ROLES_FIELD).is_some());
+  }
+
+  #[test]
+  fn validate_login_ignores_password_rules() {
+    assert_eq!(validate_login("main@jlewis.sh", "short"), Ok(()));
+    assert!(validate_login("main@jlewis.sh", "").is_err());
+  }
+}
@@ -0,0 +1,64 @@
+use core_types::{ssr::CoreId, API_TOKEN_TABLE};
+
+use crate::{DbConnection, SurrealResult};
+
+impl DbConnection {
+  #[tracing::instrument(skip(self))]
+  pub async fn select_api_tokens_for_user(
+    &self,
+    user_id: core_types::UserRecordId,
+  ) -> SurrealResult<Vec<core_types::ApiToken>> {
+    self
+      .use_main()
+      .await?
+      .query(format!(
+        "SELECT * FROM {API_TOKEN_TABLE} WHERE user = $user ORDER BY \
+         created_at DESC"
+      ))
+      .bind(("user", user_id.to_thing()))
+      .await?
+      .take(0)
+  }
+
+  #[tracing::instrument(skip(self))]
+  pub async fn select_api_token(
+    &self,
+    id: core_types::ApiTokenRecordId,
+  ) -> SurrealResult<Option<core_types::ApiToken>> {
+    self.use_main().await?.select(id).await
+  }
+
+  #[tracing::instrument(skip(self, api_token))]
+  pub async fn insert_api_token(
+    &self,
+    api_token: core_types::ApiToken,
+  ) -> SurrealResult<Option<core_types::ApiToken>> {
+    self
+      .use_main()
+      .await?
+      .create(api_token.id)
+      .content(api_token)
+      .await
+  }
+
+  #[tracing::instrument(skip(self, api_token))]
+  pub async fn update_api_token(
+    &self,
+    api_token: core_types::ApiToken,
+  ) -> SurrealResult<Option<core_types::ApiToken>> {
+    self
+      .use_main()
+      .await?
+      .update(api_token.id)
+      .content(api_token)
+      .await
+  }
+
+  #[tracing::instrument(skip(self))]
+  pub async fn delete_api_token(
+    &self,
+    id: core_types::ApiTokenRecordId,
+  ) -> SurrealResult<Option<core_types::ApiToken>> {
+    self.use_main().await?.delete(id).await
+  }
+}
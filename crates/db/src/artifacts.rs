@@ -0,0 +1,37 @@
+use core_types::ssr::CoreId;
+
+use crate::{DbConnection, SurrealResult};
+
+impl DbConnection {
+  #[tracing::instrument(skip(self))]
+  pub async fn select_artifact(
+    &self,
+    id: core_types::ArtifactRecordId,
+  ) -> SurrealResult<Option<core_types::Artifact>> {
+    self.use_main().await?.select(id).await
+  }
+
+  /// Creates an artifact and records who created it.
+  #[tracing::instrument(skip(self))]
+  pub async fn insert_artifact(
+    &self,
+    artifact: core_types::Artifact,
+    creator: core_types::UserRecordId,
+  ) -> SurrealResult<Option<core_types::Artifact>> {
+    let db = self.use_main().await?;
+    let artifact: Option<core_types::Artifact> =
+      db.create(artifact.id).content(artifact).await?;
+
+    if let Some(artifact) = &artifact {
+      db.query(
+        "RELATE $user->created_artifact->$artifact SET at = time::now()",
+      )
+      .bind(("user", creator.to_thing()))
+      .bind(("artifact", artifact.id.to_thing()))
+      .await?
+      .check()?;
+    }
+
+    Ok(artifact)
+  }
+}
@@ -0,0 +1,116 @@
+use core_types::{
+  ssr::CoreId, Bundle, BundleRecordId, BundleStatus, BUNDLE_TABLE,
+};
+
+use crate::{DbConnection, SurrealResult};
+
+impl DbConnection {
+  /// Creates a bundle, unless one of its translation is already being
+  /// built, in which case `None` is returned. The check and the create are
+  /// one transaction, so two requests can't both start a build.
+  #[tracing::instrument(skip(self))]
+  pub async fn insert_bundle(
+    &self,
+    bundle: Bundle,
+  ) -> SurrealResult<Option<Bundle>> {
+    self
+      .use_main()
+      .await?
+      .query("BEGIN TRANSACTION")
+      .query(format!(
+        "IF array::len((SELECT VALUE id FROM {BUNDLE_TABLE} WHERE translation \
+         = $translation AND status = 'building')) = 0 {{ CREATE $bundle \
+         CONTENT $content }}"
+      ))
+      .query("COMMIT TRANSACTION")
+      .bind(("translation", bundle.translation.to_thing()))
+      .bind(("bundle", bundle.id.to_thing()))
+      .bind(("content", &bundle))
+      .await?
+      .check()?;
+
+    self.select_bundle(bundle.id).await
+  }
+
+  #[tracing::instrument(skip(self))]
+  pub async fn select_bundle(
+    &self,
+    id: BundleRecordId,
+  ) -> SurrealResult<Option<Bundle>> {
+    self.use_main().await?.select(id).await
+  }
+
+  /// Fetches a translation's newest bundle with `status`.
+  #[tracing::instrument(skip(self))]
+  pub async fn select_latest_bundle(
+    &self,
+    translation: core_types::TranslationRecordId,
+    status: BundleStatus,
+  ) -> SurrealResult<Option<Bundle>> {
+    self
+      .use_main()
+      .await?
+      .query(format!(
+        "SELECT * FROM {BUNDLE_TABLE} WHERE translation = $translation AND \
+         status = $status ORDER BY created_at DESC LIMIT 1"
+      ))
+      .bind(("translation", translation.to_thing()))
+      .bind(("status", status))
+      .await?
+      .take(0)
+  }
+
+  /// Records that a bundle has been built and uploaded.
+  #[tracing::instrument(skip(self))]
+  pub async fn finish_bundle(
+    &self,
+    id: BundleRecordId,
+    size: u64,
+    recording_count: u32,
+  ) -> SurrealResult<()> {
+    self
+      .use_main()
+      .await?
+      .query(
+        "UPDATE $bundle SET status = 'ready', size = $size, recording_count = \
+         $recording_count, finished_at = time::now()",
+      )
+      .bind(("bundle", id.to_thing()))
+      .bind(("size", size))
+      .bind(("recording_count", recording_count))
+      .await?
+      .check()?;
+
+    Ok(())
+  }
+
+  #[tracing::instrument(skip(self))]
+  pub async fn fail_bundle(&self, id: BundleRecordId) -> SurrealResult<()> {
+    self
+      .use_main()
+      .await?
+      .query("UPDATE $bundle SET status = 'failed', finished_at = time::now()")
+      .bind(("bundle", id.to_thing()))
+      .await?
+      .check()?;
+
+    Ok(())
+  }
+
+  /// Marks every bundle still building as failed. Builds run in the server
+  /// process, so none survive a restart.
+  #[tracing::instrument(skip(self))]
+  pub async fn fail_unfinished_bundles(&self) -> SurrealResult<()> {
+    self
+      .use_main()
+      .await?
+      .query(format!(
+        "UPDATE {BUNDLE_TABLE} SET status = 'failed', finished_at = \
+         time::now() WHERE status = 'building'"
+      ))
+      .await?
+      .check()?;
+
+    Ok(())
+  }
+}
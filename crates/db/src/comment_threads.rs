@@ -0,0 +1,99 @@
+use core_types::{
+  ssr::CoreId, CommentThread, CommentThreadDetails, CommentThreadRecordId,
+  COMMENTED_IN_THREAD_TABLE, COMMENT_THREAD_TABLE,
+};
+
+use crate::{DbConnection, SurrealResult};
+
+impl DbConnection {
+  /// Starts a thread with its first comment.
+  #[tracing::instrument(skip(self))]
+  pub async fn insert_comment_thread(
+    &self,
+    thread: CommentThread,
+    author: core_types::UserRecordId,
+    body: String,
+  ) -> SurrealResult<Option<CommentThread>> {
+    let db = self.use_main().await?;
+    let thread: Option<CommentThread> =
+      db.create(thread.id).content(thread).await?;
+
+    if let Some(thread) = &thread {
+      self.insert_thread_comment(thread.id, author, body).await?;
+    }
+
+    Ok(thread)
+  }
+
+  #[tracing::instrument(skip(self))]
+  pub async fn select_comment_thread(
+    &self,
+    id: CommentThreadRecordId,
+  ) -> SurrealResult<Option<CommentThread>> {
+    self.use_main().await?.select(id).await
+  }
+
+  /// Adds a comment to the end of a thread.
+  #[tracing::instrument(skip(self))]
+  pub async fn insert_thread_comment(
+    &self,
+    thread: CommentThreadRecordId,
+    author: core_types::UserRecordId,
+    body: String,
+  ) -> SurrealResult<()> {
+    self
+      .use_main()
+      .await?
+      .query(format!(
+        "RELATE $user->{COMMENTED_IN_THREAD_TABLE}->$thread SET at = \
+         time::now(), body = $body"
+      ))
+      .bind(("user", author.to_thing()))
+      .bind(("thread", thread.to_thing()))
+      .bind(("body", body))
+      .await?
+      .check()?;
+
+    Ok(())
+  }
+
+  #[tracing::instrument(skip(self))]
+  pub async fn update_thread_resolved(
+    &self,
+    thread: CommentThreadRecordId,
+    resolved: bool,
+  ) -> SurrealResult<()> {
+    self
+      .use_main()
+      .await?
+      .query("UPDATE $thread SET resolved = $resolved")
+      .bind(("thread", thread.to_thing()))
+      .bind(("resolved", resolved))
+      .await?
+      .check()?;
+
+    Ok(())
+  }
+
+  /// Lists the threads on a recording in the order they appear in it, each
+  /// with its comments.
+  #[tracing::instrument(skip(self))]
+  pub async fn select_comment_threads(
+    &self,
+    artifact: core_types::ArtifactRecordId,
+  ) -> SurrealResult<Vec<CommentThreadDetails>> {
+    self
+      .use_main()
+      .await?
+      .query(format!(
+        "SELECT id, anchor, resolved, (SELECT {{ id: in.id, name: in.name, \
+         avatar_url: in.avatar_url }} AS author, body, at FROM \
+         {COMMENTED_IN_THREAD_TABLE} WHERE out = $parent.id ORDER BY at) AS \
+         comments FROM {COMMENT_THREAD_TABLE} WHERE artifact = $artifact \
+         ORDER BY anchor.start_ms"
+      ))
+      .bind(("artifact", artifact.to_thing()))
+      .await?
+      .take(0)
+  }
+}
@@ -0,0 +1,151 @@
+use core_types::{ssr::CoreId, ContributedRecording, Contribution};
+use serde::de::DeserializeOwned;
+
+use crate::{Count, DbConnection, SurrealResult};
+
+impl DbConnection {
+  /// Pages through the records a user is related to by a `created_*` edge,
+  /// newest first.
+  ///
+  /// `item` is the projection of `out` to return, like `out.*` for the whole
+  /// record or `out` for just its id. Only edges matching `condition` are
+  /// listed, if it's given.
+  async fn select_contributions<T: DeserializeOwned>(
+    &self,
+    edge: &str,
+    item: &str,
+    condition: Option<&str>,
+    user_id: core_types::UserRecordId,
+    offset: u32,
+    count: u32,
+  ) -> SurrealResult<(Vec<Contribution<T>>, usize)> {
+    let where_clause = match condition {
+      Some(condition) => format!("WHERE in = $user AND {condition}"),
+      None => "WHERE in = $user".to_string(),
+    };
+    let mut query = self
+      .use_main()
+      .await?
+      .query(format!(
+        "SELECT count() FROM {edge} {where_clause} GROUP ALL"
+      ))
+      .query(format!(
+        "SELECT {item} AS item, at FROM {edge} {where_clause} ORDER BY at \
+         DESC LIMIT $count START $offset"
+      ))
+      .bind(("user", user_id.to_thing()))
+      .bind(("count", count))
+      .bind(("offset", offset))
+      .await?;
+
+    let total: Option<Count> = query.take(0)?;
+    let content: Vec<Contribution<T>> = query.take(1)?;
+
+    Ok((content, total.map(|c| c.count).unwrap_or(0)))
+  }
+
+  /// Finds who created a mother tongue, if anyone did.
+  #[tracing::instrument(skip(self))]
+  pub async fn select_mother_tongue_creator(
+    &self,
+    tongue: core_types::MotherTongueRecordId,
+  ) -> SurrealResult<Option<core_types::User>> {
+    self
+      .use_main()
+      .await?
+      .query(
+        "SELECT VALUE in.* FROM created_mother_tongue WHERE out = $tongue \
+         LIMIT 1",
+      )
+      .bind(("tongue", tongue.to_thing()))
+      .await?
+      .take(0)
+  }
+
+  /// Finds who started a translation, if anyone did.
+  #[tracing::instrument(skip(self))]
+  pub async fn select_translation_creator(
+    &self,
+    translation: core_types::TranslationRecordId,
+  ) -> SurrealResult<Option<core_types::User>> {
+    self
+      .use_main()
+      .await?
+      .query(
+        "SELECT VALUE in.* FROM created_translation WHERE out = $translation \
+         LIMIT 1",
+      )
+      .bind(("translation", translation.to_thing()))
+      .await?
+      .take(0)
+  }
+
+  /// Pages through the mother tongues a user proposed. Unless
+  /// `include_unvetted` is set, only those a reviewer approved are listed.
+  #[tracing::instrument(skip(self))]
+  pub async fn select_mother_tongues_created_by(
+    &self,
+    user_id: core_types::UserRecordId,
+    include_unvetted: bool,
+    offset: u32,
+    count: u32,
+  ) -> SurrealResult<(Vec<Contribution<core_types::MotherTongue>>, usize)> {
+    self
+      .select_contributions(
+        "created_mother_tongue",
+        "out.*",
+        (!include_unvetted).then_some("out.is_vetted = true"),
+        user_id,
+        offset,
+        count,
+      )
+      .await
+  }
+
+  #[tracing::instrument(skip(self))]
+  pub async fn select_translations_created_by(
+    &self,
+    user_id: core_types::UserRecordId,
+    offset: u32,
+    count: u32,
+  ) -> SurrealResult<(Vec<Contribution<core_types::TranslationSummary>>, usize)>
+  {
+    self
+      .select_contributions(
+        "created_translation",
+        "out.*",
+        None,
+        user_id,
+        offset,
+        count,
+      )
+      .await
+  }
+
+  /// Pages through the recordings a user uploaded for translations. Unless
+  /// `include_unapproved` is set, only approved recordings are listed.
+  #[tracing::instrument(skip(self))]
+  pub async fn select_recordings_created_by(
+    &self,
+    user_id: core_types::UserRecordId,
+    include_unapproved: bool,
+    offset: u32,
+    count: u32,
+  ) -> SurrealResult<(Vec<Contribution<ContributedRecording>>, usize)> {
+    let condition = match include_unapproved {
+      true => "out.translation != NONE",
+      false => "out.translation != NONE AND out.status = 'approved'",
+    };
+    self
+      .select_contributions(
+        "created_artifact",
+        "{ id: out.id, translation: out.translation, translation_name: \
+         out.translation.name, passage: out.passage }",
+        Some(condition),
+        user_id,
+        offset,
+        count,
+      )
+      .await
+  }
+}
@@ -0,0 +1,52 @@
+use core_types::{ssr::CoreId, DEVICE_SESSION_TABLE};
+
+use crate::{DbConnection, SurrealResult};
+
+impl DbConnection {
+  #[tracing::instrument(skip(self))]
+  pub async fn select_device_sessions_for_user(
+    &self,
+    user_id: core_types::UserRecordId,
+  ) -> SurrealResult<Vec<core_types::DeviceSession>> {
+    self
+      .use_main()
+      .await?
+      .query(format!(
+        "SELECT * FROM {DEVICE_SESSION_TABLE} WHERE user = $user ORDER BY \
+         last_seen_at DESC"
+      ))
+      .bind(("user", user_id.to_thing()))
+      .await?
+      .take(0)
+  }
+
+  #[tracing::instrument(skip(self))]
+  pub async fn select_device_session(
+    &self,
+    id: core_types::DeviceSessionRecordId,
+  ) -> SurrealResult<Option<core_types::DeviceSession>> {
+    self.use_main().await?.select(id).await
+  }
+
+  /// Creates or replaces a device session.
+  #[tracing::instrument(skip(self, device_session))]
+  pub async fn upsert_device_session(
+    &self,
+    device_session: core_types::DeviceSession,
+  ) -> SurrealResult<Option<core_types::DeviceSession>> {
+    self
+      .use_main()
+      .await?
+      .update(device_session.id)
+      .content(device_session)
+      .await
+  }
+
+  #[tracing::instrument(skip(self))]
+  pub async fn delete_device_session(
+    &self,
+    id: core_types::DeviceSessionRecordId,
+  ) -> SurrealResult<Option<core_types::DeviceSession>> {
+    self.use_main().await?.delete(id).await
+  }
+}
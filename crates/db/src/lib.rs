@@ -1,8 +1,24 @@
+mod api_tokens;
+mod artifacts;
+mod bundles;
+mod comment_threads;
+mod contributions;
+mod device_sessions;
+mod memberships;
+mod mother_tongues;
+mod oidc_identities;
+mod passkeys;
+mod recording_reviews;
+mod takes;
+mod totp;
+mod translations;
+
 use std::sync::Arc;
 
-use core_types::{MOTHER_TONGUE_TABLE, USER_TABLE};
+use core_types::USER_TABLE;
 use eyre::{Context, Result};
 use include_dir::{include_dir, Dir};
+pub use recording_reviews::ReviewQueueFilter;
 use serde::Deserialize;
 pub use surrealdb::{
   engine::remote::ws::Client as WsClient, Error as SurrealError,
@@ -86,6 +102,13 @@ impl DbConnection {
     self.use_main().await?.insert(user.id).content(user).await
   }
 
+  pub async fn update_user(
+    &self,
+    user: core_types::User,
+  ) -> SurrealResult<Option<core_types::User>> {
+    self.use_main().await?.update(user.id).content(user).await
+  }
+
   #[tracing::instrument(skip(self))]
   pub async fn select_mother_tongue(
     &self,
@@ -94,55 +117,6 @@ impl DbConnection {
     self.use_main().await?.select(id).await
   }
 
-  #[tracing::instrument(skip(self))]
-  pub async fn select_mother_tongues(
-    &self,
-    term: Option<String>,
-    offset: u32,
-    count: u32,
-  ) -> SurrealResult<(Vec<core_types::MotherTongue>, usize)> {
-    let mut query = if let Some(term) = term {
-      let where_clause = "WHERE name @0@ $term || description @1@ $term";
-
-      let count_query = format!(
-        "SELECT count() FROM {MOTHER_TONGUE_TABLE} {where_clause} GROUP all"
-      );
-      let content_query = format!(
-        "SELECT *, search::score(0) * 2 + search::score(1) * 1 AS relevance \
-         FROM {MOTHER_TONGUE_TABLE} {where_clause} ORDER BY relevance DESC \
-         LIMIT {count} START {offset}"
-      );
-
-      self
-        .use_main()
-        .await?
-        .query(count_query)
-        .query(content_query)
-        .bind(("term", term.to_lowercase()))
-        .await?
-    } else {
-      let count_query =
-        format!("SELECT count() FROM {MOTHER_TONGUE_TABLE} GROUP all");
-      let content_query = format!(
-        "SELECT * FROM {MOTHER_TONGUE_TABLE} LIMIT {count} START {offset}"
-      );
-
-      self
-        .use_main()
-        .await?
-        .query(count_query)
-        .query(content_query)
-        .await?
-    };
-
-    let count: Option<Count> = query.take(0)?;
-    let content: Vec<core_types::MotherTongue> = query.take(1)?;
-    // we can reasonably always expect surreal to return this bc of the GROUP
-    let count = count.map(|c| c.count).unwrap_or(0);
-
-    Ok((content, count))
-  }
-
   #[tracing::instrument(skip(self))]
   pub async fn run_migrations(&self) -> Result<()> {
     let db = self.use_main().await?;
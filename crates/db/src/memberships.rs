@@ -0,0 +1,225 @@
+use core_types::{
+  ssr::CoreId, Invitation, InvitationRecordId, InvitationStatus,
+  InvitationSummary, Membership, TranslationMember, TranslationRole,
+  INVITATION_TABLE, MEMBER_OF_TABLE,
+};
+
+use crate::{DbConnection, SurrealResult};
+
+/// The fields of an [`InvitationSummary`], selected from an invitation.
+const INVITATION_SUMMARY_FIELDS: &str =
+  "id, translation, translation.name AS translation_name, roles, email, { id: \
+   invited_by.id, name: invited_by.name, avatar_url: invited_by.avatar_url } \
+   AS invited_by, expires_at";
+
+impl DbConnection {
+  /// Fetches a user's membership of a translation, if they're a member.
+  #[tracing::instrument(skip(self))]
+  pub async fn select_membership(
+    &self,
+    translation: core_types::TranslationRecordId,
+    user: core_types::UserRecordId,
+  ) -> SurrealResult<Option<Membership>> {
+    self
+      .use_main()
+      .await?
+      .query(format!(
+        "SELECT out AS translation, roles FROM {MEMBER_OF_TABLE} WHERE in = \
+         $user AND out = $translation"
+      ))
+      .bind(("user", user.to_thing()))
+      .bind(("translation", translation.to_thing()))
+      .await?
+      .take(0)
+  }
+
+  /// Lists every translation a user is a member of.
+  #[tracing::instrument(skip(self))]
+  pub async fn select_memberships(
+    &self,
+    user: core_types::UserRecordId,
+  ) -> SurrealResult<Vec<Membership>> {
+    self
+      .use_main()
+      .await?
+      .query(format!(
+        "SELECT out AS translation, roles FROM {MEMBER_OF_TABLE} WHERE in = \
+         $user"
+      ))
+      .bind(("user", user.to_thing()))
+      .await?
+      .take(0)
+  }
+
+  /// Lists a translation's members, longest-standing first.
+  #[tracing::instrument(skip(self))]
+  pub async fn select_members(
+    &self,
+    translation: core_types::TranslationRecordId,
+  ) -> SurrealResult<Vec<TranslationMember>> {
+    self
+      .use_main()
+      .await?
+      .query(format!(
+        "SELECT {{ id: in.id, name: in.name, avatar_url: in.avatar_url }} AS \
+         profile, roles, at AS joined_at FROM {MEMBER_OF_TABLE} WHERE out = \
+         $translation AND in.is_active = true ORDER BY joined_at"
+      ))
+      .bind(("translation", translation.to_thing()))
+      .await?
+      .take(0)
+  }
+
+  /// Adds a user to a translation's team with `roles`, or gives them
+  /// `roles` on top of the ones they have if they're already a member.
+  #[tracing::instrument(skip(self))]
+  pub async fn insert_membership(
+    &self,
+    translation: core_types::TranslationRecordId,
+    user: core_types::UserRecordId,
+    roles: &[TranslationRole],
+  ) -> SurrealResult<()> {
+    let query = match self.select_membership(translation, user).await? {
+      Some(_) => format!(
+        "UPDATE {MEMBER_OF_TABLE} SET roles = array::union(roles, $roles) \
+         WHERE in = $user AND out = $translation"
+      ),
+      None => format!(
+        "RELATE $user->{MEMBER_OF_TABLE}->$translation SET roles = $roles, at \
+         = time::now()"
+      ),
+    };
+
+    self
+      .use_main()
+      .await?
+      .query(query)
+      .bind(("user", user.to_thing()))
+      .bind(("translation", translation.to_thing()))
+      .bind(("roles", roles))
+      .await?
+      .check()?;
+
+    Ok(())
+  }
+
+  /// Replaces a member's roles.
+  #[tracing::instrument(skip(self))]
+  pub async fn update_member_roles(
+    &self,
+    translation: core_types::TranslationRecordId,
+    user: core_types::UserRecordId,
+    roles: &[TranslationRole],
+  ) -> SurrealResult<()> {
+    self
+      .use_main()
+      .await?
+      .query(format!(
+        "UPDATE {MEMBER_OF_TABLE} SET roles = $roles WHERE in = $user AND out \
+         = $translation"
+      ))
+      .bind(("user", user.to_thing()))
+      .bind(("translation", translation.to_thing()))
+      .bind(("roles", roles))
+      .await?
+      .check()?;
+
+    Ok(())
+  }
+
+  /// Removes a user from a translation's team.
+  #[tracing::instrument(skip(self))]
+  pub async fn delete_membership(
+    &self,
+    translation: core_types::TranslationRecordId,
+    user: core_types::UserRecordId,
+  ) -> SurrealResult<()> {
+    self
+      .use_main()
+      .await?
+      .query(format!(
+        "DELETE {MEMBER_OF_TABLE} WHERE in = $user AND out = $translation"
+      ))
+      .bind(("user", user.to_thing()))
+      .bind(("translation", translation.to_thing()))
+      .await?
+      .check()?;
+
+    Ok(())
+  }
+
+  #[tracing::instrument(skip(self))]
+  pub async fn insert_invitation(
+    &self,
+    invitation: Invitation,
+  ) -> SurrealResult<Option<Invitation>> {
+    self
+      .use_main()
+      .await?
+      .create(invitation.id)
+      .content(invitation)
+      .await
+  }
+
+  #[tracing::instrument(skip(self))]
+  pub async fn select_invitation(
+    &self,
+    id: InvitationRecordId,
+  ) -> SurrealResult<Option<Invitation>> {
+    self.use_main().await?.select(id).await
+  }
+
+  #[tracing::instrument(skip(self))]
+  pub async fn select_invitation_summary(
+    &self,
+    id: InvitationRecordId,
+  ) -> SurrealResult<Option<InvitationSummary>> {
+    self
+      .use_main()
+      .await?
+      .query(format!(
+        "SELECT {INVITATION_SUMMARY_FIELDS} FROM $invitation"
+      ))
+      .bind(("invitation", id.to_thing()))
+      .await?
+      .take(0)
+  }
+
+  /// Lists the invitations to a translation that can still be accepted.
+  #[tracing::instrument(skip(self))]
+  pub async fn select_open_invitations(
+    &self,
+    translation: core_types::TranslationRecordId,
+  ) -> SurrealResult<Vec<InvitationSummary>> {
+    self
+      .use_main()
+      .await?
+      .query(format!(
+        "SELECT {INVITATION_SUMMARY_FIELDS} FROM {INVITATION_TABLE} WHERE \
+         translation = $translation AND status = 'pending' AND expires_at > \
+         time::now() ORDER BY expires_at"
+      ))
+      .bind(("translation", translation.to_thing()))
+      .await?
+      .take(0)
+  }
+
+  /// Marks an invitation as answered or revoked.
+  #[tracing::instrument(skip(self))]
+  pub async fn update_invitation_status(
+    &self,
+    id: InvitationRecordId,
+    status: InvitationStatus,
+  ) -> SurrealResult<()> {
+    self
+      .use_main()
+      .await?
+      .query("UPDATE $invitation SET status = $status")
+      .bind(("invitation", id.to_thing()))
+      .bind(("status", status))
+      .await?
+      .check()?;
+
+    Ok(())
+  }
+}
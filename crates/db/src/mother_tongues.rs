@@ -0,0 +1,218 @@
+use core_types::{
+  ssr::CoreId, TongueSearch, TongueSearchHit, TongueSearchResults, TongueSort,
+  VettingFilter, HIGHLIGHT_END, HIGHLIGHT_START, MOTHER_TONGUE_TABLE,
+  TRANSLATION_TABLE,
+};
+
+use crate::{Count, DbConnection, SurrealResult};
+
+/// How a search term is matched.
+#[derive(Clone, Copy, PartialEq)]
+enum TermMatch {
+  /// Against the full-text indexes, with scores and highlights.
+  FullText,
+  /// Fuzzily against the name, to catch typos.
+  Fuzzy,
+}
+
+/// Builds the `WHERE` clause for a search. Values are always bound, never
+/// formatted in.
+fn search_conditions(search: &TongueSearch, term_match: TermMatch) -> String {
+  let mut conditions = vec![match search.vetting {
+    VettingFilter::Vetted => "is_vetted = true",
+    VettingFilter::Pending => "is_vetted = false AND rejection_reason = NONE",
+    VettingFilter::Any => "rejection_reason = NONE",
+  }];
+  if search.term.is_some() {
+    conditions.push(match term_match {
+      TermMatch::FullText => {
+        "(name @0@ $term OR description @1@ $term OR alternate_names @2@ $term)"
+      }
+      TermMatch::Fuzzy => "(string::lowercase(name) ~ $term)",
+    });
+  }
+  if search.region.is_some() {
+    conditions.push(
+      "(string::lowercase(region ?? '') CONTAINS $region OR $country IN \
+       countries)",
+    );
+  }
+  if search.language.is_some() {
+    conditions.push("(iso_639_3 = $language OR parent_language = $language)");
+  }
+
+  format!("WHERE {}", conditions.join(" AND "))
+}
+
+impl DbConnection {
+  /// Searches mother tongues, falling back to fuzzy matching on the name if
+  /// the term matches nothing exactly.
+  #[tracing::instrument(skip(self))]
+  pub async fn select_mother_tongues(
+    &self,
+    search: &TongueSearch,
+    offset: u32,
+    count: u32,
+  ) -> SurrealResult<TongueSearchResults> {
+    let results = self
+      .select_mother_tongues_matching(
+        search,
+        TermMatch::FullText,
+        offset,
+        count,
+      )
+      .await?;
+    if results.total > 0 || search.term.is_none() {
+      return Ok(results);
+    }
+
+    self
+      .select_mother_tongues_matching(search, TermMatch::Fuzzy, offset, count)
+      .await
+  }
+
+  async fn select_mother_tongues_matching(
+    &self,
+    search: &TongueSearch,
+    term_match: TermMatch,
+    offset: u32,
+    count: u32,
+  ) -> SurrealResult<TongueSearchResults> {
+    let where_clause = search_conditions(search, term_match);
+    let has_term = search.term.is_some();
+
+    let scoring = match (has_term, term_match) {
+      (true, TermMatch::FullText) => {
+        "search::highlight($mark_start, $mark_end, 0) AS name_highlight, \
+         search::highlight($mark_start, $mark_end, 1) AS \
+         description_highlight, search::score(0) * 2 + search::score(1) + \
+         search::score(2) * 2 AS relevance"
+      }
+      (true, TermMatch::Fuzzy) => {
+        "string::similarity::fuzzy(string::lowercase(name), $term) AS relevance"
+      }
+      (false, _) => "0 AS relevance",
+    };
+    let order = match search.sort {
+      TongueSort::Relevance if has_term => "relevance DESC, name ASC",
+      TongueSort::Relevance | TongueSort::Name => "name ASC",
+      // ids are ULIDs, so they sort by creation time
+      TongueSort::Newest => "id DESC",
+      TongueSort::MostTranslations => "translation_count DESC, name ASC",
+    };
+
+    let mut query = self
+      .use_main()
+      .await?
+      .query(format!(
+        "SELECT count() FROM {MOTHER_TONGUE_TABLE} {where_clause} GROUP ALL"
+      ))
+      .query(format!(
+        "SELECT id, name, id.* AS tongue, count((SELECT VALUE id FROM \
+         {TRANSLATION_TABLE} WHERE mother_tongue = $parent.id)) AS \
+         translation_count, {scoring} FROM {MOTHER_TONGUE_TABLE} \
+         {where_clause} ORDER BY {order} LIMIT $count START $offset"
+      ))
+      .bind(("term", search.term.as_deref().map(str::to_lowercase)))
+      .bind(("region", search.region.as_deref().map(str::to_lowercase)))
+      .bind(("country", search.region.as_deref().map(str::to_uppercase)))
+      .bind((
+        "language",
+        search.language.as_deref().map(str::to_lowercase),
+      ))
+      .bind(("mark_start", HIGHLIGHT_START.to_string()))
+      .bind(("mark_end", HIGHLIGHT_END.to_string()))
+      .bind(("count", count))
+      .bind(("offset", offset))
+      .await?;
+
+    let total: Option<Count> = query.take(0)?;
+    let hits: Vec<TongueSearchHit> = query.take(1)?;
+
+    Ok(TongueSearchResults {
+      hits,
+      total: total.map(|c| c.count).unwrap_or(0),
+      fuzzy: term_match == TermMatch::Fuzzy,
+    })
+  }
+
+  /// Creates a mother tongue and records who proposed it.
+  #[tracing::instrument(skip(self))]
+  pub async fn insert_mother_tongue(
+    &self,
+    tongue: core_types::MotherTongue,
+    creator: core_types::UserRecordId,
+  ) -> SurrealResult<Option<core_types::MotherTongue>> {
+    let db = self.use_main().await?;
+    let tongue: Option<core_types::MotherTongue> =
+      db.create(tongue.id).content(tongue).await?;
+
+    if let Some(tongue) = &tongue {
+      db.query(
+        "RELATE $user->created_mother_tongue->$tongue SET at = time::now()",
+      )
+      .bind(("user", creator.to_thing()))
+      .bind(("tongue", tongue.id.to_thing()))
+      .await?
+      .check()?;
+    }
+
+    Ok(tongue)
+  }
+
+  #[tracing::instrument(skip(self))]
+  pub async fn update_mother_tongue(
+    &self,
+    tongue: core_types::MotherTongue,
+  ) -> SurrealResult<Option<core_types::MotherTongue>> {
+    self
+      .use_main()
+      .await?
+      .update(tongue.id)
+      .content(tongue)
+      .await
+  }
+
+  /// Lists the tongues waiting for a reviewer, oldest first.
+  #[tracing::instrument(skip(self))]
+  pub async fn select_tongue_proposals(
+    &self,
+  ) -> SurrealResult<Vec<core_types::TongueProposal>> {
+    self
+      .use_main()
+      .await?
+      .query(format!(
+        "SELECT id.* AS tongue, (SELECT VALUE {{ id: in.id, name: in.name, \
+         avatar_url: in.avatar_url }} FROM created_mother_tongue WHERE out = \
+         $parent.id)[0] AS proposer, (SELECT VALUE at FROM \
+         created_mother_tongue WHERE out = $parent.id)[0] AS proposed_at FROM \
+         {MOTHER_TONGUE_TABLE} WHERE is_vetted = false AND rejection_reason = \
+         NONE ORDER BY proposed_at"
+      ))
+      .await?
+      .take(0)
+  }
+
+  /// Folds one mother tongue into another, moving its translations over and
+  /// then deleting it.
+  #[tracing::instrument(skip(self))]
+  pub async fn merge_mother_tongue(
+    &self,
+    from: core_types::MotherTongueRecordId,
+    into: core_types::MotherTongueRecordId,
+  ) -> SurrealResult<()> {
+    self
+      .use_main()
+      .await?
+      .query(format!(
+        "BEGIN TRANSACTION; UPDATE {TRANSLATION_TABLE} SET mother_tongue = \
+         $into WHERE mother_tongue = $from; DELETE $from; COMMIT TRANSACTION;"
+      ))
+      .bind(("from", from.to_thing()))
+      .bind(("into", into.to_thing()))
+      .await?
+      .check()?;
+
+    Ok(())
+  }
+}
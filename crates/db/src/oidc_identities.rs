@@ -0,0 +1,62 @@
+use core_types::{ssr::CoreId, OIDC_IDENTITY_TABLE};
+
+use crate::{DbConnection, SurrealResult};
+
+impl DbConnection {
+  #[tracing::instrument(skip(self))]
+  pub async fn select_oidc_identity(
+    &self,
+    issuer: &str,
+    subject: &str,
+  ) -> SurrealResult<Option<core_types::OidcIdentity>> {
+    self
+      .use_main()
+      .await?
+      .query(format!(
+        "SELECT * FROM {OIDC_IDENTITY_TABLE} WHERE issuer = $issuer AND \
+         subject = $subject"
+      ))
+      .bind(("issuer", issuer))
+      .bind(("subject", subject))
+      .await?
+      .take(0)
+  }
+
+  #[tracing::instrument(skip(self))]
+  pub async fn select_oidc_identities_for_user(
+    &self,
+    user_id: core_types::UserRecordId,
+  ) -> SurrealResult<Vec<core_types::OidcIdentity>> {
+    self
+      .use_main()
+      .await?
+      .query(format!(
+        "SELECT * FROM {OIDC_IDENTITY_TABLE} WHERE user = $user ORDER BY \
+         linked_at"
+      ))
+      .bind(("user", user_id.to_thing()))
+      .await?
+      .take(0)
+  }
+
+  #[tracing::instrument(skip(self, identity))]
+  pub async fn insert_oidc_identity(
+    &self,
+    identity: core_types::OidcIdentity,
+  ) -> SurrealResult<Option<core_types::OidcIdentity>> {
+    self
+      .use_main()
+      .await?
+      .create(identity.id)
+      .content(identity)
+      .await
+  }
+
+  #[tracing::instrument(skip(self))]
+  pub async fn delete_oidc_identity(
+    &self,
+    id: core_types::OidcIdentityRecordId,
+  ) -> SurrealResult<Option<core_types::OidcIdentity>> {
+    self.use_main().await?.delete(id).await
+  }
+}
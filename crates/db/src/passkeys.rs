@@ -0,0 +1,55 @@
+use core_types::{ssr::CoreId, PASSKEY_TABLE};
+
+use crate::{DbConnection, SurrealResult};
+
+impl DbConnection {
+  #[tracing::instrument(skip(self))]
+  pub async fn select_passkeys_for_user(
+    &self,
+    user_id: core_types::UserRecordId,
+  ) -> SurrealResult<Vec<core_types::Passkey>> {
+    self
+      .use_main()
+      .await?
+      .query(format!(
+        "SELECT * FROM {PASSKEY_TABLE} WHERE user = $user ORDER BY created_at"
+      ))
+      .bind(("user", user_id.to_thing()))
+      .await?
+      .take(0)
+  }
+
+  #[tracing::instrument(skip(self, passkey))]
+  pub async fn insert_passkey(
+    &self,
+    passkey: core_types::Passkey,
+  ) -> SurrealResult<Option<core_types::Passkey>> {
+    self
+      .use_main()
+      .await?
+      .create(passkey.id)
+      .content(passkey)
+      .await
+  }
+
+  #[tracing::instrument(skip(self, passkey))]
+  pub async fn update_passkey(
+    &self,
+    passkey: core_types::Passkey,
+  ) -> SurrealResult<Option<core_types::Passkey>> {
+    self
+      .use_main()
+      .await?
+      .update(passkey.id)
+      .content(passkey)
+      .await
+  }
+
+  #[tracing::instrument(skip(self))]
+  pub async fn delete_passkey(
+    &self,
+    id: core_types::PasskeyRecordId,
+  ) -> SurrealResult<Option<core_types::Passkey>> {
+    self.use_main().await?.delete(id).await
+  }
+}
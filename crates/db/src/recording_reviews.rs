@@ -0,0 +1,179 @@
+use core_types::{
+  ssr::CoreId, RecordingStatus, RecordingSummary, ReviewEvent, ARTIFACT_TABLE,
+  REVIEWED_RECORDING_TABLE,
+};
+
+use crate::{Count, DbConnection, SurrealResult};
+
+/// The fields of a [`RecordingSummary`], selected from an artifact.
+const RECORDING_SUMMARY_FIELDS: &str =
+  "id, status, passage, translation, translation.name AS translation_name, \
+   (SELECT VALUE { id: in.id, name: in.name, avatar_url: in.avatar_url } FROM \
+   created_artifact WHERE out = $parent.id)[0] AS uploader, (SELECT VALUE at \
+   FROM created_artifact WHERE out = $parent.id)[0] AS uploaded_at";
+
+/// Which recordings to list in the review queue.
+#[derive(Debug)]
+pub struct ReviewQueueFilter {
+  /// Recordings in these statuses are listed whoever uploaded them, in
+  /// every translation.
+  pub statuses:             Vec<RecordingStatus>,
+  /// Recordings are listed if they're in one of these translations and in
+  /// the status paired with it.
+  pub translation_statuses:
+    Vec<(core_types::TranslationRecordId, RecordingStatus)>,
+  /// Recordings in these statuses are listed if `uploader` uploaded them.
+  pub own_statuses:         Vec<RecordingStatus>,
+  pub uploader:             core_types::UserRecordId,
+}
+
+impl DbConnection {
+  /// Pages through the recordings matching a queue filter, oldest first so
+  /// nothing waits forever.
+  #[tracing::instrument(skip(self))]
+  pub async fn select_review_queue(
+    &self,
+    filter: &ReviewQueueFilter,
+    offset: u32,
+    count: u32,
+  ) -> SurrealResult<(Vec<RecordingSummary>, usize)> {
+    let where_clause = "WHERE translation != NONE AND (status IN $statuses OR \
+                        [translation, status] IN $translation_statuses OR \
+                        (status IN $own_statuses AND $uploader IN \
+                        <-created_artifact.in))";
+    let translation_statuses = filter
+      .translation_statuses
+      .iter()
+      .map(|(translation, status)| (translation.to_thing(), *status))
+      .collect::<Vec<_>>();
+
+    let mut query = self
+      .use_main()
+      .await?
+      .query(format!(
+        "SELECT count() FROM {ARTIFACT_TABLE} {where_clause} GROUP ALL"
+      ))
+      .query(format!(
+        "SELECT {RECORDING_SUMMARY_FIELDS} FROM {ARTIFACT_TABLE} \
+         {where_clause} ORDER BY id LIMIT $count START $offset"
+      ))
+      .bind(("statuses", &filter.statuses))
+      .bind(("translation_statuses", translation_statuses))
+      .bind(("own_statuses", &filter.own_statuses))
+      .bind(("uploader", filter.uploader.to_thing()))
+      .bind(("count", count))
+      .bind(("offset", offset))
+      .await?;
+
+    let total: Option<Count> = query.take(0)?;
+    let recordings: Vec<RecordingSummary> = query.take(1)?;
+
+    Ok((recordings, total.map(|c| c.count).unwrap_or(0)))
+  }
+
+  /// Fetches a recording as shown in review. Artifacts that aren't
+  /// recordings for a translation aren't reviewed.
+  #[tracing::instrument(skip(self))]
+  pub async fn select_recording_summary(
+    &self,
+    id: core_types::ArtifactRecordId,
+  ) -> SurrealResult<Option<RecordingSummary>> {
+    self
+      .use_main()
+      .await?
+      .query(format!(
+        "SELECT {RECORDING_SUMMARY_FIELDS} FROM $artifact WHERE translation \
+         != NONE"
+      ))
+      .bind(("artifact", id.to_thing()))
+      .await?
+      .take(0)
+  }
+
+  /// Moves a recording between statuses and records who did it.
+  ///
+  /// Returns `false` without changing anything if the recording wasn't in
+  /// `from`, e.g. because someone else moved it first.
+  #[tracing::instrument(skip(self))]
+  pub async fn update_recording_status(
+    &self,
+    id: core_types::ArtifactRecordId,
+    from: RecordingStatus,
+    to: RecordingStatus,
+    by: core_types::UserRecordId,
+    comment: Option<String>,
+  ) -> SurrealResult<bool> {
+    let db = self.use_main().await?;
+    let moved: Vec<core_types::ArtifactRecordId> = db
+      .query(
+        "UPDATE $artifact SET status = $to WHERE status = $from RETURN VALUE \
+         id",
+      )
+      .bind(("artifact", id.to_thing()))
+      .bind(("from", from))
+      .bind(("to", to))
+      .await?
+      .take(0)?;
+    if moved.is_empty() {
+      return Ok(false);
+    }
+
+    db.query(format!(
+      "RELATE $user->{REVIEWED_RECORDING_TABLE}->$artifact SET at = \
+       time::now(), previous = $from, status = $to, comment = $comment"
+    ))
+    .bind(("user", by.to_thing()))
+    .bind(("artifact", id.to_thing()))
+    .bind(("from", from))
+    .bind(("to", to))
+    .bind(("comment", comment))
+    .await?
+    .check()?;
+
+    Ok(true)
+  }
+
+  /// Records a comment on a recording without moving it.
+  #[tracing::instrument(skip(self))]
+  pub async fn insert_review_comment(
+    &self,
+    id: core_types::ArtifactRecordId,
+    by: core_types::UserRecordId,
+    comment: String,
+  ) -> SurrealResult<()> {
+    self
+      .use_main()
+      .await?
+      .query(format!(
+        "RELATE $user->{REVIEWED_RECORDING_TABLE}->$artifact SET at = \
+         time::now(), comment = $comment"
+      ))
+      .bind(("user", by.to_thing()))
+      .bind(("artifact", id.to_thing()))
+      .bind(("comment", comment))
+      .await?
+      .check()?;
+
+    Ok(())
+  }
+
+  /// Lists everything that has happened to a recording in review, oldest
+  /// first.
+  #[tracing::instrument(skip(self))]
+  pub async fn select_review_history(
+    &self,
+    id: core_types::ArtifactRecordId,
+  ) -> SurrealResult<Vec<ReviewEvent>> {
+    self
+      .use_main()
+      .await?
+      .query(format!(
+        "SELECT {{ id: in.id, name: in.name, avatar_url: in.avatar_url }} AS \
+         by, at, previous, status, comment FROM {REVIEWED_RECORDING_TABLE} \
+         WHERE out = $artifact ORDER BY at"
+      ))
+      .bind(("artifact", id.to_thing()))
+      .await?
+      .take(0)
+  }
+}
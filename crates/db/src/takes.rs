@@ -0,0 +1,212 @@
+use std::collections::HashSet;
+
+use core_types::{
+  scripture::Passage, ssr::CoreId, PassageTakes, Take, ARTIFACT_TABLE,
+  REVIEWED_RECORDING_TABLE,
+};
+
+use crate::{DbConnection, SurrealResult};
+
+/// Matches the artifacts recording `$passage` for `$translation`.
+const SAME_PASSAGE: &str =
+  "translation = $translation AND passage.book = $passage.book AND \
+   passage.chapter = $passage.chapter AND passage.verses = $passage.verses";
+
+/// The fields of a [`Take`], selected from an artifact.
+const TAKE_FIELDS: &str =
+  "id, take, is_current, status, size, (SELECT VALUE { id: in.id, name: \
+   in.name, avatar_url: in.avatar_url } FROM created_artifact WHERE out = \
+   $parent.id)[0] AS uploader, (SELECT VALUE at FROM created_artifact WHERE \
+   out = $parent.id)[0] AS uploaded_at";
+
+/// Matches the artifacts a reviewer approved, which are the only ones
+/// listeners hear.
+pub(crate) const APPROVED: &str = "status = 'approved'";
+
+/// Matches the approved takes of every passage of `$translation`.
+pub(crate) const APPROVED_TAKES: &str =
+  "translation = $translation AND passage != NONE AND status = 'approved'";
+
+/// How approved takes are ordered so that the one listeners hear comes first
+/// for each passage: the current take if it's approved, and otherwise the
+/// newest approved one.
+const LISTENING_ORDER: &str = "ORDER BY is_current DESC, take DESC";
+
+/// Keeps the first recording of each passage, which is the one listeners
+/// hear when they're in [`LISTENING_ORDER`].
+fn first_per_passage<T>(
+  recordings: Vec<T>,
+  passage: impl Fn(&T) -> &Passage,
+) -> Vec<T> {
+  let mut seen = HashSet::new();
+  recordings
+    .into_iter()
+    .filter(|r| seen.insert(passage(r).clone()))
+    .collect()
+}
+
+impl DbConnection {
+  /// Creates a recording of a passage as its newest take, making it current,
+  /// and records who created it. Earlier takes are kept.
+  ///
+  /// Artifacts without a translation and passage aren't takes of anything,
+  /// so they're created as they are.
+  #[tracing::instrument(skip(self))]
+  pub async fn insert_take(
+    &self,
+    artifact: core_types::Artifact,
+    creator: core_types::UserRecordId,
+  ) -> SurrealResult<Option<core_types::Artifact>> {
+    let (Some(translation), Some(passage)) =
+      (artifact.translation, artifact.passage.clone())
+    else {
+      return self.insert_artifact(artifact, creator).await;
+    };
+
+    let db = self.use_main().await?;
+    db.query("BEGIN TRANSACTION")
+      .query(format!(
+        "LET $takes = SELECT VALUE take FROM {ARTIFACT_TABLE} WHERE \
+         {SAME_PASSAGE}"
+      ))
+      .query(format!(
+        "UPDATE {ARTIFACT_TABLE} SET is_current = false WHERE {SAME_PASSAGE}"
+      ))
+      .query("CREATE $artifact CONTENT $content")
+      .query(
+        "UPDATE $artifact SET take = math::max(array::append($takes, 0)) + 1, \
+         is_current = true",
+      )
+      .query("RELATE $user->created_artifact->$artifact SET at = time::now()")
+      .query("COMMIT TRANSACTION")
+      .bind(("translation", translation.to_thing()))
+      .bind(("passage", passage))
+      .bind(("artifact", artifact.id.to_thing()))
+      .bind(("user", creator.to_thing()))
+      .bind(("content", &artifact))
+      .await?
+      .check()?;
+
+    self.select_artifact(artifact.id).await
+  }
+
+  /// Lists the takes of a passage, newest first.
+  #[tracing::instrument(skip(self))]
+  pub async fn select_takes(
+    &self,
+    translation: core_types::TranslationRecordId,
+    passage: &Passage,
+  ) -> SurrealResult<Vec<Take>> {
+    self
+      .use_main()
+      .await?
+      .query(format!(
+        "SELECT {TAKE_FIELDS} FROM {ARTIFACT_TABLE} WHERE {SAME_PASSAGE} \
+         ORDER BY take DESC"
+      ))
+      .bind(("translation", translation.to_thing()))
+      .bind(("passage", passage))
+      .await?
+      .take(0)
+  }
+
+  /// Lists the passages recorded for a translation with their current takes.
+  #[tracing::instrument(skip(self))]
+  pub async fn select_current_takes(
+    &self,
+    translation: core_types::TranslationRecordId,
+  ) -> SurrealResult<Vec<PassageTakes>> {
+    self
+      .use_main()
+      .await?
+      .query(format!(
+        "SELECT passage, count((SELECT VALUE id FROM {ARTIFACT_TABLE} WHERE \
+         translation = $parent.translation AND passage.book = \
+         $parent.passage.book AND passage.chapter = $parent.passage.chapter \
+         AND passage.verses = $parent.passage.verses)) AS take_count, (SELECT \
+         {TAKE_FIELDS} FROM $parent.id)[0] AS current FROM {ARTIFACT_TABLE} \
+         WHERE translation = $translation AND passage != NONE AND is_current \
+         = true"
+      ))
+      .bind(("translation", translation.to_thing()))
+      .await?
+      .take(0)
+  }
+
+  /// Lists the recordings listeners hear: the current take of each passage
+  /// if it's approved, and otherwise its newest approved take. Re-recording
+  /// a passage makes the new take current before it's approved, so the
+  /// approved take it replaces is heard until then.
+  #[tracing::instrument(skip(self))]
+  pub async fn select_listening_tracks(
+    &self,
+    translation: core_types::TranslationRecordId,
+  ) -> SurrealResult<Vec<core_types::ListeningTrack>> {
+    let tracks: Vec<core_types::ListeningTrack> = self
+      .use_main()
+      .await?
+      .query(format!(
+        "SELECT id, passage, take, is_current FROM {ARTIFACT_TABLE} WHERE \
+         {APPROVED_TAKES} {LISTENING_ORDER}"
+      ))
+      .bind(("translation", translation.to_thing()))
+      .await?
+      .take(0)?;
+
+    Ok(first_per_passage(tracks, |t| &t.passage))
+  }
+
+  /// Lists the same recordings as
+  /// [`select_listening_tracks`](Self::select_listening_tracks), with what a
+  /// podcast feed or bundle needs to know about them.
+  ///
+  /// Each is dated by when it was first approved, so its date only changes
+  /// when another take of its passage is heard in its place.
+  #[tracing::instrument(skip(self))]
+  pub async fn select_approved_recordings(
+    &self,
+    translation: core_types::TranslationRecordId,
+  ) -> SurrealResult<Vec<core_types::ApprovedRecording>> {
+    let recordings: Vec<core_types::ApprovedRecording> = self
+      .use_main()
+      .await?
+      .query(format!(
+        "SELECT id, passage, take, is_current, object_key, content_type, \
+         size, duration_ms, ((SELECT VALUE at FROM {REVIEWED_RECORDING_TABLE} \
+         WHERE out = $parent.id AND status = 'approved' ORDER BY at ASC LIMIT \
+         1)[0] ?? (SELECT VALUE at FROM created_artifact WHERE out = \
+         $parent.id)[0]) AS approved_at FROM {ARTIFACT_TABLE} WHERE \
+         {APPROVED_TAKES} {LISTENING_ORDER}"
+      ))
+      .bind(("translation", translation.to_thing()))
+      .await?
+      .take(0)?;
+
+    Ok(first_per_passage(recordings, |r| &r.passage))
+  }
+
+  /// Makes a take the current one for its passage, in place of whichever
+  /// was.
+  #[tracing::instrument(skip(self))]
+  pub async fn update_current_take(
+    &self,
+    id: core_types::ArtifactRecordId,
+  ) -> SurrealResult<()> {
+    self
+      .use_main()
+      .await?
+      .query("BEGIN TRANSACTION")
+      .query("LET $translation = $artifact.translation")
+      .query("LET $passage = $artifact.passage")
+      .query(format!(
+        "UPDATE {ARTIFACT_TABLE} SET is_current = (id = $artifact) WHERE \
+         {SAME_PASSAGE}"
+      ))
+      .query("COMMIT TRANSACTION")
+      .bind(("artifact", id.to_thing()))
+      .await?
+      .check()?;
+
+    Ok(())
+  }
+}
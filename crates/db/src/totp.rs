@@ -0,0 +1,85 @@
+//! Guarded updates to a user's TOTP state, so that concurrent logins can't
+//! both use the same code or lose each other's wrong attempts.
+
+use core_types::{ssr::CoreId, User, UserRecordId};
+use serde::Serialize;
+
+use crate::{DbConnection, SurrealResult};
+
+/// A time, bound the same way [`TotpConfig`](core_types::TotpConfig) stores
+/// it.
+#[derive(Serialize)]
+struct StoredTime(#[serde(with = "core_types::iso8601")] time::OffsetDateTime);
+
+impl DbConnection {
+  /// Records that a code for `step` was accepted, unless a code for it or a
+  /// later step already was. Returns the updated user if it was recorded.
+  #[tracing::instrument(skip(self))]
+  pub async fn accept_totp_step(
+    &self,
+    user: UserRecordId,
+    step: u64,
+  ) -> SurrealResult<Option<User>> {
+    self
+      .use_main()
+      .await?
+      .query(
+        "UPDATE $user SET totp.last_used_step = $step, totp.failed_attempts = \
+         0, totp.last_failed_at = NONE WHERE totp.enabled = true AND \
+         (totp.last_used_step = NONE OR totp.last_used_step < $step)",
+      )
+      .bind(("user", user.to_thing()))
+      .bind(("step", step))
+      .await?
+      .take(0)
+  }
+
+  /// Removes a recovery code's hash, unless it's already been used. Returns
+  /// the updated user if it was removed.
+  #[tracing::instrument(skip(self, hash))]
+  pub async fn use_recovery_code(
+    &self,
+    user: UserRecordId,
+    hash: &str,
+  ) -> SurrealResult<Option<User>> {
+    self
+      .use_main()
+      .await?
+      .query(
+        "UPDATE $user SET totp.recovery_code_hashes -= $hash, \
+         totp.failed_attempts = 0, totp.last_failed_at = NONE WHERE \
+         totp.enabled = true AND totp.recovery_code_hashes CONTAINS $hash",
+      )
+      .bind(("user", user.to_thing()))
+      .bind(("hash", hash))
+      .await?
+      .take(0)
+  }
+
+  /// Counts a wrong code entered at `now`. If the last wrong code was
+  /// `expired_failure`, it's too old to count with this one, so the count
+  /// starts again.
+  #[tracing::instrument(skip(self))]
+  pub async fn record_second_factor_failure(
+    &self,
+    user: UserRecordId,
+    now: time::OffsetDateTime,
+    expired_failure: Option<time::OffsetDateTime>,
+  ) -> SurrealResult<()> {
+    self
+      .use_main()
+      .await?
+      .query(
+        "UPDATE $user SET totp.failed_attempts = IF totp.last_failed_at = \
+         $expired_failure THEN 1 ELSE totp.failed_attempts + 1 END, \
+         totp.last_failed_at = $now WHERE totp.enabled = true",
+      )
+      .bind(("user", user.to_thing()))
+      .bind(("now", StoredTime(now)))
+      .bind(("expired_failure", expired_failure.map(StoredTime)))
+      .await?
+      .check()?;
+
+    Ok(())
+  }
+}
@@ -0,0 +1,114 @@
+use core_types::{ssr::CoreId, ARTIFACT_TABLE, TRANSLATION_TABLE};
+
+use crate::{
+  takes::{APPROVED, APPROVED_TAKES},
+  DbConnection, SurrealResult,
+};
+
+impl DbConnection {
+  #[tracing::instrument(skip(self))]
+  pub async fn select_translation(
+    &self,
+    id: core_types::TranslationRecordId,
+  ) -> SurrealResult<Option<core_types::Translation>> {
+    self.use_main().await?.select(id).await
+  }
+
+  /// Lists the translations in a mother tongue, with their recording counts.
+  #[tracing::instrument(skip(self))]
+  pub async fn select_translations_in_tongue(
+    &self,
+    tongue: core_types::MotherTongueRecordId,
+  ) -> SurrealResult<Vec<core_types::TranslationListing>> {
+    self
+      .use_main()
+      .await?
+      .query(format!(
+        "SELECT *, count((SELECT VALUE id FROM {ARTIFACT_TABLE} WHERE \
+         translation = $parent.id)) AS recording_count FROM \
+         {TRANSLATION_TABLE} WHERE mother_tongue = $tongue ORDER BY name"
+      ))
+      .bind(("tongue", tongue.to_thing()))
+      .await?
+      .take(0)
+  }
+
+  /// Picks the newest approved recording from any translation in a mother
+  /// tongue. Artifact ids are ULIDs, so they sort by when they were created.
+  #[tracing::instrument(skip(self))]
+  pub async fn select_sample_recording(
+    &self,
+    tongue: core_types::MotherTongueRecordId,
+  ) -> SurrealResult<Option<core_types::ArtifactRecordId>> {
+    self
+      .use_main()
+      .await?
+      .query(format!(
+        "SELECT VALUE id FROM {ARTIFACT_TABLE} WHERE \
+         translation.mother_tongue = $tongue AND {APPROVED} ORDER BY id DESC \
+         LIMIT 1"
+      ))
+      .bind(("tongue", tongue.to_thing()))
+      .await?
+      .take(0)
+  }
+
+  /// Lists the passages with approved recordings for a translation.
+  #[tracing::instrument(skip(self))]
+  pub async fn select_recorded_passages(
+    &self,
+    translation: core_types::TranslationRecordId,
+  ) -> SurrealResult<Vec<core_types::scripture::Passage>> {
+    self
+      .use_main()
+      .await?
+      .query(format!(
+        "SELECT VALUE passage FROM {ARTIFACT_TABLE} WHERE {APPROVED_TAKES}"
+      ))
+      .bind(("translation", translation.to_thing()))
+      .await?
+      .take(0)
+  }
+
+  /// Lists the passages with approved recordings for every translation in
+  /// the given mother tongues.
+  #[tracing::instrument(skip(self, tongues))]
+  pub async fn select_recorded_passages_in_tongues(
+    &self,
+    tongues: &[core_types::MotherTongueRecordId],
+  ) -> SurrealResult<Vec<core_types::RecordedPassage>> {
+    self
+      .use_main()
+      .await?
+      .query(format!(
+        "SELECT translation, translation.mother_tongue AS mother_tongue, \
+         passage FROM {ARTIFACT_TABLE} WHERE translation.mother_tongue IN \
+         $tongues AND passage != NONE AND {APPROVED}"
+      ))
+      .bind((
+        "tongues",
+        tongues.iter().map(|t| t.to_thing()).collect::<Vec<_>>(),
+      ))
+      .await?
+      .take(0)
+  }
+
+  /// Sets the terms a translation's recordings are shared under.
+  #[tracing::instrument(skip(self))]
+  pub async fn update_translation_license(
+    &self,
+    translation: core_types::TranslationRecordId,
+    license: core_types::License,
+  ) -> SurrealResult<()> {
+    self
+      .use_main()
+      .await?
+      .query("UPDATE $translation SET license = $license")
+      .bind(("translation", translation.to_thing()))
+      .bind(("license", license))
+      .await?
+      .check()?;
+
+    Ok(())
+  }
+}
@@ -0,0 +1,306 @@
+use core_types::{
+  format_timestamp, validation::ANCHOR_FIELD, AppError, ArtifactRecordId,
+  CommentThreadDetails, TimeAnchor,
+};
+use leptos::*;
+
+use crate::functions::{
+  app_error,
+  recordings::{
+    list_comment_threads, ReplyToThread, SetThreadResolved, StartCommentThread,
+  },
+};
+
+type ThreadAction<A> = Action<A, Result<(), ServerFnError<AppError>>>;
+
+/// A player for a recording with its comment threads shown as markers along
+/// the timeline. Clicking a marker or a thread's timestamp jumps there.
+#[component]
+pub fn AnnotatedPlayer(
+  id: ArtifactRecordId,
+  /// Whether the current user can start, reply to and resolve threads.
+  can_comment: bool,
+) -> impl IntoView {
+  let start_action = create_server_action::<StartCommentThread>();
+  let reply_action = create_server_action::<ReplyToThread>();
+  let resolve_action = create_server_action::<SetThreadResolved>();
+
+  let threads = create_resource(
+    move || {
+      (
+        start_action.version()(),
+        reply_action.version()(),
+        resolve_action.version()(),
+      )
+    },
+    move |_| list_comment_threads(id),
+  );
+
+  let audio = create_node_ref::<html::Audio>();
+  let (position_ms, set_position_ms) = create_signal(0_u32);
+  let (duration_ms, set_duration_ms) = create_signal(0_u32);
+  let update_duration = move |_| {
+    if let Some(audio) = audio.get_untracked() {
+      let duration = audio.duration();
+      // streams report an infinite duration until they've loaded
+      if duration.is_finite() {
+        set_duration_ms((duration * 1000.0) as u32);
+      }
+    }
+  };
+  let seek = move |ms: u32| {
+    if let Some(audio) = audio.get_untracked() {
+      audio.set_current_time(f64::from(ms) / 1000.0);
+    }
+  };
+
+  let failure = move || {
+    [
+      start_action.value()().and_then(Result::err),
+      reply_action.value()().and_then(Result::err),
+      resolve_action.value()().and_then(Result::err),
+    ]
+    .into_iter()
+    .flatten()
+    .next()
+    .map(|e| app_error(&e))
+    .filter(|e| !matches!(e, AppError::Validation(_)))
+    .map(|e| e.message())
+  };
+
+  view! {
+    <div class="flex flex-col gap-2 max-w-prose">
+      <audio
+        class="w-full" controls preload="metadata"
+        src=core_types::artifact_media_url(id)
+        node_ref=audio
+        on:loadedmetadata=update_duration
+        on:durationchange=update_duration
+        on:timeupdate=move |_| {
+          if let Some(audio) = audio.get_untracked() {
+            set_position_ms((audio.current_time() * 1000.0) as u32);
+          }
+        }
+      />
+      <Transition fallback=|| ()>
+        { move || threads().map(|result| match result {
+          Ok(threads) => view! {
+            <Markers threads=threads.clone() duration_ms seek />
+            { threads.into_iter().map(|thread| view! {
+              <ThreadCard thread can_comment seek reply_action resolve_action />
+            }).collect_view() }
+          }
+          .into_view(),
+          Err(e) => view! {
+            <p class="text-red-11 text-sm">{ app_error(&e).message() }</p>
+          }
+          .into_view(),
+        }) }
+      </Transition>
+      { move || failure().map(|message| view! {
+        <p class="text-red-11 text-sm">{ message }</p>
+      }) }
+      { can_comment.then(|| view! {
+        <NewThreadForm id position_ms start_action />
+      }) }
+    </div>
+  }
+}
+
+/// The timeline under the player, with a marker for each thread.
+#[component]
+fn Markers(
+  threads: Vec<CommentThreadDetails>,
+  duration_ms: ReadSignal<u32>,
+  seek: impl Fn(u32) + Copy + 'static,
+) -> impl IntoView {
+  let markers = move || {
+    let duration = duration_ms();
+    if duration == 0 {
+      return ().into_view();
+    }
+    let percent =
+      |ms: u32| (f64::from(ms) * 100.0 / f64::from(duration)).min(100.0);
+
+    threads
+      .iter()
+      .map(|thread| {
+        let anchor = thread.anchor;
+        let left = percent(anchor.start_ms);
+        // moments get a sliver so they can still be clicked
+        let width = anchor
+          .end_ms
+          .map(|end| percent(end) - left)
+          .unwrap_or(0.0)
+          .max(0.75);
+        let class = match thread.resolved {
+          true => "absolute h-full rounded-sm bg-gray-7 hover:bg-gray-9",
+          false => "absolute h-full rounded-sm bg-yellow-9 hover:bg-yellow-11",
+        };
+        view! {
+          <button
+            class=class
+            style=format!("left: {left:.2}%; width: {width:.2}%")
+            title=anchor.to_string()
+            on:click=move |_| seek(anchor.start_ms)
+          />
+        }
+      })
+      .collect_view()
+  };
+
+  view! {
+    <div class="relative h-3 w-full rounded-sm bg-gray-3">
+      { markers }
+    </div>
+  }
+}
+
+#[component]
+fn ThreadCard(
+  thread: CommentThreadDetails,
+  can_comment: bool,
+  seek: impl Fn(u32) + Copy + 'static,
+  reply_action: ThreadAction<ReplyToThread>,
+  resolve_action: ThreadAction<SetThreadResolved>,
+) -> impl IntoView {
+  let CommentThreadDetails {
+    id,
+    anchor,
+    resolved,
+    comments,
+  } = thread;
+  let (reply, set_reply) = create_signal(String::new());
+
+  view! {
+    <div class=match resolved {
+      true => "flex flex-col gap-2 border-l-2 border-gray-6 pl-3 opacity-60",
+      false => "flex flex-col gap-2 border-l-2 border-yellow-9 pl-3",
+    }>
+      <div class="flex flex-row gap-2 items-center">
+        <button
+          class="font-mono text-sm underline hover:no-underline"
+          on:click=move |_| seek(anchor.start_ms)
+        >{ anchor.to_string() }</button>
+        { resolved.then(|| view! {
+          <span class="badge badge-outline badge-sm">"Resolved"</span>
+        }) }
+        <div class="flex-1" />
+        { can_comment.then(|| view! {
+          <button
+            class="btn btn-ghost btn-xs"
+            disabled=resolve_action.pending()
+            on:click=move |_| resolve_action.dispatch(SetThreadResolved {
+              id,
+              resolved: !resolved,
+            })
+          >{ if resolved { "Reopen" } else { "Resolve" } }</button>
+        }) }
+      </div>
+      { comments.into_iter().map(|comment| view! {
+        <div class="text-sm">
+          <span class="font-semibold">
+            { comment.author.map(|a| a.name).unwrap_or_else(|| "Someone".to_string()) }
+          </span>
+          <span class="text-content2">{ format!(" on {}", comment.at.date()) }</span>
+          <p class="whitespace-pre-wrap">{ comment.body }</p>
+        </div>
+      }).collect_view() }
+      { can_comment.then(|| view! {
+        <div class="flex flex-row gap-2 items-center">
+          <input
+            placeholder="Reply"
+            class="input input-sm flex-1 max-w-full"
+            on:input=move |ev| set_reply(event_target_value(&ev))
+            prop:value=reply
+          />
+          <button
+            class="btn btn-outline btn-sm"
+            disabled=move || reply().trim().is_empty() || reply_action.pending()()
+            on:click=move |_| {
+              reply_action.dispatch(ReplyToThread {
+                id,
+                comment: reply.get_untracked(),
+              });
+              set_reply(String::new());
+            }
+          >"Reply"</button>
+        </div>
+      }) }
+    </div>
+  }
+}
+
+#[component]
+fn NewThreadForm(
+  id: ArtifactRecordId,
+  position_ms: ReadSignal<u32>,
+  start_action: Action<
+    StartCommentThread,
+    Result<core_types::CommentThreadRecordId, ServerFnError<AppError>>,
+  >,
+) -> impl IntoView {
+  let (anchor, set_anchor) = create_signal(String::new());
+  let (comment, set_comment) = create_signal(String::new());
+  let (anchor_error, set_anchor_error) = create_signal::<Option<String>>(None);
+
+  let server_anchor_error = move || match start_action.value()() {
+    Some(Err(e)) => match app_error(&e) {
+      AppError::Validation(errors) => errors.get(ANCHOR_FIELD).cloned(),
+      _ => None,
+    },
+    _ => None,
+  };
+
+  create_effect(move |_| {
+    if matches!(start_action.value()(), Some(Ok(_))) {
+      set_anchor(String::new());
+      set_comment(String::new());
+    }
+  });
+
+  let submit = move |_| match anchor.get_untracked().parse::<TimeAnchor>() {
+    Ok(parsed) => {
+      set_anchor_error(None);
+      start_action.dispatch(StartCommentThread {
+        id,
+        anchor: parsed,
+        comment: comment.get_untracked(),
+      });
+    }
+    Err(message) => set_anchor_error(Some(message)),
+  };
+
+  view! {
+    <div class="form-field">
+      <div class="flex flex-row gap-2 items-center">
+        <input
+          placeholder="1:32 or 1:32-1:40"
+          class="input input-sm w-40 font-mono"
+          on:input=move |ev| set_anchor(event_target_value(&ev))
+          prop:value=anchor
+        />
+        <button
+          class="btn btn-ghost btn-sm"
+          on:click=move |_| set_anchor(format_timestamp(position_ms.get_untracked()))
+        >"Now"</button>
+        <input
+          placeholder="What needs attention here?"
+          class="input input-sm flex-1 max-w-full"
+          on:input=move |ev| set_comment(event_target_value(&ev))
+          prop:value=comment
+        />
+        <button
+          class="btn btn-primary btn-sm"
+          disabled=move || comment().trim().is_empty() || start_action.pending()()
+          on:click=submit
+        >"Add comment"</button>
+      </div>
+      { move || anchor_error().or_else(server_anchor_error).map(|message| view! {
+        <label class="form-label animate-slide-down">
+          <span class="form-label-alt text-red-11">{ message }</span>
+        </label>
+      }) }
+    </div>
+  }
+}
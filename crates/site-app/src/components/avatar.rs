@@ -0,0 +1,20 @@
+use core_types::PublicProfile;
+use leptos::*;
+
+/// A user's avatar image, or their initials if they don't have one.
+#[component]
+pub fn Avatar(
+  profile: PublicProfile,
+  #[prop(optional, into)] class: MaybeProp<String>,
+) -> impl IntoView {
+  let class = move || format!("avatar {}", class().unwrap_or_default());
+
+  view! {
+    <div class=class>
+      { match profile.avatar_url.clone() {
+        Some(url) => view! { <img src=url alt=profile.name.clone() /> }.into_view(),
+        None => view! { <span>{ profile.initials() }</span> }.into_view(),
+      }}
+    </div>
+  }
+}
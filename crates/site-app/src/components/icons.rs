@@ -31,6 +31,14 @@ hero_icons_component_outline!(
   "M8.25 9V5.25A2.25 2.25 0 0 1 10.5 3h6a2.25 2.25 0 0 1 2.25 2.25v13.5A2.25 \
    2.25 0 0 1 16.5 21h-6a2.25 2.25 0 0 1-2.25-2.25V15m-3 0-3-3m0 0 3-3m-3 3H15"
 );
+hero_icons_component_outline!(
+  HeroIconsIdentification,
+  "M15 9h3.75M15 12h3.75M15 15h3.75M4.5 19.5h15a2.25 2.25 0 0 0 \
+   2.25-2.25V6.75A2.25 2.25 0 0 0 19.5 4.5h-15a2.25 2.25 0 0 0-2.25 \
+   2.25v10.5A2.25 2.25 0 0 0 4.5 19.5Zm6-10.125a1.875 1.875 0 1 1-3.75 0 \
+   1.875 1.875 0 0 1 3.75 0Zm1.294 6.336a6.721 6.721 0 0 1-3.17.789 6.721 \
+   6.721 0 0 1-3.168-.789 3.376 3.376 0 0 1 6.338 0Z"
+);
 hero_icons_component_outline!(HeroIconsCheck, "m4.5 12.75 6 6 9-13.5");
 hero_icons_component_outline!(HeroIconsPlus, "M12 4.5v15m7.5-7.5h-15");
 hero_icons_component_outline!(
@@ -43,3 +51,20 @@ hero_icons_component_outline!(
    0-3.32 0c-1.18.037-2.09 1.022-2.09 2.201v.916m7.5 0a48.667 48.667 0 0 \
    0-7.5 0"
 );
+hero_icons_component_outline!(
+  HeroIconsClipboardDocumentCheck,
+  "M11.35 3.836c-.065.21-.1.433-.1.664 0 .414.336.75.75.75h4.5a.75.75 0 0 0 \
+   .75-.75 2.25 2.25 0 0 0-.1-.664m-5.8 0A2.251 2.251 0 0 1 13.5 \
+   2.25H15c1.012 0 1.867.668 2.15 1.586m-5.8 \
+   0c-.376.023-.75.05-1.124.08C9.095 4.01 8.25 4.973 8.25 \
+   6.108V8.25m8.9-4.414c.376.023.75.05 1.124.08 1.131.094 1.976 1.057 1.976 \
+   2.192V16.5A2.25 2.25 0 0 1 18 18.75h-2.25m-7.5-10.5H4.875c-.621 \
+   0-1.125.504-1.125 1.125v11.25c0 .621.504 1.125 1.125 1.125h9.75c.621 0 \
+   1.125-.504 1.125-1.125V18.75m-7.5-10.5h6.375c.621 0 1.125.504 1.125 \
+   1.125v9.375m-8.25-3 1.5 1.5 3-3.75"
+);
+hero_icons_component_outline!(
+  HeroIconsMicrophone,
+  "M12 18.75a6 6 0 0 0 6-6v-1.5m-6 7.5a6 6 0 0 1-6-6v-1.5m6 7.5v3.75m-3.75 \
+   0h7.5M12 15.75a3 3 0 0 1-3-3V4.5a3 3 0 1 1 6 0v8.25a3 3 0 0 1-3 3Z"
+);
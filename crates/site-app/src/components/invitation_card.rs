@@ -0,0 +1,74 @@
+use core_types::{AppError, InvitationSummary, TranslationRole};
+use leptos::*;
+
+use crate::{
+  components::{avatar::Avatar, Link},
+  functions::members::RespondToInvitation,
+  LinkTarget,
+};
+
+/// Lists roles like "Speaker, Consultant".
+pub fn role_labels(roles: &[TranslationRole]) -> String {
+  roles
+    .iter()
+    .map(|r| r.label())
+    .collect::<Vec<_>>()
+    .join(", ")
+}
+
+/// An invitation to a translation's team, with buttons to answer it.
+#[component]
+pub fn InvitationCard(
+  invitation: InvitationSummary,
+  /// The secret from the invitation's link, which answering it needs.
+  secret: Option<String>,
+  respond: Action<RespondToInvitation, Result<(), ServerFnError<AppError>>>,
+) -> impl IntoView {
+  let id = invitation.id;
+  let decline_secret = secret.clone();
+
+  view! {
+    <div class="card border border-border max-w-full">
+      <div class="card-body gap-2">
+        <Link target=LinkTarget::Translation(invitation.translation) class="text-xl font-semibold underline hover:no-underline">
+          { invitation.translation_name }
+        </Link>
+        <p class="text-sm">
+          { format!("Join the team as {}.", role_labels(&invitation.roles).to_lowercase()) }
+        </p>
+        { invitation.invited_by.map(|by| view! {
+          <div class="flex flex-row gap-2 items-center text-sm">
+            <Avatar profile=by.clone() class="avatar-xs" />
+            <span class="text-content2">"Invited by"</span>
+            <Link target=LinkTarget::UserProfile(by.id) class="underline hover:no-underline">
+              { by.name }
+            </Link>
+          </div>
+        }) }
+        <p class="text-content2 text-sm">
+          { format!("Expires {}", invitation.expires_at.date()) }
+        </p>
+        <div class="flex flex-row gap-2">
+          <button
+            class="btn btn-primary btn-sm"
+            disabled=respond.pending()
+            on:click=move |_| respond.dispatch(RespondToInvitation {
+              id,
+              secret: secret.clone(),
+              accept: true,
+            })
+          >"Accept"</button>
+          <button
+            class="btn btn-outline btn-sm"
+            disabled=respond.pending()
+            on:click=move |_| respond.dispatch(RespondToInvitation {
+              id,
+              secret: decline_secret.clone(),
+              accept: false,
+            })
+          >"Decline"</button>
+        </div>
+      </div>
+    </div>
+  }
+}
@@ -1,15 +1,18 @@
+use core_types::AppError;
 use leptos::*;
 
+#[cfg(feature = "ssr")]
+use crate::functions::{handle_error, server_context};
+
 #[cfg_attr(feature = "ssr", tracing::instrument)]
 #[server(Logout)]
-pub async fn logout() -> Result<(), ServerFnError> {
-  let mut auth_session = use_context::<auth::AuthSession>()
-    .ok_or_else(|| ServerFnError::new("Failed to get auth session"))?;
-
-  auth_session.logout().await.map_err(|e| {
-    logging::error!("Failed to log out: {:?}", e);
-    ServerFnError::new("Failed to log out")
-  })?;
+pub async fn logout() -> Result<(), ServerFnError<AppError>> {
+  let mut auth_session = server_context::<auth::AuthSession>("auth session")?;
+
+  auth_session
+    .logout()
+    .await
+    .map_err(|e| handle_error(eyre::eyre!("{e}"), "log out"))?;
 
   Ok(())
 }
@@ -1,10 +1,15 @@
 pub mod action_status;
+pub mod annotated_player;
+pub mod avatar;
 pub mod icons;
+pub mod invitation_card;
 pub mod logout;
 pub mod mini_pages;
 pub mod mother_tongues_table;
 pub mod navbar;
+pub mod offline_bundle;
 pub mod pagination;
+pub mod passage_recorder;
 
 use leptos::*;
 pub use navbar::*;
@@ -1,3 +1,6 @@
+use core_types::{
+  highlight_segments, TongueSearch, TongueSearchHit, TongueSort, VettingFilter,
+};
 use leptos::*;
 
 use crate::{
@@ -6,38 +9,80 @@ use crate::{
 
 const DEFAULT_FETCH_LIMIT: usize = 10;
 
+/// The query string key for the current page of results.
+pub const PAGE_KEY: &str = "page";
+
 #[island]
-pub fn MotherTonguesTable() -> impl IntoView {
-  let (query_term, set_query_term) = create_signal(String::new());
-  let (current_page, set_current_page) = create_signal(0_u32);
+pub fn MotherTonguesTable(
+  /// The search to start with, from the URL.
+  initial: TongueSearch,
+  /// The page to start on, from the URL.
+  #[prop(optional)]
+  initial_page: u32,
+) -> impl IntoView {
+  let (query_term, set_query_term) =
+    create_signal(initial.term.unwrap_or_default());
+  let (region, set_region) = create_signal(initial.region.unwrap_or_default());
+  let (language, set_language) =
+    create_signal(initial.language.unwrap_or_default());
+  let (vetting, set_vetting) = create_signal(initial.vetting);
+  let (sort, set_sort) = create_signal(initial.sort);
+  let (current_page, set_current_page) = create_signal(initial_page);
+
+  let non_empty = |s: String| (!s.trim().is_empty()).then_some(s);
+  let search = create_memo(move |_| TongueSearch {
+    term:     non_empty(query_term()),
+    vetting:  vetting(),
+    region:   non_empty(region()),
+    language: non_empty(language()),
+    sort:     sort(),
+  });
+
+  // new filters make the old page number meaningless
+  create_effect(move |previous: Option<TongueSearch>| {
+    let search = search();
+    if previous.is_some_and(|p| p != search) {
+      set_current_page(0);
+    }
+    search
+  });
+
+  // keep the URL in step, so searches can be shared and survive reloads
+  create_effect(move |_| {
+    let mut pairs = search.with(TongueSearch::query_pairs);
+    if current_page() > 0 {
+      pairs.push((PAGE_KEY, current_page().to_string()));
+    }
+    crate::helpers::navigation::replace_query(&pairs);
+  });
+
   let tongues = create_resource(
-    move || {
-      with!(|query_term, current_page| {
-        let term = if query_term.is_empty() {
-          None
-        } else {
-          Some(query_term)
-        };
-        (
-          term.cloned(),
-          *current_page * DEFAULT_FETCH_LIMIT as u32,
-          DEFAULT_FETCH_LIMIT as _,
-        )
-      })
+    move || (search(), current_page()),
+    move |(search, page)| {
+      fetch_mother_tongues(
+        search,
+        page * DEFAULT_FETCH_LIMIT as u32,
+        DEFAULT_FETCH_LIMIT as _,
+      )
     },
-    move |(term, offset, count)| fetch_mother_tongues(term, offset, count),
   );
 
   let table_element = move || {
     tongues().map(|d| match d {
-      Ok((data, count)) => {
+      Ok(results) => {
         let page_count =
-          (count as f32 / DEFAULT_FETCH_LIMIT as f32).ceil() as u32;
+          (results.total as f32 / DEFAULT_FETCH_LIMIT as f32).ceil() as u32;
+        let hits = results.hits;
         view! {
+          { results.fuzzy.then(|| view! {
+            <p class="text-content2 text-sm">
+              "Nothing matched exactly, so here are some close matches."
+            </p>
+          }) }
           <InnerMotherTonguesTable>
             <tbody>
               <For
-                each=move || data.clone() key={|t| t.id}
+                each=move || hits.clone() key={|h| h.tongue.id}
                 children={ move |d| view! { <MotherTonguesTableRow d={d} /> } }
               />
             </tbody>
@@ -59,12 +104,43 @@ pub fn MotherTonguesTable() -> impl IntoView {
 
   view! {
     <div class="flex flex-col gap-4">
-      <div class="flex flex-row gap-4 items-center">
+      <div class="flex flex-row flex-wrap gap-4 items-center">
         <input class="input" placeholder="Search..."
           on:input=move |ev| set_query_term(event_target_value(&ev))
           prop:value=query_term
         />
+        <input class="input input-sm w-40" placeholder="Region or country"
+          on:input=move |ev| set_region(event_target_value(&ev))
+          prop:value=region
+        />
+        <input class="input input-sm w-32 font-mono" placeholder="ISO 639-3"
+          on:input=move |ev| set_language(event_target_value(&ev).to_lowercase())
+          prop:value=language
+        />
+        <select class="select select-sm w-48"
+          on:change=move |ev| {
+            if let Some(v) = VettingFilter::parse(&event_target_value(&ev)) {
+              set_vetting(v);
+            }
+          }
+        >
+          { VettingFilter::ALL.into_iter().map(|v| view! {
+            <option value=v.as_str() selected=move || vetting() == v>{ v.label() }</option>
+          }).collect_view() }
+        </select>
         <div class="flex-1" />
+        <label class="text-content2 text-sm">"Sort by"</label>
+        <select class="select select-sm w-48"
+          on:change=move |ev| {
+            if let Some(s) = TongueSort::parse(&event_target_value(&ev)) {
+              set_sort(s);
+            }
+          }
+        >
+          { TongueSort::ALL.into_iter().map(|s| view! {
+            <option value=s.as_str() selected=move || sort() == s>{ s.label() }</option>
+          }).collect_view() }
+        </select>
       </div>
       <Transition fallback=SuspenseMotherTonguesTable>
         { table_element }
@@ -83,25 +159,55 @@ fn Tooltip(tooltip: String, children: Children) -> impl IntoView {
 }
 
 #[component]
-fn MotherTonguesTableRow(d: core_types::MotherTongue) -> impl IntoView {
+fn MotherTonguesTableRow(d: TongueSearchHit) -> impl IntoView {
+  let TongueSearchHit {
+    tongue,
+    translation_count,
+    progress,
+    name_highlight,
+    description_highlight,
+  } = d;
+
   view! {
     <tr>
       <th class="truncate">
-        // <Tooltip tooltip={ d.name.clone() }>
-          <a class="underline hover:no-underline" href={ format!("/tongue/{}", d.id.0) }>
-            { d.name }
+        // <Tooltip tooltip={ tongue.name.clone() }>
+          <a class="underline hover:no-underline" href={ format!("/tongue/{}", tongue.id.0) }>
+            <Highlighted text=name_highlight.unwrap_or(tongue.name) />
           </a>
         // </Tooltip>
       </th>
       <td class="truncate">
-        // <Tooltip tooltip={ d.description.clone() }>
-          { d.description }
+        // <Tooltip tooltip={ tongue.description.clone() }>
+          <Highlighted text=description_highlight.unwrap_or(tongue.description) />
         // </Tooltip>
       </td>
+      <td class="text-right">{ translation_count }</td>
+      <td>
+        <div class="flex flex-row gap-2 items-center">
+          <progress class="progress progress-success" value=progress max="100" />
+          <span class="text-content2 text-sm w-12 text-right">
+            { format!("{progress:.0}%") }
+          </span>
+        </div>
+      </td>
     </tr>
   }
 }
 
+/// Renders text from a search, marking the spans that matched.
+#[component]
+fn Highlighted(text: String) -> impl IntoView {
+  highlight_segments(&text)
+    .into_iter()
+    .map(|(segment, matched)| match matched {
+      true => view! { <mark class="bg-yellow-4 rounded-sm">{ segment }</mark> }
+        .into_view(),
+      false => segment.into_view(),
+    })
+    .collect_view()
+}
+
 #[component]
 fn InnerMotherTonguesTable(children: Children) -> impl IntoView {
   view! {
@@ -110,6 +216,8 @@ fn InnerMotherTonguesTable(children: Children) -> impl IntoView {
         <tr>
           <th class="w-64">"Name"</th>
           <th>"Description"</th>
+          <th class="w-32 text-right">"Translations"</th>
+          <th class="w-48">"Progress"</th>
         </tr>
       </thead>
       { children() }
@@ -126,6 +234,8 @@ fn SuspenseMotherTonguesTable() -> impl IntoView {
           <tr>
             <td><div class="skeleton-pulse h-5 rounded-md"></div></td>
             <td><div class="skeleton-pulse h-5 rounded-md"></div></td>
+            <td><div class="skeleton-pulse h-5 rounded-md"></div></td>
+            <td><div class="skeleton-pulse h-5 rounded-md"></div></td>
           </tr>
         }).collect_view() }
       </tbody>
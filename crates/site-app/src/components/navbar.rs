@@ -5,8 +5,11 @@ use leptos::*;
 use crate::{
   components::{
     action_status::ActionStatus,
+    avatar::Avatar,
     icons::{
-      HeroIconsArrowLeftStartOnRectangle, HeroIconsCheck, HeroIconsUserCircle,
+      HeroIconsArrowLeftStartOnRectangle, HeroIconsCheck,
+      HeroIconsClipboardDocumentCheck, HeroIconsIdentification,
+      HeroIconsMicrophone, HeroIconsUserCircle,
     },
     logout::Logout,
     Link,
@@ -29,7 +32,10 @@ pub fn NavBar() -> impl IntoView {
         <div class="navbar-end">
           { match user.0 {
             Some(user) => view! {
-              <AccountDropdown user=user />
+              <AccountDropdown
+                profile=user.profile()
+                can_review=user.has_role(core_types::SiteRole::Reviewer)
+              />
             }.into_view(),
             None => view! {
               <Link target=LinkTarget::Signup class="navbar-item">{ LinkTarget::Signup.name() }</Link>
@@ -44,7 +50,10 @@ pub fn NavBar() -> impl IntoView {
 }
 
 #[island]
-pub fn AccountDropdown(user: core_types::PublicUser) -> impl IntoView {
+pub fn AccountDropdown(
+  profile: core_types::PublicProfile,
+  can_review: bool,
+) -> impl IntoView {
   let logout_action = create_server_action::<Logout>();
   let logout_pending = logout_action.pending();
   let logout_value = logout_action.value();
@@ -59,12 +68,29 @@ pub fn AccountDropdown(user: core_types::PublicUser) -> impl IntoView {
 
   view! {
     <div class="dropdown">
-      <label class="btn btn-rounded" tabindex="0">{ user.name }</label>
+      <label class="btn btn-rounded flex flex-row gap-2 items-center" tabindex="0">
+        <Avatar profile=profile.clone() class="avatar-xs" />
+        { profile.name }
+      </label>
       <div class="dropdown-menu dropdown-menu-bottom-left border border-border">
         <Link target=LinkTarget::Account class="dropdown-item flex flex-row gap-2 items-center">
           <HeroIconsUserCircle />
           <p class="text-sm">{ LinkTarget::Account.name() }</p>
         </Link>
+        <Link target=LinkTarget::UserProfile(profile.id) class="dropdown-item flex flex-row gap-2 items-center">
+          <HeroIconsIdentification />
+          <p class="text-sm">"Public Profile"</p>
+        </Link>
+        <Link target=LinkTarget::RecordingReview class="dropdown-item flex flex-row gap-2 items-center">
+          <HeroIconsMicrophone />
+          <p class="text-sm">{ LinkTarget::RecordingReview.name() }</p>
+        </Link>
+        { can_review.then(|| view! {
+          <Link target=LinkTarget::TongueReview class="dropdown-item flex flex-row gap-2 items-center">
+            <HeroIconsClipboardDocumentCheck />
+            <p class="text-sm">{ LinkTarget::TongueReview.name() }</p>
+          </Link>
+        }) }
         <button
           class="dropdown-item flex flex-row gap-2 items-center"
           on:click=move |_| { logout_action.dispatch(Logout {}); }
@@ -0,0 +1,163 @@
+use std::time::Duration;
+
+use core_types::{bundle_download_url, License, TranslationRecordId};
+use leptos::*;
+
+use crate::functions::{
+  app_error,
+  bundles::{fetch_bundles, BuildBundle, SetLicense},
+};
+
+/// How often to check on a bundle that's being built.
+const POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Formats a size in bytes like "12.3 MB".
+fn format_size(bytes: u64) -> String {
+  const MB: f64 = 1024.0 * 1024.0;
+  match bytes as f64 / MB {
+    mb if mb >= 1024.0 => format!("{:.1} GB", mb / 1024.0),
+    mb if mb >= 0.1 => format!("{mb:.1} MB"),
+    _ => format!("{} KB", bytes.div_ceil(1024)),
+  }
+}
+
+/// Offers the latest offline bundle of a translation for download, and lets
+/// its team build a new one and choose its license.
+#[island]
+pub fn OfflineBundle(translation: TranslationRecordId) -> impl IntoView {
+  let build_action = create_server_action::<BuildBundle>();
+  let license_action = create_server_action::<SetLicense>();
+  let (poll, set_poll) = create_signal(0_u32);
+
+  let bundles = create_resource(
+    move || (build_action.version()(), license_action.version()(), poll()),
+    move |_| fetch_bundles(translation),
+  );
+  let building = move || {
+    bundles()
+      .and_then(Result::ok)
+      .is_some_and(|b| b.building.is_some())
+  };
+
+  // keep checking until the bundle being built is done
+  let ticker = store_value(None::<leptos::leptos_dom::helpers::IntervalHandle>);
+  let stop_polling = move || {
+    if let Some(handle) = ticker.try_update_value(Option::take).flatten() {
+      handle.clear();
+    }
+  };
+  create_effect(move |_| match (building(), ticker.get_value().is_some()) {
+    (true, false) => ticker.set_value(
+      set_interval_with_handle(
+        move || set_poll.update(|p| *p += 1),
+        POLL_INTERVAL,
+      )
+      .ok(),
+    ),
+    (false, true) => stop_polling(),
+    _ => (),
+  });
+  on_cleanup(stop_polling);
+
+  let error = move || {
+    let build_error = build_action.value()().and_then(Result::err);
+    let license_error = license_action.value()().and_then(Result::err);
+    build_error
+      .or(license_error)
+      .map(|e| app_error(&e).message())
+  };
+
+  view! {
+    <Transition fallback=move || view! { <p>"Loading..."</p> }>
+      { move || bundles().map(|result| match result {
+        Ok(bundles) => {
+          let license = bundles.license;
+          view! {
+            <div class="flex flex-col gap-4 max-w-prose">
+              { match bundles.latest_ready {
+                Some(bundle) => view! {
+                  <div class="flex flex-row flex-wrap gap-4 items-center">
+                    <a
+                      class="btn btn-primary"
+                      href=bundle_download_url(bundle.id)
+                      rel="external" download
+                    >"Download"</a>
+                    <span class="text-content2 text-sm">
+                      { format!(
+                        "{} recordings, {}, built {}",
+                        bundle.recording_count,
+                        format_size(bundle.size.unwrap_or(0)),
+                        bundle.created_at.date(),
+                      ) }
+                    </span>
+                  </div>
+                }
+                .into_view(),
+                None => view! {
+                  <p class="text-content2 text-sm">
+                    "No bundle has been built yet."
+                  </p>
+                }
+                .into_view(),
+              } }
+              { bundles.building.map(|_| view! {
+                <p class="text-content2 text-sm">
+                  "A new bundle is being built. It'll be ready to download \
+                   here soon."
+                </p>
+              }) }
+              <p class="text-sm">
+                <span class="text-content2">"Shared under "</span>
+                { match license.url() {
+                  Some(url) => view! {
+                    <a class="underline hover:no-underline" href=url>
+                      { license.label() }
+                    </a>
+                  }
+                  .into_view(),
+                  None => license.label().into_view(),
+                } }
+                <span class="text-content2">{ format!(". {}", license.summary()) }</span>
+              </p>
+              { bundles.can_license.then(|| view! {
+                <div class="form-field">
+                  <label class="form-label">"License"</label>
+                  <select
+                    class="select max-w-xs"
+                    disabled=license_action.pending()
+                    on:change=move |ev| {
+                      if let Some(license) = License::parse(&event_target_value(&ev)) {
+                        license_action.dispatch(SetLicense { translation, license });
+                      }
+                    }
+                  >
+                    { License::ALL.into_iter().map(|l| view! {
+                      <option value=l.as_str() selected=l == license>
+                        { l.label() }
+                      </option>
+                    }).collect_view() }
+                  </select>
+                </div>
+              }) }
+              { bundles.can_build.then(|| view! {
+                <button
+                  class="btn btn-outline self-start"
+                  disabled=move || building() || build_action.pending()()
+                  on:click=move |_| build_action.dispatch(BuildBundle { translation })
+                >"Build a new bundle"</button>
+              }) }
+            </div>
+          }
+          .into_view()
+        }
+        Err(e) => view! {
+          <p class="text-red-11 text-sm">{ app_error(&e).message() }</p>
+        }
+        .into_view(),
+      }) }
+    </Transition>
+    { move || error().map(|message| view! {
+      <p class="text-red-11 text-sm">{ message }</p>
+    }) }
+  }
+}
@@ -0,0 +1,255 @@
+use std::time::Duration;
+
+use core_types::{scripture::Passage, AppError, TranslationRecordId};
+use leptos::*;
+
+use crate::{
+  components::Link,
+  helpers::recorder::{start_recording, upload_recording, Recorder, Recording},
+  LinkTarget,
+};
+
+/// Where the recorder is up to.
+#[derive(Clone, Debug)]
+enum Stage {
+  Idle,
+  /// Waiting on the browser's microphone prompt.
+  Starting,
+  Recording,
+  Stopping,
+  Recorded(Recording),
+  Uploading,
+  Uploaded(Passage),
+}
+
+/// Formats seconds like "1:05".
+fn clock(seconds: u32) -> String {
+  format!("{}:{:02}", seconds / 60, seconds % 60)
+}
+
+/// Gets the message to show for a failed upload from its response.
+fn upload_error(status: u16, body: &str) -> String {
+  match body.parse::<AppError>() {
+    Ok(AppError::Validation(errors)) => {
+      errors.0.into_values().collect::<Vec<_>>().join(" ")
+    }
+    Ok(e) => e.message(),
+    Err(_) => format!("The upload failed with status {status}."),
+  }
+}
+
+/// Records a passage with the microphone, plays it back, and uploads it as
+/// a new take once it's right.
+#[island]
+pub fn PassageRecorder(translation: TranslationRecordId) -> impl IntoView {
+  let stage = create_rw_signal(Stage::Idle);
+  let (passage, set_passage) = create_signal(String::new());
+  let (error, set_error) = create_signal(None::<String>);
+  let (elapsed, set_elapsed) = create_signal(0_u32);
+  let (progress, set_progress) = create_signal(0.0_f64);
+
+  let recorder = store_value(None::<Recorder>);
+  let ticker = store_value(None::<leptos::leptos_dom::helpers::IntervalHandle>);
+
+  // previews hold on to their audio until they're let go
+  let discard = move || {
+    if let Stage::Recorded(recording) = stage.get_untracked() {
+      recording.release();
+    }
+  };
+
+  let start = move || {
+    discard();
+    set_error(None);
+    stage.set(Stage::Starting);
+    spawn_local(async move {
+      match start_recording().await {
+        Ok(started) => {
+          recorder.set_value(Some(started));
+          set_elapsed(0);
+          ticker.set_value(
+            set_interval_with_handle(
+              move || set_elapsed.update(|s| *s += 1),
+              Duration::from_secs(1),
+            )
+            .ok(),
+          );
+          stage.set(Stage::Recording);
+        }
+        Err(e) => {
+          set_error(Some(e.message()));
+          stage.set(Stage::Idle);
+        }
+      }
+    });
+  };
+
+  let stop = move || {
+    let Some(started) = recorder.try_update_value(Option::take).flatten()
+    else {
+      return;
+    };
+    if let Some(handle) = ticker.try_update_value(Option::take).flatten() {
+      handle.clear();
+    }
+    stage.set(Stage::Stopping);
+    spawn_local(async move {
+      match started.stop().await {
+        Ok(recording) => stage.set(Stage::Recorded(recording)),
+        Err(e) => {
+          set_error(Some(e.message()));
+          stage.set(Stage::Idle);
+        }
+      }
+    });
+  };
+
+  let upload = move |recording: Recording| {
+    let passage = match passage.get_untracked().trim().parse::<Passage>() {
+      Ok(passage) => passage,
+      Err(e) => {
+        set_error(Some(e.to_string()));
+        return;
+      }
+    };
+    let url = format!(
+      "/v1/artifacts?translation={}&passage={}&duration_ms={}",
+      translation.0,
+      passage.to_string().replace(' ', "+"),
+      recording.duration_ms
+    );
+
+    set_error(None);
+    set_progress(0.0);
+    stage.set(Stage::Uploading);
+    spawn_local(async move {
+      match upload_recording(&url, &recording, set_progress).await {
+        Ok((200..=299, _)) => {
+          recording.release();
+          stage.set(Stage::Uploaded(passage));
+        }
+        Ok((status, body)) => {
+          set_error(Some(upload_error(status, &body)));
+          stage.set(Stage::Recorded(recording));
+        }
+        Err(e) => {
+          set_error(Some(format!("The upload failed: {e}")));
+          stage.set(Stage::Recorded(recording));
+        }
+      }
+    });
+  };
+
+  on_cleanup(move || {
+    discard();
+    if let Some(handle) = ticker.try_update_value(Option::take).flatten() {
+      handle.clear();
+    }
+  });
+
+  let controls = move || {
+    match stage() {
+    Stage::Idle => view! {
+      <button class="btn btn-primary self-start" on:click=move |_| start()>
+        "Record"
+      </button>
+    }
+    .into_view(),
+    Stage::Starting => view! {
+      <p class="text-content2 text-sm">
+        "Waiting for permission to use your microphone..."
+      </p>
+    }
+    .into_view(),
+    Stage::Recording => view! {
+      <div class="flex flex-row gap-4 items-center">
+        <span class="badge badge-error">"Recording"</span>
+        <span class="font-mono">{ move || clock(elapsed()) }</span>
+        <button class="btn btn-error" on:click=move |_| stop()>"Stop"</button>
+      </div>
+    }
+    .into_view(),
+    Stage::Stopping => view! {
+      <p class="text-content2 text-sm">"Finishing the recording..."</p>
+    }
+    .into_view(),
+    Stage::Recorded(recording) => {
+      let to_upload = recording.clone();
+      view! {
+        <audio class="w-full" controls src=recording.url />
+        <p class="text-content2 text-sm">
+          { format!("{} long. Listen back before uploading.", clock(recording.duration_ms / 1000)) }
+        </p>
+        <div class="flex flex-row gap-2">
+          <button
+            class="btn btn-primary"
+            on:click=move |_| upload(to_upload.clone())
+          >"Upload"</button>
+          <button class="btn btn-outline" on:click=move |_| start()>
+            "Re-record"
+          </button>
+          <button
+            class="btn btn-outline-error"
+            on:click=move |_| {
+              discard();
+              stage.set(Stage::Idle);
+            }
+          >"Discard"</button>
+        </div>
+      }
+      .into_view()
+    }
+    Stage::Uploading => view! {
+      <div class="flex flex-col gap-2">
+        <progress
+          class="progress progress-primary"
+          value=move || (progress() * 100.0).round()
+          max="100"
+        />
+        <p class="text-content2 text-sm">
+          { move || format!("Uploading... {:.0}%", progress() * 100.0) }
+        </p>
+      </div>
+    }
+    .into_view(),
+    Stage::Uploaded(uploaded) => view! {
+      <p class="text-green-11">
+        { format!("Uploaded a new take of {uploaded}. It's waiting in review as a draft.") }
+      </p>
+      <div class="flex flex-row gap-2 items-center">
+        <button
+          class="btn btn-primary"
+          on:click=move |_| stage.set(Stage::Idle)
+        >"Record another"</button>
+        <Link target=LinkTarget::Takes(translation, uploaded) class="btn btn-outline">
+          "See all takes"
+        </Link>
+      </div>
+    }
+    .into_view(),
+  }
+  };
+
+  let locked = move || !matches!(stage(), Stage::Idle | Stage::Recorded(_));
+
+  view! {
+    <div class="card border border-border max-w-prose">
+      <div class="card-body gap-4">
+        <div class="form-field">
+          <label class="form-label">"Passage"</label>
+          <input
+            placeholder="e.g. JHN 3:16-18"
+            class="input hover:input-primary focus:input-primary transition max-w-xs"
+            disabled=locked
+            on:input=move |ev| set_passage(event_target_value(&ev))
+            prop:value=passage
+          />
+        </div>
+        { controls }
+        { move || error().map(|message| view! {
+          <p class="text-red-11 text-sm">{message}</p>
+        }) }
+      </div>
+    </div>
+  }
+}
@@ -1,21 +1,20 @@
 use cfg_if::cfg_if;
+use core_types::AppError;
 use http::status::StatusCode;
 use leptos::*;
 #[cfg(feature = "ssr")]
 use leptos_axum::ResponseOptions;
-use thiserror::Error;
 
-#[derive(Clone, Debug, Error)]
-pub enum AppError {
-  #[error("Not Found")]
-  NotFound,
-}
-
-impl AppError {
-  pub fn status_code(&self) -> StatusCode {
-    match self {
-      AppError::NotFound => StatusCode::NOT_FOUND,
-    }
+/// Maps an [`AppError`] to the HTTP status code it should be served with.
+pub fn status_code(error: &AppError) -> StatusCode {
+  match error {
+    AppError::Validation(_) => StatusCode::BAD_REQUEST,
+    AppError::Conflict(_) => StatusCode::CONFLICT,
+    AppError::Unauthorized => StatusCode::UNAUTHORIZED,
+    AppError::Forbidden => StatusCode::FORBIDDEN,
+    AppError::NotFound => StatusCode::NOT_FOUND,
+    AppError::RateLimited => StatusCode::TOO_MANY_REQUESTS,
+    AppError::Internal => StatusCode::INTERNAL_SERVER_ERROR,
   }
 }
 
@@ -34,10 +33,19 @@ pub fn ErrorTemplate(
   // Get Errors from Signal
   let errors = errors.get_untracked();
 
-  // Downcast lets us take a type that implements `std::error::Error`
+  // Downcast lets us take a type that implements `std::error::Error`. Errors
+  // from server fns arrive wrapped in a `ServerFnError`.
   let errors: Vec<AppError> = errors
     .into_iter()
-    .filter_map(|(_k, v)| v.downcast_ref::<AppError>().cloned())
+    .map(|(_k, v)| {
+      if let Some(e) = v.downcast_ref::<AppError>() {
+        e.clone()
+      } else if let Some(e) = v.downcast_ref::<ServerFnError<AppError>>() {
+        crate::functions::app_error(e)
+      } else {
+        AppError::Internal
+      }
+    })
     .collect();
   println!("Errors: {errors:#?}");
 
@@ -46,7 +54,7 @@ pub fn ErrorTemplate(
   cfg_if! { if #[cfg(feature="ssr")] {
       let response = use_context::<ResponseOptions>();
       if let Some(response) = response {
-          response.set_status(errors[0].status_code());
+          response.set_status(status_code(&errors[0]));
       }
   }}
 
@@ -59,8 +67,8 @@ pub fn ErrorTemplate(
         each=move || { errors.clone().into_iter().enumerate() }
         key=|(index, _error)| *index
         children=move |error| {
-          let error_string = error.1.to_string();
-          let error_code = error.1.status_code();
+          let error_string = error.1.message();
+          let error_code = status_code(&error.1);
           view! {
             <div>
               <p class="text-2xl tracking-tight font-semibold">{error_code.to_string()}</p>
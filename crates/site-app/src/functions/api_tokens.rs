@@ -0,0 +1,59 @@
+use core_types::{
+  ApiTokenRecordId, ApiTokenScope, ApiTokenSummary, AppError, NewApiToken,
+};
+use leptos::*;
+
+#[cfg(feature = "ssr")]
+use crate::functions::{require_user, server_context};
+
+/// Lists the logged in user's API tokens.
+#[cfg_attr(feature = "ssr", tracing::instrument)]
+#[server]
+pub async fn list_api_tokens(
+) -> Result<Vec<ApiTokenSummary>, ServerFnError<AppError>> {
+  let user = require_user()?;
+  let auth_session = server_context::<auth::AuthSession>("auth session")?;
+
+  auth_session
+    .backend
+    .list_api_tokens(user.id)
+    .await
+    .map_err(|e| {
+      tracing::error!("Failed to list API tokens: {:?}", e);
+      ServerFnError::WrappedServerError(AppError::from(e))
+    })
+}
+
+/// Creates an API token for the logged in user.
+#[cfg_attr(feature = "ssr", tracing::instrument)]
+#[server]
+pub async fn create_api_token(
+  name: String,
+  scopes: Vec<ApiTokenScope>,
+  lifetime_days: u32,
+) -> Result<NewApiToken, ServerFnError<AppError>> {
+  let user = require_user()?;
+  let auth_session = server_context::<auth::AuthSession>("auth session")?;
+
+  auth_session
+    .backend
+    .create_api_token(user.id, name, scopes, lifetime_days)
+    .await
+    .map_err(|e| ServerFnError::WrappedServerError(AppError::from(e)))
+}
+
+/// Revokes one of the logged in user's API tokens.
+#[cfg_attr(feature = "ssr", tracing::instrument)]
+#[server]
+pub async fn revoke_api_token(
+  id: ApiTokenRecordId,
+) -> Result<(), ServerFnError<AppError>> {
+  let user = require_user()?;
+  let auth_session = server_context::<auth::AuthSession>("auth session")?;
+
+  auth_session
+    .backend
+    .revoke_api_token(user.id, id)
+    .await
+    .map_err(|e| ServerFnError::WrappedServerError(AppError::from(e)))
+}
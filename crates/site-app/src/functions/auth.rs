@@ -1,47 +1,57 @@
-use std::fmt::Debug;
-
+use core_types::{AppError, Pii, Secret};
 use leptos::*;
 use serde::{Deserialize, Serialize};
 
-#[derive(Clone, PartialEq, Serialize, Deserialize)]
+#[cfg(feature = "ssr")]
+use crate::functions::{handle_error, server_context};
+
+#[cfg(feature = "ssr")]
+const PENDING_SECOND_FACTOR_KEY: &str = "pending_second_factor";
+#[cfg(feature = "ssr")]
+const SECOND_FACTOR_TIMEOUT: time::Duration = time::Duration::minutes(5);
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct SignupParams {
   pub name:     String,
-  pub email:    String,
-  pub password: String,
+  pub email:    Pii,
+  pub password: Secret,
   pub remember: bool,
 }
 
-impl Debug for SignupParams {
-  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-    f.debug_struct("User")
-      .field("name", &self.name)
-      .field("email", &"[redacted]")
-      .field("pw_hash", &"[redacted]")
-      .field("remember", &self.remember)
-      .finish()
-  }
-}
-
-#[derive(Clone, PartialEq, Serialize, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct LoginParams {
-  pub email:    String,
-  pub password: String,
+  pub email:    Pii,
+  pub password: Secret,
   pub remember: bool,
 }
 
-impl Debug for LoginParams {
-  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-    f.debug_struct("User")
-      .field("email", &"[redacted]")
-      .field("pw_hash", &"[redacted]")
-      .field("remember", &self.remember)
-      .finish()
-  }
+/// The result of a successful [`login`] call.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum LoginOutcome {
+  /// The user is logged in.
+  LoggedIn,
+  /// The password was correct, but the user has two-factor authentication
+  /// enabled and must finish with [`login_second_factor`].
+  SecondFactorRequired,
+}
+
+/// A login waiting on its second factor, stored in the session.
+///
+/// Wrong codes are counted on the user rather than here, so that logging in
+/// again with the password doesn't get more guesses.
+#[cfg(feature = "ssr")]
+#[derive(Serialize, Deserialize)]
+struct PendingSecondFactor {
+  user_id:    core_types::UserRecordId,
+  remember:   bool,
+  started_at: i64,
 }
 
 #[cfg_attr(feature = "ssr", tracing::instrument)]
 #[server]
-pub async fn signup(params: SignupParams) -> Result<(), ServerFnError> {
+pub async fn signup(
+  params: SignupParams,
+) -> Result<(), ServerFnError<AppError>> {
   let SignupParams {
     name,
     email,
@@ -49,65 +59,148 @@ pub async fn signup(params: SignupParams) -> Result<(), ServerFnError> {
     remember,
   } = params;
 
-  let auth_session = use_context::<auth::AuthSession>()
-    .ok_or_else(|| ServerFnError::new("Failed to get auth session"))?;
+  let auth_session = server_context::<auth::AuthSession>("auth session")?;
 
   auth_session
     .backend
     .signup(name, email.clone(), password.clone())
     .await
     .map_err(|e| {
-      logging::error!("Failed to sign up: {:?}", e);
-      ServerFnError::new("Failed to sign up")
+      tracing::error!("Failed to sign up: {:?}", e);
+      ServerFnError::WrappedServerError(AppError::from(e))
     })?;
 
-  let login_result = login(LoginParams {
-    email: email.clone(),
-    password: password.clone(),
+  // new users can't have two-factor auth yet, so this always logs in
+  login(LoginParams {
+    email,
+    password,
     remember,
   })
   .await
-  .map_err(|e| ServerFnError::new(format!("Failed to log in: {e}")))?;
-
-  if !login_result {
-    return Err(ServerFnError::new("Failed to login after sign up"));
-  }
-
-  Ok(())
+  .map(|_| ())
 }
 
 #[cfg_attr(feature = "ssr", tracing::instrument)]
 #[server]
-pub async fn login(params: LoginParams) -> Result<bool, ServerFnError> {
-  let creds = auth::Credentials {
+pub async fn login(
+  params: LoginParams,
+) -> Result<LoginOutcome, ServerFnError<AppError>> {
+  core_types::validation::validate_login(
+    params.email.expose(),
+    params.password.expose(),
+  )
+  .map_err(|e| ServerFnError::WrappedServerError(AppError::Validation(e)))?;
+
+  let creds = auth::Credentials::Password {
     email:    params.email,
     password: params.password,
     remember: params.remember,
   };
-  let mut auth_session = use_context::<auth::AuthSession>()
-    .ok_or_else(|| ServerFnError::new("Failed to get auth session"))?;
-  let session = use_context::<tower_sessions::Session>()
-    .ok_or_else(|| ServerFnError::new("Failed to get session"))?;
+  let mut auth_session = server_context::<auth::AuthSession>("auth session")?;
+  let session = server_context::<tower_sessions::Session>("session")?;
 
   let user = match auth_session.authenticate(creds.clone()).await {
     Ok(Some(user)) => user,
-    Ok(None) => return Ok(false),
+    Ok(None) => {
+      return Err(ServerFnError::WrappedServerError(AppError::Unauthorized))
+    }
     Err(e) => {
-      return Err(ServerFnError::new(format!("Failed to authenticate: {e:?}")))
+      return Err(handle_error(eyre::eyre!("{e:?}"), "authenticate user"))
     }
   };
 
+  if user.totp_enabled() {
+    require_second_factor(&session, user.id, creds.remember())
+      .await
+      .map_err(|e| handle_error(e.into(), "store pending login"))?;
+    return Ok(LoginOutcome::SecondFactorRequired);
+  }
+
+  finish_login(&mut auth_session, &session, user, creds.remember()).await?;
+  Ok(LoginOutcome::LoggedIn)
+}
+
+/// Finishes a login that returned [`LoginOutcome::SecondFactorRequired`],
+/// using either a TOTP code or a recovery code.
+#[cfg_attr(feature = "ssr", tracing::instrument(skip(code)))]
+#[server]
+pub async fn login_second_factor(
+  code: String,
+) -> Result<(), ServerFnError<AppError>> {
+  let mut auth_session = server_context::<auth::AuthSession>("auth session")?;
+  let session = server_context::<tower_sessions::Session>("session")?;
+
+  let pending = session
+    .get::<PendingSecondFactor>(PENDING_SECOND_FACTOR_KEY)
+    .await
+    .map_err(|e| handle_error(e.into(), "read pending login"))?;
+  let Some(pending) = pending else {
+    return Err(ServerFnError::WrappedServerError(AppError::Unauthorized));
+  };
+
+  let elapsed =
+    time::OffsetDateTime::now_utc().unix_timestamp() - pending.started_at;
+  if elapsed > SECOND_FACTOR_TIMEOUT.whole_seconds() {
+    session
+      .remove::<PendingSecondFactor>(PENDING_SECOND_FACTOR_KEY)
+      .await
+      .map_err(|e| handle_error(e.into(), "clear pending login"))?;
+    return Err(ServerFnError::WrappedServerError(AppError::Unauthorized));
+  }
+
+  let user = auth_session
+    .backend
+    .verify_second_factor(pending.user_id, &code)
+    .await
+    .map_err(|e| ServerFnError::WrappedServerError(AppError::from(e)))?;
+  let Some(user) = user else {
+    return Err(ServerFnError::WrappedServerError(AppError::from(
+      auth::AuthError::InvalidCode,
+    )));
+  };
+
+  session
+    .remove::<PendingSecondFactor>(PENDING_SECOND_FACTOR_KEY)
+    .await
+    .map_err(|e| handle_error(e.into(), "clear pending login"))?;
+  finish_login(&mut auth_session, &session, user, pending.remember).await
+}
+
+/// Holds a login in the session until [`login_second_factor`] checks its
+/// second factor. Every way of logging in that a user's TOTP applies to goes
+/// through here, including OIDC.
+#[cfg(feature = "ssr")]
+pub async fn require_second_factor(
+  session: &tower_sessions::Session,
+  user_id: core_types::UserRecordId,
+  remember: bool,
+) -> Result<(), tower_sessions::session::Error> {
+  let pending = PendingSecondFactor {
+    user_id,
+    remember,
+    started_at: time::OffsetDateTime::now_utc().unix_timestamp(),
+  };
+  session.insert(PENDING_SECOND_FACTOR_KEY, pending).await
+}
+
+#[cfg(feature = "ssr")]
+pub(crate) async fn finish_login(
+  auth_session: &mut auth::AuthSession,
+  session: &tower_sessions::Session,
+  user: core_types::User,
+  remember: bool,
+) -> Result<(), ServerFnError<AppError>> {
   auth_session
     .login(&user)
     .await
-    .map_err(|e| ServerFnError::new(format!("Failed to log in: {e}")))?;
+    .map_err(|e| handle_error(eyre::eyre!("{e}"), "log in"))?;
 
-  if creds.remember {
+  if remember {
     session.set_expiry(Some(tower_sessions::Expiry::AtDateTime(
       time::OffsetDateTime::now_utc() + time::Duration::days(30),
     )));
   }
 
   tracing::info!("logged in user: {} ({})", user.name, user.id.0);
-  Ok(true)
+  Ok(())
 }
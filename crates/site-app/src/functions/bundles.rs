@@ -0,0 +1,154 @@
+use core_types::{
+  AppError, BundleSummary, License, TranslationBundles, TranslationRecordId,
+};
+#[cfg(feature = "ssr")]
+use eyre::Context;
+use leptos::*;
+
+#[cfg(feature = "ssr")]
+use crate::functions::{
+  fetch::use_db,
+  handle_error,
+  members::{can_manage, fetch_membership, require_manager},
+  require_user, server_context,
+};
+
+/// Fetches a translation's offline bundles, and what the current user can
+/// do with them.
+#[cfg_attr(feature = "ssr", tracing::instrument)]
+#[server]
+pub async fn fetch_bundles(
+  translation: TranslationRecordId,
+) -> Result<TranslationBundles, ServerFnError<AppError>> {
+  use core_types::BundleStatus;
+
+  let user = server_context::<auth::AuthSession>("auth session")?.user;
+  let db = use_db()
+    .await
+    .map_err(|e| handle_error(e, "connect to db"))?;
+
+  let (can_build, can_license) = match &user {
+    Some(user) => {
+      let membership = fetch_membership(&db, translation, user.id).await?;
+      let is_admin = user.has_role(core_types::SiteRole::Admin);
+      (
+        is_admin || membership.is_some(),
+        can_manage(user, membership.as_ref()),
+      )
+    }
+    None => (false, false),
+  };
+
+  async move {
+    let translation_record = db
+      .select_translation(translation)
+      .await
+      .wrap_err("failed to select translation from db")?
+      .ok_or_else(|| eyre::eyre!("translation doesn't exist"))?;
+    let latest_ready = db
+      .select_latest_bundle(translation, BundleStatus::Ready)
+      .await
+      .wrap_err("failed to select bundle from db")?;
+    let building = db
+      .select_latest_bundle(translation, BundleStatus::Building)
+      .await
+      .wrap_err("failed to select bundle from db")?;
+
+    Ok(TranslationBundles {
+      latest_ready: latest_ready.map(Into::into),
+      building: building.map(Into::into),
+      can_build,
+      license: translation_record.license,
+      can_license,
+    })
+  }
+  .await
+  .map_err(|e| handle_error(e, "fetch bundles"))
+}
+
+/// Starts building a new offline bundle of a translation's approved
+/// recordings. Any member of the translation's team can.
+#[cfg_attr(feature = "ssr", tracing::instrument)]
+#[server]
+pub async fn build_bundle(
+  translation: TranslationRecordId,
+) -> Result<BundleSummary, ServerFnError<AppError>> {
+  use core_types::{ssr::CoreId, Bundle, BundleRecordId, BundleStatus};
+
+  let user = require_user()?;
+  let db = use_db()
+    .await
+    .map_err(|e| handle_error(e, "connect to db"))?;
+
+  let membership = fetch_membership(&db, translation, user.id).await?;
+  if membership.is_none() && !user.has_role(core_types::SiteRole::Admin) {
+    return Err(ServerFnError::WrappedServerError(AppError::Forbidden));
+  }
+
+  let recordings = db
+    .select_listening_tracks(translation)
+    .await
+    .wrap_err("failed to select listening tracks from db")
+    .map_err(|e| handle_error(e, "check bundle"))?;
+  if recordings.is_empty() {
+    return Err(ServerFnError::WrappedServerError(AppError::Conflict(
+      "There are no approved recordings to bundle yet.".to_string(),
+    )));
+  }
+
+  let public_url = auth::public_url()
+    .map_err(|e| handle_error(e, "get public url"))?
+    .as_str()
+    .trim_end_matches('/')
+    .to_string();
+  let id = BundleRecordId::new();
+  let bundle = Bundle {
+    id,
+    translation,
+    status: BundleStatus::Building,
+    object_key: format!("bundles/{}/{}.zip", translation.0, id.0),
+    size: None,
+    recording_count: 0,
+    requested_by: user.id,
+    created_at: time::OffsetDateTime::now_utc(),
+    finished_at: None,
+  };
+  let bundle = db
+    .insert_bundle(bundle)
+    .await
+    .wrap_err("failed to insert bundle into db")
+    .map_err(|e| handle_error(e, "create bundle"))?
+    .ok_or_else(|| {
+      ServerFnError::WrappedServerError(AppError::Conflict(
+        "A bundle is already being built.".to_string(),
+      ))
+    })?;
+
+  // building takes a while, so it carries on after the response is sent
+  let summary = bundle.clone().into();
+  tokio::spawn(async move {
+    archive::export::build_bundle(&db, &public_url, bundle).await;
+  });
+
+  Ok(summary)
+}
+
+/// Sets the license a translation's recordings are shared under. Only its
+/// managers can.
+#[cfg_attr(feature = "ssr", tracing::instrument)]
+#[server]
+pub async fn set_license(
+  translation: TranslationRecordId,
+  license: License,
+) -> Result<(), ServerFnError<AppError>> {
+  let user = require_user()?;
+  let db = use_db()
+    .await
+    .map_err(|e| handle_error(e, "connect to db"))?;
+  require_manager(&db, &user, translation).await?;
+
+  db.update_translation_license(translation, license)
+    .await
+    .wrap_err("failed to update translation license in db")
+    .map_err(|e| handle_error(e, "set license"))
+}
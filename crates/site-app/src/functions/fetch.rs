@@ -1,3 +1,4 @@
+use core_types::AppError;
 #[cfg(feature = "ssr")]
 use eyre::{Context, Result};
 use leptos::*;
@@ -5,9 +6,36 @@ use leptos::*;
 #[cfg(feature = "ssr")]
 use crate::functions::handle_error;
 
+/// Works out each translation's progress from its recorded passages.
+#[cfg(feature = "ssr")]
+fn progress_by_translation(
+  passages: Vec<core_types::RecordedPassage>,
+) -> std::collections::HashMap<
+  core_types::TranslationRecordId,
+  (core_types::MotherTongueRecordId, f32),
+> {
+  let mut grouped = std::collections::HashMap::<_, (_, Vec<_>)>::new();
+  for recorded in passages {
+    grouped
+      .entry(recorded.translation)
+      .or_insert_with(|| (recorded.mother_tongue, Vec::new()))
+      .1
+      .push(recorded.passage);
+  }
+
+  grouped
+    .into_iter()
+    .map(|(translation, (tongue, passages))| {
+      let progress =
+        core_types::TranslationProgress::from_passages(&passages).percent();
+      (translation, (tongue, progress))
+    })
+    .collect()
+}
+
 #[cfg(feature = "ssr")]
 #[tracing::instrument]
-async fn use_db() -> Result<db::DbConnection> {
+pub(crate) async fn use_db() -> Result<db::DbConnection> {
   Ok(if let Some(db) = use_context::<db::DbConnection>() {
     tracing::debug!("got db connection from context");
     db
@@ -24,17 +52,30 @@ async fn use_db() -> Result<db::DbConnection> {
 #[server]
 #[cfg_attr(feature = "ssr", tracing::instrument)]
 pub async fn fetch_mother_tongues(
-  term: Option<String>,
+  search: core_types::TongueSearch,
   offset: u32,
   count: u32,
-) -> Result<(Vec<core_types::MotherTongue>, usize), ServerFnError> {
+) -> Result<core_types::TongueSearchResults, ServerFnError<AppError>> {
   async move {
     let db = use_db().await?;
-    let tongues = db
-      .select_mother_tongues(term, offset, count)
+    let mut tongues = db
+      .select_mother_tongues(&search, offset, count)
       .await
       .wrap_err("failed to select mother tongues from db")?;
 
+    let ids = tongues.hits.iter().map(|h| h.tongue.id).collect::<Vec<_>>();
+    let passages = db
+      .select_recorded_passages_in_tongues(&ids)
+      .await
+      .wrap_err("failed to select recorded passages from db")?;
+    // a tongue is as far along as its furthest translation
+    for (tongue, progress) in progress_by_translation(passages).into_values() {
+      if let Some(hit) = tongues.hits.iter_mut().find(|h| h.tongue.id == tongue)
+      {
+        hit.progress = hit.progress.max(progress);
+      }
+    }
+
     Ok(tongues)
   }
   .await
@@ -45,7 +86,7 @@ pub async fn fetch_mother_tongues(
 #[cfg_attr(feature = "ssr", tracing::instrument)]
 pub async fn fetch_mother_tongue(
   id: core_types::MotherTongueRecordId,
-) -> Result<Option<core_types::MotherTongue>, ServerFnError> {
+) -> Result<Option<core_types::MotherTongue>, ServerFnError<AppError>> {
   async move {
     let db = use_db().await?;
     let tongue = db
@@ -58,3 +99,239 @@ pub async fn fetch_mother_tongue(
   .await
   .map_err(|e| handle_error(e, "fetch mother tongue"))
 }
+
+/// Fetches a mother tongue along with its creator, translations, and a
+/// sample recording.
+#[server]
+#[cfg_attr(feature = "ssr", tracing::instrument)]
+pub async fn fetch_mother_tongue_details(
+  id: core_types::MotherTongueRecordId,
+) -> Result<Option<core_types::MotherTongueDetails>, ServerFnError<AppError>> {
+  async move {
+    let db = use_db().await?;
+    let Some(tongue) = db
+      .select_mother_tongue(id)
+      .await
+      .wrap_err("failed to select mother tongue from db")?
+    else {
+      return Ok(None);
+    };
+
+    let creator = db
+      .select_mother_tongue_creator(id)
+      .await
+      .wrap_err("failed to select mother tongue creator from db")?
+      .map(core_types::PublicProfile::from);
+    let mut translations = db
+      .select_translations_in_tongue(id)
+      .await
+      .wrap_err("failed to select translations from db")?;
+    let progress = progress_by_translation(
+      db.select_recorded_passages_in_tongues(&[id])
+        .await
+        .wrap_err("failed to select recorded passages from db")?,
+    );
+    for listing in &mut translations {
+      if let Some((_, percent)) = progress.get(&listing.translation.id) {
+        listing.progress = *percent;
+      }
+    }
+    let sample_recording = db
+      .select_sample_recording(id)
+      .await
+      .wrap_err("failed to select sample recording from db")?;
+
+    let lookup = |code: &Option<String>| {
+      code
+        .as_deref()
+        .and_then(core_types::iso_639_3::lookup)
+        .cloned()
+    };
+    let language = lookup(&tongue.iso_639_3);
+    let parent_language = lookup(&tongue.parent_language);
+
+    Ok(Some(core_types::MotherTongueDetails {
+      tongue,
+      language,
+      parent_language,
+      creator,
+      translations,
+      sample_recording,
+    }))
+  }
+  .await
+  .map_err(|e| handle_error(e, "fetch mother tongue details"))
+}
+
+/// Fetches a translation along with its mother tongue and creator.
+#[server]
+#[cfg_attr(feature = "ssr", tracing::instrument)]
+pub async fn fetch_translation_details(
+  id: core_types::TranslationRecordId,
+) -> Result<Option<core_types::TranslationDetails>, ServerFnError<AppError>> {
+  async move {
+    let db = use_db().await?;
+    let Some(translation) = db
+      .select_translation(id)
+      .await
+      .wrap_err("failed to select translation from db")?
+    else {
+      return Ok(None);
+    };
+
+    let mother_tongue = db
+      .select_mother_tongue(translation.mother_tongue)
+      .await
+      .wrap_err("failed to select mother tongue from db")?
+      .ok_or_else(|| eyre::eyre!("translation's mother tongue is missing"))?;
+    let creator = db
+      .select_translation_creator(id)
+      .await
+      .wrap_err("failed to select translation creator from db")?
+      .map(core_types::PublicProfile::from);
+
+    Ok(Some(core_types::TranslationDetails {
+      translation: translation.into(),
+      mother_tongue,
+      creator,
+    }))
+  }
+  .await
+  .map_err(|e| handle_error(e, "fetch translation details"))
+}
+
+/// Works out how much of each book and chapter a translation has recorded.
+#[server]
+#[cfg_attr(feature = "ssr", tracing::instrument)]
+pub async fn fetch_translation_progress(
+  id: core_types::TranslationRecordId,
+) -> Result<core_types::TranslationProgress, ServerFnError<AppError>> {
+  async move {
+    let db = use_db().await?;
+    let passages = db
+      .select_recorded_passages(id)
+      .await
+      .wrap_err("failed to select recorded passages from db")?;
+
+    Ok(core_types::TranslationProgress::from_passages(&passages))
+  }
+  .await
+  .map_err(|e| handle_error(e, "fetch translation progress"))
+}
+
+/// Fetches what anyone can see about a user. Deactivated users have no
+/// public profile.
+#[server]
+#[cfg_attr(feature = "ssr", tracing::instrument)]
+pub async fn fetch_user_profile(
+  id: core_types::UserRecordId,
+) -> Result<Option<core_types::PublicProfile>, ServerFnError<AppError>> {
+  async move {
+    let db = use_db().await?;
+    let user = db
+      .select_user(id)
+      .await
+      .wrap_err("failed to select user from db")?;
+
+    Ok(
+      user
+        .filter(|u| u.is_active)
+        .map(core_types::PublicProfile::from),
+    )
+  }
+  .await
+  .map_err(|e| handle_error(e, "fetch user profile"))
+}
+
+/// Whether the current user can see all of `user`'s contributions, including
+/// those that haven't been approved: their own, or any if they review them.
+#[cfg(feature = "ssr")]
+fn sees_unapproved_contributions(
+  user: core_types::UserRecordId,
+) -> Result<bool, ServerFnError<AppError>> {
+  let viewer =
+    crate::functions::server_context::<auth::AuthSession>("auth session")?.user;
+  Ok(viewer.is_some_and(|v| {
+    v.id == user || v.has_role(core_types::SiteRole::Reviewer)
+  }))
+}
+
+#[server]
+#[cfg_attr(feature = "ssr", tracing::instrument)]
+pub async fn fetch_created_mother_tongues(
+  user: core_types::UserRecordId,
+  offset: u32,
+  count: u32,
+) -> Result<
+  (
+    Vec<core_types::Contribution<core_types::MotherTongue>>,
+    usize,
+  ),
+  ServerFnError<AppError>,
+> {
+  let include_unvetted = sees_unapproved_contributions(user)?;
+  async move {
+    let db = use_db().await?;
+    let tongues = db
+      .select_mother_tongues_created_by(user, include_unvetted, offset, count)
+      .await
+      .wrap_err("failed to select created mother tongues from db")?;
+
+    Ok(tongues)
+  }
+  .await
+  .map_err(|e| handle_error(e, "fetch created mother tongues"))
+}
+
+#[server]
+#[cfg_attr(feature = "ssr", tracing::instrument)]
+pub async fn fetch_created_translations(
+  user: core_types::UserRecordId,
+  offset: u32,
+  count: u32,
+) -> Result<
+  (
+    Vec<core_types::Contribution<core_types::TranslationSummary>>,
+    usize,
+  ),
+  ServerFnError<AppError>,
+> {
+  async move {
+    let db = use_db().await?;
+    let translations = db
+      .select_translations_created_by(user, offset, count)
+      .await
+      .wrap_err("failed to select created translations from db")?;
+
+    Ok(translations)
+  }
+  .await
+  .map_err(|e| handle_error(e, "fetch created translations"))
+}
+
+#[server]
+#[cfg_attr(feature = "ssr", tracing::instrument)]
+pub async fn fetch_created_recordings(
+  user: core_types::UserRecordId,
+  offset: u32,
+  count: u32,
+) -> Result<
+  (
+    Vec<core_types::Contribution<core_types::ContributedRecording>>,
+    usize,
+  ),
+  ServerFnError<AppError>,
+> {
+  let include_unapproved = sees_unapproved_contributions(user)?;
+  async move {
+    let db = use_db().await?;
+    let recordings = db
+      .select_recordings_created_by(user, include_unapproved, offset, count)
+      .await
+      .wrap_err("failed to select created recordings from db")?;
+
+    Ok(recordings)
+  }
+  .await
+  .map_err(|e| handle_error(e, "fetch created recordings"))
+}
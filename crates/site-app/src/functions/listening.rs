@@ -0,0 +1,34 @@
+use core_types::{AppError, Playlist, TranslationRecordId};
+#[cfg(feature = "ssr")]
+use eyre::Context;
+use leptos::*;
+
+#[cfg(feature = "ssr")]
+use crate::functions::{fetch::use_db, handle_error};
+
+/// Fetches what there is to listen to in a translation, or `None` if there's
+/// no such translation.
+#[cfg_attr(feature = "ssr", tracing::instrument)]
+#[server]
+pub async fn fetch_playlist(
+  translation: TranslationRecordId,
+) -> Result<Option<Playlist>, ServerFnError<AppError>> {
+  async move {
+    let db = use_db().await?;
+    let Some(details) = db
+      .select_translation(translation)
+      .await
+      .wrap_err("failed to select translation from db")?
+    else {
+      return Ok(None);
+    };
+    let tracks = db
+      .select_listening_tracks(translation)
+      .await
+      .wrap_err("failed to select listening tracks from db")?;
+
+    Ok(Some(Playlist::new(details.name, tracks)))
+  }
+  .await
+  .map_err(|e| handle_error(e, "fetch playlist"))
+}
@@ -0,0 +1,373 @@
+use core_types::{
+  AppError, InvitationRecordId, InvitationSummary, TranslationRecordId,
+  TranslationRole, TranslationTeam, UserRecordId,
+};
+#[cfg(feature = "ssr")]
+use eyre::Context;
+use leptos::*;
+
+#[cfg(feature = "ssr")]
+use crate::functions::{
+  fetch::use_db, handle_error, require_user, server_context,
+};
+
+/// Fetches a user's membership of a translation.
+#[cfg(feature = "ssr")]
+pub(crate) async fn fetch_membership(
+  db: &db::DbConnection,
+  translation: TranslationRecordId,
+  user: UserRecordId,
+) -> Result<Option<core_types::Membership>, ServerFnError<AppError>> {
+  db.select_membership(translation, user)
+    .await
+    .wrap_err("failed to select membership from db")
+    .map_err(|e| handle_error(e, "fetch membership"))
+}
+
+/// Whether `user` can manage a translation's team: its managers and admins
+/// can.
+#[cfg(feature = "ssr")]
+pub(crate) fn can_manage(
+  user: &core_types::User,
+  membership: Option<&core_types::Membership>,
+) -> bool {
+  user.has_role(core_types::SiteRole::Admin)
+    || membership.is_some_and(|m| m.has_role(TranslationRole::Manager))
+}
+
+/// Fails with [`AppError::Forbidden`] unless `user` can manage the
+/// translation's team.
+#[cfg(feature = "ssr")]
+pub(crate) async fn require_manager(
+  db: &db::DbConnection,
+  user: &core_types::User,
+  translation: TranslationRecordId,
+) -> Result<(), ServerFnError<AppError>> {
+  let membership = fetch_membership(db, translation, user.id).await?;
+  match can_manage(user, membership.as_ref()) {
+    true => Ok(()),
+    false => Err(ServerFnError::WrappedServerError(AppError::Forbidden)),
+  }
+}
+
+/// Fails if giving `member` `roles` would take away the translation's last
+/// manager.
+#[cfg(feature = "ssr")]
+async fn keep_a_manager(
+  db: &db::DbConnection,
+  translation: TranslationRecordId,
+  member: UserRecordId,
+  roles: &[TranslationRole],
+) -> Result<(), ServerFnError<AppError>> {
+  if roles.contains(&TranslationRole::Manager) {
+    return Ok(());
+  }
+
+  let members = db
+    .select_members(translation)
+    .await
+    .wrap_err("failed to select members from db")
+    .map_err(|e| handle_error(e, "fetch members"))?;
+  let (this, others): (Vec<_>, Vec<_>) = members
+    .iter()
+    .filter(|m| m.roles.contains(&TranslationRole::Manager))
+    .partition(|m| m.profile.id == member);
+  match this.is_empty() || !others.is_empty() {
+    true => Ok(()),
+    false => Err(ServerFnError::WrappedServerError(AppError::Conflict(
+      "A translation needs at least one manager.".to_string(),
+    ))),
+  }
+}
+
+/// Fetches a translation's team. Only managers see open invitations.
+#[cfg_attr(feature = "ssr", tracing::instrument)]
+#[server]
+pub async fn fetch_team(
+  translation: TranslationRecordId,
+) -> Result<TranslationTeam, ServerFnError<AppError>> {
+  let user = server_context::<auth::AuthSession>("auth session")?.user;
+  let db = use_db()
+    .await
+    .map_err(|e| handle_error(e, "connect to db"))?;
+
+  let can_manage = match &user {
+    Some(user) => {
+      let membership = fetch_membership(&db, translation, user.id).await?;
+      can_manage(user, membership.as_ref())
+    }
+    None => false,
+  };
+
+  async move {
+    let members = db
+      .select_members(translation)
+      .await
+      .wrap_err("failed to select members from db")?;
+    let invitations = match can_manage {
+      true => db
+        .select_open_invitations(translation)
+        .await
+        .wrap_err("failed to select invitations from db")?,
+      false => vec![],
+    };
+
+    Ok(TranslationTeam {
+      members,
+      invitations,
+      can_manage,
+      viewer: user.map(|u| u.id),
+    })
+  }
+  .await
+  .map_err(|e| handle_error(e, "fetch team"))
+}
+
+/// Whether the current user can record passages for a translation: its
+/// speakers and managers can, and admins.
+#[cfg_attr(feature = "ssr", tracing::instrument)]
+#[server]
+pub async fn can_record(
+  translation: TranslationRecordId,
+) -> Result<bool, ServerFnError<AppError>> {
+  let Some(user) = server_context::<auth::AuthSession>("auth session")?.user
+  else {
+    return Ok(false);
+  };
+  let db = use_db()
+    .await
+    .map_err(|e| handle_error(e, "connect to db"))?;
+
+  let membership = fetch_membership(&db, translation, user.id).await?;
+  Ok(
+    user.has_role(core_types::SiteRole::Admin)
+      || membership.is_some_and(|m| m.can_upload()),
+  )
+}
+
+/// Invites someone onto a translation's team, returning the path of the
+/// invitation link for the manager to pass on. With an email, the link is
+/// used up once it's answered; without one, anyone with the link can join
+/// until it expires.
+#[cfg_attr(feature = "ssr", tracing::instrument(skip(email)))]
+#[server]
+pub async fn invite_to_translation(
+  translation: TranslationRecordId,
+  email: String,
+  roles: Vec<TranslationRole>,
+) -> Result<String, ServerFnError<AppError>> {
+  use core_types::{ssr::CoreId, validation::validate_invitation};
+
+  let user = require_user()?;
+  validate_invitation(&email, &roles)
+    .map_err(|e| ServerFnError::WrappedServerError(AppError::Validation(e)))?;
+
+  let db = use_db()
+    .await
+    .map_err(|e| handle_error(e, "connect to db"))?;
+  require_manager(&db, &user, translation).await?;
+
+  let email = email.trim().to_lowercase();
+  let (secret, secret_hash) = auth::new_invitation_secret();
+  let now = time::OffsetDateTime::now_utc();
+  let invitation = core_types::Invitation {
+    id: InvitationRecordId::new(),
+    translation,
+    roles,
+    email: (!email.is_empty()).then(|| email.into()),
+    secret_hash,
+    invited_by: user.id,
+    status: core_types::InvitationStatus::Pending,
+    created_at: now,
+    expires_at: now
+      + time::Duration::days(core_types::INVITATION_LIFETIME_DAYS),
+  };
+
+  async move {
+    let invitation = db
+      .insert_invitation(invitation)
+      .await
+      .wrap_err("failed to insert invitation into db")?
+      .ok_or_else(|| eyre::eyre!("db returned no invitation"))?;
+
+    Ok(core_types::invitation_path(invitation.id, &secret))
+  }
+  .await
+  .map_err(|e| handle_error(e, "invite to translation"))
+}
+
+/// Revokes an invitation so it can't be accepted.
+#[cfg_attr(feature = "ssr", tracing::instrument)]
+#[server]
+pub async fn revoke_invitation(
+  id: InvitationRecordId,
+) -> Result<(), ServerFnError<AppError>> {
+  let user = require_user()?;
+  let db = use_db()
+    .await
+    .map_err(|e| handle_error(e, "connect to db"))?;
+
+  let invitation = db
+    .select_invitation(id)
+    .await
+    .wrap_err("failed to select invitation from db")
+    .map_err(|e| handle_error(e, "fetch invitation"))?
+    .ok_or(ServerFnError::WrappedServerError(AppError::NotFound))?;
+  require_manager(&db, &user, invitation.translation).await?;
+
+  db.update_invitation_status(id, core_types::InvitationStatus::Revoked)
+    .await
+    .wrap_err("failed to update invitation in db")
+    .map_err(|e| handle_error(e, "revoke invitation"))?;
+
+  Ok(())
+}
+
+/// Replaces a member's roles on a translation.
+#[cfg_attr(feature = "ssr", tracing::instrument)]
+#[server]
+pub async fn set_member_roles(
+  translation: TranslationRecordId,
+  member: UserRecordId,
+  roles: Vec<TranslationRole>,
+) -> Result<(), ServerFnError<AppError>> {
+  use core_types::validation::{ValidationErrors, ROLES_FIELD};
+
+  let user = require_user()?;
+  if roles.is_empty() {
+    let mut errors = ValidationErrors::new();
+    errors.check(
+      ROLES_FIELD,
+      Some("Members need at least one role. Remove them instead.".to_string()),
+    );
+    return Err(ServerFnError::WrappedServerError(AppError::Validation(
+      errors,
+    )));
+  }
+
+  let db = use_db()
+    .await
+    .map_err(|e| handle_error(e, "connect to db"))?;
+  require_manager(&db, &user, translation).await?;
+  keep_a_manager(&db, translation, member, &roles).await?;
+
+  db.update_member_roles(translation, member, &roles)
+    .await
+    .wrap_err("failed to update member roles in db")
+    .map_err(|e| handle_error(e, "set member roles"))?;
+
+  Ok(())
+}
+
+/// Removes a member from a translation's team. Managers can remove anyone,
+/// and members can remove themselves.
+#[cfg_attr(feature = "ssr", tracing::instrument)]
+#[server]
+pub async fn remove_member(
+  translation: TranslationRecordId,
+  member: UserRecordId,
+) -> Result<(), ServerFnError<AppError>> {
+  let user = require_user()?;
+  let db = use_db()
+    .await
+    .map_err(|e| handle_error(e, "connect to db"))?;
+  if member != user.id {
+    require_manager(&db, &user, translation).await?;
+  }
+  keep_a_manager(&db, translation, member, &[]).await?;
+
+  db.delete_membership(translation, member)
+    .await
+    .wrap_err("failed to delete membership from db")
+    .map_err(|e| handle_error(e, "remove member"))?;
+
+  Ok(())
+}
+
+/// Fetches the invitation a link opens, or `None` if the link is wrong or
+/// the invitation can't be accepted any more.
+#[cfg_attr(feature = "ssr", tracing::instrument(skip(token)))]
+#[server]
+pub async fn fetch_invitation(
+  token: String,
+) -> Result<Option<InvitationSummary>, ServerFnError<AppError>> {
+  let Some((id, secret)) = core_types::parse_invitation_token(&token) else {
+    return Ok(None);
+  };
+
+  async move {
+    let db = use_db().await?;
+    let invitation = db
+      .select_invitation(id)
+      .await
+      .wrap_err("failed to select invitation from db")?
+      .filter(|i| i.is_open())
+      .filter(|i| auth::invitation_secret_matches(secret, &i.secret_hash));
+    if invitation.is_none() {
+      return Ok(None);
+    }
+
+    db.select_invitation_summary(id)
+      .await
+      .wrap_err("failed to select invitation summary from db")
+  }
+  .await
+  .map_err(|e| handle_error(e, "fetch invitation"))
+}
+
+/// Accepts or declines an invitation, which needs the secret from its link.
+/// Emails aren't verified, so having the email an invitation is addressed
+/// to isn't enough to answer it.
+#[cfg_attr(feature = "ssr", tracing::instrument(skip(secret)))]
+#[server]
+pub async fn respond_to_invitation(
+  id: InvitationRecordId,
+  secret: Option<String>,
+  accept: bool,
+) -> Result<(), ServerFnError<AppError>> {
+  let user = require_user()?;
+  let db = use_db()
+    .await
+    .map_err(|e| handle_error(e, "connect to db"))?;
+
+  let invitation = db
+    .select_invitation(id)
+    .await
+    .wrap_err("failed to select invitation from db")
+    .map_err(|e| handle_error(e, "fetch invitation"))?
+    .ok_or(ServerFnError::WrappedServerError(AppError::NotFound))?;
+  if !invitation.is_open() {
+    return Err(ServerFnError::WrappedServerError(AppError::Conflict(
+      "This invitation has expired or has already been answered.".to_string(),
+    )));
+  }
+  let allowed = secret.is_some_and(|s| {
+    auth::invitation_secret_matches(&s, &invitation.secret_hash)
+  });
+  if !allowed {
+    return Err(ServerFnError::WrappedServerError(AppError::Forbidden));
+  }
+
+  async move {
+    if accept {
+      db.insert_membership(invitation.translation, user.id, &invitation.roles)
+        .await
+        .wrap_err("failed to insert membership into db")?;
+    }
+    // links addressed to someone are single-use; the rest stay open for
+    // whoever else they were shared with
+    if invitation.email.is_some() {
+      let status = match accept {
+        true => core_types::InvitationStatus::Accepted,
+        false => core_types::InvitationStatus::Declined,
+      };
+      db.update_invitation_status(id, status)
+        .await
+        .wrap_err("failed to update invitation in db")?;
+    }
+
+    Ok(())
+  }
+  .await
+  .map_err(|e| handle_error(e, "respond to invitation"))
+}
@@ -1,14 +1,67 @@
+pub mod api_tokens;
 pub mod auth;
+pub mod bundles;
 pub mod fetch;
+pub mod listening;
+pub mod members;
+pub mod oidc;
+pub mod passkey;
+pub mod recordings;
+pub mod sessions;
+pub mod takes;
+pub mod tongues;
+pub mod two_factor;
 
-#[cfg(feature = "ssr")]
+use core_types::AppError;
 use leptos::ServerFnError;
 
+/// Pulls the [`AppError`] out of a server fn error.
+///
+/// Errors that didn't come from the server fn body (e.g. network failures) are
+/// treated as [`AppError::Internal`].
+pub fn app_error(error: &ServerFnError<AppError>) -> AppError {
+  match error {
+    ServerFnError::WrappedServerError(e) => e.clone(),
+    _ => AppError::Internal,
+  }
+}
+
 #[cfg(feature = "ssr")]
 pub fn handle_error(
   error: eyre::Report,
   failed_action: &'static str,
-) -> ServerFnError {
+) -> ServerFnError<AppError> {
   tracing::error!("Failed to {failed_action}: {error:?}");
-  ServerFnError::new(error)
+  ServerFnError::WrappedServerError(AppError::Internal)
+}
+
+/// Gets a value from the server fn context, or fails with
+/// [`AppError::Internal`].
+#[cfg(feature = "ssr")]
+pub fn server_context<T: Clone + 'static>(
+  name: &'static str,
+) -> Result<T, ServerFnError<AppError>> {
+  leptos::use_context::<T>().ok_or_else(|| {
+    tracing::error!("Failed to get {name} from server fn context");
+    ServerFnError::WrappedServerError(AppError::Internal)
+  })
+}
+
+/// Gets the logged in user, or fails with [`AppError::Unauthorized`].
+#[cfg(feature = "ssr")]
+pub fn require_user() -> Result<core_types::User, ServerFnError<AppError>> {
+  server_context::<::auth::AuthSession>("auth session")?
+    .user
+    .ok_or(ServerFnError::WrappedServerError(AppError::Unauthorized))
+}
+
+/// Gets the logged in user if they can review submissions, or fails with
+/// [`AppError::Forbidden`].
+#[cfg(feature = "ssr")]
+pub fn require_reviewer() -> Result<core_types::User, ServerFnError<AppError>> {
+  let user = require_user()?;
+  match user.has_role(core_types::SiteRole::Reviewer) {
+    true => Ok(user),
+    false => Err(ServerFnError::WrappedServerError(AppError::Forbidden)),
+  }
 }
@@ -0,0 +1,39 @@
+use core_types::{AppError, OidcIdentityRecordId, OidcIdentitySummary};
+use leptos::*;
+
+#[cfg(feature = "ssr")]
+use crate::functions::{require_user, server_context};
+
+/// Lists the OIDC identities linked to the logged in user.
+#[cfg_attr(feature = "ssr", tracing::instrument)]
+#[server]
+pub async fn list_oidc_identities(
+) -> Result<Vec<OidcIdentitySummary>, ServerFnError<AppError>> {
+  let user = require_user()?;
+  let auth_session = server_context::<auth::AuthSession>("auth session")?;
+
+  auth_session
+    .backend
+    .list_oidc_identities(user.id)
+    .await
+    .map_err(|e| {
+      tracing::error!("Failed to list OIDC identities: {:?}", e);
+      ServerFnError::WrappedServerError(AppError::from(e))
+    })
+}
+
+/// Unlinks one of the logged in user's OIDC identities.
+#[cfg_attr(feature = "ssr", tracing::instrument)]
+#[server]
+pub async fn unlink_oidc_identity(
+  id: OidcIdentityRecordId,
+) -> Result<(), ServerFnError<AppError>> {
+  let user = require_user()?;
+  let auth_session = server_context::<auth::AuthSession>("auth session")?;
+
+  auth_session
+    .backend
+    .unlink_oidc_identity(user.id, id)
+    .await
+    .map_err(|e| ServerFnError::WrappedServerError(AppError::from(e)))
+}
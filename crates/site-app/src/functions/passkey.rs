@@ -0,0 +1,198 @@
+use core_types::{AppError, PasskeyRecordId, PasskeySummary, Pii};
+use leptos::{server_fn::codec::Json, *};
+use webauthn_rs_proto::{
+  CreationChallengeResponse, PublicKeyCredential, RegisterPublicKeyCredential,
+  RequestChallengeResponse,
+};
+
+#[cfg(feature = "ssr")]
+use crate::functions::{
+  auth::finish_login, handle_error, require_user, server_context,
+};
+
+#[cfg(feature = "ssr")]
+const PASSKEY_REGISTRATION_KEY: &str = "passkey_registration";
+#[cfg(feature = "ssr")]
+const PENDING_PASSKEY_LOGIN_KEY: &str = "pending_passkey_login";
+#[cfg(feature = "ssr")]
+const PASSKEY_CHALLENGE_TIMEOUT: time::Duration = time::Duration::minutes(5);
+
+/// A passkey login waiting on the browser's assertion, stored in the session.
+#[cfg(feature = "ssr")]
+#[derive(serde::Serialize, serde::Deserialize)]
+struct PendingPasskeyLogin {
+  user_id:    core_types::UserRecordId,
+  state:      auth::PasskeyAuthentication,
+  remember:   bool,
+  started_at: i64,
+}
+
+/// Lists the logged in user's passkeys.
+#[cfg_attr(feature = "ssr", tracing::instrument)]
+#[server]
+pub async fn list_passkeys(
+) -> Result<Vec<PasskeySummary>, ServerFnError<AppError>> {
+  let user = require_user()?;
+  let auth_session = server_context::<auth::AuthSession>("auth session")?;
+
+  auth_session
+    .backend
+    .list_passkeys(user.id)
+    .await
+    .map_err(|e| ServerFnError::WrappedServerError(AppError::from(e)))
+}
+
+/// Removes one of the logged in user's passkeys.
+#[cfg_attr(feature = "ssr", tracing::instrument)]
+#[server]
+pub async fn delete_passkey(
+  id: PasskeyRecordId,
+) -> Result<(), ServerFnError<AppError>> {
+  let user = require_user()?;
+  let auth_session = server_context::<auth::AuthSession>("auth session")?;
+
+  auth_session
+    .backend
+    .delete_passkey(user.id, id)
+    .await
+    .map_err(|e| ServerFnError::WrappedServerError(AppError::from(e)))
+}
+
+/// Starts registering a passkey for the logged in user, returning the
+/// challenge to pass to `navigator.credentials.create()`.
+#[cfg_attr(feature = "ssr", tracing::instrument)]
+#[server]
+pub async fn start_passkey_registration(
+) -> Result<CreationChallengeResponse, ServerFnError<AppError>> {
+  let user = require_user()?;
+  let auth_session = server_context::<auth::AuthSession>("auth session")?;
+  let session = server_context::<tower_sessions::Session>("session")?;
+
+  let (challenge, state) = auth_session
+    .backend
+    .start_passkey_registration(&user)
+    .await
+    .map_err(|e| {
+      tracing::error!("Failed to start passkey registration: {:?}", e);
+      ServerFnError::WrappedServerError(AppError::from(e))
+    })?;
+
+  session
+    .insert(PASSKEY_REGISTRATION_KEY, state)
+    .await
+    .map_err(|e| handle_error(e.into(), "store passkey registration"))?;
+
+  Ok(challenge)
+}
+
+/// Finishes registering a passkey with the browser's response.
+#[cfg_attr(feature = "ssr", tracing::instrument(skip(response)))]
+#[server(input = Json)]
+pub async fn finish_passkey_registration(
+  name: String,
+  response: RegisterPublicKeyCredential,
+) -> Result<PasskeySummary, ServerFnError<AppError>> {
+  let user = require_user()?;
+  let auth_session = server_context::<auth::AuthSession>("auth session")?;
+  let session = server_context::<tower_sessions::Session>("session")?;
+
+  let name = name.trim().to_string();
+  if name.is_empty() {
+    let mut errors = core_types::validation::ValidationErrors::new();
+    errors.check(
+      core_types::validation::NAME_FIELD,
+      Some("Give your passkey a name.".to_string()),
+    );
+    return Err(ServerFnError::WrappedServerError(AppError::Validation(
+      errors,
+    )));
+  }
+
+  let state = session
+    .remove::<auth::PasskeyRegistration>(PASSKEY_REGISTRATION_KEY)
+    .await
+    .map_err(|e| handle_error(e.into(), "read passkey registration"))?
+    .ok_or(ServerFnError::WrappedServerError(AppError::Unauthorized))?;
+
+  auth_session
+    .backend
+    .finish_passkey_registration(user.id, name, &response, &state)
+    .await
+    .map_err(|e| ServerFnError::WrappedServerError(AppError::from(e)))
+}
+
+/// Starts a passkey login, returning the challenge to pass to
+/// `navigator.credentials.get()`.
+#[cfg_attr(feature = "ssr", tracing::instrument)]
+#[server]
+pub async fn start_passkey_login(
+  email: Pii,
+  remember: bool,
+) -> Result<RequestChallengeResponse, ServerFnError<AppError>> {
+  let auth_session = server_context::<auth::AuthSession>("auth session")?;
+  let session = server_context::<tower_sessions::Session>("session")?;
+
+  let (challenge, state, user_id) = auth_session
+    .backend
+    .start_passkey_authentication(&email)
+    .await
+    .map_err(|e| ServerFnError::WrappedServerError(AppError::from(e)))?;
+
+  let pending = PendingPasskeyLogin {
+    user_id,
+    state,
+    remember,
+    started_at: time::OffsetDateTime::now_utc().unix_timestamp(),
+  };
+  session
+    .insert(PENDING_PASSKEY_LOGIN_KEY, pending)
+    .await
+    .map_err(|e| handle_error(e.into(), "store pending passkey login"))?;
+
+  Ok(challenge)
+}
+
+/// Finishes a passkey login with the browser's assertion.
+///
+/// Passkeys already prove possession of a device and usually a biometric or
+/// PIN, so this skips the TOTP step that password logins need.
+#[cfg_attr(feature = "ssr", tracing::instrument(skip(response)))]
+#[server(input = Json)]
+pub async fn finish_passkey_login(
+  response: PublicKeyCredential,
+) -> Result<(), ServerFnError<AppError>> {
+  let mut auth_session = server_context::<auth::AuthSession>("auth session")?;
+  let session = server_context::<tower_sessions::Session>("session")?;
+
+  let pending = session
+    .remove::<PendingPasskeyLogin>(PENDING_PASSKEY_LOGIN_KEY)
+    .await
+    .map_err(|e| handle_error(e.into(), "read pending passkey login"))?
+    .ok_or(ServerFnError::WrappedServerError(AppError::Unauthorized))?;
+
+  let elapsed =
+    time::OffsetDateTime::now_utc().unix_timestamp() - pending.started_at;
+  if elapsed > PASSKEY_CHALLENGE_TIMEOUT.whole_seconds() {
+    return Err(ServerFnError::WrappedServerError(AppError::Unauthorized));
+  }
+
+  let creds = auth::Credentials::Passkey {
+    user_id:  pending.user_id,
+    response: Box::new(response),
+    state:    Box::new(pending.state),
+    remember: pending.remember,
+  };
+  let user = match auth_session.authenticate(creds).await {
+    Ok(Some(user)) => user,
+    Ok(None) => {
+      return Err(ServerFnError::WrappedServerError(AppError::from(
+        auth::AuthError::PasskeyRejected,
+      )))
+    }
+    Err(e) => {
+      return Err(handle_error(eyre::eyre!("{e:?}"), "authenticate user"))
+    }
+  };
+
+  finish_login(&mut auth_session, &session, user, pending.remember).await
+}
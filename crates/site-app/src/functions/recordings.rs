@@ -0,0 +1,436 @@
+use core_types::{
+  AppError, ArtifactRecordId, CommentThreadDetails, CommentThreadRecordId,
+  QueuedRecording, RecordingStatus, ReviewEvent, TimeAnchor,
+};
+#[cfg(feature = "ssr")]
+use eyre::Context;
+use leptos::*;
+
+#[cfg(feature = "ssr")]
+use crate::functions::{
+  fetch::use_db, handle_error, members::fetch_membership, require_user,
+};
+
+/// Roles whose holders can see every recording in review in the
+/// translations they check.
+#[cfg(feature = "ssr")]
+const CHECKER_ROLES: [core_types::SiteRole; 2] = [
+  core_types::SiteRole::CommunityChecker,
+  core_types::SiteRole::Consultant,
+];
+
+#[cfg(feature = "ssr")]
+fn is_checker(roles: &[core_types::SiteRole]) -> bool {
+  CHECKER_ROLES
+    .into_iter()
+    .any(|checker| roles.iter().any(|r| r.grants(checker)))
+}
+
+/// Whether `user` can comment on a recording: its uploader and checkers
+/// can. `roles` are the ones they review its translation with.
+#[cfg(feature = "ssr")]
+fn can_comment(
+  user: &core_types::User,
+  recording: &core_types::RecordingSummary,
+  roles: &[core_types::SiteRole],
+) -> bool {
+  recording.is_uploaded_by(user.id) || is_checker(roles)
+}
+
+/// The roles `user` reviews a translation's recordings with, which depend
+/// on their membership of it.
+#[cfg(feature = "ssr")]
+async fn review_roles_in(
+  db: &db::DbConnection,
+  user: &core_types::User,
+  translation: core_types::TranslationRecordId,
+) -> Result<Vec<core_types::SiteRole>, ServerFnError<AppError>> {
+  let membership = fetch_membership(db, translation, user.id).await?;
+  Ok(core_types::review_roles(
+    &user.roles,
+    membership.as_ref().map(|m| m.roles.as_slice()),
+  ))
+}
+
+/// Fetches a recording for the current user to act on, or fails with
+/// [`AppError::NotFound`].
+#[cfg(feature = "ssr")]
+async fn fetch_reviewable_recording(
+  db: &db::DbConnection,
+  id: ArtifactRecordId,
+) -> Result<core_types::RecordingSummary, ServerFnError<AppError>> {
+  db.select_recording_summary(id)
+    .await
+    .wrap_err("failed to select recording from db")
+    .map_err(|e| handle_error(e, "fetch recording"))?
+    .ok_or(ServerFnError::WrappedServerError(AppError::NotFound))
+}
+
+/// Fetches a recording whose review the current user can see: its uploader,
+/// members of its translation and admins can.
+#[cfg(feature = "ssr")]
+async fn fetch_visible_recording(
+  db: &db::DbConnection,
+  user: &core_types::User,
+  id: ArtifactRecordId,
+) -> Result<core_types::RecordingSummary, ServerFnError<AppError>> {
+  let recording = fetch_reviewable_recording(db, id).await?;
+  let membership = fetch_membership(db, recording.translation, user.id).await?;
+  match recording.is_visible_to(user, membership.as_ref()) {
+    true => Ok(recording),
+    false => Err(ServerFnError::WrappedServerError(AppError::Forbidden)),
+  }
+}
+
+/// Checks a review comment, treating blank comments as missing.
+#[cfg(feature = "ssr")]
+fn review_comment(
+  comment: String,
+  required: bool,
+) -> Result<Option<String>, ServerFnError<AppError>> {
+  use core_types::validation::{ValidationErrors, COMMENT_FIELD};
+
+  let comment = comment.trim().to_string();
+  if comment.is_empty() && required {
+    let mut errors = ValidationErrors::new();
+    errors.check(COMMENT_FIELD, Some("Add a comment saying why.".to_string()));
+    return Err(ServerFnError::WrappedServerError(AppError::Validation(
+      errors,
+    )));
+  }
+
+  Ok((!comment.is_empty()).then_some(comment))
+}
+
+/// Lists recordings in review. Without a status this is the recordings
+/// waiting on the current user: their own drafts and rejections, and
+/// whatever their roles let them check in the translations they're members
+/// of. With one, checkers see every recording in that status in the
+/// translations they check, and everyone else sees their own.
+#[cfg_attr(feature = "ssr", tracing::instrument)]
+#[server]
+pub async fn list_review_queue(
+  status: Option<RecordingStatus>,
+  offset: u32,
+  count: u32,
+) -> Result<(Vec<QueuedRecording>, usize), ServerFnError<AppError>> {
+  use std::collections::HashMap;
+
+  let user = require_user()?;
+
+  async move {
+    let db = use_db().await?;
+    let memberships: HashMap<_, _> = db
+      .select_memberships(user.id)
+      .await
+      .wrap_err("failed to select memberships from db")?
+      .into_iter()
+      .map(|m| (m.translation, m.roles))
+      .collect();
+    let roles_in = |translation| {
+      core_types::review_roles(
+        &user.roles,
+        memberships.get(&translation).map(Vec::as_slice),
+      )
+    };
+    // what the user's roles let them do outside their memberships
+    let site_wide_roles = core_types::review_roles(&user.roles, None);
+
+    let filter = match status {
+      None => db::ReviewQueueFilter {
+        statuses:             RecordingStatus::waiting_on_roles(
+          &site_wide_roles,
+        ),
+        translation_statuses: memberships
+          .keys()
+          .flat_map(|&translation| {
+            RecordingStatus::waiting_on_roles(&roles_in(translation))
+              .into_iter()
+              .map(move |status| (translation, status))
+          })
+          .collect(),
+        own_statuses:         RecordingStatus::ALL
+          .into_iter()
+          .filter(RecordingStatus::waits_on_uploader)
+          .collect(),
+        uploader:             user.id,
+      },
+      Some(status) => db::ReviewQueueFilter {
+        statuses:             match is_checker(&site_wide_roles) {
+          true => vec![status],
+          false => vec![],
+        },
+        translation_statuses: memberships
+          .keys()
+          .filter(|&&translation| is_checker(&roles_in(translation)))
+          .map(|&translation| (translation, status))
+          .collect(),
+        own_statuses:         vec![status],
+        uploader:             user.id,
+      },
+    };
+
+    let (recordings, total) = db
+      .select_review_queue(&filter, offset, count)
+      .await
+      .wrap_err("failed to select review queue from db")?;
+
+    let queued = recordings
+      .into_iter()
+      .map(|recording| {
+        let roles = roles_in(recording.translation);
+        QueuedRecording {
+          next_statuses: recording
+            .status
+            .next_statuses(recording.is_uploaded_by(user.id), &roles),
+          can_comment: can_comment(&user, &recording, &roles),
+          recording,
+        }
+      })
+      .collect();
+
+    Ok((queued, total))
+  }
+  .await
+  .map_err(|e| handle_error(e, "list review queue"))
+}
+
+/// Lists the moves and comments on a recording, for those who can see its
+/// review.
+#[cfg_attr(feature = "ssr", tracing::instrument)]
+#[server]
+pub async fn fetch_review_history(
+  id: ArtifactRecordId,
+) -> Result<Vec<ReviewEvent>, ServerFnError<AppError>> {
+  let user = require_user()?;
+  let db = use_db()
+    .await
+    .map_err(|e| handle_error(e, "connect to db"))?;
+  fetch_visible_recording(&db, &user, id).await?;
+
+  async move {
+    let history = db
+      .select_review_history(id)
+      .await
+      .wrap_err("failed to select review history from db")?;
+
+    Ok(history)
+  }
+  .await
+  .map_err(|e| handle_error(e, "fetch review history"))
+}
+
+/// Moves a recording to another status, if the current user is allowed to.
+/// Rejections need a comment.
+#[cfg_attr(feature = "ssr", tracing::instrument)]
+#[server]
+pub async fn move_recording(
+  id: ArtifactRecordId,
+  to: RecordingStatus,
+  comment: String,
+) -> Result<(), ServerFnError<AppError>> {
+  let user = require_user()?;
+  let db = use_db()
+    .await
+    .map_err(|e| handle_error(e, "connect to db"))?;
+
+  let recording = fetch_reviewable_recording(&db, id).await?;
+  let roles = review_roles_in(&db, &user, recording.translation).await?;
+  let allowed = recording
+    .status
+    .next_statuses(recording.is_uploaded_by(user.id), &roles)
+    .contains(&to);
+  if !allowed {
+    return Err(ServerFnError::WrappedServerError(AppError::Forbidden));
+  }
+  let comment = review_comment(comment, to.needs_comment())?;
+
+  let moved = db
+    .update_recording_status(id, recording.status, to, user.id, comment)
+    .await
+    .wrap_err("failed to update recording status in db")
+    .map_err(|e| handle_error(e, "move recording"))?;
+  if !moved {
+    return Err(ServerFnError::WrappedServerError(AppError::Conflict(
+      "Someone else moved this recording first. Reload to see where it is now."
+        .to_string(),
+    )));
+  }
+
+  Ok(())
+}
+
+/// Comments on a recording without moving it.
+#[cfg_attr(feature = "ssr", tracing::instrument)]
+#[server]
+pub async fn comment_on_recording(
+  id: ArtifactRecordId,
+  comment: String,
+) -> Result<(), ServerFnError<AppError>> {
+  let user = require_user()?;
+  let db = use_db()
+    .await
+    .map_err(|e| handle_error(e, "connect to db"))?;
+
+  fetch_commentable_recording(&db, &user, id).await?;
+  let comment = review_comment(comment, true)?.unwrap_or_default();
+
+  db.insert_review_comment(id, user.id, comment)
+    .await
+    .wrap_err("failed to insert review comment into db")
+    .map_err(|e| handle_error(e, "comment on recording"))?;
+
+  Ok(())
+}
+
+/// Fetches the recording a comment thread is on, failing unless the current
+/// user can comment on it.
+#[cfg(feature = "ssr")]
+async fn fetch_commentable_recording(
+  db: &db::DbConnection,
+  user: &core_types::User,
+  id: ArtifactRecordId,
+) -> Result<core_types::RecordingSummary, ServerFnError<AppError>> {
+  let recording = fetch_reviewable_recording(db, id).await?;
+  let roles = review_roles_in(db, user, recording.translation).await?;
+  match can_comment(user, &recording, &roles) {
+    true => Ok(recording),
+    false => Err(ServerFnError::WrappedServerError(AppError::Forbidden)),
+  }
+}
+
+/// Fetches a comment thread, or fails with [`AppError::NotFound`].
+#[cfg(feature = "ssr")]
+async fn fetch_comment_thread(
+  db: &db::DbConnection,
+  id: CommentThreadRecordId,
+) -> Result<core_types::CommentThread, ServerFnError<AppError>> {
+  db.select_comment_thread(id)
+    .await
+    .wrap_err("failed to select comment thread from db")
+    .map_err(|e| handle_error(e, "fetch comment thread"))?
+    .ok_or(ServerFnError::WrappedServerError(AppError::NotFound))
+}
+
+/// Lists the comment threads on a recording, for those who can see its
+/// review.
+#[cfg_attr(feature = "ssr", tracing::instrument)]
+#[server]
+pub async fn list_comment_threads(
+  id: ArtifactRecordId,
+) -> Result<Vec<CommentThreadDetails>, ServerFnError<AppError>> {
+  let user = require_user()?;
+  let db = use_db()
+    .await
+    .map_err(|e| handle_error(e, "connect to db"))?;
+  fetch_visible_recording(&db, &user, id).await?;
+
+  async move {
+    let threads = db
+      .select_comment_threads(id)
+      .await
+      .wrap_err("failed to select comment threads from db")?;
+
+    Ok(threads)
+  }
+  .await
+  .map_err(|e| handle_error(e, "list comment threads"))
+}
+
+/// Starts a comment thread at a moment or stretch of a recording.
+#[cfg_attr(feature = "ssr", tracing::instrument)]
+#[server]
+pub async fn start_comment_thread(
+  id: ArtifactRecordId,
+  anchor: TimeAnchor,
+  comment: String,
+) -> Result<CommentThreadRecordId, ServerFnError<AppError>> {
+  use core_types::{
+    ssr::CoreId,
+    validation::{ValidationErrors, ANCHOR_FIELD},
+  };
+
+  let user = require_user()?;
+  if !anchor.is_valid() {
+    let mut errors = ValidationErrors::new();
+    errors.check(
+      ANCHOR_FIELD,
+      Some("A range has to end after it starts.".to_string()),
+    );
+    return Err(ServerFnError::WrappedServerError(AppError::Validation(
+      errors,
+    )));
+  }
+  let comment = review_comment(comment, true)?.unwrap_or_default();
+
+  let db = use_db()
+    .await
+    .map_err(|e| handle_error(e, "connect to db"))?;
+  fetch_commentable_recording(&db, &user, id).await?;
+
+  let thread = core_types::CommentThread {
+    id: CommentThreadRecordId::new(),
+    artifact: id,
+    anchor,
+    resolved: false,
+    created_at: time::OffsetDateTime::now_utc(),
+  };
+
+  async move {
+    let thread = db
+      .insert_comment_thread(thread, user.id, comment)
+      .await
+      .wrap_err("failed to insert comment thread into db")?
+      .ok_or_else(|| eyre::eyre!("db returned no comment thread"))?;
+
+    Ok(thread.id)
+  }
+  .await
+  .map_err(|e| handle_error(e, "start comment thread"))
+}
+
+/// Replies to a comment thread.
+#[cfg_attr(feature = "ssr", tracing::instrument)]
+#[server]
+pub async fn reply_to_thread(
+  id: CommentThreadRecordId,
+  comment: String,
+) -> Result<(), ServerFnError<AppError>> {
+  let user = require_user()?;
+  let comment = review_comment(comment, true)?.unwrap_or_default();
+
+  let db = use_db()
+    .await
+    .map_err(|e| handle_error(e, "connect to db"))?;
+  let thread = fetch_comment_thread(&db, id).await?;
+  fetch_commentable_recording(&db, &user, thread.artifact).await?;
+
+  db.insert_thread_comment(id, user.id, comment)
+    .await
+    .wrap_err("failed to insert thread comment into db")
+    .map_err(|e| handle_error(e, "reply to thread"))?;
+
+  Ok(())
+}
+
+/// Marks a comment thread as resolved, or reopens it.
+#[cfg_attr(feature = "ssr", tracing::instrument)]
+#[server]
+pub async fn set_thread_resolved(
+  id: CommentThreadRecordId,
+  resolved: bool,
+) -> Result<(), ServerFnError<AppError>> {
+  let user = require_user()?;
+  let db = use_db()
+    .await
+    .map_err(|e| handle_error(e, "connect to db"))?;
+  let thread = fetch_comment_thread(&db, id).await?;
+  fetch_commentable_recording(&db, &user, thread.artifact).await?;
+
+  db.update_thread_resolved(id, resolved)
+    .await
+    .wrap_err("failed to update comment thread in db")
+    .map_err(|e| handle_error(e, "resolve comment thread"))?;
+
+  Ok(())
+}
@@ -0,0 +1,63 @@
+use core_types::{ActiveSession, AppError, DeviceSessionRecordId};
+use leptos::*;
+
+#[cfg(feature = "ssr")]
+use crate::functions::{require_user, server_context};
+
+/// Lists the logged in user's active sessions.
+#[cfg_attr(feature = "ssr", tracing::instrument)]
+#[server]
+pub async fn list_sessions(
+) -> Result<Vec<ActiveSession>, ServerFnError<AppError>> {
+  let user = require_user()?;
+  let auth_session = server_context::<auth::AuthSession>("auth session")?;
+  let session = server_context::<tower_sessions::Session>("session")?;
+
+  let current = auth::current_device_session(&session).await;
+  auth_session
+    .backend
+    .list_sessions(user.id, current)
+    .await
+    .map_err(|e| {
+      tracing::error!("Failed to list sessions: {:?}", e);
+      ServerFnError::WrappedServerError(AppError::from(e))
+    })
+}
+
+/// Logs out one of the logged in user's sessions.
+#[cfg_attr(feature = "ssr", tracing::instrument)]
+#[server]
+pub async fn revoke_session(
+  id: DeviceSessionRecordId,
+) -> Result<(), ServerFnError<AppError>> {
+  let user = require_user()?;
+  let auth_session = server_context::<auth::AuthSession>("auth session")?;
+
+  auth_session
+    .backend
+    .revoke_session(user.id, id)
+    .await
+    .map_err(|e| ServerFnError::WrappedServerError(AppError::from(e)))
+}
+
+/// Logs out every session of the logged in user except this one.
+#[cfg_attr(feature = "ssr", tracing::instrument)]
+#[server]
+pub async fn revoke_other_sessions() -> Result<(), ServerFnError<AppError>> {
+  let user = require_user()?;
+  let auth_session = server_context::<auth::AuthSession>("auth session")?;
+  let session = server_context::<tower_sessions::Session>("session")?;
+
+  // the current session is tracked on its first request after login, so
+  // this only fails if tracking is broken
+  let Some(current) = auth::current_device_session(&session).await else {
+    tracing::error!("Failed to find the current device session");
+    return Err(ServerFnError::WrappedServerError(AppError::Internal));
+  };
+
+  auth_session
+    .backend
+    .revoke_other_sessions(user.id, current)
+    .await
+    .map_err(|e| ServerFnError::WrappedServerError(AppError::from(e)))
+}
@@ -0,0 +1,124 @@
+use core_types::{
+  scripture::Passage, AppError, ArtifactRecordId, PassageTakes, Take,
+  TranslationRecordId,
+};
+#[cfg(feature = "ssr")]
+use eyre::Context;
+use leptos::*;
+
+#[cfg(feature = "ssr")]
+use crate::functions::{
+  fetch::use_db,
+  handle_error,
+  members::{can_manage, fetch_membership},
+  require_user, server_context,
+};
+
+/// Whether `user` can choose which take of a passage is current: the
+/// translation's managers and admins can, and so can anyone who uploaded
+/// one of the takes while they can still upload to the translation.
+#[cfg(feature = "ssr")]
+async fn can_choose_take(
+  db: &db::DbConnection,
+  user: &core_types::User,
+  translation: TranslationRecordId,
+  takes: &[Take],
+) -> Result<bool, ServerFnError<AppError>> {
+  let membership = fetch_membership(db, translation, user.id).await?;
+  if can_manage(user, membership.as_ref()) {
+    return Ok(true);
+  }
+
+  let uploaded_a_take = takes
+    .iter()
+    .any(|t| t.uploader.as_ref().is_some_and(|u| u.id == user.id));
+  Ok(uploaded_a_take && membership.is_some_and(|m| m.can_upload()))
+}
+
+/// Lists the passages recorded for a translation with their current takes,
+/// in canonical order.
+#[cfg_attr(feature = "ssr", tracing::instrument)]
+#[server]
+pub async fn list_recorded_passages(
+  translation: TranslationRecordId,
+) -> Result<Vec<PassageTakes>, ServerFnError<AppError>> {
+  async move {
+    let db = use_db().await?;
+    let mut passages = db
+      .select_current_takes(translation)
+      .await
+      .wrap_err("failed to select current takes from db")?;
+    passages.sort_by_key(|p| p.passage.canonical_key());
+
+    Ok(passages)
+  }
+  .await
+  .map_err(|e| handle_error(e, "list recorded passages"))
+}
+
+/// Lists the takes of a passage, newest first, along with whether the
+/// current user can choose which one is current.
+#[cfg_attr(feature = "ssr", tracing::instrument)]
+#[server]
+pub async fn list_takes(
+  translation: TranslationRecordId,
+  passage: Passage,
+) -> Result<(Vec<Take>, bool), ServerFnError<AppError>> {
+  let user = server_context::<auth::AuthSession>("auth session")?.user;
+  let db = use_db()
+    .await
+    .map_err(|e| handle_error(e, "connect to db"))?;
+
+  let takes = db
+    .select_takes(translation, &passage)
+    .await
+    .wrap_err("failed to select takes from db")
+    .map_err(|e| handle_error(e, "list takes"))?;
+  let can_choose = match &user {
+    Some(user) => can_choose_take(&db, user, translation, &takes).await?,
+    None => false,
+  };
+
+  Ok((takes, can_choose))
+}
+
+/// Makes an earlier take of a passage current again, or switches to a
+/// newer one. No take is ever deleted.
+#[cfg_attr(feature = "ssr", tracing::instrument)]
+#[server]
+pub async fn make_take_current(
+  id: ArtifactRecordId,
+) -> Result<(), ServerFnError<AppError>> {
+  let user = require_user()?;
+  let db = use_db()
+    .await
+    .map_err(|e| handle_error(e, "connect to db"))?;
+
+  let recording = db
+    .select_recording_summary(id)
+    .await
+    .wrap_err("failed to select recording from db")
+    .map_err(|e| handle_error(e, "fetch recording"))?
+    .ok_or(ServerFnError::WrappedServerError(AppError::NotFound))?;
+  let Some(passage) = recording.passage else {
+    return Err(ServerFnError::WrappedServerError(AppError::Conflict(
+      "This recording isn't a take of a passage.".to_string(),
+    )));
+  };
+
+  let takes = db
+    .select_takes(recording.translation, &passage)
+    .await
+    .wrap_err("failed to select takes from db")
+    .map_err(|e| handle_error(e, "fetch takes"))?;
+  if !can_choose_take(&db, &user, recording.translation, &takes).await? {
+    return Err(ServerFnError::WrappedServerError(AppError::Forbidden));
+  }
+
+  db.update_current_take(id)
+    .await
+    .wrap_err("failed to update current take in db")
+    .map_err(|e| handle_error(e, "make take current"))?;
+
+  Ok(())
+}
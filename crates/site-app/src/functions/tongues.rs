@@ -0,0 +1,233 @@
+use core_types::{
+  iso_639_3::Language, AppError, MotherTongueRecordId, TongueProposal,
+};
+#[cfg(feature = "ssr")]
+use eyre::Context;
+use leptos::*;
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "ssr")]
+use crate::functions::{
+  fetch::use_db, handle_error, require_reviewer, require_user,
+};
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct TongueProposalParams {
+  pub name:            String,
+  pub description:     String,
+  pub alternate_names: Vec<String>,
+  pub region:          Option<String>,
+  pub countries:       Vec<String>,
+  pub iso_639_3:       Option<String>,
+  pub parent_language: Option<String>,
+  pub script:          Option<String>,
+}
+
+/// Trims an optional field, treating blank input as missing.
+#[cfg(feature = "ssr")]
+fn non_empty(value: Option<String>) -> Option<String> {
+  value
+    .map(|v| v.trim().to_string())
+    .filter(|v| !v.is_empty())
+}
+
+/// Trims each entry of a list field, dropping blank ones.
+#[cfg(feature = "ssr")]
+fn non_empty_list(values: Vec<String>) -> Vec<String> {
+  values
+    .into_iter()
+    .filter_map(|v| non_empty(Some(v)))
+    .collect()
+}
+
+/// Fetches a mother tongue for a reviewer to act on, or fails with
+/// [`AppError::NotFound`].
+#[cfg(feature = "ssr")]
+async fn fetch_tongue(
+  db: &db::DbConnection,
+  id: MotherTongueRecordId,
+) -> Result<core_types::MotherTongue, ServerFnError<AppError>> {
+  db.select_mother_tongue(id)
+    .await
+    .wrap_err("failed to select mother tongue from db")
+    .map_err(|e| handle_error(e, "fetch mother tongue"))?
+    .ok_or(ServerFnError::WrappedServerError(AppError::NotFound))
+}
+
+/// Proposes a new mother tongue. It stays out of listings until a reviewer
+/// vets it.
+#[cfg_attr(feature = "ssr", tracing::instrument)]
+#[server]
+pub async fn propose_mother_tongue(
+  params: TongueProposalParams,
+) -> Result<MotherTongueRecordId, ServerFnError<AppError>> {
+  use core_types::ssr::CoreId;
+
+  let user = require_user()?;
+
+  let alternate_names = non_empty_list(params.alternate_names);
+  let region = non_empty(params.region);
+  let countries = non_empty_list(params.countries);
+  let iso_639_3 = non_empty(params.iso_639_3);
+  let parent_language = non_empty(params.parent_language);
+  let script = non_empty(params.script);
+  core_types::validation::validate_tongue_proposal(
+    core_types::validation::TongueProposalInput {
+      name:            &params.name,
+      description:     &params.description,
+      countries:       &countries,
+      iso_639_3:       iso_639_3.as_deref(),
+      parent_language: parent_language.as_deref(),
+      script:          script.as_deref(),
+    },
+  )
+  .map_err(|e| ServerFnError::WrappedServerError(AppError::Validation(e)))?;
+
+  let tongue = core_types::MotherTongue {
+    id: MotherTongueRecordId::new(),
+    name: params.name.trim().to_string(),
+    description: params.description.trim().to_string(),
+    alternate_names,
+    region,
+    countries,
+    iso_639_3,
+    parent_language,
+    script,
+    is_vetted: false,
+    rejection_reason: None,
+  };
+
+  async move {
+    let db = use_db().await?;
+    let tongue = db
+      .insert_mother_tongue(tongue, user.id)
+      .await
+      .wrap_err("failed to insert mother tongue into db")?
+      .ok_or_else(|| eyre::eyre!("db returned no mother tongue"))?;
+
+    Ok(tongue.id)
+  }
+  .await
+  .map_err(|e| handle_error(e, "propose mother tongue"))
+}
+
+/// Looks up a code in the bundled ISO 639-3 table.
+#[cfg_attr(feature = "ssr", tracing::instrument)]
+#[server]
+pub async fn lookup_language(
+  code: String,
+) -> Result<Option<Language>, ServerFnError<AppError>> {
+  Ok(core_types::iso_639_3::lookup(code.trim()).cloned())
+}
+
+/// Lists the mother tongues waiting for review.
+#[cfg_attr(feature = "ssr", tracing::instrument)]
+#[server]
+pub async fn list_tongue_proposals(
+) -> Result<Vec<TongueProposal>, ServerFnError<AppError>> {
+  require_reviewer()?;
+
+  async move {
+    let db = use_db().await?;
+    let proposals = db
+      .select_tongue_proposals()
+      .await
+      .wrap_err("failed to select tongue proposals from db")?;
+
+    Ok(proposals)
+  }
+  .await
+  .map_err(|e| handle_error(e, "list tongue proposals"))
+}
+
+/// Vets a proposed mother tongue, listing it publicly.
+#[cfg_attr(feature = "ssr", tracing::instrument)]
+#[server]
+pub async fn approve_tongue(
+  id: MotherTongueRecordId,
+) -> Result<(), ServerFnError<AppError>> {
+  require_reviewer()?;
+  let db = use_db()
+    .await
+    .map_err(|e| handle_error(e, "connect to db"))?;
+
+  let tongue = fetch_tongue(&db, id)
+    .await?
+    .approve()
+    .map_err(ServerFnError::WrappedServerError)?;
+
+  db.update_mother_tongue(tongue)
+    .await
+    .wrap_err("failed to update mother tongue in db")
+    .map_err(|e| handle_error(e, "approve mother tongue"))?;
+
+  Ok(())
+}
+
+/// Turns down a proposed mother tongue. The reason is shown on its page.
+#[cfg_attr(feature = "ssr", tracing::instrument)]
+#[server]
+pub async fn reject_tongue(
+  id: MotherTongueRecordId,
+  reason: String,
+) -> Result<(), ServerFnError<AppError>> {
+  use core_types::validation::{ValidationErrors, REASON_FIELD};
+
+  require_reviewer()?;
+
+  let reason = reason.trim().to_string();
+  if reason.is_empty() {
+    let mut errors = ValidationErrors::new();
+    errors.check(
+      REASON_FIELD,
+      Some("Give a reason for the rejection.".to_string()),
+    );
+    return Err(ServerFnError::WrappedServerError(AppError::Validation(
+      errors,
+    )));
+  }
+
+  let db = use_db()
+    .await
+    .map_err(|e| handle_error(e, "connect to db"))?;
+
+  let tongue = fetch_tongue(&db, id)
+    .await?
+    .reject(reason)
+    .map_err(ServerFnError::WrappedServerError)?;
+
+  db.update_mother_tongue(tongue)
+    .await
+    .wrap_err("failed to update mother tongue in db")
+    .map_err(|e| handle_error(e, "reject mother tongue"))?;
+
+  Ok(())
+}
+
+/// Merges a proposed mother tongue into an existing vetted one, moving its
+/// translations across.
+#[cfg_attr(feature = "ssr", tracing::instrument)]
+#[server]
+pub async fn merge_tongue(
+  id: MotherTongueRecordId,
+  into: MotherTongueRecordId,
+) -> Result<(), ServerFnError<AppError>> {
+  require_reviewer()?;
+
+  let db = use_db()
+    .await
+    .map_err(|e| handle_error(e, "connect to db"))?;
+
+  let tongue = fetch_tongue(&db, id).await?;
+  let target = fetch_tongue(&db, into).await?;
+  tongue
+    .check_merge_into(&target)
+    .map_err(ServerFnError::WrappedServerError)?;
+
+  db.merge_mother_tongue(id, into)
+    .await
+    .wrap_err("failed to merge mother tongues in db")
+    .map_err(|e| handle_error(e, "merge mother tongues"))?;
+
+  Ok(())
+}
@@ -0,0 +1,53 @@
+use core_types::{AppError, TotpEnrollment};
+use leptos::*;
+
+#[cfg(feature = "ssr")]
+use crate::functions::{require_user, server_context};
+
+/// Starts TOTP enrollment for the logged in user.
+#[cfg_attr(feature = "ssr", tracing::instrument)]
+#[server]
+pub async fn begin_totp_enrollment(
+) -> Result<TotpEnrollment, ServerFnError<AppError>> {
+  let user = require_user()?;
+  let auth_session = server_context::<auth::AuthSession>("auth session")?;
+
+  auth_session
+    .backend
+    .begin_totp_enrollment(user.id)
+    .await
+    .map_err(|e| {
+      tracing::error!("Failed to begin TOTP enrollment: {:?}", e);
+      ServerFnError::WrappedServerError(AppError::from(e))
+    })
+}
+
+/// Confirms TOTP enrollment, returning the user's recovery codes.
+#[cfg_attr(feature = "ssr", tracing::instrument(skip(code)))]
+#[server]
+pub async fn confirm_totp_enrollment(
+  code: String,
+) -> Result<Vec<String>, ServerFnError<AppError>> {
+  let user = require_user()?;
+  let auth_session = server_context::<auth::AuthSession>("auth session")?;
+
+  auth_session
+    .backend
+    .confirm_totp_enrollment(user.id, &code)
+    .await
+    .map_err(|e| ServerFnError::WrappedServerError(AppError::from(e)))
+}
+
+/// Turns off TOTP for the logged in user.
+#[cfg_attr(feature = "ssr", tracing::instrument(skip(code)))]
+#[server]
+pub async fn disable_totp(code: String) -> Result<(), ServerFnError<AppError>> {
+  let user = require_user()?;
+  let auth_session = server_context::<auth::AuthSession>("auth session")?;
+
+  auth_session
+    .backend
+    .disable_totp(user.id, &code)
+    .await
+    .map_err(|e| ServerFnError::WrappedServerError(AppError::from(e)))
+}
@@ -12,6 +12,40 @@ pub fn navigate_to(path: &str) {
   }
 }
 
+/// Replaces the query string of the current URL without reloading, so the
+/// page's state survives a refresh or a shared link.
+pub fn replace_query(pairs: &[(&str, String)]) {
+  let window = web_sys::window().expect("Failed to get window");
+  let query = match web_sys::UrlSearchParams::new() {
+    Ok(params) => {
+      for (key, value) in pairs {
+        params.append(key, value);
+      }
+      String::from(params.to_string())
+    }
+    Err(e) => {
+      logging::error!("failed to build query string: {:?}", e);
+      return;
+    }
+  };
+  let path = window.location().pathname().unwrap_or_default();
+  let url = match query.is_empty() {
+    true => path,
+    false => format!("{path}?{query}"),
+  };
+
+  let result = window.history().and_then(|history| {
+    history.replace_state_with_url(
+      &web_sys::wasm_bindgen::JsValue::NULL,
+      "",
+      Some(&url),
+    )
+  });
+  if let Err(e) = result {
+    logging::error!("failed to replace url: {:?}", e);
+  }
+}
+
 pub fn reload() {
   let result = web_sys::window()
     .expect("Failed to get window")
@@ -0,0 +1,278 @@
+//! Browser audio recording with `MediaRecorder`, and uploads with progress.
+
+/// Why recording couldn't start.
+// only the browser build ever records
+#[cfg_attr(not(feature = "hydrate"), allow(dead_code))]
+#[derive(Clone, Debug, PartialEq)]
+pub enum RecorderError {
+  /// The user, or the browser's settings, refused the microphone.
+  PermissionDenied,
+  NoMicrophone,
+  /// Another app is using the microphone.
+  MicrophoneBusy,
+  /// The browser can't record, or the page isn't served securely.
+  Unsupported,
+  Other(String),
+}
+
+impl RecorderError {
+  pub fn message(&self) -> String {
+    match self {
+      Self::PermissionDenied => "Microphone access was blocked. Allow this \
+                                 site to use your microphone in your \
+                                 browser's settings, then try again."
+        .to_string(),
+      Self::NoMicrophone => {
+        "No microphone was found. Plug one in and try again.".to_string()
+      }
+      Self::MicrophoneBusy => "Your microphone is being used by another app. \
+                               Close it and try again."
+        .to_string(),
+      Self::Unsupported => "Your browser can't record audio here. Try a \
+                            recent version of Firefox, Chrome or Safari."
+        .to_string(),
+      Self::Other(e) => format!("Recording failed: {e}"),
+    }
+  }
+}
+
+/// A finished recording, held by the browser until it's uploaded.
+#[cfg_attr(not(feature = "hydrate"), allow(dead_code))]
+#[derive(Clone, Debug)]
+pub struct Recording {
+  pub blob:        web_sys::Blob,
+  /// An object URL to preview the recording with. Release it with
+  /// [`Recording::release`] once it's no longer shown.
+  pub url:         String,
+  /// How long the recording ran for. Browsers often can't tell from the
+  /// recorded file itself until it's been played through.
+  pub duration_ms: u32,
+}
+
+impl Recording {
+  pub fn release(&self) {
+    if let Err(e) = web_sys::Url::revoke_object_url(&self.url) {
+      leptos::logging::error!("failed to release recording: {e:?}");
+    }
+  }
+}
+
+#[cfg(feature = "hydrate")]
+pub use self::browser::{start_recording, upload_recording, Recorder};
+
+#[cfg(feature = "hydrate")]
+mod browser {
+  use std::{cell::RefCell, rc::Rc};
+
+  use web_sys::{
+    js_sys,
+    wasm_bindgen::{closure::Closure, JsCast, JsValue},
+  };
+
+  use super::{RecorderError, Recording};
+
+  /// A recording in progress. Call [`Recorder::stop`] to finish it.
+  pub struct Recorder {
+    recorder:   web_sys::MediaRecorder,
+    stream:     web_sys::MediaStream,
+    chunks:     Rc<RefCell<Vec<web_sys::Blob>>>,
+    started_at: f64,
+    _on_data:   Closure<dyn FnMut(web_sys::BlobEvent)>,
+  }
+
+  fn recorder_error(e: JsValue) -> RecorderError {
+    let Some(e) = e.dyn_ref::<web_sys::DomException>() else {
+      return RecorderError::Other(format!("{e:?}"));
+    };
+    match e.name().as_str() {
+      "NotAllowedError" | "SecurityError" => RecorderError::PermissionDenied,
+      "NotFoundError" | "OverconstrainedError" => RecorderError::NoMicrophone,
+      "NotReadableError" | "AbortError" => RecorderError::MicrophoneBusy,
+      "NotSupportedError" => RecorderError::Unsupported,
+      _ => RecorderError::Other(e.message()),
+    }
+  }
+
+  /// Asks for the microphone and starts recording from it.
+  pub async fn start_recording() -> Result<Recorder, RecorderError> {
+    let devices = web_sys::window()
+      .ok_or(RecorderError::Unsupported)?
+      .navigator()
+      .media_devices()
+      // missing outside of secure contexts
+      .map_err(|_| RecorderError::Unsupported)?;
+    let mut constraints = web_sys::MediaStreamConstraints::new();
+    constraints.audio(&JsValue::TRUE);
+
+    let promise = devices
+      .get_user_media_with_constraints(&constraints)
+      .map_err(recorder_error)?;
+    let stream: web_sys::MediaStream =
+      wasm_bindgen_futures::JsFuture::from(promise)
+        .await
+        .map_err(recorder_error)?
+        .unchecked_into();
+
+    let recorder = match web_sys::MediaRecorder::new_with_media_stream(&stream)
+    {
+      Ok(recorder) => recorder,
+      Err(_) => {
+        stop_tracks(&stream);
+        return Err(RecorderError::Unsupported);
+      }
+    };
+
+    let chunks = Rc::new(RefCell::new(Vec::new()));
+    let on_data = {
+      let chunks = chunks.clone();
+      Closure::<dyn FnMut(web_sys::BlobEvent)>::new(
+        move |ev: web_sys::BlobEvent| {
+          if let Some(data) = ev.data().filter(|d| d.size() > 0.0) {
+            chunks.borrow_mut().push(data);
+          }
+        },
+      )
+    };
+    recorder.set_ondataavailable(Some(on_data.as_ref().unchecked_ref()));
+    if let Err(e) = recorder.start() {
+      stop_tracks(&stream);
+      return Err(recorder_error(e));
+    }
+
+    Ok(Recorder {
+      recorder,
+      stream,
+      chunks,
+      started_at: js_sys::Date::now(),
+      _on_data: on_data,
+    })
+  }
+
+  impl Recorder {
+    /// Stops recording and releases the microphone.
+    pub async fn stop(self) -> Result<Recording, RecorderError> {
+      // the last chunk arrives just before the recorder stops
+      let stopped = js_sys::Promise::new(&mut |resolve, _| {
+        let on_stop = Closure::once_into_js(move || {
+          let _ = resolve.call0(&JsValue::NULL);
+        });
+        self.recorder.set_onstop(Some(on_stop.unchecked_ref()));
+      });
+      self.recorder.stop().map_err(recorder_error)?;
+      let duration_ms = (js_sys::Date::now() - self.started_at) as u32;
+      let _ = wasm_bindgen_futures::JsFuture::from(stopped).await;
+      stop_tracks(&self.stream);
+
+      let parts = self.chunks.borrow().iter().collect::<js_sys::Array>();
+      let mut options = web_sys::BlobPropertyBag::new();
+      options.type_(&self.recorder.mime_type());
+      let blob =
+        web_sys::Blob::new_with_blob_sequence_and_options(&parts, &options)
+          .map_err(recorder_error)?;
+      let url = web_sys::Url::create_object_url_with_blob(&blob)
+        .map_err(recorder_error)?;
+
+      Ok(Recording {
+        blob,
+        url,
+        duration_ms,
+      })
+    }
+  }
+
+  fn stop_tracks(stream: &web_sys::MediaStream) {
+    for track in stream.get_tracks().iter() {
+      track.unchecked_into::<web_sys::MediaStreamTrack>().stop();
+    }
+  }
+
+  /// Posts a recording to `url`, calling `on_progress` with the fraction
+  /// sent so far. Returns the response's status and body.
+  ///
+  /// This uses `XMLHttpRequest`, since `fetch` can't report upload
+  /// progress.
+  pub async fn upload_recording(
+    url: &str,
+    recording: &Recording,
+    on_progress: impl Fn(f64) + 'static,
+  ) -> Result<(u16, String), String> {
+    let request =
+      web_sys::XmlHttpRequest::new().map_err(|e| format!("{e:?}"))?;
+    request.open("POST", url).map_err(|e| format!("{e:?}"))?;
+    request
+      .set_request_header("Content-Type", &recording.blob.type_())
+      .map_err(|e| format!("{e:?}"))?;
+    request
+      .set_request_header(
+        core_types::API_REQUESTED_WITH_HEADER,
+        core_types::API_REQUESTED_WITH,
+      )
+      .map_err(|e| format!("{e:?}"))?;
+
+    let on_progress = Closure::<dyn FnMut(web_sys::ProgressEvent)>::new(
+      move |ev: web_sys::ProgressEvent| {
+        if ev.length_computable() && ev.total() > 0.0 {
+          on_progress(ev.loaded() / ev.total());
+        }
+      },
+    );
+    request
+      .upload()
+      .map_err(|e| format!("{e:?}"))?
+      .set_onprogress(Some(on_progress.as_ref().unchecked_ref()));
+
+    let done = js_sys::Promise::new(&mut |resolve, reject| {
+      request.set_onload(Some(
+        Closure::once_into_js(move || {
+          let _ = resolve.call0(&JsValue::NULL);
+        })
+        .unchecked_ref(),
+      ));
+      request.set_onerror(Some(
+        Closure::once_into_js(move || {
+          let _ = reject.call0(&JsValue::NULL);
+        })
+        .unchecked_ref(),
+      ));
+    });
+    request
+      .send_with_opt_blob(Some(&recording.blob))
+      .map_err(|e| format!("{e:?}"))?;
+    wasm_bindgen_futures::JsFuture::from(done)
+      .await
+      .map_err(|_| "The connection was lost.".to_string())?;
+    drop(on_progress);
+
+    let status = request.status().map_err(|e| format!("{e:?}"))?;
+    let body = request
+      .response_text()
+      .map_err(|e| format!("{e:?}"))?
+      .unwrap_or_default();
+    Ok((status, body))
+  }
+}
+
+/// Recording is only possible in the browser.
+#[cfg(not(feature = "hydrate"))]
+pub struct Recorder;
+
+#[cfg(not(feature = "hydrate"))]
+pub async fn start_recording() -> Result<Recorder, RecorderError> {
+  Err(RecorderError::Unsupported)
+}
+
+#[cfg(not(feature = "hydrate"))]
+impl Recorder {
+  pub async fn stop(self) -> Result<Recording, RecorderError> {
+    Err(RecorderError::Unsupported)
+  }
+}
+
+#[cfg(not(feature = "hydrate"))]
+pub async fn upload_recording(
+  _url: &str,
+  _recording: &Recording,
+  _on_progress: impl Fn(f64) + 'static,
+) -> Result<(u16, String), String> {
+  Err("uploads are only available in the browser".to_string())
+}
@@ -0,0 +1,22 @@
+//! The browser's `localStorage`, for remembering things between visits
+//! without an account. Failures are logged and otherwise ignored, since
+//! storage can be disabled or full.
+
+use leptos::*;
+
+fn local_storage() -> Option<web_sys::Storage> {
+  web_sys::window()?.local_storage().ok().flatten()
+}
+
+pub fn get_item(key: &str) -> Option<String> {
+  local_storage()?.get_item(key).ok().flatten()
+}
+
+pub fn set_item(key: &str, value: &str) {
+  let Some(storage) = local_storage() else {
+    return;
+  };
+  if let Err(e) = storage.set_item(key, value) {
+    logging::error!("failed to store {key}: {e:?}");
+  }
+}
@@ -0,0 +1,60 @@
+//! Browser WebAuthn calls for registering and using passkeys.
+
+use webauthn_rs_proto::{
+  CreationChallengeResponse, PublicKeyCredential, RegisterPublicKeyCredential,
+  RequestChallengeResponse,
+};
+
+/// Asks the browser to create a passkey for a registration challenge.
+#[cfg(feature = "hydrate")]
+pub async fn create_passkey(
+  challenge: CreationChallengeResponse,
+) -> Result<RegisterPublicKeyCredential, String> {
+  let options: web_sys::CredentialCreationOptions = challenge.into();
+  let promise = credentials_container()?
+    .create_with_options(&options)
+    .map_err(|e| format!("{e:?}"))?;
+  let credential = wasm_bindgen_futures::JsFuture::from(promise)
+    .await
+    .map_err(|e| format!("{e:?}"))?;
+  Ok(web_sys::PublicKeyCredential::from(credential).into())
+}
+
+/// Asks the browser to sign a login challenge with one of the user's passkeys.
+#[cfg(feature = "hydrate")]
+pub async fn get_passkey(
+  challenge: RequestChallengeResponse,
+) -> Result<PublicKeyCredential, String> {
+  let options: web_sys::CredentialRequestOptions = challenge.into();
+  let promise = credentials_container()?
+    .get_with_options(&options)
+    .map_err(|e| format!("{e:?}"))?;
+  let credential = wasm_bindgen_futures::JsFuture::from(promise)
+    .await
+    .map_err(|e| format!("{e:?}"))?;
+  Ok(web_sys::PublicKeyCredential::from(credential).into())
+}
+
+#[cfg(feature = "hydrate")]
+fn credentials_container() -> Result<web_sys::CredentialsContainer, String> {
+  Ok(
+    web_sys::window()
+      .ok_or("no window available")?
+      .navigator()
+      .credentials(),
+  )
+}
+
+#[cfg(not(feature = "hydrate"))]
+pub async fn create_passkey(
+  _challenge: CreationChallengeResponse,
+) -> Result<RegisterPublicKeyCredential, String> {
+  Err("passkeys are only available in the browser".to_string())
+}
+
+#[cfg(not(feature = "hydrate"))]
+pub async fn get_passkey(
+  _challenge: RequestChallengeResponse,
+) -> Result<PublicKeyCredential, String> {
+  Err("passkeys are only available in the browser".to_string())
+}
@@ -8,6 +8,10 @@ use leptos::*;
 use leptos_meta::*;
 use leptos_router::{Route, Router, Routes};
 
+pub use self::error_template::status_code;
+#[cfg(feature = "ssr")]
+pub use self::functions::auth::require_second_factor;
+
 #[allow(dead_code)]
 #[derive(Clone)]
 pub enum LinkTarget {
@@ -18,6 +22,17 @@ pub enum LinkTarget {
   MotherTongue(core_types::MotherTongueRecordId),
   AllTongues,
   NewTranslation,
+  ProposeTongue,
+  TongueReview,
+  RecordingReview,
+  Translation(core_types::TranslationRecordId),
+  Takes(
+    core_types::TranslationRecordId,
+    core_types::scripture::Passage,
+  ),
+  TranslationTeam(core_types::TranslationRecordId),
+  Listen(core_types::TranslationRecordId),
+  UserProfile(core_types::UserRecordId),
   External(String),
 }
 
@@ -31,6 +46,20 @@ impl LinkTarget {
       LinkTarget::MotherTongue(id) => format!("/tongue/{}", id.0),
       LinkTarget::AllTongues => "/all-tongues".to_owned(),
       LinkTarget::NewTranslation => "/new-translation".to_owned(),
+      LinkTarget::ProposeTongue => "/propose-tongue".to_owned(),
+      LinkTarget::TongueReview => "/review/tongues".to_owned(),
+      LinkTarget::RecordingReview => "/review/recordings".to_owned(),
+      LinkTarget::Translation(id) => format!("/translation/{}", id.0),
+      LinkTarget::Takes(id, passage) => format!(
+        "/translation/{}/takes?passage={}",
+        id.0,
+        passage.to_string().replace(' ', "+")
+      ),
+      LinkTarget::TranslationTeam(id) => {
+        format!("/translation/{}/team", id.0)
+      }
+      LinkTarget::Listen(id) => format!("/translation/{}/listen", id.0),
+      LinkTarget::UserProfile(id) => format!("/user/{}", id.0),
       LinkTarget::External(href) => href.to_owned(),
     }
   }
@@ -52,6 +81,38 @@ impl LinkTarget {
         LinkTarget::Account,
         LinkTarget::NewTranslation,
       ],
+      LinkTarget::ProposeTongue => vec![
+        LinkTarget::Home,
+        LinkTarget::AllTongues,
+        LinkTarget::ProposeTongue,
+      ],
+      LinkTarget::TongueReview => {
+        vec![LinkTarget::Home, LinkTarget::TongueReview]
+      }
+      LinkTarget::RecordingReview => {
+        vec![LinkTarget::Home, LinkTarget::RecordingReview]
+      }
+      LinkTarget::Translation(id) => {
+        vec![LinkTarget::Home, LinkTarget::Translation(*id)]
+      }
+      LinkTarget::Takes(id, passage) => vec![
+        LinkTarget::Home,
+        LinkTarget::Translation(*id),
+        LinkTarget::Takes(*id, passage.clone()),
+      ],
+      LinkTarget::TranslationTeam(id) => vec![
+        LinkTarget::Home,
+        LinkTarget::Translation(*id),
+        LinkTarget::TranslationTeam(*id),
+      ],
+      LinkTarget::Listen(id) => vec![
+        LinkTarget::Home,
+        LinkTarget::Translation(*id),
+        LinkTarget::Listen(*id),
+      ],
+      LinkTarget::UserProfile(id) => {
+        vec![LinkTarget::Home, LinkTarget::UserProfile(*id)]
+      }
       LinkTarget::External(_) => {
         unimplemented!("cannot calculate link chain for eternal link")
       }
@@ -67,6 +128,14 @@ impl LinkTarget {
       LinkTarget::MotherTongue(_) => "Mother Tongue",
       LinkTarget::AllTongues => "All Tongues",
       LinkTarget::NewTranslation => "New Translation",
+      LinkTarget::ProposeTongue => "Propose a Tongue",
+      LinkTarget::TongueReview => "Tongue Review",
+      LinkTarget::RecordingReview => "Recording Review",
+      LinkTarget::Translation(_) => "Translation",
+      LinkTarget::Takes(..) => "Takes",
+      LinkTarget::TranslationTeam(_) => "Team",
+      LinkTarget::Listen(_) => "Listen",
+      LinkTarget::UserProfile(_) => "Contributor",
       LinkTarget::External(_) => {
         unimplemented!("name unknowable for external link")
       }
@@ -97,7 +166,7 @@ pub fn App() -> impl IntoView {
         trailing_slash=leptos_router::TrailingSlash::Redirect
         fallback=|| {
           let mut outside_errors = Errors::default();
-          outside_errors.insert_with_default_key(error_template::AppError::NotFound);
+          outside_errors.insert_with_default_key(core_types::AppError::NotFound);
           view! { <error_template::ErrorTemplate outside_errors/> }.into_view()
         }
       >
@@ -109,6 +178,15 @@ pub fn App() -> impl IntoView {
           <Route path={LinkTarget::Account.href()} view=crate::pages::account::AccountPage />
           <Route path="/tongue/:id" view=crate::pages::mother_tongue::MotherTonguePage />
           <Route path={LinkTarget::NewTranslation.href()} view=crate::pages::new_translation::NewTranslationPage />
+          <Route path={LinkTarget::ProposeTongue.href()} view=crate::pages::propose_tongue::ProposeTonguePage />
+          <Route path={LinkTarget::TongueReview.href()} view=crate::pages::tongue_review::TongueReviewPage />
+          <Route path={LinkTarget::RecordingReview.href()} view=crate::pages::recording_review::RecordingReviewPage />
+          <Route path="/translation/:id" view=crate::pages::translation::TranslationPage />
+          <Route path="/translation/:id/takes" view=crate::pages::takes::TakesPage />
+          <Route path="/translation/:id/team" view=crate::pages::team::TeamPage />
+          <Route path="/translation/:id/listen" view=crate::pages::listen::ListenPage />
+          <Route path="/invitation/:token" view=crate::pages::invitation::InvitationPage />
+          <Route path="/user/:id" view=crate::pages::user_profile::UserProfilePage />
         </Routes>
       </Router>
     </crate::components::PageWrapper>
@@ -1,9 +1,28 @@
+use core_types::{
+  validation::{
+    CODE_FIELD, LIFETIME_FIELD, NAME_FIELD, PASSKEY_FIELD, SCOPES_FIELD,
+  },
+  ApiTokenScope, AppError, API_TOKEN_LIFETIME_DAYS,
+};
 use leptos::*;
-use leptos_router::Redirect;
+use leptos_router::{use_query_map, Redirect};
 
 use crate::{
-  components::{icons::HeroIconsPlus, BreadCrumbs, Link},
-  helpers::get_auth_context,
+  components::{avatar::Avatar, icons::HeroIconsPlus, BreadCrumbs, Link},
+  functions::{
+    api_tokens::{list_api_tokens, CreateApiToken, RevokeApiToken},
+    app_error,
+    oidc::{list_oidc_identities, UnlinkOidcIdentity},
+    passkey::{
+      finish_passkey_registration, list_passkeys, start_passkey_registration,
+      DeletePasskey,
+    },
+    sessions::{list_sessions, RevokeOtherSessions, RevokeSession},
+    two_factor::{BeginTotpEnrollment, ConfirmTotpEnrollment, DisableTotp},
+  },
+  helpers::{
+    get_auth_context, get_oidc_provider_name, webauthn::create_passkey,
+  },
   LinkTarget,
 };
 
@@ -14,6 +33,10 @@ pub fn AccountPage() -> impl IntoView {
   let Some(user) = auth_context.0 else {
     return view! { <Redirect path="/" /> };
   };
+  let oidc_provider = get_oidc_provider_name();
+  let oidc_link_failed = use_query_map().with_untracked(|q| {
+    q.get("oidc_error").is_some_and(|e| e == "link_failed")
+  });
 
   view! {
     <BreadCrumbs target=LinkTarget::Account />
@@ -31,8 +54,658 @@ pub fn AccountPage() -> impl IntoView {
         </Link>
       </div>
       <div class="h-[1px] border-gray-6 border-b"></div>
-      <p>{ format!("{:?}", user) }</p>
+      <div class="flex flex-row gap-4 items-center">
+        <Avatar profile=user.profile() class="avatar-lg" />
+        <div class="flex flex-col">
+          <p class="text-xl font-semibold">{ user.name.clone() }</p>
+          <p class="text-content2">{ user.email.expose().to_string() }</p>
+          <p class="text-content2 text-sm">
+            { format!("Joined {}", user.registered_at.date()) }
+          </p>
+        </div>
+      </div>
+      <p class="text-3xl tracking-tight font-semibold">"Security"</p>
+      <TwoFactorSettings enabled=user.totp_enabled />
+      <p class="text-xl tracking-tight font-semibold">"Passkeys"</p>
+      <PasskeySettings />
+      { oidc_provider.map(|provider| view! {
+        <p class="text-xl tracking-tight font-semibold">"Linked Accounts"</p>
+        <LinkedAccountSettings provider link_failed=oidc_link_failed />
+      }) }
+      <p class="text-xl tracking-tight font-semibold">"Sessions"</p>
+      <SessionSettings />
+      <p class="text-3xl tracking-tight font-semibold">"API Tokens"</p>
+      <ApiTokenSettings />
     </div>
   }
   .into_view()
 }
+
+/// Gets the message to show for a failed two-factor action.
+fn two_factor_error(error: &ServerFnError<AppError>) -> String {
+  match app_error(error) {
+    AppError::Validation(errors) => {
+      errors.get(CODE_FIELD).cloned().unwrap_or_default()
+    }
+    e => e.message(),
+  }
+}
+
+#[island]
+fn TwoFactorSettings(enabled: bool) -> impl IntoView {
+  let (enabled, set_enabled) = create_signal(enabled);
+  let (code, set_code) = create_signal(String::new());
+
+  let begin_action = create_server_action::<BeginTotpEnrollment>();
+  let confirm_action = create_server_action::<ConfirmTotpEnrollment>();
+  let disable_action = create_server_action::<DisableTotp>();
+
+  let enrollment = move || match begin_action.value()() {
+    Some(Ok(enrollment)) => Some(enrollment),
+    _ => None,
+  };
+  let recovery_codes = move || match confirm_action.value()() {
+    Some(Ok(codes)) => Some(codes),
+    _ => None,
+  };
+
+  create_effect(move |_| {
+    if recovery_codes().is_some() {
+      set_enabled(true);
+      set_code(String::new());
+    }
+  });
+  // reload to start over from a clean slate
+  create_effect(move |_| {
+    if matches!(disable_action.value()(), Some(Ok(()))) {
+      crate::helpers::navigation::reload();
+    }
+  });
+
+  let error = move || {
+    [
+      begin_action.value()().and_then(Result::err),
+      confirm_action.value()().and_then(Result::err),
+      disable_action.value()().and_then(Result::err),
+    ]
+    .into_iter()
+    .flatten()
+    .next()
+    .map(|e| two_factor_error(&e))
+  };
+
+  let code_input = move || {
+    view! {
+      <input
+        placeholder="123456" autocomplete="one-time-code"
+        class="input hover:input-primary focus:input-primary transition max-w-xs"
+        on:input=move |ev| set_code(event_target_value(&ev))
+        prop:value=code
+      />
+    }
+  };
+
+  let body = move || {
+    match (enabled(), recovery_codes(), enrollment()) {
+    (true, Some(codes), _) => view! {
+      <p class="text-green-11">"Two-factor authentication is enabled."</p>
+      <p class="text-content2 text-sm max-w-prose">
+        "Save these recovery codes somewhere safe. Each one can be used once \
+         to log in if you lose access to your authenticator app. They won't \
+         be shown again."
+      </p>
+      <ul class="font-mono grid grid-cols-2 gap-2 max-w-xs">
+        { codes.into_iter().map(|c| view! { <li>{c}</li> }).collect_view() }
+      </ul>
+    }
+    .into_view(),
+    (true, None, _) => view! {
+      <p>"Two-factor authentication is enabled."</p>
+      <p class="text-content2 text-sm max-w-prose">
+        "To turn it off, enter a code from your authenticator app or a \
+         recovery code."
+      </p>
+      <div class="flex flex-row gap-4 items-center">
+        { code_input }
+        <button
+          class="btn btn-error"
+          disabled=disable_action.pending()
+          on:click=move |_| disable_action.dispatch(DisableTotp { code: code() })
+        >"Disable"</button>
+      </div>
+    }
+    .into_view(),
+    (false, _, Some(enrollment)) => view! {
+      <p class="text-content2 text-sm max-w-prose">
+        "Scan this QR code with your authenticator app, then enter the code \
+         it shows to finish setting up."
+      </p>
+      <img
+        class="w-48 h-48 bg-white rounded-md"
+        alt="QR code for your authenticator app"
+        src=format!("data:image/png;base64,{}", enrollment.qr_code_png)
+      />
+      <p class="text-content2 text-sm">
+        "Can't scan it? Enter this key instead: "
+        <span class="font-mono">{ enrollment.secret_base32.expose().to_string() }</span>
+      </p>
+      <div class="flex flex-row gap-4 items-center">
+        { code_input }
+        <button
+          class="btn btn-primary"
+          disabled=confirm_action.pending()
+          on:click=move |_| {
+            confirm_action.dispatch(ConfirmTotpEnrollment { code: code() })
+          }
+        >"Confirm"</button>
+      </div>
+    }
+    .into_view(),
+    (false, _, None) => view! {
+      <p class="text-content2 text-sm max-w-prose">
+        "Protect your account by requiring a code from an authenticator app \
+         when you log in."
+      </p>
+      <button
+        class="btn btn-primary self-start"
+        disabled=begin_action.pending()
+        on:click=move |_| begin_action.dispatch(BeginTotpEnrollment {})
+      >"Set up two-factor authentication"</button>
+    }
+    .into_view(),
+  }
+  };
+
+  view! {
+    <div class="flex flex-col gap-4">
+      { body }
+      { move || error().map(|message| view! {
+        <p class="text-red-11 text-sm">{message}</p>
+      }) }
+    </div>
+  }
+}
+
+/// Runs the whole passkey registration ceremony, returning a message to show
+/// on failure.
+async fn register_passkey(name: String) -> Result<(), String> {
+  let passkey_error = |e: &ServerFnError<AppError>| match app_error(e) {
+    AppError::Validation(errors) => errors
+      .get(NAME_FIELD)
+      .or_else(|| errors.get(PASSKEY_FIELD))
+      .cloned()
+      .unwrap_or_default(),
+    e => e.message(),
+  };
+
+  let challenge = start_passkey_registration()
+    .await
+    .map_err(|e| passkey_error(&e))?;
+  let response = create_passkey(challenge).await.map_err(|e| {
+    logging::error!("passkey creation failed: {e}");
+    "Your browser didn't create a passkey.".to_string()
+  })?;
+  finish_passkey_registration(name, response)
+    .await
+    .map(|_| ())
+    .map_err(|e| passkey_error(&e))
+}
+
+#[island]
+fn PasskeySettings() -> impl IntoView {
+  let (name, set_name) = create_signal(String::new());
+
+  let register_action =
+    create_action(move |name: &String| register_passkey(name.clone()));
+  let delete_action = create_server_action::<DeletePasskey>();
+
+  let passkeys = create_resource(
+    move || (register_action.version()(), delete_action.version()()),
+    |_| list_passkeys(),
+  );
+
+  create_effect(move |_| {
+    if matches!(register_action.value()(), Some(Ok(()))) {
+      set_name(String::new());
+    }
+  });
+
+  let error = move || {
+    let register_error = match register_action.value()() {
+      Some(Err(message)) => Some(message),
+      _ => None,
+    };
+    let delete_error = match delete_action.value()() {
+      Some(Err(e)) => Some(app_error(&e).message()),
+      _ => None,
+    };
+    register_error.or(delete_error)
+  };
+
+  let passkey_list = move || {
+    passkeys.get().map(|passkeys| match passkeys {
+      Ok(passkeys) if passkeys.is_empty() => view! {
+        <p class="text-content2 text-sm">"You haven't added any passkeys."</p>
+      }
+      .into_view(),
+      Ok(passkeys) => passkeys
+        .into_iter()
+        .map(|passkey| {
+          let last_used = passkey
+            .last_used_at
+            .map(|t| format!("Last used {}", t.date()))
+            .unwrap_or_else(|| "Never used".to_string());
+          view! {
+            <div class="flex flex-row gap-4 items-center">
+              <p class="font-semibold">{ passkey.name }</p>
+              <p class="text-content2 text-sm">
+                { format!("Added {}", passkey.created_at.date()) }
+              </p>
+              <p class="text-content2 text-sm">{ last_used }</p>
+              <div class="flex-1" />
+              <button
+                class="btn btn-outline-error btn-sm"
+                disabled=delete_action.pending()
+                on:click=move |_| {
+                  delete_action.dispatch(DeletePasskey { id: passkey.id })
+                }
+              >"Remove"</button>
+            </div>
+          }
+        })
+        .collect_view(),
+      Err(e) => view! {
+        <p class="text-red-11 text-sm">{ app_error(&e).message() }</p>
+      }
+      .into_view(),
+    })
+  };
+
+  view! {
+    <div class="flex flex-col gap-4 max-w-prose">
+      <p class="text-content2 text-sm">
+        "Passkeys let you log in with your device's fingerprint reader, face \
+         recognition, or screen lock instead of your password."
+      </p>
+      <Transition fallback=move || view! { <p>"Loading..."</p> }>
+        { passkey_list }
+      </Transition>
+      <div class="flex flex-row gap-4 items-center">
+        <input
+          placeholder="Passkey name, e.g. \"Laptop\""
+          class="input hover:input-primary focus:input-primary transition max-w-xs"
+          on:input=move |ev| set_name(event_target_value(&ev))
+          prop:value=name
+        />
+        <button
+          class="btn btn-primary"
+          disabled=register_action.pending()
+          on:click=move |_| register_action.dispatch(name())
+        >"Add passkey"</button>
+      </div>
+      { move || error().map(|message| view! {
+        <p class="text-red-11 text-sm">{message}</p>
+      }) }
+    </div>
+  }
+}
+
+#[island]
+fn SessionSettings() -> impl IntoView {
+  let revoke_action = create_server_action::<RevokeSession>();
+  let revoke_others_action = create_server_action::<RevokeOtherSessions>();
+
+  let sessions = create_resource(
+    move || (revoke_action.version()(), revoke_others_action.version()()),
+    |_| list_sessions(),
+  );
+
+  let error = move || {
+    [revoke_action.value()(), revoke_others_action.value()()]
+      .into_iter()
+      .find_map(|v| match v {
+        Some(Err(e)) => Some(app_error(&e).message()),
+        _ => None,
+      })
+  };
+
+  let session_list = move || {
+    sessions.get().map(|sessions| match sessions {
+      Ok(sessions) => sessions
+        .into_iter()
+        .map(|session| {
+          let details = [
+            session.ip.clone(),
+            Some(format!("Signed in {}", session.created_at.date())),
+            Some(format!("Last active {}", session.last_seen_at.date())),
+          ]
+          .into_iter()
+          .flatten()
+          .collect::<Vec<_>>()
+          .join(" · ");
+          view! {
+            <div class="flex flex-row gap-4 items-center">
+              <div class="flex flex-col">
+                <p class="font-semibold">{ session.device }</p>
+                <p class="text-content2 text-sm">{ details }</p>
+              </div>
+              <div class="flex-1" />
+              { match session.current {
+                true => view! {
+                  <span class="badge badge-outline-primary">"This device"</span>
+                }
+                .into_view(),
+                false => view! {
+                  <button
+                    class="btn btn-outline-error btn-sm"
+                    disabled=revoke_action.pending()
+                    on:click=move |_| {
+                      revoke_action.dispatch(RevokeSession { id: session.id })
+                    }
+                  >"Log out"</button>
+                }
+                .into_view(),
+              } }
+            </div>
+          }
+        })
+        .collect_view(),
+      Err(e) => view! {
+        <p class="text-red-11 text-sm">{ app_error(&e).message() }</p>
+      }
+      .into_view(),
+    })
+  };
+
+  view! {
+    <div class="flex flex-col gap-4 max-w-prose">
+      <p class="text-content2 text-sm">
+        "These devices are logged in to your account. Log out any you don't \
+         recognize."
+      </p>
+      <Transition fallback=move || view! { <p>"Loading..."</p> }>
+        { session_list }
+      </Transition>
+      <button
+        class="btn btn-outline-error self-start"
+        disabled=revoke_others_action.pending()
+        on:click=move |_| revoke_others_action.dispatch(RevokeOtherSessions {})
+      >"Log out all other sessions"</button>
+      { move || error().map(|message| view! {
+        <p class="text-red-11 text-sm">{message}</p>
+      }) }
+    </div>
+  }
+}
+
+#[island]
+fn ApiTokenSettings() -> impl IntoView {
+  let (name, set_name) = create_signal(String::new());
+  let (scopes, set_scopes) = create_signal(vec![ApiTokenScope::Upload]);
+  let (lifetime_days, set_lifetime_days) = create_signal(90_u32);
+
+  let create_action = create_server_action::<CreateApiToken>();
+  let revoke_action = create_server_action::<RevokeApiToken>();
+
+  let tokens = create_resource(
+    move || (create_action.version()(), revoke_action.version()()),
+    |_| list_api_tokens(),
+  );
+
+  let new_token = move || match create_action.value()() {
+    Some(Ok(new_token)) => Some(new_token),
+    _ => None,
+  };
+  create_effect(move |_| {
+    if new_token().is_some() {
+      set_name(String::new());
+    }
+  });
+
+  let field_error = move |field: &'static str| match create_action.value()() {
+    Some(Err(e)) => match app_error(&e) {
+      AppError::Validation(errors) => errors.get(field).cloned(),
+      _ => None,
+    },
+    _ => None,
+  };
+  let error = move || {
+    let create_error = match create_action.value()() {
+      Some(Err(e)) => match app_error(&e) {
+        AppError::Validation(_) => None,
+        e => Some(e.message()),
+      },
+      _ => None,
+    };
+    let revoke_error = match revoke_action.value()() {
+      Some(Err(e)) => Some(app_error(&e).message()),
+      _ => None,
+    };
+    create_error.or(revoke_error)
+  };
+
+  let toggle_scope = move |scope: ApiTokenScope, checked: bool| {
+    set_scopes.update(|scopes| {
+      scopes.retain(|s| *s != scope);
+      if checked {
+        scopes.push(scope);
+      }
+    })
+  };
+
+  let token_list = move || {
+    tokens.get().map(|tokens| match tokens {
+      Ok(tokens) if tokens.is_empty() => view! {
+        <p class="text-content2 text-sm">"You haven't created any tokens."</p>
+      }
+      .into_view(),
+      Ok(tokens) => tokens
+        .into_iter()
+        .map(|token| {
+          let expiry = match token.expired {
+            true => format!("Expired {}", token.expires_at.date()),
+            false => format!("Expires {}", token.expires_at.date()),
+          };
+          let scopes = token
+            .scopes
+            .iter()
+            .map(|s| s.label())
+            .collect::<Vec<_>>()
+            .join(", ");
+          view! {
+            <div class="flex flex-row gap-4 items-center">
+              <div class="flex flex-col">
+                <p class="font-semibold">{ token.name }</p>
+                <p class="text-content2 text-sm">{ scopes }</p>
+                <p class="text-content2 text-sm">{ expiry }</p>
+              </div>
+              <div class="flex-1" />
+              <button
+                class="btn btn-outline-error btn-sm"
+                disabled=revoke_action.pending()
+                on:click=move |_| {
+                  revoke_action.dispatch(RevokeApiToken { id: token.id })
+                }
+              >"Revoke"</button>
+            </div>
+          }
+        })
+        .collect_view(),
+      Err(e) => view! {
+        <p class="text-red-11 text-sm">{ app_error(&e).message() }</p>
+      }
+      .into_view(),
+    })
+  };
+
+  view! {
+    <div class="flex flex-col gap-4 max-w-prose">
+      <p class="text-content2 text-sm">
+        "Tokens let scripts use the API as you. Send one in an \
+         \"Authorization: Bearer\" header."
+      </p>
+      <Transition fallback=move || view! { <p>"Loading..."</p> }>
+        { token_list }
+      </Transition>
+
+      { move || new_token().map(|new_token| view! {
+        <div class="flex flex-col gap-2">
+          <p class="text-green-11">
+            { format!("Created \"{}\". Copy it now, it won't be shown again:", new_token.summary.name) }
+          </p>
+          <p class="font-mono break-all">{ new_token.token.expose().to_string() }</p>
+        </div>
+      }) }
+
+      <div class="form-group gap-4">
+        <div class="form-field">
+          <label class="form-label">"Token name"</label>
+          <input
+            placeholder="e.g. \"Field recorder sync\""
+            class="input hover:input-primary focus:input-primary transition max-w-xs"
+            on:input=move |ev| set_name(event_target_value(&ev))
+            prop:value=name
+          />
+          { move || field_error(NAME_FIELD).map(|message| view! {
+            <span class="form-label-alt text-red-11">{message}</span>
+          }) }
+        </div>
+        <div class="form-field">
+          <label class="form-label">"Permissions"</label>
+          { ApiTokenScope::ALL.into_iter().map(|scope| view! {
+            <div class="flex gap-2">
+              <input
+                type="checkbox" class="checkbox"
+                on:input=move |ev| toggle_scope(scope, event_target_checked(&ev))
+                prop:checked=move || scopes.with(|s| s.contains(&scope))
+              />
+              <p>{ scope.label() }</p>
+            </div>
+          }).collect_view() }
+          { move || field_error(SCOPES_FIELD).map(|message| view! {
+            <span class="form-label-alt text-red-11">{message}</span>
+          }) }
+        </div>
+        <div class="form-field">
+          <label class="form-label">"Expires after"</label>
+          <select
+            class="select max-w-xs"
+            on:change=move |ev| {
+              if let Ok(days) = event_target_value(&ev).parse() {
+                set_lifetime_days(days);
+              }
+            }
+          >
+            { API_TOKEN_LIFETIME_DAYS.into_iter().map(|days| view! {
+              <option value=days.to_string() selected=move || lifetime_days() == days>
+                { format!("{days} days") }
+              </option>
+            }).collect_view() }
+          </select>
+          { move || field_error(LIFETIME_FIELD).map(|message| view! {
+            <span class="form-label-alt text-red-11">{message}</span>
+          }) }
+        </div>
+        <button
+          class="btn btn-primary self-start"
+          disabled=create_action.pending()
+          on:click=move |_| create_action.dispatch(CreateApiToken {
+            name: name(),
+            scopes: scopes(),
+            lifetime_days: lifetime_days(),
+          })
+        >"Create token"</button>
+      </div>
+
+      { move || error().map(|message| view! {
+        <p class="text-red-11 text-sm">{message}</p>
+      }) }
+    </div>
+  }
+}
+
+#[island]
+fn LinkedAccountSettings(provider: String, link_failed: bool) -> impl IntoView {
+  let unlink_action = create_server_action::<UnlinkOidcIdentity>();
+  let identities = create_resource(
+    move || unlink_action.version()(),
+    |_| list_oidc_identities(),
+  );
+
+  let error = move || match unlink_action.value()() {
+    Some(Err(e)) => Some(app_error(&e).message()),
+    _ if link_failed => Some(
+      "Linking failed. That account may already be linked to another user."
+        .to_string(),
+    ),
+    _ => None,
+  };
+
+  let identity_list = {
+    let provider = provider.clone();
+    move || {
+      let provider = provider.clone();
+      identities.get().map(move |identities| match identities {
+        Ok(identities) if identities.is_empty() => view! {
+          <p class="text-content2 text-sm">
+            { format!("You haven't linked a {provider} account.") }
+          </p>
+        }
+        .into_view(),
+        Ok(identities) => identities
+          .into_iter()
+          .map(|identity| {
+            let label = identity
+              .email
+              .as_ref()
+              .map(|e| e.expose().to_string())
+              .unwrap_or_else(|| format!("{provider} account"));
+            view! {
+              <div class="flex flex-row gap-4 items-center">
+                <div class="flex flex-col">
+                  <p class="font-semibold">{ label }</p>
+                  <p class="text-content2 text-sm">
+                    { format!("Linked {}", identity.linked_at.date()) }
+                  </p>
+                </div>
+                <div class="flex-1" />
+                <button
+                  class="btn btn-outline-error btn-sm"
+                  disabled=move || {
+                    identity.created_account || unlink_action.pending()()
+                  }
+                  on:click=move |_| {
+                    unlink_action
+                      .dispatch(UnlinkOidcIdentity { id: identity.id })
+                  }
+                >"Unlink"</button>
+              </div>
+            }
+          })
+          .collect_view(),
+        Err(e) => view! {
+          <p class="text-red-11 text-sm">{ app_error(&e).message() }</p>
+        }
+        .into_view(),
+      })
+    }
+  };
+
+  view! {
+    <div class="flex flex-col gap-4 max-w-prose">
+      <p class="text-content2 text-sm">
+        { format!("Link your {provider} account to log in with it.") }
+      </p>
+      <Transition fallback=move || view! { <p>"Loading..."</p> }>
+        { identity_list.clone() }
+      </Transition>
+      <a
+        href="/auth/oidc/start?link=true" rel="external"
+        class="btn btn-primary self-start"
+      >{ format!("Link {provider}") }</a>
+      { move || error().map(|message| view! {
+        <p class="text-red-11 text-sm">{message}</p>
+      }) }
+    </div>
+  }
+}
@@ -1,17 +1,45 @@
+use core_types::TongueSearch;
 use leptos::*;
+use leptos_router::use_query_map;
 
-use crate::{components::BreadCrumbs, LinkTarget};
+use crate::{
+  components::{
+    icons::HeroIconsPlus,
+    mother_tongues_table::{MotherTonguesTable, PAGE_KEY},
+    BreadCrumbs, Link,
+  },
+  helpers::get_auth_context,
+  LinkTarget,
+};
 
 #[component]
 pub fn AllTonguesPage() -> impl IntoView {
+  let logged_in = get_auth_context().0.is_some();
+  let (search, page) = use_query_map().with_untracked(|query| {
+    let search = TongueSearch::from_query(|key| query.get(key).cloned());
+    let page = query.get(PAGE_KEY).and_then(|p| p.parse().ok());
+    (search, page.unwrap_or(0))
+  });
+
   view! {
     <BreadCrumbs target=LinkTarget::AllTongues />
     <div class="flex flex-col p-8 gap-4">
       <p class="text-5xl tracking-tight font-semibold">
         "All Mother Tongues"
       </p>
-      <p class="text-3xl tracking-tight font-semibold">"Mother Tongues"</p>
-      <crate::components::mother_tongues_table::MotherTonguesTable />
+      <div class="flex flex-row gap-4 items-center">
+        <p class="text-3xl tracking-tight font-semibold">"Mother Tongues"</p>
+        <div class="flex-1" />
+        { logged_in.then(|| view! {
+          <Link target=LinkTarget::ProposeTongue
+            class="btn btn-primary flex flex-row gap-2 items-center"
+          >
+            <HeroIconsPlus />
+            { LinkTarget::ProposeTongue.name() }
+          </Link>
+        }) }
+      </div>
+      <MotherTonguesTable initial=search initial_page=page />
     </div>
   }
 }
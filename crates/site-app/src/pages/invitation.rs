@@ -0,0 +1,121 @@
+use core_types::{InvitationSummary, TranslationRecordId};
+use leptos::*;
+use leptos_router::use_params_map;
+
+use crate::{
+  components::{invitation_card::InvitationCard, BreadCrumbs, Link},
+  functions::{
+    app_error,
+    members::{fetch_invitation, RespondToInvitation},
+  },
+  helpers::get_auth_context,
+  LinkTarget,
+};
+
+/// Opens an invitation link.
+#[component]
+pub fn InvitationPage() -> impl IntoView {
+  let params = use_params_map();
+  let token =
+    move || with!(|params| params.get("token").cloned().unwrap_or_default());
+  let invitation = create_resource(token, fetch_invitation);
+  let logged_in = get_auth_context().0.is_some();
+
+  view! {
+    <BreadCrumbs target=LinkTarget::Home />
+    <div class="flex flex-col p-8 gap-4">
+      <p class="text-5xl tracking-tight font-semibold">"Invitation"</p>
+      <div class="h-[1px] border-gray-6 border-b"></div>
+      <Suspense fallback={move || view! { <p>"Loading..."</p> }}>
+        { move || invitation().map(|result| match result {
+          Ok(Some(invitation)) => {
+            let secret = core_types::parse_invitation_token(&token())
+              .map(|(_, secret)| secret.to_string());
+            view! {
+              <InvitationResponse invitation secret logged_in />
+            }
+            .into_view()
+          }
+          Ok(None) => view! {
+            <p class="text-content2 max-w-prose">
+              "This invitation has expired, been revoked, or already been \
+               answered. Ask the translation's managers for a new one."
+            </p>
+          }
+          .into_view(),
+          Err(e) => view! {
+            <p class="text-red-11 text-sm">{ app_error(&e).message() }</p>
+          }
+          .into_view(),
+        }) }
+      </Suspense>
+    </div>
+  }
+}
+
+#[island]
+fn InvitationResponse(
+  invitation: InvitationSummary,
+  secret: Option<String>,
+  logged_in: bool,
+) -> impl IntoView {
+  let translation = invitation.translation;
+  let respond_action = create_server_action::<RespondToInvitation>();
+
+  // the action forgets its input once it's done, so remember the answer
+  let (accepted, set_accepted) = create_signal(false);
+  create_effect(move |_| {
+    if let Some(input) = respond_action.input()() {
+      set_accepted(input.accept);
+    }
+  });
+  let answered = move || matches!(respond_action.value()(), Some(Ok(())));
+
+  view! {
+    <div class="flex flex-col gap-4 max-w-prose">
+      { move || match (logged_in, answered()) {
+        (false, _) => view! {
+          <p class="text-content2 text-sm">
+            "You've been invited to join a translation's team. "
+            <Link target=LinkTarget::Login class="underline hover:no-underline">
+              "Log in"
+            </Link>
+            " or "
+            <Link target=LinkTarget::Signup class="underline hover:no-underline">
+              "sign up"
+            </Link>
+            ", then open this link again to answer."
+          </p>
+        }
+        .into_view(),
+        (true, false) => view! {
+          <InvitationCard
+            invitation=invitation.clone() secret=secret.clone()
+            respond=respond_action
+          />
+        }
+        .into_view(),
+        (true, true) => answered_view(translation, accepted()),
+      } }
+      { move || respond_action.value()().and_then(Result::err).map(|e| view! {
+        <p class="text-red-11 text-sm">{ app_error(&e).message() }</p>
+      }) }
+    </div>
+  }
+}
+
+fn answered_view(translation: TranslationRecordId, accepted: bool) -> View {
+  match accepted {
+    true => view! {
+      <p class="text-green-11">"You've joined the team."</p>
+      <Link target=LinkTarget::Translation(translation) class="btn btn-primary self-start">
+        "Go to the translation"
+      </Link>
+    }
+    .into_view(),
+    false => view! {
+      <p class="text-content2">"You've declined the invitation."</p>
+    }
+    .into_view(),
+  }
+}
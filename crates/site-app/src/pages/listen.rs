@@ -0,0 +1,332 @@
+use core_types::{Playlist, TranslationRecordId};
+use leptos::*;
+use leptos_router::use_params_map;
+
+use crate::{
+  components::{
+    mini_pages::{BadLinkError, MissingResourceError},
+    BreadCrumbs,
+  },
+  functions::{app_error, listening::fetch_playlist},
+  helpers::storage,
+  LinkTarget,
+};
+
+/// The playback speeds listeners can pick from.
+const SPEEDS: [f64; 6] = [0.75, 1.0, 1.25, 1.5, 1.75, 2.0];
+/// Where the chosen speed is remembered. It's shared by every translation.
+const SPEED_KEY: &str = "listen:speed";
+
+#[component]
+pub fn ListenPage() -> impl IntoView {
+  let params = use_params_map();
+
+  let id = move || {
+    with!(|params| {
+      params.get("id").cloned().and_then(|s| {
+        s.parse::<core_types::Ulid>().map(TranslationRecordId).ok()
+      })
+    })
+  };
+
+  view! {
+    { move || match id() {
+      Some(id) => view! { <PlaylistFetcher id /> }.into_view(),
+      None => view! { <BadLinkError /> }.into_view(),
+    }}
+  }
+}
+
+#[component]
+fn PlaylistFetcher(id: TranslationRecordId) -> impl IntoView {
+  let playlist = create_resource(move || id, fetch_playlist);
+
+  view! {
+    <BreadCrumbs target=LinkTarget::Listen(id) />
+    <Suspense fallback={move || view! { <p>"Loading..."</p> }}>
+      { move || playlist().map(|result| match result {
+        Ok(Some(playlist)) => view! {
+          <div class="flex flex-col p-8 gap-4 pb-40">
+            <div class="flex flex-row flex-wrap gap-4 items-center">
+              <p class="text-5xl tracking-tight font-semibold">
+                { playlist.translation_name.clone() }
+              </p>
+              <div class="flex-1" />
+              { (!playlist.chapters.is_empty()).then(|| view! {
+                <a
+                  class="btn btn-outline btn-sm"
+                  href=core_types::translation_feed_url(id)
+                  rel="external"
+                >"Podcast feed"</a>
+              }) }
+            </div>
+            <div class="h-[1px] border-gray-6 border-b"></div>
+            { match playlist.chapters.is_empty() {
+              true => view! {
+                <p class="text-content2">
+                  "Nothing is ready to listen to yet. Recordings appear here \
+                   once they've been approved."
+                </p>
+              }
+              .into_view(),
+              false => view! {
+                <ListeningPlayer translation=id playlist />
+              }
+              .into_view(),
+            } }
+          </div>
+        }
+        .into_view(),
+        Ok(None) => view! { <MissingResourceError /> }.into_view(),
+        Err(e) => view! {
+          <p class="text-red-11 text-sm">{ app_error(&e).message() }</p>
+        }
+        .into_view(),
+      }) }
+    </Suspense>
+  }
+}
+
+/// Where a listener was: a chapter's key, the track in it, and the second
+/// they'd reached.
+fn parse_position(saved: &str) -> Option<(String, usize, f64)> {
+  let mut parts = saved.splitn(3, '|');
+  let chapter = parts.next()?.to_string();
+  let track = parts.next()?.parse().ok()?;
+  let seconds = parts.next()?.parse().ok()?;
+  Some((chapter, track, seconds))
+}
+
+/// Plays a translation chapter by chapter, carrying on through each book.
+///
+/// The player stays docked to the bottom of the window while the chapter
+/// list scrolls. Where the listener was and how fast they listen are kept
+/// in the browser, so they can pick up where they left off.
+#[island]
+fn ListeningPlayer(
+  translation: TranslationRecordId,
+  playlist: Playlist,
+) -> impl IntoView {
+  let position_key = format!("listen:{}", translation.0);
+  let playlist = store_value(playlist);
+
+  let (chapter, set_chapter) = create_signal(0_usize);
+  let (track, set_track) = create_signal(0_usize);
+  let (speed, set_speed) = create_signal(1.0_f64);
+  let (finished, set_finished) = create_signal(None::<String>);
+  // applied once the next recording has loaded
+  let resume_at = store_value(None::<f64>);
+  let autoplay = store_value(false);
+  let saved_second = store_value(None::<u32>);
+
+  let audio = create_node_ref::<html::Audio>();
+
+  let current = move || {
+    playlist.with_value(|p| {
+      let chapter = p.chapters.get(chapter())?;
+      Some((chapter.clone(), chapter.tracks.get(track())?.clone()))
+    })
+  };
+  let src = move || {
+    current().map(|(_, track)| core_types::artifact_media_url(track.id))
+  };
+
+  let save_position = {
+    let position_key = position_key.clone();
+    move |seconds: f64| {
+      let Some(key) = playlist.with_value(|p| {
+        p.chapters.get(chapter.get_untracked()).map(|c| c.key())
+      }) else {
+        return;
+      };
+      storage::set_item(
+        &position_key,
+        &format!("{key}|{}|{seconds:.1}", track.get_untracked()),
+      );
+    }
+  };
+  let save_position = store_value(save_position);
+
+  // pick up where the listener left off
+  create_effect(move |ran: Option<()>| {
+    if ran.is_some() {
+      return;
+    }
+    if let Some(saved) = storage::get_item(SPEED_KEY)
+      .and_then(|s| s.parse::<f64>().ok())
+      .filter(|s| SPEEDS.contains(s))
+    {
+      set_speed(saved);
+    }
+    let saved = storage::get_item(&position_key)
+      .as_deref()
+      .and_then(parse_position);
+    if let Some((key, saved_track, seconds)) = saved {
+      let index = playlist.with_value(|p| {
+        let index = p.find(&key)?;
+        (saved_track < p.chapters[index].tracks.len()).then_some(index)
+      });
+      if let Some(index) = index {
+        set_chapter(index);
+        set_track(saved_track);
+        resume_at.set_value(Some(seconds));
+      }
+    }
+  });
+
+  let play_chapter = move |index: usize| {
+    set_finished(None);
+    set_chapter(index);
+    set_track(0);
+    resume_at.set_value(None);
+    autoplay.set_value(true);
+    save_position.with_value(|save| save(0.0));
+  };
+
+  let advance = move || {
+    let (chapter_now, track_now) =
+      (chapter.get_untracked(), track.get_untracked());
+    let (track_count, next_chapter, book_name) = playlist.with_value(|p| {
+      (
+        p.chapters[chapter_now].tracks.len(),
+        p.next_in_book(chapter_now),
+        p.chapters[chapter_now].book_name.clone(),
+      )
+    });
+
+    if track_now + 1 < track_count {
+      set_track(track_now + 1);
+      autoplay.set_value(true);
+      save_position.with_value(|save| save(0.0));
+    } else if let Some(next) = next_chapter {
+      play_chapter(next);
+    } else {
+      set_finished(Some(book_name));
+    }
+  };
+
+  let on_loaded = move |_| {
+    let Some(audio) = audio.get_untracked() else {
+      return;
+    };
+    // loading a new recording resets the rate to the default
+    audio.set_default_playback_rate(speed.get_untracked());
+    audio.set_playback_rate(speed.get_untracked());
+    if let Some(seconds) = resume_at.try_update_value(Option::take).flatten() {
+      audio.set_current_time(seconds);
+    }
+    if autoplay.try_update_value(std::mem::take).unwrap_or(false) {
+      // browsers may refuse to play before the listener has interacted
+      let _ = audio.play();
+    }
+  };
+
+  let on_time = move |_| {
+    let Some(audio) = audio.get_untracked() else {
+      return;
+    };
+    let seconds = audio.current_time();
+    // timeupdate fires several times a second, so only store whole seconds
+    let second = seconds as u32;
+    if saved_second.get_value() != Some(second) {
+      saved_second.set_value(Some(second));
+      save_position.with_value(|save| save(seconds));
+    }
+  };
+
+  let change_speed = move |value: f64| {
+    set_speed(value);
+    storage::set_item(SPEED_KEY, &value.to_string());
+    if let Some(audio) = audio.get_untracked() {
+      audio.set_default_playback_rate(value);
+      audio.set_playback_rate(value);
+    }
+  };
+
+  let books = playlist.with_value(|p| {
+    let mut books: Vec<(String, Vec<(usize, u16)>)> = Vec::new();
+    for (i, c) in p.chapters.iter().enumerate() {
+      match books.last_mut() {
+        Some((name, chapters)) if *name == c.book_name => {
+          chapters.push((i, c.chapter))
+        }
+        _ => books.push((c.book_name.clone(), vec![(i, c.chapter)])),
+      }
+    }
+    books
+  });
+
+  view! {
+    <div class="flex flex-col gap-4">
+      { books.into_iter().map(|(name, chapters)| view! {
+        <div class="flex flex-col gap-2">
+          <p class="text-xl tracking-tight font-semibold">{ name }</p>
+          <div class="flex flex-row flex-wrap gap-2">
+            { chapters.into_iter().map(|(index, number)| view! {
+              <button
+                class=move || match chapter() == index {
+                  true => "btn btn-primary btn-sm",
+                  false => "btn btn-outline btn-sm",
+                }
+                on:click=move |_| play_chapter(index)
+              >{ number }</button>
+            }).collect_view() }
+          </div>
+        </div>
+      }).collect_view() }
+    </div>
+
+    <div class="fixed bottom-0 inset-x-0 z-10 bg-gray-1 border-gray-6 border-t">
+      <div class="container mx-auto flex flex-col gap-2 p-4">
+        <div class="flex flex-row flex-wrap gap-4 items-center">
+          <p class="font-semibold">
+            { move || current().map(|(chapter, track)| match chapter.tracks.len() {
+              1 => chapter.title(),
+              _ => format!("{} ({})", chapter.title(), track.passage),
+            }) }
+          </p>
+          <div class="flex-1" />
+          <button
+            class="btn btn-outline btn-sm"
+            disabled=move || chapter() == 0
+            on:click=move |_| play_chapter(chapter.get_untracked() - 1)
+          >"Previous chapter"</button>
+          <button
+            class="btn btn-outline btn-sm"
+            disabled=move || {
+              chapter() + 1 >= playlist.with_value(|p| p.chapters.len())
+            }
+            on:click=move |_| play_chapter(chapter.get_untracked() + 1)
+          >"Next chapter"</button>
+          <select
+            class="select select-sm w-24"
+            on:change=move |ev| {
+              if let Ok(value) = event_target_value(&ev).parse() {
+                change_speed(value);
+              }
+            }
+          >
+            { SPEEDS.into_iter().map(|s| view! {
+              <option value=s.to_string() selected=move || speed() == s>
+                { format!("{s}x") }
+              </option>
+            }).collect_view() }
+          </select>
+        </div>
+        <audio
+          class="w-full" controls preload="metadata"
+          node_ref=audio
+          src=src
+          on:loadedmetadata=on_loaded
+          on:timeupdate=on_time
+          on:ended=move |_| advance()
+        />
+        { move || finished().map(|book| view! {
+          <p class="text-content2 text-sm">
+            { format!("That's the end of {book}. Pick a chapter to keep listening.") }
+          </p>
+        }) }
+      </div>
+    </div>
+  }
+}
@@ -1,4 +1,11 @@
+use core_types::{
+  validation::{
+    validate_email, ValidationErrors, CODE_FIELD, EMAIL_FIELD, PASSWORD_FIELD,
+  },
+  AppError, OidcLoginError,
+};
 use leptos::*;
+use leptos_router::use_query_map;
 
 #[derive(Clone, PartialEq)]
 pub enum DispatchState {
@@ -6,39 +13,68 @@ pub enum DispatchState {
   Unsubmitted,
   Pending,
   Success,
+  SecondFactorRequired,
   BadCredentials,
-  InternalError,
+  Failed(AppError),
 }
 
 use crate::{
   components::{BreadCrumbs, *},
-  functions::auth::{Login, LoginParams},
-  helpers::navigation::navigate_to,
+  functions::{
+    app_error,
+    auth::{Login, LoginOutcome, LoginParams, LoginSecondFactor},
+    passkey::{finish_passkey_login, start_passkey_login},
+  },
+  helpers::{
+    get_oidc_provider_name, navigation::navigate_to, webauthn::get_passkey,
+  },
   LinkTarget,
 };
 
-#[island]
+#[component]
 pub fn LoginPage() -> impl IntoView {
+  let oidc_provider = get_oidc_provider_name();
+  let oidc_error = use_query_map().with_untracked(|q| {
+    q.get("oidc_error")
+      .and_then(|e| OidcLoginError::from_query(e))
+      .zip(oidc_provider.as_ref())
+      .map(|(e, provider)| e.message(provider))
+  });
+  // set when an OIDC login is waiting on the user's second factor
+  let second_factor = use_query_map()
+    .with_untracked(|q| q.get("second_factor").is_some_and(|v| v == "1"));
+
+  view! { <LoginForm oidc_provider oidc_error second_factor /> }
+}
+
+#[island]
+fn LoginForm(
+  oidc_provider: Option<String>,
+  oidc_error: Option<String>,
+  second_factor: bool,
+) -> impl IntoView {
   let (email, set_email) = create_signal::<Option<String>>(None);
   let (password, set_password) = create_signal::<Option<String>>(None);
   let (remember, set_remember) = create_signal(true);
 
   let email_validated = create_memo(move |_| match email() {
     None => None,
-    Some(email) => crate::helpers::validate_email(email),
+    Some(email) => validate_email(&email),
   });
 
   let password_validated = create_memo(move |_| match password() {
     None => None,
-    Some(password) => crate::helpers::validate_password(password),
+    Some(password) => password
+      .is_empty()
+      .then(|| "A password is required.".to_string()),
   });
 
   let params = create_memo(move |_| match (email(), password()) {
     (Some(email), Some(password)) => {
-      if email_validated().is_none() {
+      if email_validated().is_none() && password_validated().is_none() {
         Some(LoginParams {
-          email,
-          password,
+          email:    email.into(),
+          password: password.into(),
           remember: remember(),
         })
       } else {
@@ -68,19 +104,58 @@ pub fn LoginPage() -> impl IntoView {
     }
   };
 
+  // field-level errors from the server, shown until the next submission
+  let server_errors = create_memo(move |_| match value() {
+    Some(Err(e)) => match app_error(&e) {
+      AppError::Validation(errors) => errors,
+      _ => ValidationErrors::new(),
+    },
+    _ => ValidationErrors::new(),
+  });
+  let field_error = move |field: &'static str| {
+    server_errors.with(|errors| errors.get(field).cloned())
+  };
+
   let dispatch_state =
     create_memo(move |_| match (params(), pending(), value()) {
       (None, _, _) => DispatchState::InsufficientInformation,
       (Some(_), true, _) => DispatchState::Pending,
       (Some(_), false, None) => DispatchState::Unsubmitted,
-      (Some(_), false, Some(Ok(true))) => DispatchState::Success,
-      (Some(_), false, Some(Ok(false))) => DispatchState::BadCredentials,
-      (Some(_), false, Some(Err(_))) => DispatchState::InternalError,
+      (Some(_), false, Some(Ok(LoginOutcome::LoggedIn))) => {
+        DispatchState::Success
+      }
+      (Some(_), false, Some(Ok(LoginOutcome::SecondFactorRequired))) => {
+        DispatchState::SecondFactorRequired
+      }
+      (Some(_), false, Some(Err(e))) => match app_error(&e) {
+        AppError::Unauthorized => DispatchState::BadCredentials,
+        // these are shown next to their fields instead
+        AppError::Validation(_) => DispatchState::Unsubmitted,
+        e => DispatchState::Failed(e),
+      },
     });
 
+  let passkey_action = create_action(move |params: &(String, bool)| {
+    let (email, remember) = params.clone();
+    passkey_login(email, remember)
+  });
+  let passkey_value = passkey_action.value();
+  let passkey_pending = passkey_action.pending();
+
+  let dispatch_passkey = move |_| {
+    let email = email().unwrap_or_default();
+    if validate_email(&email).is_some() {
+      set_email(Some(email));
+      return;
+    }
+    passkey_action.dispatch((email, remember()));
+  };
+
   // redirect effect
   create_effect(move |_| {
-    if dispatch_state() == DispatchState::Success {
+    if dispatch_state() == DispatchState::Success
+      || matches!(passkey_value(), Some(Ok(())))
+    {
       navigate_to("/account");
     }
   });
@@ -91,12 +166,16 @@ pub fn LoginPage() -> impl IntoView {
       DispatchState::Unsubmitted => "btn-primary",
       DispatchState::Pending => "btn-outline btn-loading",
       DispatchState::Success => "btn-outline",
+      DispatchState::SecondFactorRequired => "btn-outline",
       DispatchState::BadCredentials => "btn-outline",
-      DispatchState::InternalError => "btn-outline",
+      DispatchState::Failed(_) => "btn-outline",
     })
   };
   let dispatch_button_disabled =
     move || matches!(dispatch_state(), DispatchState::Pending);
+  let second_factor_required = move || {
+    second_factor || dispatch_state() == DispatchState::SecondFactorRequired
+  };
 
   view! {
     <BreadCrumbs target=LinkTarget::Login />
@@ -108,7 +187,11 @@ pub fn LoginPage() -> impl IntoView {
             <p>"Login to "<OmtHub/></p>
           </div>
 
-          <div class="form-group gap-4">
+          <Show when=second_factor_required>
+            <SecondFactorForm />
+          </Show>
+
+          <div class="form-group gap-4" class:hidden=second_factor_required>
 
             <div class="form-field">
               <label class="form-label">"Email address"</label>
@@ -121,7 +204,7 @@ pub fn LoginPage() -> impl IntoView {
                 }
                 prop:value=move || email().unwrap_or_default()
               />
-              { move || email_validated().map(move |message| view! {
+              { move || email_validated().or_else(|| field_error(EMAIL_FIELD)).map(move |message| view! {
                 <label class="form-label animate-slide-down">
                   <span class="form-label-alt text-red-11">{message}</span>
                 </label>
@@ -138,7 +221,7 @@ pub fn LoginPage() -> impl IntoView {
                 }
                 prop:value=move || password().unwrap_or_default()
               />
-              { move || password_validated().map(move |message| view! {
+              { move || password_validated().or_else(|| field_error(PASSWORD_FIELD)).map(move |message| view! {
                 <label class="form-label animate-slide-down">
                   <span class="form-label-alt text-red-11">{message}</span>
                 </label>
@@ -166,6 +249,31 @@ pub fn LoginPage() -> impl IntoView {
                   disabled=dispatch_button_disabled
                 >"Login"</button>
               </div>
+              <div class="form-control justify-between">
+                <button
+                  type="button" on:click=dispatch_passkey
+                  class=move || format!("btn btn-outline w-full transition {}", match passkey_pending() {
+                    true => "btn-loading",
+                    false => "",
+                  })
+                  disabled=passkey_pending
+                >"Login with a passkey"</button>
+              </div>
+              { move || match passkey_value() {
+                Some(Ok(())) => Some(view! {
+                  <label class="form-label animate-slide-down">
+                    <span class="form-label-alt text-green-11">
+                      "Logged in successfully! Redirecting..."
+                    </span>
+                  </label>
+                }),
+                Some(Err(message)) => Some(view! {
+                  <label class="form-label animate-slide-down">
+                    <span class="form-label-alt text-red-11">{message}</span>
+                  </label>
+                }),
+                None => None,
+              }}
               { move || {
                 match dispatch_state() {
                   DispatchState::Success => Some(view! {
@@ -182,10 +290,10 @@ pub fn LoginPage() -> impl IntoView {
                       </span>
                     </label>
                   }),
-                  DispatchState::InternalError => Some(view! {
+                  DispatchState::Failed(e) => Some(view! {
                     <label class="form-label animate-slide-down">
                       <span class="form-label-alt text-red-11">
-                        "Something went wrong. Please try again."
+                        { e.message() }
                       </span>
                     </label>
                   }),
@@ -194,6 +302,21 @@ pub fn LoginPage() -> impl IntoView {
               }}
             </div>
 
+            { oidc_provider.map(|provider| view! {
+              <div class="form-field">
+                <div class="form-control justify-between">
+                  <a href="/auth/oidc/start" rel="external" class="btn btn-outline w-full">
+                    { format!("Login with {provider}") }
+                  </a>
+                </div>
+              </div>
+            }) }
+            { oidc_error.map(|message| view! {
+              <label class="form-label">
+                <span class="form-label-alt text-red-11">{message}</span>
+              </label>
+            }) }
+
             <div class="form-field">
               <div class="form-control justify-center">
                 <Link target=LinkTarget::Signup class="link link-underline link-primary text-sm">
@@ -208,3 +331,99 @@ pub fn LoginPage() -> impl IntoView {
     </div>
   }
 }
+
+/// Runs the whole passkey login ceremony, returning a message to show on
+/// failure.
+async fn passkey_login(email: String, remember: bool) -> Result<(), String> {
+  let challenge = start_passkey_login(email.into(), remember)
+    .await
+    .map_err(|e| app_error(&e).message())?;
+  let response = get_passkey(challenge).await.map_err(|e| {
+    logging::error!("passkey request failed: {e}");
+    "Your browser didn't provide a passkey.".to_string()
+  })?;
+  finish_passkey_login(response)
+    .await
+    .map_err(|e| app_error(&e).message())
+}
+
+#[component]
+fn SecondFactorForm() -> impl IntoView {
+  let (code, set_code) = create_signal(String::new());
+
+  let second_factor_action = create_server_action::<LoginSecondFactor>();
+  let value = second_factor_action.value();
+  let pending = second_factor_action.pending();
+
+  let dispatch =
+    move |_| second_factor_action.dispatch(LoginSecondFactor { code: code() });
+
+  let error = move || match value() {
+    Some(Err(e)) => Some(match app_error(&e) {
+      AppError::Validation(errors) => {
+        errors.get(CODE_FIELD).cloned().unwrap_or_default()
+      }
+      AppError::Unauthorized => {
+        "Your login has expired. Please log in again.".to_string()
+      }
+      e => e.message(),
+    }),
+    _ => None,
+  };
+
+  // redirect effect
+  create_effect(move |_| {
+    if matches!(value(), Some(Ok(()))) {
+      navigate_to("/account");
+    }
+  });
+
+  let dispatch_button_styles = move || {
+    format!("btn w-full transition {}", match pending() {
+      true => "btn-outline btn-loading",
+      false => "btn-primary",
+    })
+  };
+
+  view! {
+    <div class="form-group gap-4">
+      <p class="text-content2 text-sm max-w-prose">
+        "Enter the code from your authenticator app, or one of your recovery codes."
+      </p>
+
+      <div class="form-field">
+        <label class="form-label">"Authentication code"</label>
+        <input
+          placeholder="123456" autocomplete="one-time-code"
+          class="input hover:input-primary focus:input-primary transition max-w-full"
+          on:input=move |ev| {
+            set_code(event_target_value(&ev));
+          }
+          prop:value=code
+        />
+        { move || error().map(move |message| view! {
+          <label class="form-label animate-slide-down">
+            <span class="form-label-alt text-red-11">{message}</span>
+          </label>
+        }) }
+      </div>
+
+      <div class="form-field pt-5">
+        <div class="form-control justify-between">
+          <button
+            type="button" on:click=dispatch
+            class=dispatch_button_styles
+            disabled=pending
+          >"Verify"</button>
+        </div>
+        { move || matches!(value(), Some(Ok(()))).then(|| view! {
+          <label class="form-label animate-slide-down">
+            <span class="form-label-alt text-green-11">
+              "Logged in successfully! Redirecting..."
+            </span>
+          </label>
+        }) }
+      </div>
+    </div>
+  }
+}
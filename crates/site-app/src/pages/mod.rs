@@ -1,7 +1,16 @@
 pub mod account;
 pub mod all_tongues;
 pub mod homepage;
+pub mod invitation;
+pub mod listen;
 pub mod login;
 pub mod mother_tongue;
 pub mod new_translation;
+pub mod propose_tongue;
+pub mod recording_review;
 pub mod signup;
+pub mod takes;
+pub mod team;
+pub mod tongue_review;
+pub mod translation;
+pub mod user_profile;
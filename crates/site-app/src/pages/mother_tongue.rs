@@ -1,12 +1,14 @@
+use core_types::{iso_639_3::Language, VettingStatus};
 use leptos::*;
 use leptos_router::use_params_map;
 
 use crate::{
   components::{
+    avatar::Avatar,
     mini_pages::{BadLinkError, MissingResourceError},
-    BreadCrumbs,
+    BreadCrumbs, Link,
   },
-  functions::fetch::fetch_mother_tongue,
+  functions::{app_error, fetch::fetch_mother_tongue_details},
   LinkTarget,
 };
 
@@ -34,7 +36,7 @@ pub fn MotherTonguePage() -> impl IntoView {
 
 #[component]
 fn MotherTongueFetcher(id: core_types::MotherTongueRecordId) -> impl IntoView {
-  let mother_tongue = create_resource(move || id, fetch_mother_tongue);
+  let mother_tongue = create_resource(move || id, fetch_mother_tongue_details);
 
   view! {
     <BreadCrumbs target=LinkTarget::MotherTongue(id) />
@@ -42,15 +44,138 @@ fn MotherTongueFetcher(id: core_types::MotherTongueRecordId) -> impl IntoView {
       { move || mother_tongue().map(|data| match data {
         Ok(Some(data)) => view! { <MotherTongueData data=data /> }.into_view(),
         Ok(None) => view! { <MissingResourceError /> }.into_view(),
-        Err(e) => view! { <p>{ format!("failed to fetch mother tongue: {e}") }</p> }.into_view(),
+        Err(e) => view! { <p>{ app_error(&e).message() }</p> }.into_view(),
       }) }
     </Suspense>
   }
 }
 
 #[component]
-fn MotherTongueData(data: core_types::MotherTongue) -> impl IntoView {
+fn MotherTongueData(data: core_types::MotherTongueDetails) -> impl IntoView {
+  let core_types::MotherTongueDetails {
+    tongue,
+    language,
+    parent_language,
+    creator,
+    translations,
+    sample_recording,
+  } = data;
+
+  let vetting_badge = match tongue.vetting_status() {
+    VettingStatus::Vetted => {
+      view! { <span class="badge badge-success">"Vetted"</span> }
+    }
+    VettingStatus::Pending => {
+      view! { <span class="badge badge-outline-warning">"Awaiting vetting"</span> }
+    }
+    VettingStatus::Rejected => {
+      view! { <span class="badge badge-outline-error">"Rejected"</span> }
+    }
+  };
+
+  // codes missing from the ISO 639-3 table are still shown, just unnamed
+  let describe_code = |code: String, language: Option<Language>| match language
+  {
+    Some(language) => format!("{} ({code})", language.name),
+    None => code,
+  };
+  let facts = [
+    (!tongue.alternate_names.is_empty())
+      .then(|| ("Also known as", tongue.alternate_names.join(", "))),
+    tongue
+      .iso_639_3
+      .map(|code| ("Language", describe_code(code, language))),
+    tongue
+      .parent_language
+      .map(|code| ("Variety of", describe_code(code, parent_language))),
+    tongue.region.map(|region| ("Region", region)),
+    (!tongue.countries.is_empty())
+      .then(|| ("Countries", tongue.countries.join(", "))),
+    tongue.script.map(|script| ("Script", script)),
+  ]
+  .into_iter()
+  .flatten()
+  .collect::<Vec<_>>();
+
   view! {
-    <p>{ format!("{:?}", data) }</p>
+    <div class="flex flex-col p-8 gap-4">
+      <div class="flex flex-row gap-4 items-center">
+        <p class="text-5xl tracking-tight font-semibold">{ tongue.name }</p>
+        { vetting_badge }
+      </div>
+      { tongue.rejection_reason.map(|reason| view! {
+        <p class="text-red-11 text-sm max-w-prose">
+          { format!("A reviewer turned this tongue down: {reason}") }
+        </p>
+      }) }
+      <p class="text-content2 max-w-prose">{ tongue.description }</p>
+      { (!facts.is_empty()).then(|| view! {
+        <dl class="grid grid-cols-[max-content_1fr] gap-x-4 gap-y-1 text-sm">
+          { facts.into_iter().map(|(label, value)| view! {
+            <dt class="text-content2">{ label }</dt>
+            <dd>{ value }</dd>
+          }).collect_view() }
+        </dl>
+      }) }
+      { creator.map(|creator| view! {
+        <div class="flex flex-row gap-2 items-center text-sm">
+          <Avatar profile=creator.clone() class="avatar-xs" />
+          <span class="text-content2">"Added by"</span>
+          <Link target=LinkTarget::UserProfile(creator.id) class="underline hover:no-underline">
+            { creator.name }
+          </Link>
+        </div>
+      }) }
+      <div class="h-[1px] border-gray-6 border-b"></div>
+      { sample_recording.map(|id| view! {
+        <p class="text-3xl tracking-tight font-semibold">"Listen"</p>
+        <audio
+          class="w-full max-w-prose" controls preload="none"
+          src=core_types::artifact_media_url(id)
+        />
+      }) }
+      <p class="text-3xl tracking-tight font-semibold">"Translations"</p>
+      <TranslationList translations />
+    </div>
   }
 }
+
+#[component]
+fn TranslationList(
+  translations: Vec<core_types::TranslationListing>,
+) -> impl IntoView {
+  if translations.is_empty() {
+    return view! {
+      <p class="text-content2 text-sm">
+        "Nobody has started a translation in this tongue yet."
+      </p>
+    }
+    .into_view();
+  }
+
+  translations
+    .into_iter()
+    .map(|listing| {
+      let recordings = match listing.recording_count {
+        1 => "1 recording".to_string(),
+        n => format!("{n} recordings"),
+      };
+      view! {
+        <div class="flex flex-row gap-4 items-center">
+          <Link target=LinkTarget::Translation(listing.translation.id) class="font-semibold underline hover:no-underline">
+            { listing.translation.name }
+          </Link>
+          <div class="flex-1" />
+          <progress class="progress progress-success w-32" value=listing.progress max="100" />
+          <p class="text-content2 text-sm">
+            { format!("{:.1}% recorded", listing.progress) }
+          </p>
+          <p class="text-content2 text-sm">{ recordings }</p>
+          <p class="text-content2 text-sm">
+            { format!("Started {}", listing.translation.created_at.date()) }
+          </p>
+        </div>
+      }
+    })
+    .collect_view()
+}
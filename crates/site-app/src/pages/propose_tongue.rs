@@ -0,0 +1,283 @@
+use core_types::{
+  validation::{
+    validate_tongue_proposal, TongueProposalInput, ValidationErrors,
+    ALTERNATE_NAMES_FIELD, COUNTRIES_FIELD, DESCRIPTION_FIELD, ISO_639_3_FIELD,
+    NAME_FIELD, PARENT_LANGUAGE_FIELD, REGION_FIELD, SCRIPT_FIELD,
+  },
+  AppError,
+};
+use leptos::*;
+use leptos_router::Redirect;
+
+use crate::{
+  components::{BreadCrumbs, Link},
+  functions::{
+    app_error,
+    tongues::{lookup_language, ProposeMotherTongue, TongueProposalParams},
+  },
+  helpers::{get_auth_context, navigation::navigate_to},
+  LinkTarget,
+};
+
+#[component]
+pub fn ProposeTonguePage() -> impl IntoView {
+  if get_auth_context().0.is_none() {
+    return view! { <Redirect path=LinkTarget::Login.href() /> }.into_view();
+  }
+
+  view! {
+    <BreadCrumbs target=LinkTarget::ProposeTongue />
+    <div class="flex flex-col p-8 gap-4">
+      <p class="text-5xl tracking-tight font-semibold">
+        { LinkTarget::ProposeTongue.name() }
+      </p>
+      <div class="h-[1px] border-gray-6 border-b mb-4"></div>
+      <p class="text-content2 text-sm max-w-prose">
+        "Can't find your mother tongue? Tell us about it. A reviewer will check your proposal before it's listed, and may merge it into a tongue we already have."
+      </p>
+      <ProposeTongueForm />
+    </div>
+  }
+  .into_view()
+}
+
+/// Turns blank input into `None`.
+fn optional(value: String) -> Option<String> {
+  match value.trim().is_empty() {
+    true => None,
+    false => Some(value),
+  }
+}
+
+/// Splits comma-separated input into its entries.
+fn list(value: &str) -> Vec<String> {
+  value
+    .split(',')
+    .map(|v| v.trim().to_string())
+    .filter(|v| !v.is_empty())
+    .collect()
+}
+
+#[island]
+fn ProposeTongueForm() -> impl IntoView {
+  let (name, set_name) = create_signal(String::new());
+  let (description, set_description) = create_signal(String::new());
+  let (alternate_names, set_alternate_names) = create_signal(String::new());
+  let (region, set_region) = create_signal(String::new());
+  let (countries, set_countries) = create_signal(String::new());
+  let (iso_639_3, set_iso_639_3) = create_signal(String::new());
+  let (parent_language, set_parent_language) = create_signal(String::new());
+  let (script, set_script) = create_signal(String::new());
+  let (touched, set_touched) = create_signal(false);
+
+  let local_errors = create_memo(move |_| {
+    validate_tongue_proposal(TongueProposalInput {
+      name:            &name(),
+      description:     &description(),
+      countries:       &list(&countries()),
+      iso_639_3:       optional(iso_639_3()).as_deref(),
+      parent_language: optional(parent_language()).as_deref(),
+      script:          optional(script()).as_deref(),
+    })
+    .err()
+    .unwrap_or_default()
+  });
+
+  let propose_action = create_server_action::<ProposeMotherTongue>();
+  let value = propose_action.value();
+  let pending = propose_action.pending();
+
+  // field-level errors from the server, shown until the next submission
+  let server_errors = create_memo(move |_| match value() {
+    Some(Err(e)) => match app_error(&e) {
+      AppError::Validation(errors) => errors,
+      _ => ValidationErrors::new(),
+    },
+    _ => ValidationErrors::new(),
+  });
+  let field_error = move |field: &'static str| {
+    touched()
+      .then(|| local_errors.with(|errors| errors.get(field).cloned()))
+      .flatten()
+      .or_else(|| server_errors.with(|errors| errors.get(field).cloned()))
+  };
+  let failure = move || match value() {
+    Some(Err(e)) => match app_error(&e) {
+      AppError::Validation(_) => None,
+      e => Some(e.message()),
+    },
+    _ => None,
+  };
+
+  create_effect(move |_| {
+    if let Some(Ok(id)) = value() {
+      navigate_to(&LinkTarget::MotherTongue(id).href());
+    }
+  });
+
+  let dispatch = move |_| {
+    set_touched(true);
+    if !local_errors.with(ValidationErrors::is_empty) {
+      return;
+    }
+    propose_action.dispatch(ProposeMotherTongue {
+      params: TongueProposalParams {
+        name:            name(),
+        description:     description(),
+        alternate_names: list(&alternate_names()),
+        region:          optional(region()),
+        countries:       list(&countries()),
+        iso_639_3:       optional(iso_639_3()),
+        parent_language: optional(parent_language()),
+        script:          optional(script()),
+      },
+    });
+  };
+
+  let field_error_view = move |field: &'static str| {
+    move || {
+      field_error(field).map(|message| {
+        view! {
+          <label class="form-label animate-slide-down">
+            <span class="form-label-alt text-red-11">{message}</span>
+          </label>
+        }
+      })
+    }
+  };
+
+  view! {
+    <div class="form-group gap-4 max-w-prose">
+      <div class="form-field">
+        <label class="form-label">"Name"</label>
+        <input
+          placeholder="What speakers call it"
+          class="input hover:input-primary focus:input-primary transition max-w-full"
+          on:input=move |ev| set_name(event_target_value(&ev))
+          prop:value=name
+        />
+        { field_error_view(NAME_FIELD) }
+      </div>
+
+      <div class="form-field">
+        <label class="form-label">"Description"</label>
+        <textarea
+          placeholder="Who speaks it, and how it relates to nearby tongues"
+          class="textarea hover:textarea-primary focus:textarea-primary transition max-w-full"
+          on:input=move |ev| set_description(event_target_value(&ev))
+          prop:value=description
+        />
+        { field_error_view(DESCRIPTION_FIELD) }
+      </div>
+
+      <div class="form-field">
+        <label class="form-label">"Other names"<span class="text-content2">" (optional)"</span></label>
+        <input
+          placeholder="Including what speakers call it, separated by commas"
+          class="input hover:input-primary focus:input-primary transition max-w-full"
+          on:input=move |ev| set_alternate_names(event_target_value(&ev))
+          prop:value=alternate_names
+        />
+        { field_error_view(ALTERNATE_NAMES_FIELD) }
+      </div>
+
+      <div class="form-field">
+        <label class="form-label">"Region"<span class="text-content2">" (optional)"</span></label>
+        <input
+          placeholder="Where it's spoken"
+          class="input hover:input-primary focus:input-primary transition max-w-full"
+          on:input=move |ev| set_region(event_target_value(&ev))
+          prop:value=region
+        />
+        { field_error_view(REGION_FIELD) }
+      </div>
+
+      <div class="form-field">
+        <label class="form-label">"Countries"<span class="text-content2">" (optional)"</span></label>
+        <input
+          placeholder="Two-letter country codes, like US, CA"
+          class="input hover:input-primary focus:input-primary transition max-w-full font-mono"
+          on:input=move |ev| set_countries(event_target_value(&ev).to_uppercase())
+          prop:value=countries
+        />
+        { field_error_view(COUNTRIES_FIELD) }
+      </div>
+
+      <div class="form-field">
+        <label class="form-label">"ISO 639-3 code"<span class="text-content2">" (optional)"</span></label>
+        <input
+          placeholder="e.g. gul"
+          class="input hover:input-primary focus:input-primary transition max-w-full font-mono"
+          on:input=move |ev| set_iso_639_3(event_target_value(&ev).to_lowercase())
+          prop:value=iso_639_3
+        />
+        <LanguagePreview code=iso_639_3 />
+        { field_error_view(ISO_639_3_FIELD) }
+      </div>
+
+      <div class="form-field">
+        <label class="form-label">"Parent language"<span class="text-content2">" (optional)"</span></label>
+        <input
+          placeholder="The ISO 639-3 code of the language this is a variety of, e.g. eng"
+          class="input hover:input-primary focus:input-primary transition max-w-full font-mono"
+          on:input=move |ev| set_parent_language(event_target_value(&ev).to_lowercase())
+          prop:value=parent_language
+        />
+        <LanguagePreview code=parent_language />
+        { field_error_view(PARENT_LANGUAGE_FIELD) }
+      </div>
+
+      <div class="form-field">
+        <label class="form-label">"Script"<span class="text-content2">" (optional)"</span></label>
+        <input
+          placeholder="The ISO 15924 code of its usual script, e.g. Latn"
+          class="input hover:input-primary focus:input-primary transition max-w-full font-mono"
+          on:input=move |ev| set_script(event_target_value(&ev))
+          prop:value=script
+        />
+        { field_error_view(SCRIPT_FIELD) }
+      </div>
+
+      <div class="form-field pt-5">
+        <div class="form-control justify-between">
+          <Link target=LinkTarget::AllTongues class="btn btn-outline">"Cancel"</Link>
+          <button
+            type="button" on:click=dispatch
+            class=move || format!("btn transition {}", match pending() {
+              true => "btn-outline btn-loading",
+              false => "btn-primary",
+            })
+            disabled=pending
+          >"Propose"</button>
+        </div>
+        { move || failure().map(|message| view! {
+          <label class="form-label animate-slide-down">
+            <span class="form-label-alt text-red-11">{ message }</span>
+          </label>
+        }) }
+      </div>
+    </div>
+  }
+}
+
+/// Names the language for a complete ISO 639-3 code, so the proposer can
+/// check they've got the right one.
+#[component]
+fn LanguagePreview(#[prop(into)] code: Signal<String>) -> impl IntoView {
+  let language = create_resource(code, |code| async move {
+    match code.len() {
+      3 => lookup_language(code).await.ok().flatten(),
+      _ => None,
+    }
+  });
+
+  view! {
+    <Transition fallback=|| ()>
+      { move || language().flatten().map(|language| view! {
+        <label class="form-label">
+          <span class="form-label-alt text-content2">{ language.name }</span>
+        </label>
+      }) }
+    </Transition>
+  }
+}
@@ -0,0 +1,298 @@
+use core_types::{
+  validation::COMMENT_FIELD, AppError, QueuedRecording, RecordingStatus,
+  ReviewEvent,
+};
+use leptos::*;
+use leptos_router::Redirect;
+
+use crate::{
+  components::{
+    annotated_player::AnnotatedPlayer, avatar::Avatar, pagination::Pagination,
+    BreadCrumbs, Link,
+  },
+  functions::{
+    app_error,
+    recordings::{
+      fetch_review_history, list_review_queue, CommentOnRecording,
+      MoveRecording,
+    },
+  },
+  helpers::get_auth_context,
+  LinkTarget,
+};
+
+const RECORDINGS_PER_PAGE: u32 = 10;
+
+#[component]
+pub fn RecordingReviewPage() -> impl IntoView {
+  if get_auth_context().0.is_none() {
+    return view! { <Redirect path=LinkTarget::Login.href() /> }.into_view();
+  }
+
+  view! {
+    <BreadCrumbs target=LinkTarget::RecordingReview />
+    <div class="flex flex-col p-8 gap-4">
+      <p class="text-5xl tracking-tight font-semibold">
+        { LinkTarget::RecordingReview.name() }
+      </p>
+      <div class="h-[1px] border-gray-6 border-b"></div>
+      <p class="text-content2 text-sm max-w-prose">
+        "Recordings are submitted by their uploaders, checked with the community, and then checked by a consultant before they're approved. Either check can reject a recording so its uploader can rework it."
+      </p>
+      <RecordingQueue />
+    </div>
+  }
+  .into_view()
+}
+
+#[island]
+fn RecordingQueue() -> impl IntoView {
+  let move_action = create_server_action::<MoveRecording>();
+  let comment_action = create_server_action::<CommentOnRecording>();
+
+  let (status, set_status) = create_signal::<Option<RecordingStatus>>(None);
+  let (current_page, set_current_page) = create_signal(0_u32);
+
+  let recordings = create_resource(
+    move || {
+      (
+        status(),
+        current_page(),
+        move_action.version()(),
+        comment_action.version()(),
+      )
+    },
+    |(status, page, ..)| {
+      list_review_queue(status, page * RECORDINGS_PER_PAGE, RECORDINGS_PER_PAGE)
+    },
+  );
+
+  let failure = move || {
+    [move_action.value()(), comment_action.value()()]
+      .into_iter()
+      .find_map(|v| v.and_then(Result::err))
+      .map(|e| app_error(&e))
+      // missing comments are shown next to their field instead
+      .filter(|e| !matches!(e, AppError::Validation(_)))
+      .map(|e| e.message())
+  };
+
+  view! {
+    <div class="flex flex-row gap-4 items-center">
+      <label class="text-content2 text-sm">"Show"</label>
+      <select class="select select-sm w-56"
+        on:change=move |ev| {
+          set_current_page(0);
+          set_status(RecordingStatus::parse(&event_target_value(&ev)));
+        }
+      >
+        <option value="" selected=move || status().is_none()>"Waiting on me"</option>
+        { RecordingStatus::ALL.into_iter().map(|s| view! {
+          <option value=s.as_str() selected=move || status() == Some(s)>{ s.label() }</option>
+        }).collect_view() }
+      </select>
+    </div>
+    { move || failure().map(|message| view! {
+      <p class="text-red-11 text-sm">{ message }</p>
+    }) }
+    <Transition fallback=move || view! { <p>"Loading..."</p> }>
+      { move || recordings().map(|result| match result {
+        Ok((recordings, _)) if recordings.is_empty() => view! {
+          <p class="text-content2 text-sm">"No recordings here."</p>
+        }
+        .into_view(),
+        Ok((recordings, total)) => view! {
+          { recordings.into_iter().map(|queued| view! {
+            <RecordingCard queued move_action comment_action />
+          }).collect_view() }
+          <Pagination class="self-center"
+            total_pages={((total as f32 / RECORDINGS_PER_PAGE as f32).ceil() as u32).into()}
+            current_page={current_page.into()}
+            set_page=set_current_page
+          />
+        }
+        .into_view(),
+        Err(e) => view! {
+          <p class="text-red-11 text-sm">{ app_error(&e).message() }</p>
+        }
+        .into_view(),
+      }) }
+    </Transition>
+  }
+}
+
+#[component]
+fn RecordingCard(
+  queued: QueuedRecording,
+  move_action: Action<MoveRecording, Result<(), ServerFnError<AppError>>>,
+  comment_action: Action<
+    CommentOnRecording,
+    Result<(), ServerFnError<AppError>>,
+  >,
+) -> impl IntoView {
+  let QueuedRecording {
+    recording,
+    next_statuses,
+    can_comment,
+  } = queued;
+  let id = recording.id;
+
+  let (comment, set_comment) = create_signal(String::new());
+  let comment_error = move || {
+    [move_action.value()(), comment_action.value()()]
+      .into_iter()
+      .find_map(|v| v.and_then(Result::err))
+      .and_then(|e| match app_error(&e) {
+        AppError::Validation(errors) => errors.get(COMMENT_FIELD).cloned(),
+        _ => None,
+      })
+  };
+
+  let (show_history, set_show_history) = create_signal(false);
+  let history = create_resource(show_history, move |show| async move {
+    match show {
+      true => fetch_review_history(id).await.map(Some),
+      false => Ok(None),
+    }
+  });
+
+  let title = match &recording.passage {
+    Some(passage) => format!("{} · {passage}", recording.translation_name),
+    None => recording.translation_name.clone(),
+  };
+  let uploaded = recording
+    .uploaded_at
+    .map(|at| format!("Uploaded {}", at.date()));
+
+  view! {
+    <div class="card border border-border max-w-full">
+      <div class="card-body gap-4">
+        <div class="flex flex-row gap-4 items-center">
+          <Link target=LinkTarget::Translation(recording.translation) class="text-xl font-semibold underline hover:no-underline">
+            { title }
+          </Link>
+          <span class="badge badge-outline">{ recording.status.label() }</span>
+          <div class="flex-1" />
+          { recording.uploader.map(|uploader| view! {
+            <div class="flex flex-row gap-2 items-center text-sm">
+              <Avatar profile=uploader.clone() class="avatar-xs" />
+              <Link target=LinkTarget::UserProfile(uploader.id) class="underline hover:no-underline">
+                { uploader.name }
+              </Link>
+            </div>
+          }) }
+        </div>
+        { uploaded.map(|uploaded| view! {
+          <p class="text-content2 text-sm">{ uploaded }</p>
+        }) }
+        <AnnotatedPlayer id can_comment />
+
+        { (can_comment || !next_statuses.is_empty()).then(|| view! {
+          <div class="form-field">
+            <textarea
+              placeholder="Comment"
+              class="textarea textarea-sm max-w-full"
+              on:input=move |ev| set_comment(event_target_value(&ev))
+              prop:value=comment
+            />
+            { move || comment_error().map(|message| view! {
+              <label class="form-label animate-slide-down">
+                <span class="form-label-alt text-red-11">{ message }</span>
+              </label>
+            }) }
+          </div>
+        }) }
+        <div class="flex flex-row flex-wrap gap-2 items-center">
+          { next_statuses.into_iter().map(|to| {
+            let class = match to {
+              RecordingStatus::Approved => "btn btn-success btn-sm",
+              RecordingStatus::Rejected => "btn btn-error btn-sm",
+              _ => "btn btn-primary btn-sm",
+            };
+            view! {
+              <button
+                class=class
+                disabled=move_action.pending()
+                on:click=move |_| move_action.dispatch(MoveRecording {
+                  id,
+                  to,
+                  comment: comment.get_untracked(),
+                })
+              >{ to.action_label() }</button>
+            }
+          }).collect_view() }
+          { can_comment.then(|| view! {
+            <button
+              class="btn btn-outline btn-sm"
+              disabled=comment_action.pending()
+              on:click=move |_| comment_action.dispatch(CommentOnRecording {
+                id,
+                comment: comment.get_untracked(),
+              })
+            >"Comment"</button>
+          }) }
+          <div class="flex-1" />
+          <button
+            class="btn btn-ghost btn-sm"
+            on:click=move |_| set_show_history.update(|s| *s = !*s)
+          >
+            { move || match show_history() {
+              true => "Hide history",
+              false => "Show history",
+            } }
+          </button>
+        </div>
+        <Transition fallback=|| ()>
+          { move || history().map(|result| match result {
+            Ok(Some(events)) => view! { <ReviewHistory events /> }.into_view(),
+            Ok(None) => ().into_view(),
+            Err(e) => view! {
+              <p class="text-red-11 text-sm">{ app_error(&e).message() }</p>
+            }
+            .into_view(),
+          }) }
+        </Transition>
+      </div>
+    </div>
+  }
+}
+
+#[component]
+fn ReviewHistory(events: Vec<ReviewEvent>) -> impl IntoView {
+  if events.is_empty() {
+    return view! {
+      <p class="text-content2 text-sm">"Nothing has happened in review yet."</p>
+    }
+    .into_view();
+  }
+
+  view! {
+    <ol class="flex flex-col gap-2">
+      { events.into_iter().map(|event| {
+        let who = event
+          .by
+          .as_ref()
+          .map(|by| by.name.clone())
+          .unwrap_or_else(|| "Someone".to_string());
+        let what = match (event.previous, event.status) {
+          (Some(previous), Some(status)) => {
+            format!("moved it from {} to {}", previous.label(), status.label())
+          }
+          _ => "commented".to_string(),
+        };
+        view! {
+          <li class="flex flex-col gap-1 text-sm">
+            <p>
+              <span class="font-semibold">{ who }</span>
+              { format!(" {what} on {}", event.at.date()) }
+            </p>
+            { event.comment.map(|comment| view! {
+              <p class="text-content2 max-w-prose whitespace-pre-wrap">{ comment }</p>
+            }) }
+          </li>
+        }
+      }).collect_view() }
+    </ol>
+  }
+  .into_view()
+}
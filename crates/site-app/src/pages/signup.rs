@@ -1,8 +1,18 @@
+use core_types::{
+  validation::{
+    validate_email, validate_name, validate_password, ValidationErrors,
+    EMAIL_FIELD, NAME_FIELD, PASSWORD_FIELD,
+  },
+  AppError,
+};
 use leptos::*;
 
 use crate::{
   components::{BreadCrumbs, *},
-  functions::auth::{Signup, SignupParams},
+  functions::{
+    app_error,
+    auth::{Signup, SignupParams},
+  },
   helpers::navigation::navigate_to,
   LinkTarget,
 };
@@ -13,7 +23,7 @@ pub enum DispatchState {
   Unsubmitted,
   Pending,
   Success,
-  InternalError,
+  Failed(AppError),
 }
 
 #[island]
@@ -26,17 +36,17 @@ pub fn SignupPage() -> impl IntoView {
 
   let name_validated = create_memo(move |_| match name() {
     None => None,
-    Some(name) => crate::helpers::validate_name(name),
+    Some(name) => validate_name(&name),
   });
 
   let email_validated = create_memo(move |_| match email() {
     None => None,
-    Some(email) => crate::helpers::validate_email(email),
+    Some(email) => validate_email(&email),
   });
 
   let password_validated = create_memo(move |_| match password() {
     None => None,
-    Some(password) => crate::helpers::validate_password(password),
+    Some(password) => validate_password(&password),
   });
 
   let confirm_validated = create_memo(move |_| {
@@ -51,11 +61,15 @@ pub fn SignupPage() -> impl IntoView {
 
   let params = create_memo(move |_| match (name(), email(), password()) {
     (Some(name), Some(email), Some(password)) => {
-      if email_validated().is_none() && confirm_validated().is_none() {
+      if name_validated().is_none()
+        && email_validated().is_none()
+        && password_validated().is_none()
+        && confirm_validated().is_none()
+      {
         Some(SignupParams {
           name,
-          email,
-          password,
+          email: email.into(),
+          password: password.into(),
           remember: remember(),
         })
       } else {
@@ -91,13 +105,29 @@ pub fn SignupPage() -> impl IntoView {
     }
   };
 
+  // field-level errors from the server, shown until the next submission
+  let server_errors = create_memo(move |_| match value() {
+    Some(Err(e)) => match app_error(&e) {
+      AppError::Validation(errors) => errors,
+      _ => ValidationErrors::new(),
+    },
+    _ => ValidationErrors::new(),
+  });
+  let field_error = move |field: &'static str| {
+    server_errors.with(|errors| errors.get(field).cloned())
+  };
+
   let dispatch_state =
     create_memo(move |_| match (params(), pending(), value()) {
       (None, _, _) => DispatchState::InsufficientInformation,
       (Some(_), true, _) => DispatchState::Pending,
       (Some(_), false, None) => DispatchState::Unsubmitted,
       (Some(_), false, Some(Ok(()))) => DispatchState::Success,
-      (Some(_), false, Some(Err(_))) => DispatchState::InternalError,
+      (Some(_), false, Some(Err(e))) => match app_error(&e) {
+        // these are shown next to their fields instead
+        AppError::Validation(_) => DispatchState::Unsubmitted,
+        e => DispatchState::Failed(e),
+      },
     });
 
   // redirect effect
@@ -113,7 +143,7 @@ pub fn SignupPage() -> impl IntoView {
       DispatchState::Unsubmitted => "btn-primary",
       DispatchState::Pending => "btn-outline btn-loading",
       DispatchState::Success => "btn-outline",
-      DispatchState::InternalError => "btn-outline",
+      DispatchState::Failed(_) => "btn-outline",
     })
   };
   let dispatch_button_disabled =
@@ -141,7 +171,7 @@ pub fn SignupPage() -> impl IntoView {
                 }
                 prop:value=move || name().unwrap_or_default()
               />
-              { move || name_validated().map(move |message| view! {
+              { move || name_validated().or_else(|| field_error(NAME_FIELD)).map(move |message| view! {
                 <label class="form-label animate-slide-down">
                   <span class="form-label-alt text-red-11">{message}</span>
                 </label>
@@ -159,7 +189,7 @@ pub fn SignupPage() -> impl IntoView {
                 }
                 prop:value=move || email().unwrap_or_default()
               />
-              { move || email_validated().map(move |message| view! {
+              { move || email_validated().or_else(|| field_error(EMAIL_FIELD)).map(move |message| view! {
                 <label class="form-label animate-slide-down">
                   <span class="form-label-alt text-red-11">{message}</span>
                 </label>
@@ -176,7 +206,7 @@ pub fn SignupPage() -> impl IntoView {
                 }
                 prop:value=move || password().unwrap_or_default()
               />
-              { move || password_validated().map(move |message| view! {
+              { move || password_validated().or_else(|| field_error(PASSWORD_FIELD)).map(move |message| view! {
                 <label class="form-label animate-slide-down">
                   <span class="form-label-alt text-red-11">{message}</span>
                 </label>
@@ -230,10 +260,10 @@ pub fn SignupPage() -> impl IntoView {
                       </span>
                     </label>
                   }),
-                  DispatchState::InternalError => Some(view! {
+                  DispatchState::Failed(e) => Some(view! {
                     <label class="form-label animate-slide-down">
                       <span class="form-label-alt text-red-11">
-                        "Something went wrong. Please try again."
+                        { e.message() }
                       </span>
                     </label>
                   }),
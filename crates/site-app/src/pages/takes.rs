@@ -0,0 +1,182 @@
+use core_types::{scripture::Passage, AppError, Take, TranslationRecordId};
+use leptos::*;
+use leptos_router::{use_params_map, use_query_map};
+
+use crate::{
+  components::{avatar::Avatar, mini_pages::BadLinkError, BreadCrumbs, Link},
+  functions::{
+    app_error,
+    takes::{list_takes, MakeTakeCurrent},
+  },
+  LinkTarget,
+};
+
+#[component]
+pub fn TakesPage() -> impl IntoView {
+  let params = use_params_map();
+  let query = use_query_map();
+
+  let target = move || {
+    let id = with!(|params| {
+      params
+        .get("id")
+        .and_then(|s| s.parse::<core_types::Ulid>().ok())
+        .map(TranslationRecordId)
+    })?;
+    let passage = with!(|query| {
+      query.get("passage").and_then(|s| s.parse::<Passage>().ok())
+    })?;
+    Some((id, passage))
+  };
+
+  view! {
+    { move || match target() {
+      Some((id, passage)) => view! {
+        <BreadCrumbs target=LinkTarget::Takes(id, passage.clone()) />
+        <div class="flex flex-col p-8 gap-4">
+          <p class="text-5xl tracking-tight font-semibold">
+            { format!("Takes of {passage}") }
+          </p>
+          <div class="h-[1px] border-gray-6 border-b"></div>
+          <p class="text-content2 text-sm max-w-prose">
+            "Re-recording a passage adds a take instead of replacing the recording. Every take is kept, so you can listen to them side by side and go back to an earlier one."
+          </p>
+          <TakeComparison translation=id passage />
+        </div>
+      }
+      .into_view(),
+      None => view! { <BadLinkError /> }.into_view(),
+    }}
+  }
+}
+
+#[island]
+fn TakeComparison(
+  translation: TranslationRecordId,
+  passage: Passage,
+) -> impl IntoView {
+  let make_current = create_server_action::<MakeTakeCurrent>();
+  let takes = create_resource(
+    move || make_current.version()(),
+    move |_| list_takes(translation, passage.clone()),
+  );
+
+  view! {
+    { move || make_current.value()().and_then(Result::err).map(|e| view! {
+      <p class="text-red-11 text-sm">{ app_error(&e).message() }</p>
+    }) }
+    <Transition fallback=move || view! { <p>"Loading..."</p> }>
+      { move || takes().map(|result| match result {
+        Ok((takes, _)) if takes.is_empty() => view! {
+          <p class="text-content2 text-sm">"This passage hasn't been recorded yet."</p>
+        }
+        .into_view(),
+        Ok((takes, can_choose)) => view! {
+          <SideBySide takes=takes.clone() />
+          <p class="text-3xl tracking-tight font-semibold">"All takes"</p>
+          <div class="flex flex-col gap-2">
+            { takes.into_iter().map(|take| view! {
+              <TakeRow take can_choose make_current />
+            }).collect_view() }
+          </div>
+        }
+        .into_view(),
+        Err(e) => view! {
+          <p class="text-red-11 text-sm">{ app_error(&e).message() }</p>
+        }
+        .into_view(),
+      }) }
+    </Transition>
+  }
+}
+
+/// Two players next to each other, each playing a chosen take. They start
+/// on the current take and the newest other one.
+#[component]
+fn SideBySide(takes: Vec<Take>) -> impl IntoView {
+  let current = takes.iter().position(|t| t.is_current).unwrap_or(0);
+  let other = (0..takes.len()).find(|&i| i != current).unwrap_or(current);
+
+  view! {
+    <div class="grid grid-cols-1 md:grid-cols-2 gap-4">
+      <TakePicker takes=takes.clone() initial=current />
+      <TakePicker takes initial=other />
+    </div>
+  }
+}
+
+#[component]
+fn TakePicker(takes: Vec<Take>, initial: usize) -> impl IntoView {
+  let (chosen, set_chosen) = create_signal(initial);
+  let src = {
+    let takes = takes.clone();
+    move || core_types::artifact_media_url(takes[chosen()].id)
+  };
+
+  view! {
+    <div class="card border border-border">
+      <div class="card-body gap-4">
+        <select class="select select-sm"
+          on:change=move |ev| {
+            if let Ok(i) = event_target_value(&ev).parse() {
+              set_chosen(i);
+            }
+          }
+        >
+          { takes.iter().enumerate().map(|(i, take)| view! {
+            <option value=i.to_string() selected=move || chosen() == i>
+              { take_label(take) }
+            </option>
+          }).collect_view() }
+        </select>
+        <audio class="w-full" controls preload="none" src=src />
+      </div>
+    </div>
+  }
+}
+
+#[component]
+fn TakeRow(
+  take: Take,
+  can_choose: bool,
+  make_current: Action<MakeTakeCurrent, Result<(), ServerFnError<AppError>>>,
+) -> impl IntoView {
+  let id = take.id;
+  let uploaded = take.uploaded_at.map(|at| format!("Uploaded {}", at.date()));
+
+  view! {
+    <div class="flex flex-row flex-wrap gap-4 items-center">
+      <p class="font-semibold">{ format!("Take {}", take.take) }</p>
+      { take.is_current.then(|| view! {
+        <span class="badge badge-success badge-sm">"Current"</span>
+      }) }
+      <span class="badge badge-outline badge-sm">{ take.status.label() }</span>
+      { take.uploader.map(|uploader| view! {
+        <div class="flex flex-row gap-2 items-center text-sm">
+          <Avatar profile=uploader.clone() class="avatar-xs" />
+          <Link target=LinkTarget::UserProfile(uploader.id) class="underline hover:no-underline">
+            { uploader.name }
+          </Link>
+        </div>
+      }) }
+      { uploaded.map(|uploaded| view! {
+        <span class="text-content2 text-sm">{ uploaded }</span>
+      }) }
+      <div class="flex-1" />
+      { (can_choose && !take.is_current).then(|| view! {
+        <button
+          class="btn btn-outline btn-sm"
+          disabled=make_current.pending()
+          on:click=move |_| make_current.dispatch(MakeTakeCurrent { id })
+        >"Make current"</button>
+      }) }
+    </div>
+  }
+}
+
+fn take_label(take: &Take) -> String {
+  match take.is_current {
+    true => format!("Take {} (current)", take.take),
+    false => format!("Take {}", take.take),
+  }
+}
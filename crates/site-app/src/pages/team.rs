@@ -0,0 +1,358 @@
+use core_types::{
+  validation::{EMAIL_FIELD, ROLES_FIELD},
+  AppError, InvitationSummary, TranslationMember, TranslationRecordId,
+  TranslationRole, UserRecordId,
+};
+use leptos::*;
+use leptos_router::use_params_map;
+
+use crate::{
+  components::{
+    avatar::Avatar, invitation_card::role_labels, mini_pages::BadLinkError,
+    BreadCrumbs, Link,
+  },
+  functions::{
+    app_error,
+    members::{
+      fetch_team, InviteToTranslation, RemoveMember, RevokeInvitation,
+      SetMemberRoles,
+    },
+  },
+  LinkTarget,
+};
+
+#[component]
+pub fn TeamPage() -> impl IntoView {
+  let params = use_params_map();
+
+  let id = move || {
+    with!(|params| {
+      params.get("id").cloned().and_then(|s| {
+        s.parse::<core_types::Ulid>().map(TranslationRecordId).ok()
+      })
+    })
+  };
+
+  view! {
+    { move || match id() {
+      Some(id) => view! {
+        <BreadCrumbs target=LinkTarget::TranslationTeam(id) />
+        <div class="flex flex-col p-8 gap-4">
+          <p class="text-5xl tracking-tight font-semibold">"Team"</p>
+          <div class="h-[1px] border-gray-6 border-b"></div>
+          <p class="text-content2 text-sm max-w-prose">
+            "Speakers record passages, community checkers and consultants \
+             review them, and managers run the team. Only members can upload \
+             or review recordings for this translation."
+          </p>
+          <TeamManager translation=id />
+        </div>
+      }
+      .into_view(),
+      None => view! { <BadLinkError /> }.into_view(),
+    }}
+  }
+}
+
+type RolesAction = Action<SetMemberRoles, Result<(), ServerFnError<AppError>>>;
+type RemoveAction = Action<RemoveMember, Result<(), ServerFnError<AppError>>>;
+
+#[island]
+fn TeamManager(translation: TranslationRecordId) -> impl IntoView {
+  let invite_action = create_server_action::<InviteToTranslation>();
+  let revoke_action = create_server_action::<RevokeInvitation>();
+  let roles_action = create_server_action::<SetMemberRoles>();
+  let remove_action = create_server_action::<RemoveMember>();
+
+  let team = create_resource(
+    move || {
+      (
+        invite_action.version()(),
+        revoke_action.version()(),
+        roles_action.version()(),
+        remove_action.version()(),
+      )
+    },
+    move |_| fetch_team(translation),
+  );
+
+  let error = move || {
+    [
+      revoke_action.value()(),
+      roles_action.value()(),
+      remove_action.value()(),
+    ]
+    .into_iter()
+    .find_map(|v| match v {
+      Some(Err(e)) => Some(match app_error(&e) {
+        AppError::Validation(errors) => {
+          errors.get(ROLES_FIELD).cloned().unwrap_or_default()
+        }
+        e => e.message(),
+      }),
+      _ => None,
+    })
+  };
+
+  view! {
+    <Transition fallback=move || view! { <p>"Loading..."</p> }>
+      { move || team().map(|result| match result {
+        Ok(team) => {
+          let can_manage = team.can_manage;
+          let viewer = team.viewer;
+          view! {
+            <div class="flex flex-col gap-4 max-w-prose">
+              { team.members.into_iter().map(|member| view! {
+                <MemberRow
+                  translation member can_manage viewer
+                  roles_action remove_action
+                />
+              }).collect_view() }
+            </div>
+            { can_manage.then(|| view! {
+              <p class="text-3xl tracking-tight font-semibold">"Invitations"</p>
+              <OpenInvitations invitations=team.invitations.clone() revoke_action />
+            }) }
+          }
+          .into_view()
+        }
+        Err(e) => view! {
+          <p class="text-red-11 text-sm">{ app_error(&e).message() }</p>
+        }
+        .into_view(),
+      }) }
+    </Transition>
+    { move || error().map(|message| view! {
+      <p class="text-red-11 text-sm">{message}</p>
+    }) }
+    { move || matches!(team(), Some(Ok(team)) if team.can_manage).then(|| view! {
+      <InviteForm translation invite_action />
+    }) }
+  }
+}
+
+#[component]
+fn MemberRow(
+  translation: TranslationRecordId,
+  member: TranslationMember,
+  can_manage: bool,
+  viewer: Option<UserRecordId>,
+  roles_action: RolesAction,
+  remove_action: RemoveAction,
+) -> impl IntoView {
+  let id = member.profile.id;
+  let is_viewer = viewer == Some(id);
+  let roles = member.roles.clone();
+
+  let toggle_role = move |role: TranslationRole, checked: bool| {
+    let mut roles = roles.clone();
+    roles.retain(|r| *r != role);
+    if checked {
+      roles.push(role);
+    }
+    roles_action.dispatch(SetMemberRoles {
+      translation,
+      member: id,
+      roles,
+    });
+  };
+
+  view! {
+    <div class="flex flex-row flex-wrap gap-4 items-center">
+      <Avatar profile=member.profile.clone() class="avatar-sm" />
+      <div class="flex flex-col">
+        <Link target=LinkTarget::UserProfile(id) class="font-semibold underline hover:no-underline">
+          { member.profile.name.clone() }
+        </Link>
+        <p class="text-content2 text-sm">
+          { format!("Joined {}", member.joined_at.date()) }
+        </p>
+      </div>
+      <div class="flex-1" />
+      { match can_manage {
+        true => TranslationRole::ALL.into_iter().map(|role| {
+          let toggle_role = toggle_role.clone();
+          view! {
+            <div class="flex gap-2">
+              <input
+                type="checkbox" class="checkbox"
+                disabled=roles_action.pending()
+                on:input=move |ev| toggle_role(role, event_target_checked(&ev))
+                prop:checked=member.roles.contains(&role)
+              />
+              <p class="text-sm">{ role.label() }</p>
+            </div>
+          }
+        }).collect_view(),
+        false => member.roles.iter().map(|role| view! {
+          <span class="badge badge-outline badge-sm">{ role.label() }</span>
+        }).collect_view(),
+      } }
+      { (can_manage || is_viewer).then(|| view! {
+        <button
+          class="btn btn-outline-error btn-sm"
+          disabled=remove_action.pending()
+          on:click=move |_| remove_action.dispatch(RemoveMember {
+            translation,
+            member: id,
+          })
+        >{ if is_viewer { "Leave" } else { "Remove" } }</button>
+      }) }
+    </div>
+  }
+}
+
+#[component]
+fn OpenInvitations(
+  invitations: Vec<InvitationSummary>,
+  revoke_action: Action<RevokeInvitation, Result<(), ServerFnError<AppError>>>,
+) -> impl IntoView {
+  if invitations.is_empty() {
+    return view! {
+      <p class="text-content2 text-sm">"There are no open invitations."</p>
+    }
+    .into_view();
+  }
+
+  view! {
+    <div class="flex flex-col gap-4 max-w-prose">
+      { invitations.into_iter().map(|invitation| {
+        let id = invitation.id;
+        let who = invitation
+          .email
+          .unwrap_or_else(|| "Anyone with the link".to_string());
+        view! {
+          <div class="flex flex-row gap-4 items-center">
+            <div class="flex flex-col">
+              <p class="font-semibold">{ who }</p>
+              <p class="text-content2 text-sm">{ role_labels(&invitation.roles) }</p>
+              <p class="text-content2 text-sm">
+                { format!("Expires {}", invitation.expires_at.date()) }
+              </p>
+            </div>
+            <div class="flex-1" />
+            <button
+              class="btn btn-outline-error btn-sm"
+              disabled=revoke_action.pending()
+              on:click=move |_| revoke_action.dispatch(RevokeInvitation { id })
+            >"Revoke"</button>
+          </div>
+        }
+      }).collect_view() }
+    </div>
+  }
+  .into_view()
+}
+
+#[component]
+fn InviteForm(
+  translation: TranslationRecordId,
+  invite_action: Action<
+    InviteToTranslation,
+    Result<String, ServerFnError<AppError>>,
+  >,
+) -> impl IntoView {
+  let (email, set_email) = create_signal(String::new());
+  let (roles, set_roles) = create_signal(vec![TranslationRole::Speaker]);
+
+  let new_link = move || match invite_action.value()() {
+    Some(Ok(path)) => Some(path),
+    _ => None,
+  };
+  create_effect(move |_| {
+    if new_link().is_some() {
+      set_email(String::new());
+    }
+  });
+
+  let field_error = move |field: &'static str| match invite_action.value()() {
+    Some(Err(e)) => match app_error(&e) {
+      AppError::Validation(errors) => errors.get(field).cloned(),
+      _ => None,
+    },
+    _ => None,
+  };
+  let error = move || match invite_action.value()() {
+    Some(Err(e)) => match app_error(&e) {
+      AppError::Validation(_) => None,
+      e => Some(e.message()),
+    },
+    _ => None,
+  };
+
+  let toggle_role = move |role: TranslationRole, checked: bool| {
+    set_roles.update(|roles| {
+      roles.retain(|r| *r != role);
+      if checked {
+        roles.push(role);
+      }
+    })
+  };
+
+  view! {
+    <p class="text-3xl tracking-tight font-semibold">"Invite someone"</p>
+    <div class="flex flex-col gap-4 max-w-prose">
+      <p class="text-content2 text-sm">
+        "Invitations are links, and no emails are sent: pass the link on to \
+         whoever you're inviting yourself. With an email, the link can only \
+         be used once. Leave the email blank to make a link anyone can join \
+         with. Invitations expire after two weeks."
+      </p>
+
+      { move || new_link().map(|path| view! {
+        <div class="flex flex-col gap-2">
+          <p class="text-green-11">
+            "Invitation created. It isn't sent anywhere, so pass this link on:"
+          </p>
+          <a href=path.clone() class="font-mono break-all underline hover:no-underline">
+            { path }
+          </a>
+        </div>
+      }) }
+
+      <div class="form-group gap-4">
+        <div class="form-field">
+          <label class="form-label">"Email"</label>
+          <input
+            type="email" placeholder="Leave blank for a link"
+            class="input hover:input-primary focus:input-primary transition max-w-xs"
+            on:input=move |ev| set_email(event_target_value(&ev))
+            prop:value=email
+          />
+          { move || field_error(EMAIL_FIELD).map(|message| view! {
+            <span class="form-label-alt text-red-11">{message}</span>
+          }) }
+        </div>
+        <div class="form-field">
+          <label class="form-label">"Roles"</label>
+          { TranslationRole::ALL.into_iter().map(|role| view! {
+            <div class="flex gap-2">
+              <input
+                type="checkbox" class="checkbox"
+                on:input=move |ev| toggle_role(role, event_target_checked(&ev))
+                prop:checked=move || roles.with(|r| r.contains(&role))
+              />
+              <p>{ role.label() }</p>
+            </div>
+          }).collect_view() }
+          { move || field_error(ROLES_FIELD).map(|message| view! {
+            <span class="form-label-alt text-red-11">{message}</span>
+          }) }
+        </div>
+        <button
+          class="btn btn-primary self-start"
+          disabled=invite_action.pending()
+          on:click=move |_| invite_action.dispatch(InviteToTranslation {
+            translation,
+            email: email(),
+            roles: roles(),
+          })
+        >"Create invitation"</button>
+      </div>
+
+      { move || error().map(|message| view! {
+        <p class="text-red-11 text-sm">{message}</p>
+      }) }
+    </div>
+  }
+}
@@ -0,0 +1,253 @@
+use core_types::{
+  validation::REASON_FIELD, AppError, MotherTongueRecordId, TongueProposal,
+  TongueSearch, TongueSearchResults,
+};
+use leptos::*;
+use leptos_router::Redirect;
+
+use crate::{
+  components::{avatar::Avatar, BreadCrumbs, Link},
+  functions::{
+    app_error,
+    fetch::fetch_mother_tongues,
+    tongues::{
+      list_tongue_proposals, ApproveTongue, MergeTongue, RejectTongue,
+    },
+  },
+  helpers::get_auth_context,
+  LinkTarget,
+};
+
+/// How many merge targets to suggest for a search.
+const MERGE_SUGGESTIONS: u32 = 5;
+
+#[component]
+pub fn TongueReviewPage() -> impl IntoView {
+  let can_review = get_auth_context()
+    .0
+    .is_some_and(|u| u.has_role(core_types::SiteRole::Reviewer));
+  if !can_review {
+    return view! { <Redirect path="/" /> }.into_view();
+  }
+
+  view! {
+    <BreadCrumbs target=LinkTarget::TongueReview />
+    <div class="flex flex-col p-8 gap-4">
+      <p class="text-5xl tracking-tight font-semibold">
+        { LinkTarget::TongueReview.name() }
+      </p>
+      <div class="h-[1px] border-gray-6 border-b"></div>
+      <p class="text-content2 text-sm max-w-prose">
+        "Approve proposals to list them publicly. Reject ones that aren't mother tongues, or merge duplicates into the tongue they duplicate."
+      </p>
+      <ReviewQueue />
+    </div>
+  }
+  .into_view()
+}
+
+#[island]
+fn ReviewQueue() -> impl IntoView {
+  let approve_action = create_server_action::<ApproveTongue>();
+  let reject_action = create_server_action::<RejectTongue>();
+  let merge_action = create_server_action::<MergeTongue>();
+
+  let proposals = create_resource(
+    move || {
+      (
+        approve_action.version()(),
+        reject_action.version()(),
+        merge_action.version()(),
+      )
+    },
+    |_| list_tongue_proposals(),
+  );
+
+  let failure = move || {
+    [
+      approve_action.value()(),
+      reject_action.value()(),
+      merge_action.value()(),
+    ]
+    .into_iter()
+    .find_map(|v| v.and_then(Result::err))
+    .map(|e| app_error(&e))
+    // rejection reasons are shown next to their field instead
+    .filter(|e| !matches!(e, AppError::Validation(_)))
+    .map(|e| e.message())
+  };
+
+  view! {
+    { move || failure().map(|message| view! {
+      <p class="text-red-11 text-sm">{ message }</p>
+    }) }
+    <Transition fallback=move || view! { <p>"Loading..."</p> }>
+      { move || proposals().map(|result| match result {
+        Ok(proposals) if proposals.is_empty() => view! {
+          <p class="text-content2 text-sm">"Nothing is waiting for review."</p>
+        }
+        .into_view(),
+        Ok(proposals) => proposals
+          .into_iter()
+          .map(|proposal| view! {
+            <ProposalCard proposal approve_action reject_action merge_action />
+          })
+          .collect_view(),
+        Err(e) => view! {
+          <p class="text-red-11 text-sm">{ app_error(&e).message() }</p>
+        }
+        .into_view(),
+      }) }
+    </Transition>
+  }
+}
+
+#[component]
+fn ProposalCard(
+  proposal: TongueProposal,
+  approve_action: Action<ApproveTongue, Result<(), ServerFnError<AppError>>>,
+  reject_action: Action<RejectTongue, Result<(), ServerFnError<AppError>>>,
+  merge_action: Action<MergeTongue, Result<(), ServerFnError<AppError>>>,
+) -> impl IntoView {
+  let TongueProposal {
+    tongue,
+    proposer,
+    proposed_at,
+  } = proposal;
+  let id = tongue.id;
+
+  let (reason, set_reason) = create_signal(String::new());
+  let reason_error = move || match reject_action.value()() {
+    Some(Err(e)) => match app_error(&e) {
+      AppError::Validation(errors) => errors.get(REASON_FIELD).cloned(),
+      _ => None,
+    },
+    _ => None,
+  };
+
+  let (merge_term, set_merge_term) = create_signal(String::new());
+  let (merge_target, set_merge_target) =
+    create_signal::<Option<MotherTongueRecordId>>(None);
+  let merge_candidates = create_resource(merge_term, move |term| async move {
+    if term.trim().is_empty() {
+      return Ok(TongueSearchResults::default());
+    }
+    let search = TongueSearch {
+      term: Some(term),
+      ..Default::default()
+    };
+    fetch_mother_tongues(search, 0, MERGE_SUGGESTIONS).await
+  });
+
+  let details = [
+    (!tongue.alternate_names.is_empty())
+      .then(|| format!("Also known as {}", tongue.alternate_names.join(", "))),
+    tongue.region.map(|r| format!("Region: {r}")),
+    (!tongue.countries.is_empty())
+      .then(|| format!("Countries: {}", tongue.countries.join(", "))),
+    tongue.iso_639_3.map(|c| format!("ISO 639-3: {c}")),
+    tongue.parent_language.map(|c| format!("Variety of: {c}")),
+    tongue.script.map(|s| format!("Script: {s}")),
+    proposed_at.map(|at| format!("Proposed {}", at.date())),
+  ]
+  .into_iter()
+  .flatten()
+  .collect::<Vec<_>>()
+  .join(" · ");
+
+  view! {
+    <div class="card border border-border max-w-full">
+      <div class="card-body gap-4">
+        <div class="flex flex-row gap-4 items-center">
+          <Link target=LinkTarget::MotherTongue(id) class="text-xl font-semibold underline hover:no-underline">
+            { tongue.name }
+          </Link>
+          <div class="flex-1" />
+          { proposer.map(|proposer| view! {
+            <div class="flex flex-row gap-2 items-center text-sm">
+              <Avatar profile=proposer.clone() class="avatar-xs" />
+              <Link target=LinkTarget::UserProfile(proposer.id) class="underline hover:no-underline">
+                { proposer.name }
+              </Link>
+            </div>
+          }) }
+        </div>
+        <p class="text-content2 text-sm">{ details }</p>
+        <p class="max-w-prose">{ tongue.description }</p>
+
+        <div class="flex flex-row gap-2 items-center">
+          <button
+            class="btn btn-success btn-sm"
+            disabled=approve_action.pending()
+            on:click=move |_| approve_action.dispatch(ApproveTongue { id })
+          >"Approve"</button>
+        </div>
+
+        <div class="form-field">
+          <div class="flex flex-row gap-2 items-center">
+            <input
+              placeholder="Reason for rejecting"
+              class="input input-sm flex-1 max-w-full"
+              on:input=move |ev| set_reason(event_target_value(&ev))
+              prop:value=reason
+            />
+            <button
+              class="btn btn-error btn-sm"
+              disabled=reject_action.pending()
+              on:click=move |_| reject_action.dispatch(RejectTongue {
+                id,
+                reason: reason.get_untracked(),
+              })
+            >"Reject"</button>
+          </div>
+          { move || reason_error().map(|message| view! {
+            <label class="form-label animate-slide-down">
+              <span class="form-label-alt text-red-11">{ message }</span>
+            </label>
+          }) }
+        </div>
+
+        <div class="flex flex-row gap-2 items-center">
+          <input
+            placeholder="Search for a tongue to merge into"
+            class="input input-sm flex-1 max-w-full"
+            on:input=move |ev| {
+              set_merge_target(None);
+              set_merge_term(event_target_value(&ev));
+            }
+            prop:value=merge_term
+          />
+          <select
+            class="select select-sm"
+            on:change=move |ev| {
+              set_merge_target(event_target_value(&ev).parse().ok().map(MotherTongueRecordId))
+            }
+          >
+            <option value="" selected=move || merge_target().is_none()>"Choose..."</option>
+            <Transition fallback=|| ()>
+              { move || merge_candidates().map(|result| {
+                result
+                  .map(|results| results.hits)
+                  .unwrap_or_default()
+                  .into_iter()
+                  .map(|hit| hit.tongue)
+                  .filter(|t| t.id != id)
+                  .map(|t| view! { <option value=t.id.0.to_string()>{ t.name }</option> })
+                  .collect_view()
+              }) }
+            </Transition>
+          </select>
+          <button
+            class="btn btn-outline btn-sm"
+            disabled=move || merge_target().is_none() || merge_action.pending()()
+            on:click=move |_| {
+              if let Some(into) = merge_target.get_untracked() {
+                merge_action.dispatch(MergeTongue { id, into });
+              }
+            }
+          >"Merge"</button>
+        </div>
+      </div>
+    </div>
+  }
+}
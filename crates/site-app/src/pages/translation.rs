@@ -0,0 +1,281 @@
+use core_types::{
+  BookProgress, ChapterProgress, PassageTakes, TranslationRecordId,
+};
+use leptos::*;
+use leptos_router::use_params_map;
+
+use crate::{
+  components::{
+    avatar::Avatar,
+    invitation_card::role_labels,
+    mini_pages::{BadLinkError, MissingResourceError},
+    offline_bundle::OfflineBundle,
+    passage_recorder::PassageRecorder,
+    BreadCrumbs, Link,
+  },
+  functions::{
+    app_error,
+    fetch::{fetch_translation_details, fetch_translation_progress},
+    members::{can_record, fetch_team},
+    takes::list_recorded_passages,
+  },
+  LinkTarget,
+};
+
+#[component]
+pub fn TranslationPage() -> impl IntoView {
+  let params = use_params_map();
+
+  let id = move || {
+    with!(|params| {
+      params.get("id").cloned().and_then(|s| {
+        s.parse::<core_types::Ulid>().map(TranslationRecordId).ok()
+      })
+    })
+  };
+
+  view! {
+    { move || match id() {
+      Some(id) => view! { <TranslationFetcher id=id /> },
+      None => view! { <BadLinkError /> },
+    }}
+  }
+}
+
+#[component]
+fn TranslationFetcher(id: TranslationRecordId) -> impl IntoView {
+  let translation = create_resource(move || id, fetch_translation_details);
+
+  view! {
+    <BreadCrumbs target=LinkTarget::Translation(id) />
+    <Suspense fallback={move || view! { <p>"Loading..."</p> }}>
+      { move || translation().map(|data| match data {
+        Ok(Some(data)) => view! { <TranslationData data=data /> }.into_view(),
+        Ok(None) => view! { <MissingResourceError /> }.into_view(),
+        Err(e) => view! { <p>{ app_error(&e).message() }</p> }.into_view(),
+      }) }
+    </Suspense>
+  }
+}
+
+#[component]
+fn TranslationData(data: core_types::TranslationDetails) -> impl IntoView {
+  let core_types::TranslationDetails {
+    translation,
+    mother_tongue,
+    creator,
+  } = data;
+
+  view! {
+    <div class="flex flex-col p-8 gap-4">
+      <div class="flex flex-row gap-4 items-center">
+        <p class="text-5xl tracking-tight font-semibold">{ translation.name }</p>
+        <div class="flex-1" />
+        <Link target=LinkTarget::Listen(translation.id) class="btn btn-primary">
+          "Listen"
+        </Link>
+      </div>
+      <div class="flex flex-row gap-2 items-center text-sm">
+        <span class="text-content2">"A translation into"</span>
+        <Link target=LinkTarget::MotherTongue(mother_tongue.id) class="underline hover:no-underline">
+          { mother_tongue.name }
+        </Link>
+        <span class="text-content2">
+          { format!("started {}", translation.created_at.date()) }
+        </span>
+      </div>
+      { creator.map(|creator| view! {
+        <div class="flex flex-row gap-2 items-center text-sm">
+          <Avatar profile=creator.clone() class="avatar-xs" />
+          <span class="text-content2">"Started by"</span>
+          <Link target=LinkTarget::UserProfile(creator.id) class="underline hover:no-underline">
+            { creator.name }
+          </Link>
+        </div>
+      }) }
+      <div class="h-[1px] border-gray-6 border-b"></div>
+      <div class="flex flex-row gap-4 items-center">
+        <p class="text-3xl tracking-tight font-semibold">"Team"</p>
+        <Link target=LinkTarget::TranslationTeam(translation.id) class="text-sm underline hover:no-underline">
+          "Manage"
+        </Link>
+      </div>
+      <TeamSummary id=translation.id />
+      <p class="text-3xl tracking-tight font-semibold">"Progress"</p>
+      <ProgressGrid id=translation.id />
+      <p class="text-3xl tracking-tight font-semibold">"Recordings"</p>
+      <RecordSection id=translation.id />
+      <RecordedPassages id=translation.id />
+      <p class="text-3xl tracking-tight font-semibold">"Offline bundle"</p>
+      <p class="text-content2 text-sm max-w-prose">
+        "A ZIP of every approved recording, in folders by book and chapter \
+         with a playlist, for copying onto phones and SD cards to listen \
+         without a connection."
+      </p>
+      <OfflineBundle translation=translation.id />
+    </div>
+  }
+}
+
+/// Shows each book as a row of chapters, shaded by how much of each has
+/// been recorded.
+#[component]
+fn ProgressGrid(id: TranslationRecordId) -> impl IntoView {
+  let progress = create_resource(move || id, fetch_translation_progress);
+
+  view! {
+    <Suspense fallback={move || view! { <p>"Loading..."</p> }}>
+      { move || progress().map(|result| match result {
+        Ok(progress) => view! {
+          <p class="text-content2 text-sm">
+            { format!(
+              "{:.1}% recorded: {} of {} verses.",
+              progress.percent(),
+              progress.recorded_verses(),
+              progress.total_verses(),
+            ) }
+          </p>
+          <div class="grid grid-cols-[max-content_8rem_1fr] gap-x-4 gap-y-2 items-center">
+            { progress.books.into_iter().map(|book| view! {
+              <BookProgressRow book />
+            }).collect_view() }
+          </div>
+        }
+        .into_view(),
+        Err(e) => view! {
+          <p class="text-red-11 text-sm">{ app_error(&e).message() }</p>
+        }
+        .into_view(),
+      }) }
+    </Suspense>
+  }
+}
+
+/// Lists the team's members with their roles.
+#[component]
+fn TeamSummary(id: TranslationRecordId) -> impl IntoView {
+  let team = create_resource(move || id, fetch_team);
+
+  view! {
+    <Suspense fallback={move || view! { <p>"Loading..."</p> }}>
+      { move || team().map(|result| match result {
+        Ok(team) => view! {
+          <div class="flex flex-row flex-wrap gap-4">
+            { team.members.into_iter().map(|member| view! {
+              <div class="flex flex-row gap-2 items-center text-sm">
+                <Avatar profile=member.profile.clone() class="avatar-xs" />
+                <Link target=LinkTarget::UserProfile(member.profile.id) class="underline hover:no-underline">
+                  { member.profile.name }
+                </Link>
+                <span class="text-content2">{ role_labels(&member.roles) }</span>
+              </div>
+            }).collect_view() }
+          </div>
+        }
+        .into_view(),
+        Err(e) => view! {
+          <p class="text-red-11 text-sm">{ app_error(&e).message() }</p>
+        }
+        .into_view(),
+      }) }
+    </Suspense>
+  }
+}
+
+/// Lets the translation's speakers record passages right on the page.
+#[component]
+fn RecordSection(id: TranslationRecordId) -> impl IntoView {
+  let can_record = create_resource(move || id, can_record);
+
+  view! {
+    <Suspense fallback={move || view! { <p>"Loading..."</p> }}>
+      { move || matches!(can_record(), Some(Ok(true))).then(|| view! {
+        <p class="text-xl tracking-tight font-semibold">"Record a passage"</p>
+        <PassageRecorder translation=id />
+      }) }
+    </Suspense>
+  }
+}
+
+/// Lists the recorded passages with their current takes.
+#[component]
+fn RecordedPassages(id: TranslationRecordId) -> impl IntoView {
+  let passages = create_resource(move || id, list_recorded_passages);
+
+  view! {
+    <Suspense fallback={move || view! { <p>"Loading..."</p> }}>
+      { move || passages().map(|result| match result {
+        Ok(passages) if passages.is_empty() => view! {
+          <p class="text-content2 text-sm">"Nothing has been recorded yet."</p>
+        }
+        .into_view(),
+        Ok(passages) => view! {
+          <div class="flex flex-col gap-2">
+            { passages.into_iter().map(|passage| view! {
+              <RecordedPassageRow id passage />
+            }).collect_view() }
+          </div>
+        }
+        .into_view(),
+        Err(e) => view! {
+          <p class="text-red-11 text-sm">{ app_error(&e).message() }</p>
+        }
+        .into_view(),
+      }) }
+    </Suspense>
+  }
+}
+
+#[component]
+fn RecordedPassageRow(
+  id: TranslationRecordId,
+  passage: PassageTakes,
+) -> impl IntoView {
+  let PassageTakes {
+    passage,
+    current,
+    take_count,
+  } = passage;
+
+  view! {
+    <div class="flex flex-row flex-wrap gap-4 items-center">
+      <p class="font-semibold w-32">{ passage.to_string() }</p>
+      <audio controls preload="none" src=core_types::artifact_media_url(current.id) />
+      <span class="badge badge-outline badge-sm">{ current.status.label() }</span>
+      <Link target=LinkTarget::Takes(id, passage) class="text-sm underline hover:no-underline">
+        { format!("Take {} of {take_count}", current.take) }
+      </Link>
+    </div>
+  }
+}
+
+#[component]
+fn BookProgressRow(book: BookProgress) -> impl IntoView {
+  let percent = book.percent();
+  let name = book.name.clone();
+
+  view! {
+    <p class="text-sm font-semibold">{ book.name }</p>
+    <progress class="progress progress-success" value=percent max="100" />
+    <div class="flex flex-row flex-wrap gap-1">
+      { book.chapters.into_iter().enumerate().map(|(i, chapter)| {
+        let title = format!(
+          "{name} {}: {:.0}% recorded",
+          i + 1,
+          chapter.percent()
+        );
+        view! {
+          <span class=format!("w-3 h-3 rounded-sm {}", chapter_shade(chapter)) title=title />
+        }
+      }).collect_view() }
+    </div>
+  }
+}
+
+fn chapter_shade(chapter: ChapterProgress) -> &'static str {
+  match chapter.recorded {
+    0 => "bg-gray-5",
+    n if n < chapter.total => "bg-green-6",
+    _ => "bg-green-10",
+  }
+}
@@ -0,0 +1,212 @@
+use std::future::Future;
+
+use core_types::{
+  AppError, ContributedRecording, Contribution, MotherTongue,
+  TranslationSummary, UserRecordId,
+};
+use leptos::*;
+use leptos_router::use_params_map;
+use serde::{de::DeserializeOwned, Serialize};
+
+use crate::{
+  components::{
+    avatar::Avatar,
+    mini_pages::{BadLinkError, MissingResourceError},
+    pagination::Pagination,
+    BreadCrumbs, Link,
+  },
+  functions::{
+    app_error,
+    fetch::{
+      fetch_created_mother_tongues, fetch_created_recordings,
+      fetch_created_translations, fetch_user_profile,
+    },
+  },
+  LinkTarget,
+};
+
+const CONTRIBUTIONS_PER_PAGE: u32 = 10;
+
+fn page_count(total: usize) -> u32 {
+  (total as f32 / CONTRIBUTIONS_PER_PAGE as f32).ceil() as u32
+}
+
+#[component]
+pub fn UserProfilePage() -> impl IntoView {
+  let params = use_params_map();
+
+  let id = move || {
+    with!(|params| {
+      params
+        .get("id")
+        .cloned()
+        .and_then(|s| s.parse::<core_types::Ulid>().map(UserRecordId).ok())
+    })
+  };
+
+  view! {
+    { move || match id() {
+      Some(id) => view! { <UserProfileFetcher id=id /> },
+      None => view! { <BadLinkError /> },
+    }}
+  }
+}
+
+#[component]
+fn UserProfileFetcher(id: UserRecordId) -> impl IntoView {
+  let profile = create_resource(move || id, fetch_user_profile);
+
+  view! {
+    <BreadCrumbs target=LinkTarget::UserProfile(id) />
+    <Suspense fallback={move || view! { <p>"Loading..."</p> }}>
+      { move || profile().map(|data| match data {
+        Ok(Some(profile)) => view! { <UserProfileData profile=profile /> }.into_view(),
+        Ok(None) => view! { <MissingResourceError /> }.into_view(),
+        Err(e) => view! { <p>{ app_error(&e).message() }</p> }.into_view(),
+      }) }
+    </Suspense>
+  }
+}
+
+#[component]
+fn UserProfileData(profile: core_types::PublicProfile) -> impl IntoView {
+  let id = profile.id;
+
+  view! {
+    <div class="flex flex-col p-8 gap-4">
+      <div class="flex flex-row gap-4 items-center">
+        <Avatar profile=profile.clone() class="avatar-xl" />
+        <p class="text-5xl tracking-tight font-semibold">{ profile.name }</p>
+      </div>
+      <div class="h-[1px] border-gray-6 border-b"></div>
+      <p class="text-3xl tracking-tight font-semibold">"Mother Tongues"</p>
+      <CreatedMotherTongues user=id />
+      <p class="text-3xl tracking-tight font-semibold">"Translations"</p>
+      <CreatedTranslations user=id />
+      <p class="text-3xl tracking-tight font-semibold">"Recordings"</p>
+      <CreatedRecordings user=id />
+    </div>
+  }
+}
+
+/// A user's contributions of one kind, a page at a time.
+#[component]
+fn ContributionList<T, F, Fut, R, V>(
+  /// Fetches contributions and how many there are in total, given an offset
+  /// and a count.
+  fetch: F,
+  /// Shows a contribution in its row.
+  row: R,
+  /// Shown if there are no contributions.
+  empty: &'static str,
+) -> impl IntoView
+where
+  T: Clone + Serialize + DeserializeOwned + 'static,
+  F: Fn(u32, u32) -> Fut + 'static,
+  Fut: Future<
+      Output = Result<(Vec<Contribution<T>>, usize), ServerFnError<AppError>>,
+    > + 'static,
+  R: Fn(T) -> V + Copy + 'static,
+  V: IntoView,
+{
+  let (current_page, set_current_page) = create_signal(0_u32);
+  let contributions = create_resource(current_page, move |page| {
+    fetch(page * CONTRIBUTIONS_PER_PAGE, CONTRIBUTIONS_PER_PAGE)
+  });
+
+  view! {
+    <Transition fallback=move || view! { <p>"Loading..."</p> }>
+      { move || contributions().map(|result| match result {
+        Ok((contributions, _)) if contributions.is_empty() => view! {
+          <p class="text-content2 text-sm">{ empty }</p>
+        }
+        .into_view(),
+        Ok((contributions, total)) => view! {
+          <div class="flex flex-col gap-2">
+            { contributions.into_iter().map(|c| view! {
+              <ContributionRow added=c.at.date().to_string()>
+                { row(c.item) }
+              </ContributionRow>
+            }).collect_view() }
+          </div>
+          <Pagination class="self-center"
+            total_pages={page_count(total).into()}
+            current_page={current_page.into()}
+            set_page=set_current_page
+          />
+        }
+        .into_view(),
+        Err(e) => view! {
+          <p class="text-red-11 text-sm">{ app_error(&e).message() }</p>
+        }
+        .into_view(),
+      }) }
+    </Transition>
+  }
+}
+
+#[island]
+fn CreatedMotherTongues(user: UserRecordId) -> impl IntoView {
+  view! {
+    <ContributionList
+      fetch=move |offset, count| {
+        fetch_created_mother_tongues(user, offset, count)
+      }
+      row=|tongue: MotherTongue| view! {
+        <Link target=LinkTarget::MotherTongue(tongue.id) class="underline hover:no-underline">
+          { tongue.name }
+        </Link>
+      }
+      empty="No mother tongues yet."
+    />
+  }
+}
+
+#[island]
+fn CreatedTranslations(user: UserRecordId) -> impl IntoView {
+  view! {
+    <ContributionList
+      fetch=move |offset, count| {
+        fetch_created_translations(user, offset, count)
+      }
+      row=|translation: TranslationSummary| view! {
+        <Link target=LinkTarget::Translation(translation.id) class="underline hover:no-underline">
+          { translation.name }
+        </Link>
+        <Link target=LinkTarget::MotherTongue(translation.mother_tongue) class="text-content2 text-sm underline hover:no-underline">
+          "Mother tongue"
+        </Link>
+      }
+      empty="No translations yet."
+    />
+  }
+}
+
+#[island]
+fn CreatedRecordings(user: UserRecordId) -> impl IntoView {
+  view! {
+    <ContributionList
+      fetch=move |offset, count| {
+        fetch_created_recordings(user, offset, count)
+      }
+      row=|recording: ContributedRecording| view! {
+        { recording.passage.map(|p| view! { <span>{ p.to_string() }</span> }) }
+        <Link target=LinkTarget::Translation(recording.translation) class="text-content2 text-sm underline hover:no-underline">
+          { recording.translation_name }
+        </Link>
+      }
+      empty="No recordings yet."
+    />
+  }
+}
+
+#[component]
+fn ContributionRow(added: String, children: Children) -> impl IntoView {
+  view! {
+    <div class="flex flex-row gap-4 items-center">
+      { children() }
+      <div class="flex-1" />
+      <p class="text-content2 text-sm">{ format!("Added {added}") }</p>
+    </div>
+  }
+}
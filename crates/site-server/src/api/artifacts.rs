@@ -0,0 +1,200 @@
+use artifact::StreamedUpload;
+use axum::{
+  body::Body,
+  extract::{Path, Query, State},
+  http::{header::CONTENT_TYPE, HeaderMap},
+  Json,
+};
+use core_types::{
+  audio_mime_type,
+  scripture::Passage,
+  ssr::CoreId,
+  validation::{PASSAGE_FIELD, UPLOAD_FIELD},
+  ApiTokenScope, AppError, Artifact, ArtifactRecordId, RecordingStatus,
+  SiteRole, TranslationRecordId,
+};
+use serde::{Deserialize, Serialize};
+
+use super::{
+  internal_error, validation_error, ApiError, ApiUser, MAX_UPLOAD_BYTES,
+};
+use crate::AppState;
+
+#[derive(Serialize)]
+pub struct ArtifactResponse {
+  pub id:          ArtifactRecordId,
+  pub translation: Option<TranslationRecordId>,
+  pub passage:     Option<String>,
+  pub status:      RecordingStatus,
+  pub take:        u32,
+  pub is_current:  bool,
+  pub duration_ms: Option<u32>,
+}
+
+impl From<Artifact> for ArtifactResponse {
+  fn from(value: Artifact) -> Self {
+    ArtifactResponse {
+      id:          value.id,
+      translation: value.translation,
+      passage:     value.passage.map(|p| p.to_string()),
+      status:      value.status,
+      take:        value.take,
+      is_current:  value.is_current,
+      duration_ms: value.duration_ms,
+    }
+  }
+}
+
+#[derive(Deserialize)]
+pub struct UploadParams {
+  /// The translation the upload is a recording for.
+  translation: Option<core_types::Ulid>,
+  /// The passage the recording covers, like `JHN 3` or `JHN 3:16-18`.
+  /// Needs a translation.
+  passage:     Option<String>,
+  /// How long the recording plays for, in milliseconds. Podcast feeds list
+  /// it.
+  duration_ms: Option<u32>,
+}
+
+/// Uploads the request body as a new artifact, using its `Content-Type`. The
+/// body is streamed to the object store as it arrives.
+pub async fn upload_artifact(
+  api_user: ApiUser,
+  State(app_state): State<AppState>,
+  Query(params): Query<UploadParams>,
+  headers: HeaderMap,
+  body: Body,
+) -> Result<Json<ArtifactResponse>, ApiError> {
+  api_user.require(ApiTokenScope::Upload)?;
+  // anything else could be served back as a page on the site
+  let content_type = headers
+    .get(CONTENT_TYPE)
+    .and_then(|v| v.to_str().ok())
+    .and_then(audio_mime_type)
+    .ok_or_else(|| {
+      validation_error(
+        UPLOAD_FIELD,
+        "Uploads must be audio, like MP3, M4A, Ogg, WebM, WAV or FLAC.",
+      )
+    })?;
+
+  let passage = match (params.passage.as_deref(), params.translation) {
+    (Some(_), None) => {
+      return Err(validation_error(
+        PASSAGE_FIELD,
+        "A passage can only be given for a translation.",
+      ))
+    }
+    (Some(passage), Some(_)) => Some(
+      passage
+        .parse::<Passage>()
+        .map_err(|e| validation_error(PASSAGE_FIELD, &e.to_string()))?,
+    ),
+    (None, _) => None,
+  };
+
+  let translation = match params.translation {
+    Some(id) => Some(
+      app_state
+        .db
+        .select_translation(TranslationRecordId(id))
+        .await
+        .map_err(|e| internal_error(e.into(), "fetch translation"))?
+        .ok_or(ApiError(AppError::NotFound))?
+        .id,
+    ),
+    None => None,
+  };
+  // only the translation's speakers and managers record for it
+  if let Some(translation) = translation {
+    let membership = app_state
+      .db
+      .select_membership(translation, api_user.user.id)
+      .await
+      .map_err(|e| internal_error(e.into(), "fetch membership"))?;
+    let allowed = api_user.user.has_role(SiteRole::Admin)
+      || membership.is_some_and(|m| m.can_upload());
+    if !allowed {
+      return Err(ApiError(AppError::Forbidden));
+    }
+  }
+
+  let id = ArtifactRecordId::new();
+  let object_key = format!("artifacts/{}", id.0);
+  let size = match artifact::upload_stream(
+    &object_key,
+    body.into_data_stream(),
+    MAX_UPLOAD_BYTES,
+  )
+  .await
+  .map_err(|e| internal_error(e, "upload artifact"))?
+  {
+    StreamedUpload::Uploaded(size) => size,
+    StreamedUpload::Empty => {
+      return Err(validation_error(UPLOAD_FIELD, "The upload was empty."))
+    }
+    StreamedUpload::TooLarge => {
+      return Err(validation_error(
+        UPLOAD_FIELD,
+        "Uploads can be at most 512 MiB.",
+      ))
+    }
+  };
+
+  let artifact = Artifact {
+    id,
+    object_key: object_key.clone(),
+    content_type: Some(content_type.to_string()),
+    size: Some(size),
+    duration_ms: params.duration_ms.filter(|ms| *ms > 0),
+    translation,
+    passage,
+    status: RecordingStatus::Draft,
+    // numbered when it's inserted, after any earlier takes of the passage
+    take: 1,
+    is_current: true,
+  };
+
+  let artifact =
+    match app_state.db.insert_take(artifact, api_user.user.id).await {
+      Ok(Some(artifact)) => artifact,
+      result => {
+        // the blob is useless without its record
+        if let Err(e) = artifact::delete_object(&object_key).await {
+          log::error!("Failed to delete orphaned upload {object_key}: {e:?}");
+        }
+        let error = match result {
+          Err(e) => e.into(),
+          _ => color_eyre::eyre::eyre!("artifact was not created"),
+        };
+        return Err(internal_error(error, "create artifact"));
+      }
+    };
+
+  log::info!(
+    "user {} uploaded artifact {} with {}",
+    api_user.user.id.0,
+    id.0,
+    api_user.via()
+  );
+  Ok(Json(artifact.into()))
+}
+
+/// Gets an artifact's metadata.
+pub async fn get_artifact(
+  api_user: ApiUser,
+  State(app_state): State<AppState>,
+  Path(id): Path<core_types::Ulid>,
+) -> Result<Json<ArtifactResponse>, ApiError> {
+  api_user.require(ApiTokenScope::Read)?;
+
+  let artifact = app_state
+    .db
+    .select_artifact(ArtifactRecordId(id))
+    .await
+    .map_err(|e| internal_error(e.into(), "fetch artifact"))?
+    .ok_or(ApiError(AppError::NotFound))?;
+
+  Ok(Json(artifact.into()))
+}
@@ -0,0 +1,100 @@
+use axum::{
+  async_trait,
+  extract::FromRequestParts,
+  http::{header::AUTHORIZATION, request::Parts},
+};
+use core_types::{
+  ApiTokenScope, AppError, Secret, API_REQUESTED_WITH,
+  API_REQUESTED_WITH_HEADER,
+};
+
+use super::{internal_error, ApiError};
+
+/// The user behind a bearer token, extracted from the `Authorization`
+/// header.
+///
+/// This is the API's counterpart to [`auth::AuthSession`]: handlers get the
+/// authenticated user, plus the token's scopes to check with
+/// [`require`](ApiUser::require). Requests without a token fall back to the
+/// session cookie, so the site's own pages can use the API too; writes made
+/// that way need the [`API_REQUESTED_WITH_HEADER`] header.
+#[derive(Clone, Debug)]
+pub struct ApiUser {
+  pub user:  core_types::User,
+  /// The token the request was made with, or `None` if it came from a
+  /// logged-in page.
+  pub token: Option<core_types::ApiToken>,
+}
+
+impl ApiUser {
+  /// Fails with [`AppError::Forbidden`] unless the token has `scope`.
+  /// Sessions can do anything their user can.
+  pub fn require(&self, scope: ApiTokenScope) -> Result<(), ApiError> {
+    match self.token.as_ref().is_none_or(|t| t.has_scope(scope)) {
+      true => Ok(()),
+      false => Err(ApiError(AppError::Forbidden)),
+    }
+  }
+
+  /// Describes how the request was authenticated, for logs.
+  pub fn via(&self) -> String {
+    match &self.token {
+      Some(token) => format!("token {}", token.id.0),
+      None => "session".to_string(),
+    }
+  }
+}
+
+#[async_trait]
+impl<S: Send + Sync> FromRequestParts<S> for ApiUser {
+  type Rejection = ApiError;
+
+  async fn from_request_parts(
+    parts: &mut Parts,
+    state: &S,
+  ) -> Result<Self, Self::Rejection> {
+    let token = parts
+      .headers
+      .get(AUTHORIZATION)
+      .and_then(|v| v.to_str().ok())
+      .and_then(|v| v.strip_prefix("Bearer "))
+      .map(|v| Secret::new(v.trim()));
+
+    // the auth layer provides the backend, and the cookie session for
+    // requests without a token
+    let auth_session = auth::AuthSession::from_request_parts(parts, state)
+      .await
+      .map_err(|_| {
+        internal_error(
+          color_eyre::eyre::eyre!("auth layer is missing"),
+          "get auth session",
+        )
+      })?;
+
+    let Some(token) = token else {
+      let user = auth_session.user.ok_or(ApiError(AppError::Unauthorized))?;
+      // browsers send the cookie with requests from any site, so writes
+      // must show they came from our pages
+      let from_page = parts
+        .headers
+        .get(API_REQUESTED_WITH_HEADER)
+        .is_some_and(|v| v == API_REQUESTED_WITH);
+      if !parts.method.is_safe() && !from_page {
+        return Err(ApiError(AppError::Forbidden));
+      }
+      return Ok(ApiUser { user, token: None });
+    };
+
+    match auth_session.backend.authenticate_api_token(&token).await {
+      Ok(Some((user, token))) => Ok(ApiUser {
+        user,
+        token: Some(token),
+      }),
+      Ok(None) => Err(ApiError(AppError::Unauthorized)),
+      Err(e) => Err(internal_error(
+        color_eyre::eyre::eyre!("{e:?}"),
+        "authenticate api token",
+      )),
+    }
+  }
+}
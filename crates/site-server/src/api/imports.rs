@@ -0,0 +1,136 @@
+use std::io::BufReader;
+
+use archive::import::{
+  import_recordings, ImportOptions, ImportReport, ZipSource,
+};
+use axum::{
+  body::Body,
+  extract::{Path, Query, State},
+  Json,
+};
+use core_types::{
+  validation::UPLOAD_FIELD, ApiTokenScope, AppError, SiteRole,
+  TranslationRecordId, TranslationRole, UserRecordId,
+};
+use futures::StreamExt;
+use tokio::io::AsyncWriteExt;
+
+use super::{
+  internal_error, validation_error, ApiError, ApiUser, MAX_UPLOAD_BYTES,
+};
+use crate::AppState;
+
+/// Imports the recordings in a ZIP request body into a translation. Only
+/// reports what would be imported unless `apply` is set.
+///
+/// ZIPs are read from the end, so the body is saved to a temporary file
+/// first rather than held in memory.
+pub async fn import_translation(
+  api_user: ApiUser,
+  State(app_state): State<AppState>,
+  Path(id): Path<core_types::Ulid>,
+  Query(options): Query<ImportOptions>,
+  body: Body,
+) -> Result<Json<ImportReport>, ApiError> {
+  api_user.require(ApiTokenScope::Upload)?;
+
+  let translation = app_state
+    .db
+    .select_translation(TranslationRecordId(id))
+    .await
+    .map_err(|e| internal_error(e.into(), "fetch translation"))?
+    .ok_or(ApiError(AppError::NotFound))?
+    .id;
+  // imports can replace a lot of work at once, so only managers run them
+  let membership = app_state
+    .db
+    .select_membership(translation, api_user.user.id)
+    .await
+    .map_err(|e| internal_error(e.into(), "fetch membership"))?;
+  let allowed = api_user.user.has_role(SiteRole::Admin)
+    || membership.is_some_and(|m| m.has_role(TranslationRole::Manager));
+  if !allowed {
+    return Err(ApiError(AppError::Forbidden));
+  }
+
+  let path = std::env::temp_dir()
+    .join(format!("omthub-import-{}.zip", core_types::Ulid::new()));
+  let result = import_body(
+    &app_state.db,
+    translation,
+    api_user.user.id,
+    body,
+    &path,
+    options,
+  )
+  .await;
+  match tokio::fs::remove_file(&path).await {
+    Err(e) if e.kind() != std::io::ErrorKind::NotFound => {
+      log::warn!("failed to remove {}: {e}", path.display());
+    }
+    _ => (),
+  }
+  let report = result?;
+
+  log::info!(
+    "user {} {} an import of {} recordings into translation {} with {}",
+    api_user.user.id.0,
+    match report.applied {
+      true => "ran",
+      false => "dry-ran",
+    },
+    report.matched.len(),
+    translation.0,
+    api_user.via(),
+  );
+  Ok(Json(report))
+}
+
+/// Saves an import's body to `path` as it arrives, then imports from it.
+async fn import_body(
+  db: &db::DbConnection,
+  translation: TranslationRecordId,
+  creator: UserRecordId,
+  body: Body,
+  path: &std::path::Path,
+  options: ImportOptions,
+) -> Result<ImportReport, ApiError> {
+  let mut file = tokio::fs::File::create(path)
+    .await
+    .map_err(|e| internal_error(e.into(), "save import"))?;
+  let mut size = 0_u64;
+  let mut stream = body.into_data_stream();
+  while let Some(chunk) = stream.next().await {
+    let chunk =
+      chunk.map_err(|e| internal_error(e.into(), "receive import"))?;
+    size += chunk.len() as u64;
+    if size > MAX_UPLOAD_BYTES {
+      return Err(validation_error(
+        UPLOAD_FIELD,
+        "Imports can be at most 512 MiB.",
+      ));
+    }
+    file
+      .write_all(&chunk)
+      .await
+      .map_err(|e| internal_error(e.into(), "save import"))?;
+  }
+  file
+    .flush()
+    .await
+    .map_err(|e| internal_error(e.into(), "save import"))?;
+  let file = file.into_std().await;
+
+  // reading the ZIP's directory is blocking I/O
+  let source =
+    tokio::task::spawn_blocking(move || ZipSource::new(BufReader::new(file)))
+      .await
+      .map_err(|e| internal_error(e.into(), "read import"))?
+      .map_err(|_| {
+        validation_error(UPLOAD_FIELD, "Imports must be ZIP files.")
+      })?;
+
+  import_recordings(db, translation, creator, source, options)
+    .await
+    .map_err(|e| internal_error(e, "import recordings"))
+}
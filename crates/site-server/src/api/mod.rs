@@ -0,0 +1,66 @@
+//! The JSON API for scripted clients, authenticated with bearer tokens. The
+//! site's own pages use it too, with their session.
+
+mod artifacts;
+mod bearer;
+mod imports;
+
+use axum::{
+  response::{IntoResponse, Response},
+  routing::{get, post},
+  Router,
+};
+use core_types::{validation::ValidationErrors, AppError};
+
+pub use self::bearer::ApiUser;
+use crate::AppState;
+
+/// Recordings can be long, so uploads and imports get a much larger limit
+/// than axum's default. Their bodies are streamed rather than buffered, and
+/// counted against this as they arrive.
+const MAX_UPLOAD_BYTES: u64 = 512 * 1024 * 1024;
+
+/// An [`AppError`] returned from an API route, serialized as JSON.
+pub struct ApiError(pub AppError);
+
+impl From<AppError> for ApiError {
+  fn from(value: AppError) -> Self { ApiError(value) }
+}
+
+impl IntoResponse for ApiError {
+  fn into_response(self) -> Response {
+    (
+      site_app::status_code(&self.0),
+      [(axum::http::header::CONTENT_TYPE, "application/json")],
+      self.0.to_string(),
+    )
+      .into_response()
+  }
+}
+
+/// Logs an unexpected error and hides it behind [`AppError::Internal`].
+fn internal_error(
+  error: color_eyre::eyre::Report,
+  failed_action: &str,
+) -> ApiError {
+  log::error!("Failed to {failed_action}: {error:?}");
+  ApiError(AppError::Internal)
+}
+
+/// Rejects a request with a message about one of its fields.
+fn validation_error(field: &'static str, message: &str) -> ApiError {
+  let mut errors = ValidationErrors::new();
+  errors.check(field, Some(message.to_string()));
+  ApiError(AppError::Validation(errors))
+}
+
+pub fn router() -> Router<AppState> {
+  Router::new()
+    .route("/artifacts", post(artifacts::upload_artifact))
+    .route("/artifacts/:id", get(artifacts::get_artifact))
+    .route(
+      "/translations/:id/import",
+      post(imports::import_translation),
+    )
+    .fallback(|| async { ApiError(AppError::NotFound) })
+}
@@ -0,0 +1,93 @@
+//! Downloads of offline bundles.
+
+use axum::{
+  body::Body,
+  extract::{Path, State},
+  http::{header, StatusCode},
+  response::{IntoResponse, Response},
+};
+use core_types::{BundleRecordId, BundleStatus};
+
+use crate::AppState;
+
+/// Turns a translation's name into something safe to name a file with.
+fn file_name(translation_name: &str) -> String {
+  let name = translation_name
+    .chars()
+    .map(|c| match c.is_ascii_alphanumeric() {
+      true => c.to_ascii_lowercase(),
+      false => '-',
+    })
+    .collect::<String>()
+    .split('-')
+    .filter(|part| !part.is_empty())
+    .collect::<Vec<_>>()
+    .join("-");
+  match name.is_empty() {
+    true => "bundle.zip".to_string(),
+    false => format!("{name}.zip"),
+  }
+}
+
+/// Sends a bundle's ZIP. Bundles that aren't ready yet 404.
+pub async fn download_bundle(
+  State(app_state): State<AppState>,
+  Path(id): Path<core_types::Ulid>,
+) -> Response {
+  let db = &app_state.db;
+  let bundle = match db.select_bundle(BundleRecordId(id)).await {
+    Ok(Some(bundle)) if bundle.status == BundleStatus::Ready => bundle,
+    Ok(_) => return StatusCode::NOT_FOUND.into_response(),
+    Err(e) => {
+      log::error!("failed to fetch bundle {id}: {e:?}");
+      return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+    }
+  };
+  let name = match db.select_translation(bundle.translation).await {
+    Ok(translation) => {
+      file_name(&translation.map(|t| t.name).unwrap_or_default())
+    }
+    Err(e) => {
+      log::error!("failed to fetch translation of bundle {id}: {e:?}");
+      return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+    }
+  };
+
+  let (size, stream) =
+    match artifact::stream_object(&bundle.object_key, None).await {
+      Ok(Some(object)) => object,
+      Ok(None) => {
+        log::warn!("bundle {id} has no object in the store");
+        return StatusCode::NOT_FOUND.into_response();
+      }
+      Err(e) => {
+        log::error!("failed to fetch bundle {id}: {e:?}");
+        return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+      }
+    };
+
+  (
+    [
+      (header::CONTENT_TYPE, "application/zip".to_string()),
+      (header::CONTENT_LENGTH, size.to_string()),
+      (
+        header::CONTENT_DISPOSITION,
+        format!("attachment; filename=\"{name}\""),
+      ),
+    ],
+    Body::from_stream(stream),
+  )
+    .into_response()
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn file_names_are_safe() {
+    assert_eq!(file_name("Kala Lagaw Ya (2024)"), "kala-lagaw-ya-2024.zip");
+    assert_eq!(file_name("Évangile"), "vangile.zip");
+    assert_eq!(file_name("???"), "bundle.zip");
+  }
+}
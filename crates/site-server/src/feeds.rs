@@ -0,0 +1,477 @@
+//! Podcast feeds, so translations can be heard in any podcast app.
+//!
+//! Each translation with approved recordings gets an RSS 2.0 feed with the
+//! iTunes extensions. Feeds are built from the database on every request,
+//! so a recording shows up as soon as it's approved.
+//!
+//! Podcast directories want an owner to contact and artwork for each feed,
+//! which are configured with env vars:
+//! - `PODCAST_OWNER_EMAIL`: the email directories list as the owner's.
+//! - `PODCAST_IMAGE_URL`: square artwork, 1400 to 3000 pixels a side.
+//!
+//! Feeds work without them, but directories like Apple Podcasts won't list
+//! them.
+
+use std::fmt::Write;
+
+use axum::{
+  extract::{Path, State},
+  http::{header, StatusCode},
+  response::{IntoResponse, Response},
+};
+use core_types::{
+  iso_639_3, ApprovedRecording, ListeningTrack, MotherTongue, Playlist,
+  TranslationRecordId,
+};
+use time::format_description::well_known::Rfc2822;
+
+use crate::AppState;
+
+/// The content type to assume for recordings uploaded without one.
+const DEFAULT_CONTENT_TYPE: &str = "audio/mpeg";
+
+/// The name directories show as every feed's author and owner.
+const PODCAST_OWNER_NAME: &str = "OMTHub";
+
+/// What every feed says about who runs it.
+#[derive(Clone, Debug, Default)]
+pub struct PodcastConfig {
+  pub owner_email: Option<String>,
+  pub image_url:   Option<String>,
+}
+
+impl PodcastConfig {
+  /// Reads the config from the environment, warning about what's missing.
+  pub fn from_env() -> Self {
+    let var = |name: &str| {
+      let value = std::env::var(name).ok().filter(|v| !v.is_empty());
+      if value.is_none() {
+        log::warn!(
+          "`{name}` isn't set, so podcast directories won't list feeds"
+        );
+      }
+      value
+    };
+
+    Self {
+      owner_email: var("PODCAST_OWNER_EMAIL"),
+      image_url:   var("PODCAST_IMAGE_URL"),
+    }
+  }
+}
+
+/// Escapes text for use in XML content and attribute values.
+fn escape(text: &str) -> String {
+  let mut escaped = String::with_capacity(text.len());
+  for c in text.chars() {
+    match c {
+      '&' => escaped.push_str("&amp;"),
+      '<' => escaped.push_str("&lt;"),
+      '>' => escaped.push_str("&gt;"),
+      '"' => escaped.push_str("&quot;"),
+      '\'' => escaped.push_str("&apos;"),
+      c => escaped.push(c),
+    }
+  }
+  escaped
+}
+
+/// Formats a duration like `1:02:03` or `4:05`, the way podcast apps expect.
+fn itunes_duration(ms: u32) -> String {
+  let seconds = ms / 1000;
+  match seconds / 3600 {
+    0 => format!("{}:{:02}", seconds / 60, seconds % 60),
+    hours => format!("{hours}:{:02}:{:02}", (seconds / 60) % 60, seconds % 60),
+  }
+}
+
+fn rfc2822(at: time::OffsetDateTime) -> String {
+  at.format(&Rfc2822).unwrap_or_default()
+}
+
+/// An episode: a whole chapter, or part of one recorded on its own.
+struct Episode<'a> {
+  title:     String,
+  recording: &'a ApprovedRecording,
+  /// The recording's size in bytes.
+  size:      u64,
+}
+
+/// Makes one episode per chapter. Chapters recorded in several parts get an
+/// episode per part, since an episode can only have one file.
+///
+/// Recordings of unknown size are left out, since podcast apps need an
+/// episode's length to download it.
+fn episodes(recordings: &[ApprovedRecording]) -> Vec<Episode<'_>> {
+  let playlist = Playlist::new(
+    String::new(),
+    recordings.iter().map(ApprovedRecording::track),
+  );
+  let find =
+    |track: &ListeningTrack| recordings.iter().find(|r| r.id == track.id);
+
+  playlist
+    .chapters
+    .iter()
+    .flat_map(|chapter| {
+      let whole = chapter.tracks.len() == 1;
+      chapter.tracks.iter().filter_map(move |track| {
+        let title = match (whole, track.passage.verses) {
+          (false, Some(verses)) if verses.start != verses.end => format!(
+            "{} {}:{}-{}",
+            chapter.book_name, chapter.chapter, verses.start, verses.end
+          ),
+          (false, Some(verses)) => {
+            format!(
+              "{} {}:{}",
+              chapter.book_name, chapter.chapter, verses.start
+            )
+          }
+          _ => chapter.title(),
+        };
+        let recording = find(track)?;
+        Some(Episode {
+          title,
+          recording,
+          size: recording.size?,
+        })
+      })
+    })
+    .collect()
+}
+
+/// Writes a translation's feed. `public_url` is the site's origin, without a
+/// trailing slash.
+fn render_feed(
+  public_url: &str,
+  podcast: &PodcastConfig,
+  id: TranslationRecordId,
+  name: &str,
+  mother_tongue: &MotherTongue,
+  recordings: &[ApprovedRecording],
+) -> String {
+  let episodes = episodes(recordings);
+  let description = format!(
+    "The Bible read aloud in {}, chapter by chapter.",
+    mother_tongue.name
+  );
+  // feeds take RFC 5646 tags, which use ISO 639-1 codes where there are
+  // any, so unknown codes are left out rather than guessed at
+  let language = mother_tongue
+    .iso_639_3
+    .as_ref()
+    .or(mother_tongue.parent_language.as_ref())
+    .and_then(|code| iso_639_3::lookup(code))
+    .map(iso_639_3::Language::language_tag);
+  let last_approved = recordings.iter().map(|r| r.approved_at).max();
+
+  let mut xml = String::new();
+  // writing to a string can't fail
+  let _ = write!(
+    xml,
+    "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<rss version=\"2.0\" \
+     xmlns:itunes=\"http://www.itunes.com/dtds/podcast-1.0.dtd\" \
+     xmlns:atom=\"http://www.w3.org/2005/Atom\">\n<channel>\n"
+  );
+  let _ = writeln!(xml, "<title>{}</title>", escape(name));
+  let _ =
+    writeln!(xml, "<link>{public_url}/translation/{}/listen</link>", id.0);
+  let _ = writeln!(
+    xml,
+    "<atom:link href=\"{public_url}{}\" rel=\"self\" \
+     type=\"application/rss+xml\" />",
+    core_types::translation_feed_url(id)
+  );
+  let _ = writeln!(xml, "<description>{}</description>", escape(&description));
+  if let Some(language) = language {
+    let _ = writeln!(xml, "<language>{}</language>", escape(language));
+  }
+  if let Some(at) = last_approved {
+    let _ = writeln!(xml, "<lastBuildDate>{}</lastBuildDate>", rfc2822(at));
+  }
+  let _ = writeln!(xml, "<itunes:author>{PODCAST_OWNER_NAME}</itunes:author>");
+  if let Some(email) = &podcast.owner_email {
+    let _ = writeln!(
+      xml,
+      "<itunes:owner><itunes:name>{PODCAST_OWNER_NAME}</itunes:name><itunes:\
+       email>{}</itunes:email></itunes:owner>",
+      escape(email)
+    );
+  }
+  if let Some(image_url) = &podcast.image_url {
+    let _ = writeln!(xml, "<itunes:image href=\"{}\" />", escape(image_url));
+  }
+  let _ = writeln!(
+    xml,
+    "<itunes:summary>{}</itunes:summary>",
+    escape(&description)
+  );
+  let _ = writeln!(
+    xml,
+    "<itunes:category text=\"Religion &amp; Spirituality\"><itunes:category \
+     text=\"Christianity\" /></itunes:category>"
+  );
+  let _ = writeln!(xml, "<itunes:explicit>false</itunes:explicit>");
+  // episodes are meant to be heard in order, like an audiobook
+  let _ = writeln!(xml, "<itunes:type>serial</itunes:type>");
+
+  for (number, episode) in episodes.iter().enumerate() {
+    let recording = episode.recording;
+    let _ = writeln!(xml, "<item>");
+    let _ = writeln!(xml, "<title>{}</title>", escape(&episode.title));
+    // a recording keeps its guid and date for as long as it's the newest
+    // approved take, so apps don't offer it again
+    let _ =
+      writeln!(xml, "<guid isPermaLink=\"false\">{}</guid>", recording.id.0);
+    let _ = writeln!(
+      xml,
+      "<enclosure url=\"{public_url}{}\" length=\"{}\" type=\"{}\" />",
+      core_types::artifact_media_url(recording.id),
+      episode.size,
+      escape(
+        recording
+          .content_type
+          .as_deref()
+          .unwrap_or(DEFAULT_CONTENT_TYPE)
+      )
+    );
+    let _ =
+      writeln!(xml, "<pubDate>{}</pubDate>", rfc2822(recording.approved_at));
+    if let Some(ms) = recording.duration_ms {
+      let _ = writeln!(
+        xml,
+        "<itunes:duration>{}</itunes:duration>",
+        itunes_duration(ms)
+      );
+    }
+    let _ = writeln!(xml, "<itunes:episode>{}</itunes:episode>", number + 1);
+    let _ = writeln!(xml, "<itunes:episodeType>full</itunes:episodeType>");
+    let _ = writeln!(xml, "</item>");
+  }
+
+  xml.push_str("</channel>\n</rss>\n");
+  xml
+}
+
+/// Serves a translation's podcast feed. Translations are published once they
+/// have an approved recording; until then their feed 404s.
+pub async fn translation_feed(
+  State(app_state): State<AppState>,
+  Path(id): Path<core_types::Ulid>,
+) -> Response {
+  let id = TranslationRecordId(id);
+  let db = &app_state.db;
+
+  let translation = match db.select_translation(id).await {
+    Ok(Some(translation)) => translation,
+    Ok(None) => return StatusCode::NOT_FOUND.into_response(),
+    Err(e) => {
+      log::error!("failed to fetch translation {}: {e:?}", id.0);
+      return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+    }
+  };
+  let recordings = match db.select_approved_recordings(id).await {
+    Ok(recordings) if recordings.is_empty() => {
+      return StatusCode::NOT_FOUND.into_response()
+    }
+    Ok(recordings) => recordings,
+    Err(e) => {
+      log::error!("failed to fetch recordings of {}: {e:?}", id.0);
+      return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+    }
+  };
+  let mother_tongue =
+    match db.select_mother_tongue(translation.mother_tongue).await {
+      Ok(Some(mother_tongue)) => mother_tongue,
+      Ok(None) => {
+        log::error!("translation {} has no mother tongue", id.0);
+        return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+      }
+      Err(e) => {
+        log::error!("failed to fetch mother tongue of {}: {e:?}", id.0);
+        return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+      }
+    };
+
+  let feed = render_feed(
+    &app_state.public_url,
+    &app_state.podcast,
+    id,
+    &translation.name,
+    &mother_tongue,
+    &recordings,
+  );
+  (
+    [
+      (header::CONTENT_TYPE, "application/rss+xml; charset=utf-8"),
+      // podcast apps poll, so let caches absorb some of it
+      (header::CACHE_CONTROL, "public, max-age=300"),
+    ],
+    feed,
+  )
+    .into_response()
+}
+
+#[cfg(test)]
+mod tests {
+  use core_types::ArtifactRecordId;
+
+  use super::*;
+
+  #[test]
+  fn durations_are_formatted_for_podcast_apps() {
+    assert_eq!(itunes_duration(0), "0:00");
+    assert_eq!(itunes_duration(65_400), "1:05");
+    assert_eq!(itunes_duration(3_723_000), "1:02:03");
+  }
+
+  #[test]
+  fn text_is_escaped_for_xml() {
+    assert_eq!(
+      escape("Tom & Jerry's <\"feed\">"),
+      "Tom &amp; Jerry&apos;s &lt;&quot;feed&quot;&gt;"
+    );
+  }
+
+  fn recording(passage: &str, day: u8) -> ApprovedRecording {
+    ApprovedRecording {
+      id:           ArtifactRecordId(core_types::Ulid::from_parts(
+        u64::from(day),
+        0,
+      )),
+      passage:      passage.parse().unwrap(),
+      object_key:   format!("recordings/{passage}"),
+      content_type: Some("audio/mpeg".to_string()),
+      size:         Some(1024),
+      duration_ms:  Some(65_000),
+      approved_at:  time::Date::from_calendar_date(
+        2024,
+        time::Month::June,
+        day,
+      )
+      .unwrap()
+      .midnight()
+      .assume_utc(),
+    }
+  }
+
+  fn mother_tongue(iso_639_3: Option<&str>) -> MotherTongue {
+    MotherTongue {
+      id:               core_types::MotherTongueRecordId(
+        core_types::Ulid::from_parts(2, 2),
+      ),
+      name:             "Kala Lagaw Ya".to_string(),
+      description:      String::new(),
+      alternate_names:  Vec::new(),
+      region:           None,
+      countries:        Vec::new(),
+      iso_639_3:        iso_639_3.map(str::to_string),
+      parent_language:  None,
+      script:           None,
+      is_vetted:        true,
+      rejection_reason: None,
+    }
+  }
+
+  #[test]
+  fn feeds_list_an_episode_per_recording() {
+    let id = TranslationRecordId(core_types::Ulid::from_parts(1, 1));
+    let mother_tongue = mother_tongue(Some("mwp"));
+    let podcast = PodcastConfig {
+      owner_email: Some("owner@example.com".to_string()),
+      image_url:   Some("https://example.com/art.png".to_string()),
+    };
+    let recordings = [recording("JHN 3:1-8", 2), recording("JHN 3:9-21", 3)];
+
+    let feed = render_feed(
+      "https://omthub.example",
+      &podcast,
+      id,
+      "Tom & Jerry's Bible",
+      &mother_tongue,
+      &recordings,
+    );
+
+    assert!(feed.contains("<title>Tom &amp; Jerry&apos;s Bible</title>"));
+    assert!(feed.contains("<language>mwp</language>"));
+    assert!(!feed.contains("length=\"0\""));
+    assert!(feed.contains(
+      "<itunes:owner><itunes:name>OMTHub</itunes:name><itunes:email>owner@\
+       example.com</itunes:email></itunes:owner>"
+    ));
+    assert!(
+      feed.contains("<itunes:image href=\"https://example.com/art.png\" />")
+    );
+    assert!(feed.contains(
+      "<lastBuildDate>Mon, 03 Jun 2024 00:00:00 +0000</lastBuildDate>"
+    ));
+    assert_eq!(feed.matches("<item>").count(), 2);
+    assert!(feed.contains("<title>John 3:1-8</title>"));
+    assert!(feed.contains("<title>John 3:9-21</title>"));
+    assert!(feed.contains(&format!(
+      "<guid isPermaLink=\"false\">{}</guid>",
+      recordings[0].id.0
+    )));
+    assert!(feed.contains(&format!(
+      "<enclosure url=\"https://omthub.example/media/artifacts/{}\" \
+       length=\"1024\" type=\"audio/mpeg\" />",
+      recordings[0].id.0
+    )));
+    assert!(feed.contains("<pubDate>Sun, 02 Jun 2024 00:00:00 +0000</pubDate>"));
+    assert!(feed.contains("<itunes:duration>1:05</itunes:duration>"));
+  }
+
+  #[test]
+  fn feeds_leave_out_unconfigured_owners_and_images() {
+    let feed = render_feed(
+      "https://omthub.example",
+      &PodcastConfig::default(),
+      TranslationRecordId(core_types::Ulid::from_parts(1, 1)),
+      "Bible",
+      &mother_tongue(None),
+      &[recording("JHN 3", 2)],
+    );
+
+    assert!(!feed.contains("<itunes:owner>"));
+    assert!(!feed.contains("<itunes:image"));
+    assert!(!feed.contains("<language>"));
+    assert!(feed.contains("<title>John 3</title>"));
+  }
+
+  #[test]
+  fn feeds_tag_languages_with_their_shortest_codes() {
+    let feed = |code: Option<&str>| {
+      render_feed(
+        "https://omthub.example",
+        &PodcastConfig::default(),
+        TranslationRecordId(core_types::Ulid::from_parts(1, 1)),
+        "Bible",
+        &mother_tongue(code),
+        &[recording("JHN 3", 2)],
+      )
+    };
+
+    assert!(feed(Some("eng")).contains("<language>en</language>"));
+    assert!(feed(Some("mwp")).contains("<language>mwp</language>"));
+    assert!(!feed(Some("qqq")).contains("<language>"));
+  }
+
+  #[test]
+  fn feeds_leave_out_recordings_of_unknown_size() {
+    let mut unknown_size = recording("JHN 3:9-21", 3);
+    unknown_size.size = None;
+
+    let feed = render_feed(
+      "https://omthub.example",
+      &PodcastConfig::default(),
+      TranslationRecordId(core_types::Ulid::from_parts(1, 1)),
+      "Bible",
+      &mother_tongue(None),
+      &[recording("JHN 3:1-8", 2), unknown_size],
+    );
+
+    assert_eq!(feed.matches("<item>").count(), 1);
+    assert!(feed.contains("<title>John 3:1-8</title>"));
+    assert!(!feed.contains("length=\"0\""));
+  }
+}
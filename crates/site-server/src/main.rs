@@ -1,4 +1,10 @@
+pub mod api;
+pub mod bundles;
+pub mod feeds;
 pub mod fileserv;
+pub mod media;
+pub mod oidc;
+pub mod session_tracking;
 
 use axum::{
   body::Body,
@@ -19,13 +25,19 @@ use tower::ServiceBuilder;
 use tower_http::compression::CompressionLayer;
 use tracing_subscriber::prelude::*;
 
-use self::fileserv::file_and_error_handler;
+use self::{
+  fileserv::file_and_error_handler, session_tracking::track_sessions,
+};
 
 #[derive(FromRef, Debug, Clone)]
 pub struct AppState {
   pub leptos_options: LeptosOptions,
   pub routes:         Vec<RouteListing>,
   pub db:             db::DbConnection,
+  /// The site's origin, without a trailing slash, for links that leave the
+  /// site like those in podcast feeds.
+  pub public_url:     String,
+  pub podcast:        feeds::PodcastConfig,
 }
 
 async fn server_fn_handler(
@@ -39,7 +51,7 @@ async fn server_fn_handler(
       provide_context(auth_session.clone());
       provide_context(session.clone());
       provide_context(core_types::LoggedInUser(
-        auth_session.user.clone().map(core_types::PublicUser::from),
+        auth_session.user.clone().map(core_types::CurrentUser::from),
       ));
       provide_context(app_state.db.clone());
     },
@@ -61,7 +73,7 @@ async fn leptos_routes_handler(
       provide_context(auth_session.clone());
       provide_context(session.clone());
       provide_context(core_types::LoggedInUser(
-        auth_session.user.clone().map(core_types::PublicUser::from),
+        auth_session.user.clone().map(core_types::CurrentUser::from),
       ));
       provide_context(app_state.db.clone());
     },
@@ -100,6 +112,9 @@ async fn main() -> Result<()> {
     .await
     .wrap_err("failed to run db migrations")?;
   log::info!("ran migrations");
+  db.fail_unfinished_bundles()
+    .await
+    .wrap_err("failed to clean up unfinished bundles")?;
 
   // Setting get_configuration(None) means we'll be using cargo-leptos's env
   // values For deployment these variables are:
@@ -115,6 +130,11 @@ async fn main() -> Result<()> {
     leptos_options,
     routes: routes.clone(),
     db,
+    public_url: auth::public_url()?
+      .as_str()
+      .trim_end_matches('/')
+      .to_string(),
+    podcast: feeds::PodcastConfig::from_env(),
   };
 
   let auth_layer = auth::build_auth_layer().await?;
@@ -125,8 +145,15 @@ async fn main() -> Result<()> {
       "/api/*fn_name",
       get(server_fn_handler).post(server_fn_handler),
     )
+    .nest("/v1", api::router())
+    .route("/media/artifacts/:id", get(media::stream_artifact))
+    .route("/feeds/translations/:id", get(feeds::translation_feed))
+    .route("/bundles/:id/download", get(bundles::download_bundle))
+    .route("/auth/oidc/start", get(oidc::start_oidc_login))
+    .route(auth::OIDC_CALLBACK_PATH, get(oidc::oidc_callback))
     .leptos_routes_with_handler(routes, get(leptos_routes_handler))
     .fallback(file_and_error_handler)
+    .layer(axum::middleware::from_fn(track_sessions))
     .layer(
       ServiceBuilder::new()
         .layer(CompressionLayer::new())
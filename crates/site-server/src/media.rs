@@ -0,0 +1,219 @@
+//! Public streaming of recordings, with support for range requests so that
+//! players can seek.
+
+use std::ops::Range;
+
+use axum::{
+  body::Body,
+  extract::{Path, State},
+  http::{header, HeaderMap, StatusCode},
+  response::{IntoResponse, Response},
+};
+use core_types::{ArtifactRecordId, RecordingStatus};
+
+use crate::AppState;
+
+/// The content type for recordings that weren't uploaded as audio.
+const DEFAULT_CONTENT_TYPE: &str = "application/octet-stream";
+
+/// What a `Range` header asks for, against an object of a known size.
+#[derive(Debug, PartialEq, Eq)]
+enum RangeRequest {
+  /// No usable range, so send the whole object.
+  Full,
+  /// Send these bytes.
+  Partial(Range<usize>),
+  /// The range can't be satisfied.
+  Unsatisfiable,
+}
+
+/// Parses a single-range `Range` header, like `bytes=0-1023`, `bytes=1024-`
+/// or `bytes=-512`.
+///
+/// Multiple ranges and malformed headers fall back to the whole object, which
+/// the spec allows.
+fn parse_range(header: Option<&str>, size: usize) -> RangeRequest {
+  let Some(spec) = header.and_then(|h| h.trim().strip_prefix("bytes=")) else {
+    return RangeRequest::Full;
+  };
+  if spec.contains(',') {
+    return RangeRequest::Full;
+  }
+  let Some((start, end)) = spec.split_once('-') else {
+    return RangeRequest::Full;
+  };
+
+  let (start, end) = match (start.trim(), end.trim()) {
+    ("", "") => return RangeRequest::Full,
+    // a suffix range, for the last `n` bytes
+    ("", n) => match n.parse::<usize>() {
+      Ok(0) => return RangeRequest::Unsatisfiable,
+      Ok(n) => (size.saturating_sub(n), size.saturating_sub(1)),
+      Err(_) => return RangeRequest::Full,
+    },
+    (start, "") => match start.parse::<usize>() {
+      Ok(start) => (start, size.saturating_sub(1)),
+      Err(_) => return RangeRequest::Full,
+    },
+    (start, end) => match (start.parse::<usize>(), end.parse::<usize>()) {
+      (Ok(start), Ok(end)) if start <= end => {
+        (start, end.min(size.saturating_sub(1)))
+      }
+      _ => return RangeRequest::Full,
+    },
+  };
+
+  if size == 0 || start >= size {
+    return RangeRequest::Unsatisfiable;
+  }
+  RangeRequest::Partial(start..end + 1)
+}
+
+/// Whether the current user can hear a recording that isn't approved yet:
+/// only those who can see its review can.
+async fn can_hear_unapproved(
+  app_state: &AppState,
+  user: Option<&core_types::User>,
+  id: ArtifactRecordId,
+) -> Result<bool, db::SurrealError> {
+  let Some(user) = user else {
+    return Ok(false);
+  };
+  let Some(recording) = app_state.db.select_recording_summary(id).await? else {
+    return Ok(false);
+  };
+  let membership = app_state
+    .db
+    .select_membership(recording.translation, user.id)
+    .await?;
+  Ok(recording.is_visible_to(user, membership.as_ref()))
+}
+
+/// Streams a recording. Approved recordings are public; others can only be
+/// heard by those who can see their review. Artifacts that aren't recordings
+/// for a translation, or that the user can't hear, 404.
+pub async fn stream_artifact(
+  auth_session: auth::AuthSession,
+  State(app_state): State<AppState>,
+  Path(id): Path<core_types::Ulid>,
+  headers: HeaderMap,
+) -> Response {
+  let artifact = match app_state.db.select_artifact(ArtifactRecordId(id)).await
+  {
+    Ok(Some(artifact)) if artifact.translation.is_some() => artifact,
+    Ok(_) => return StatusCode::NOT_FOUND.into_response(),
+    Err(e) => {
+      log::error!("failed to fetch artifact {}: {e:?}", id);
+      return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+    }
+  };
+  if artifact.status != RecordingStatus::Approved {
+    match can_hear_unapproved(
+      &app_state,
+      auth_session.user.as_ref(),
+      artifact.id,
+    )
+    .await
+    {
+      Ok(true) => {}
+      Ok(false) => return StatusCode::NOT_FOUND.into_response(),
+      Err(e) => {
+        log::error!("failed to check access to artifact {}: {e:?}", id);
+        return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+      }
+    }
+  }
+
+  let size = match artifact::object_size(&artifact.object_key).await {
+    Ok(Some(size)) => size,
+    Ok(None) => {
+      log::warn!("artifact {} has no object in the store", id);
+      return StatusCode::NOT_FOUND.into_response();
+    }
+    Err(e) => {
+      log::error!("failed to fetch size of artifact {}: {e:?}", id);
+      return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+    }
+  };
+
+  let range_header = headers.get(header::RANGE).and_then(|v| v.to_str().ok());
+  let range = match parse_range(range_header, size) {
+    RangeRequest::Full => None,
+    RangeRequest::Partial(range) => Some(range),
+    RangeRequest::Unsatisfiable => {
+      return (StatusCode::RANGE_NOT_SATISFIABLE, [(
+        header::CONTENT_RANGE,
+        format!("bytes */{size}"),
+      )])
+        .into_response();
+    }
+  };
+
+  let stream =
+    match artifact::stream_object(&artifact.object_key, range.clone()).await {
+      Ok(Some((_, stream))) => stream,
+      Ok(None) => {
+        log::warn!("artifact {} has no object in the store", id);
+        return StatusCode::NOT_FOUND.into_response();
+      }
+      Err(e) => {
+        log::error!("failed to fetch artifact {}: {e:?}", id);
+        return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+      }
+    };
+
+  // only audio is served as what the uploader said it was, so nothing else
+  // can be rendered as a page on our origin
+  let audio_type = artifact
+    .content_type
+    .as_deref()
+    .and_then(core_types::audio_mime_type);
+  let mut response = Response::builder()
+    .header(
+      header::CONTENT_TYPE,
+      audio_type.unwrap_or(DEFAULT_CONTENT_TYPE),
+    )
+    .header(header::X_CONTENT_TYPE_OPTIONS, "nosniff")
+    .header(header::ACCEPT_RANGES, "bytes");
+  if audio_type.is_none() {
+    response = response.header(header::CONTENT_DISPOSITION, "attachment");
+  }
+  response = match range {
+    Some(range) => response
+      .status(StatusCode::PARTIAL_CONTENT)
+      .header(header::CONTENT_LENGTH, range.len())
+      .header(
+        header::CONTENT_RANGE,
+        format!("bytes {}-{}/{size}", range.start, range.end - 1),
+      ),
+    None => response
+      .status(StatusCode::OK)
+      .header(header::CONTENT_LENGTH, size),
+  };
+
+  response
+    .body(Body::from_stream(stream))
+    .unwrap_or_else(|_| StatusCode::INTERNAL_SERVER_ERROR.into_response())
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn range_headers_are_resolved_against_the_size() {
+    use RangeRequest::*;
+
+    assert_eq!(parse_range(None, 100), Full);
+    assert_eq!(parse_range(Some("bytes=0-9"), 100), Partial(0..10));
+    assert_eq!(parse_range(Some("bytes=90-"), 100), Partial(90..100));
+    assert_eq!(parse_range(Some("bytes=-10"), 100), Partial(90..100));
+    assert_eq!(parse_range(Some("bytes=-500"), 100), Partial(0..100));
+    assert_eq!(parse_range(Some("bytes=50-500"), 100), Partial(50..100));
+    assert_eq!(parse_range(Some("bytes=100-"), 100), Unsatisfiable);
+    assert_eq!(parse_range(Some("bytes=-0"), 100), Unsatisfiable);
+    assert_eq!(parse_range(Some("bytes=0-1,5-6"), 100), Full);
+    assert_eq!(parse_range(Some("bytes=9-0"), 100), Full);
+    assert_eq!(parse_range(Some("items=0-9"), 100), Full);
+  }
+}
@@ -0,0 +1,146 @@
+//! The redirect endpoints for OpenID Connect login.
+
+use axum::{
+  extract::Query,
+  response::{IntoResponse, Redirect, Response},
+};
+use core_types::OidcLoginError;
+use serde::Deserialize;
+
+/// The session key holding an [`auth::PendingOidcLogin`].
+const PENDING_OIDC_LOGIN_KEY: &str = "pending_oidc_login";
+
+#[derive(Deserialize)]
+pub struct StartParams {
+  #[serde(default)]
+  link: bool,
+}
+
+/// Redirects to the provider, to log in or to link the provider's account
+/// to the logged in user.
+pub async fn start_oidc_login(
+  auth_session: auth::AuthSession,
+  session: tower_sessions::Session,
+  Query(params): Query<StartParams>,
+) -> Response {
+  if params.link && auth_session.user.is_none() {
+    return Redirect::to("/auth/login").into_response();
+  }
+
+  let (auth_url, pending) =
+    match auth_session.backend.start_oidc_login(params.link) {
+      Ok(started) => started,
+      Err(e) => {
+        log::error!("failed to start OIDC login: {e:?}");
+        return login_error(OidcLoginError::Failed);
+      }
+    };
+  if let Err(e) = session.insert(PENDING_OIDC_LOGIN_KEY, pending).await {
+    log::error!("failed to store pending OIDC login: {e:?}");
+    return login_error(OidcLoginError::Failed);
+  }
+
+  Redirect::to(&auth_url).into_response()
+}
+
+#[derive(Deserialize)]
+pub struct CallbackParams {
+  code:  Option<String>,
+  state: Option<String>,
+  error: Option<String>,
+}
+
+/// Handles the provider's redirect back to us.
+pub async fn oidc_callback(
+  mut auth_session: auth::AuthSession,
+  session: tower_sessions::Session,
+  Query(params): Query<CallbackParams>,
+) -> Response {
+  let pending = session
+    .remove::<auth::PendingOidcLogin>(PENDING_OIDC_LOGIN_KEY)
+    .await
+    .ok()
+    .flatten();
+  let (Some(pending), Some(code), Some(state)) =
+    (pending, params.code, params.state)
+  else {
+    if let Some(error) = params.error {
+      log::info!("OIDC provider returned an error: {error}");
+    }
+    return login_error(OidcLoginError::Failed);
+  };
+  let link = pending.link;
+
+  let claims = match auth_session
+    .backend
+    .finish_oidc_login(code, &state, pending)
+    .await
+  {
+    Ok(claims) => claims,
+    Err(e) => {
+      log::warn!("failed to finish OIDC login: {e:?}");
+      return login_error(OidcLoginError::Failed);
+    }
+  };
+
+  if link {
+    let Some(user) = auth_session.user.clone() else {
+      return Redirect::to("/auth/login").into_response();
+    };
+    return match auth_session
+      .backend
+      .link_oidc_identity(user.id, &claims)
+      .await
+    {
+      Ok(()) => Redirect::to("/account").into_response(),
+      Err(e) => {
+        log::warn!("failed to link OIDC identity: {e:?}");
+        Redirect::to("/account?oidc_error=link_failed").into_response()
+      }
+    };
+  }
+
+  let user = match auth_session.backend.resolve_oidc_login(&claims).await {
+    Ok(auth::OidcResolution::Existing(user))
+    | Ok(auth::OidcResolution::SignedUp(user)) => user,
+    Ok(auth::OidcResolution::LinkRequired) => {
+      return login_error(OidcLoginError::LinkRequired)
+    }
+    Ok(auth::OidcResolution::EmailUnverified) => {
+      return login_error(OidcLoginError::EmailUnverified)
+    }
+    Err(e) => {
+      log::error!("failed to resolve OIDC login: {e:?}");
+      return login_error(OidcLoginError::Failed);
+    }
+  };
+
+  if !user.is_active {
+    log::info!("refused OIDC login for inactive user {}", user.id.0);
+    return login_error(OidcLoginError::Failed);
+  }
+  // the provider's own second factors don't stand in for the one the user
+  // chose here
+  if user.totp_enabled() {
+    if let Err(e) =
+      site_app::require_second_factor(&session, user.id, false).await
+    {
+      log::error!("failed to store pending OIDC login: {e:?}");
+      return login_error(OidcLoginError::Failed);
+    }
+    return Redirect::to("/auth/login?second_factor=1").into_response();
+  }
+
+  if let Err(e) = auth_session.login(&user).await {
+    log::error!("failed to log in OIDC user: {e:?}");
+    return login_error(OidcLoginError::Failed);
+  }
+  log::info!("logged in user with OIDC: {} ({})", user.name, user.id.0);
+
+  Redirect::to("/account").into_response()
+}
+
+fn login_error(error: OidcLoginError) -> Response {
+  Redirect::to(&format!("/auth/login?oidc_error={}", error.as_query()))
+    .into_response()
+}
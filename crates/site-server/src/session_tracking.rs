@@ -0,0 +1,48 @@
+use axum::{
+  extract::Request,
+  http::{header::USER_AGENT, HeaderMap},
+  middleware::Next,
+  response::Response,
+};
+
+/// Records device metadata for logged in sessions, so that users can see and
+/// revoke them from their account page.
+pub async fn track_sessions(
+  auth_session: auth::AuthSession,
+  session: tower_sessions::Session,
+  request: Request,
+  next: Next,
+) -> Response {
+  if let Some(user) = &auth_session.user {
+    let activity = auth::SessionActivity {
+      user_agent: header_value(request.headers(), USER_AGENT.as_str()),
+      ip:         client_ip(request.headers()),
+    };
+    // failing to track shouldn't fail the request
+    if let Err(e) = auth_session
+      .backend
+      .track_session(user.id, &session, activity)
+      .await
+    {
+      log::warn!("failed to track session: {e:?}");
+    }
+  }
+
+  next.run(request).await
+}
+
+fn header_value(headers: &HeaderMap, name: &str) -> Option<String> {
+  headers
+    .get(name)
+    .and_then(|v| v.to_str().ok())
+    .map(|v| v.to_string())
+}
+
+/// Gets the client's IP from the proxy headers, preferring the one set by
+/// Fly's edge.
+fn client_ip(headers: &HeaderMap) -> Option<String> {
+  header_value(headers, "fly-client-ip").or_else(|| {
+    header_value(headers, "x-forwarded-for")
+      .and_then(|v| v.split(',').next().map(|ip| ip.trim().to_string()))
+  })
+}